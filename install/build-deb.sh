@@ -168,6 +168,8 @@ mkdir -p "${DEB_DIR}/usr/share/battery-manager/docs"
 echo -e "${BLUE}Copie des fichiers...${NC}"
 cp "${PROJECT_ROOT}/target/release/battery_manager" "${DEB_DIR}/usr/bin/battery-manager"
 chmod 755 "${DEB_DIR}/usr/bin/battery-manager"
+cp "${PROJECT_ROOT}/target/release/battery-manager-apply" "${DEB_DIR}/usr/bin/battery-manager-apply"
+chmod 755 "${DEB_DIR}/usr/bin/battery-manager-apply"
 echo -e "${GREEN}✓${NC} Binaire copié"
 
 # Copier l'icône
@@ -36,6 +36,8 @@ mkdir -p /lib/systemd/system
 echo "Installation du binaire..."
 cp "${PROJECT_ROOT}/target/release/battery_manager" /usr/bin/battery-manager
 chmod +x /usr/bin/battery-manager
+cp "${PROJECT_ROOT}/target/release/battery-manager-apply" /usr/bin/battery-manager-apply
+chmod +x /usr/bin/battery-manager-apply
 
 # Copier le script de restauration
 echo "Installation du script de restauration..."
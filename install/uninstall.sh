@@ -25,14 +25,19 @@ systemctl stop battery-manager.service 2>/dev/null || true
 # Supprimer les fichiers
 echo "Suppression des fichiers..."
 rm -f /usr/bin/battery-manager
+rm -f /usr/bin/battery-manager-apply
 rm -f /usr/bin/battery-manager-restore
 rm -f /lib/systemd/system/battery-manager.service
+# Service et règle udev installés depuis l'application
+rm -f /etc/systemd/system/battery-manager.service
+rm -f /etc/udev/rules.d/99-battery-manager.rules
 rm -f /usr/share/applications/battery-manager.desktop
 rm -rf /usr/share/battery-manager
 
-# Recharger systemd
-echo "Rechargement de systemd..."
+# Recharger systemd et udev
+echo "Rechargement de systemd et udev..."
 systemctl daemon-reload
+udevadm control --reload 2>/dev/null || true
 
 echo -e "\n${GREEN}✓ Désinstallation terminée avec succès!${NC}"
 echo -e "\n${YELLOW}Note: Les fichiers de configuration dans /etc/battery-manager/ ont été conservés${NC}"
@@ -0,0 +1,529 @@
+//! Privileged helper for Battery Manager
+//!
+//! Invoked by the GUI through pkexec with an apply plan serialized as
+//! arguments (see `core::threshold_apply`):
+//!
+//! `battery-manager-apply --write <path> <value>... --service <enable|disable|keep>`
+//!
+//! The restore unit (`--install-unit`, with the threshold files it restores
+//! as `--unit-start-path`/`--unit-stop-path`) and the udev rule
+//! (`--udev-rule`, `--remove-udev-rule`) are generated here from validated
+//! values, never passed as text.
+//!
+//! Only writes to threshold files below the allowed sysfs prefixes, or to
+//! `<battery>.conf` in the config directory, are accepted. The service is
+//! managed through systemd's D-Bus API, or `systemctl` when the
+//! system bus is unreachable.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+#[allow(dead_code)]
+#[path = "../core/threshold_apply.rs"]
+mod threshold_apply;
+
+#[allow(dead_code)]
+#[path = "../core/systemd.rs"]
+mod systemd;
+
+use threshold_apply::{
+    ApplyPlan, ServiceAction, UdevRuleAction, UnitInstall, CONFIG_DIR, UDEV_RULE_INSTALL_PATH,
+    UNIT_INSTALL_PATH,
+};
+
+/// Unit restoring the thresholds at boot
+const SERVICE_UNIT: &str = "battery-manager.service";
+
+/// sysfs prefixes holding the threshold files the helper may write
+const ALLOWED_SYSFS_PREFIXES: [&str; 4] = [
+    "/sys/class/power_supply/",
+    "/sys/devices/",
+    // HP threshold files (BIOS settings driver, ACPI device)
+    "/sys/bus/wmi/drivers/hp-bioscfg/",
+    "/sys/bus/platform/devices/ACPI000D:00/",
+];
+
+/// Checks that a write target is absolute, normalized and either a threshold
+/// file below an allowed sysfs prefix or a `<battery>.conf` config file
+fn is_allowed_path(path: &Path) -> bool {
+    let normalized = path.is_absolute()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::RootDir | Component::Normal(_)));
+    let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+        return false;
+    };
+    if !normalized {
+        return false;
+    }
+    if path.parent() == Some(Path::new(CONFIG_DIR)) {
+        return name
+            .strip_suffix(".conf")
+            .is_some_and(is_allowed_battery_name);
+    }
+    ALLOWED_SYSFS_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        && threshold_apply::is_threshold_control_file(name)
+}
+
+/// Checks that a threshold file restored by the unit is a sysfs threshold
+/// file the unit can name without quoting
+fn is_allowed_unit_path(path: &str) -> bool {
+    threshold_apply::is_unit_safe_path(path)
+        && Path::new(path).parent() != Some(Path::new(CONFIG_DIR))
+        && is_allowed_path(Path::new(path))
+}
+
+/// Checks that a value only contains characters used by thresholds and config files
+fn is_allowed_value(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '=' | '_' | '-' | '\n'))
+}
+
+/// Checks that a battery name is a plain sysfs entry name (no path or shell syntax)
+fn is_allowed_battery_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+/// Parses a threshold percentage argument
+fn parse_percent(value: &str) -> Result<u8, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|percent| *percent <= 100)
+        .ok_or_else(|| format!("invalid threshold: {value}"))
+}
+
+/// Parses helper arguments into a plan
+///
+/// # Arguments
+///
+/// * `args` - Arguments without the program name
+///
+/// # Returns
+///
+/// * `Ok(ApplyPlan)` - Validated plan
+/// * `Err(String)` - Description of the invalid argument
+fn parse_args(args: &[String]) -> Result<ApplyPlan, String> {
+    let mut writes = Vec::new();
+    let mut install_unit = None;
+    let mut udev_rule = None;
+    let mut service_action = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--write" => {
+                let (Some(path), Some(value)) = (iter.next(), iter.next()) else {
+                    return Err("--write expects <path> <value>".to_string());
+                };
+                let path = PathBuf::from(path);
+                if !is_allowed_path(&path) {
+                    return Err(format!("path not allowed: {}", path.display()));
+                }
+                if !is_allowed_value(value) {
+                    return Err(format!("invalid value for {}", path.display()));
+                }
+                writes.push((path, value.clone()));
+            }
+            "--install-unit" => {
+                let (Some(battery_name), Some(start), Some(stop)) =
+                    (iter.next(), iter.next(), iter.next())
+                else {
+                    return Err("--install-unit expects <battery> <start|-> <stop>".to_string());
+                };
+                if !is_allowed_battery_name(battery_name) {
+                    return Err(format!("invalid battery name: {battery_name}"));
+                }
+                let start = match start.as_str() {
+                    "-" => None,
+                    start => Some(parse_percent(start)?),
+                };
+                install_unit = Some(UnitInstall {
+                    battery_name: battery_name.clone(),
+                    start,
+                    stop: parse_percent(stop)?,
+                    start_paths: Vec::new(),
+                    stop_paths: Vec::new(),
+                });
+            }
+            flag @ ("--unit-start-path" | "--unit-stop-path") => {
+                let Some(unit) = install_unit.as_mut() else {
+                    return Err(format!("{flag} expects a preceding --install-unit"));
+                };
+                let Some(path) = iter.next() else {
+                    return Err(format!("{flag} expects <path>"));
+                };
+                if !is_allowed_unit_path(path) {
+                    return Err(format!("path not allowed: {path}"));
+                }
+                if flag == "--unit-start-path" {
+                    unit.start_paths.push(path.clone());
+                } else {
+                    unit.stop_paths.push(path.clone());
+                }
+            }
+            "--udev-rule" => {
+                let (Some(battery_name), Some(stop)) = (iter.next(), iter.next()) else {
+                    return Err("--udev-rule expects <battery> <stop>".to_string());
+                };
+                if !is_allowed_battery_name(battery_name) {
+                    return Err(format!("invalid battery name: {battery_name}"));
+                }
+                udev_rule = Some(UdevRuleAction::Install {
+                    battery_name: battery_name.clone(),
+                    stop: parse_percent(stop)?,
+                });
+            }
+            "--remove-udev-rule" => udev_rule = Some(UdevRuleAction::Remove),
+            "--service" => {
+                service_action = Some(match iter.next().map(String::as_str) {
+                    Some("enable") => ServiceAction::Enable,
+                    Some("disable") => ServiceAction::Disable,
+                    Some("keep") => ServiceAction::Keep,
+                    other => return Err(format!("invalid service action: {other:?}")),
+                });
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+    }
+
+    Ok(ApplyPlan {
+        writes,
+        install_unit,
+        udev_rule,
+        service_action: service_action.ok_or("missing --service")?,
+    })
+}
+
+/// Runs `systemctl` with the given arguments
+fn systemctl(args: &[&str]) -> Result<(), String> {
+    let status = Command::new("systemctl")
+        .args(args)
+        .status()
+        .map_err(|e| format!("systemctl {}: {e}", args.join(" ")))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("systemctl {} failed: {status}", args.join(" ")))
+    }
+}
+
+/// Runs the service action with `systemctl` (no system bus)
+fn service_with_systemctl(action: ServiceAction) -> Vec<String> {
+    let mut errors = Vec::new();
+    match action {
+        ServiceAction::Enable => {
+            // Picks up a freshly installed unit (the D-Bus path reloads too)
+            if let Err(e) = systemctl(&["daemon-reload"]) {
+                errors.push(e);
+            }
+            for args in [["enable", SERVICE_UNIT], ["start", SERVICE_UNIT]] {
+                if let Err(e) = systemctl(&args) {
+                    errors.push(e);
+                }
+            }
+        }
+        ServiceAction::Disable => {
+            // Best effort, like the previous shell version
+            let _ = systemctl(&["disable", SERVICE_UNIT]);
+            let _ = systemctl(&["stop", SERVICE_UNIT]);
+        }
+        ServiceAction::Keep => {}
+    }
+    errors
+}
+
+/// Runs the service action over D-Bus, falling back to `systemctl`
+fn apply_service_action(action: ServiceAction) -> Vec<String> {
+    if action == ServiceAction::Keep {
+        return Vec::new();
+    }
+    let connection = match systemd::system_bus() {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("battery-manager-apply: system bus unreachable ({e}), using systemctl");
+            return service_with_systemctl(action);
+        }
+    };
+
+    match action {
+        ServiceAction::Enable => systemd::enable_and_start(&connection, SERVICE_UNIT)
+            .err()
+            .map(|e| format!("enable {SERVICE_UNIT}: {e}"))
+            .into_iter()
+            .collect(),
+        ServiceAction::Disable => {
+            // Best effort, like the systemctl version
+            let _ = systemd::disable_and_stop(&connection, SERVICE_UNIT);
+            Vec::new()
+        }
+        ServiceAction::Keep => Vec::new(),
+    }
+}
+
+/// Performs every write of the plan, then the service action
+///
+/// # Returns
+///
+/// List of errors (empty on full success)
+fn execute(plan: &ApplyPlan) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for (path, value) in &plan.writes {
+        if path.starts_with(CONFIG_DIR) {
+            if let Err(e) = fs::create_dir_all(CONFIG_DIR) {
+                errors.push(format!("{CONFIG_DIR}: {e}"));
+                continue;
+            }
+        }
+        if let Err(e) = fs::write(path, value) {
+            errors.push(format!("{}: {e}", path.display()));
+        }
+    }
+
+    if let Some(unit) = &plan.install_unit {
+        let content = threshold_apply::unit_file(unit);
+        if let Err(e) = fs::write(UNIT_INSTALL_PATH, content) {
+            // Enabling a unit that could not be written would only fail later
+            errors.push(format!("{UNIT_INSTALL_PATH}: {e}"));
+            return errors;
+        }
+    }
+
+    match &plan.udev_rule {
+        Some(UdevRuleAction::Install { battery_name, stop }) => {
+            let content = threshold_apply::udev_rule(battery_name, *stop);
+            match fs::write(UDEV_RULE_INSTALL_PATH, content) {
+                Ok(()) => reload_udev_rules(),
+                Err(e) => errors.push(format!("{UDEV_RULE_INSTALL_PATH}: {e}")),
+            }
+        }
+        Some(UdevRuleAction::Remove) => match fs::remove_file(UDEV_RULE_INSTALL_PATH) {
+            Ok(()) => reload_udev_rules(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => errors.push(format!("{UDEV_RULE_INSTALL_PATH}: {e}")),
+        },
+        None => {}
+    }
+
+    errors.extend(apply_service_action(plan.service_action));
+    errors
+}
+
+/// Asks udev to re-read its rules (best effort: udev also notices on its own)
+fn reload_udev_rules() {
+    if let Err(e) = Command::new("udevadm")
+        .args(["control", "--reload"])
+        .status()
+    {
+        eprintln!("battery-manager-apply: udevadm control --reload: {e}");
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let plan = match parse_args(&args) {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("battery-manager-apply: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let errors = execute(&plan);
+    if errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        for e in &errors {
+            eprintln!("battery-manager-apply: {e}");
+        }
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_args(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let plan = ApplyPlan {
+            writes: vec![
+                (
+                    PathBuf::from("/sys/class/power_supply/BAT0/charge_control_end_threshold"),
+                    "80".to_string(),
+                ),
+                (
+                    PathBuf::from("/etc/battery-manager/BAT0.conf"),
+                    "START_THRESHOLD=60\nSTOP_THRESHOLD=80\n".to_string(),
+                ),
+            ],
+            install_unit: None,
+            udev_rule: Some(UdevRuleAction::Install {
+                battery_name: "BAT0".to_string(),
+                stop: 80,
+            }),
+            service_action: ServiceAction::Enable,
+        };
+
+        assert_eq!(parse_args(&plan.to_args()), Ok(plan.clone()));
+
+        let removal = ApplyPlan {
+            udev_rule: Some(UdevRuleAction::Remove),
+            ..plan
+        };
+        assert_eq!(parse_args(&removal.to_args()), Ok(removal));
+
+        let start_paths =
+            ["/sys/class/power_supply/BAT1/charge_control_start_threshold".to_string()];
+        let stop_paths = ["/sys/class/power_supply/BAT1/charge_control_end_threshold".to_string()];
+        let install = threshold_apply::build_install_plan(
+            "BAT1",
+            Some(60),
+            80,
+            &threshold_apply::ApplyEnvironment {
+                power_supply_root: Path::new("/sys/class/power_supply"),
+                config_dir: Path::new(CONFIG_DIR),
+                start_paths: &start_paths,
+                stop_paths: &stop_paths,
+                service_unit_exists: false,
+                udev_rule_exists: false,
+            },
+        );
+        assert_eq!(parse_args(&install.to_args()), Ok(install));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_unit_paths() {
+        for path in [
+            "/etc/passwd",
+            "/etc/battery-manager/BAT0.conf",
+            "/sys/class/power_supply/BAT0/charge_control_end_threshold\"; reboot",
+            "/sys/class/power_supply/BAT0/%h/charge_control_end_threshold",
+            "/sys/class/power_supply/BAT0/uevent",
+        ] {
+            let args = to_args(&[
+                "--install-unit",
+                "BAT0",
+                "-",
+                "80",
+                "--unit-stop-path",
+                path,
+                "--service",
+                "enable",
+            ]);
+            assert!(parse_args(&args).is_err(), "accepted {path}");
+        }
+        // Paths only belong to a unit
+        let args = to_args(&[
+            "--unit-stop-path",
+            "/sys/class/power_supply/BAT0/charge_control_end_threshold",
+            "--service",
+            "keep",
+        ]);
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_unit_install() {
+        for unit_args in [
+            ["../BAT0", "60", "80"],
+            ["BAT0;reboot", "60", "80"],
+            ["--service", "60", "80"],
+            ["BAT0", "60", "180"],
+            ["BAT0", "x", "80"],
+        ] {
+            let mut args = to_args(&["--install-unit"]);
+            args.extend(to_args(&unit_args));
+            args.extend(to_args(&["--service", "enable"]));
+            assert!(parse_args(&args).is_err(), "accepted {unit_args:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_udev_rule() {
+        for rule_args in [["BAT0\"", "80"], ["BAT0", "101"], ["BAT0", "80\""]] {
+            let mut args = to_args(&["--udev-rule"]);
+            args.extend(to_args(&rule_args));
+            args.extend(to_args(&["--service", "keep"]));
+            assert!(parse_args(&args).is_err(), "accepted {rule_args:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_disallowed_paths() {
+        for path in [
+            "/etc/passwd",
+            "/sys/class/power_supply/../../../etc/shadow",
+            "sys/class/power_supply/BAT0/alarm",
+            "/sys/bus/pci/drivers/nvme/unbind",
+            // Allowed prefixes, but not threshold files
+            "/sys/devices/pci0000:00/0000:00:1f.0/remove",
+            "/sys/class/power_supply/BAT0/device/driver/unbind",
+            "/sys/devices/system/cpu/cpu1/online",
+            "/etc/battery-manager/../shadow.conf",
+            "/etc/battery-manager/sub/BAT0.conf",
+            "/etc/battery-manager/paths",
+        ] {
+            let args = to_args(&["--write", path, "80", "--service", "keep"]);
+            assert!(parse_args(&args).is_err(), "accepted {path}");
+        }
+    }
+
+    #[test]
+    fn test_parse_accepts_threshold_files() {
+        for path in [
+            "/sys/class/power_supply/BAT0/charge_control_end_threshold",
+            "/sys/class/power_supply/BAT0/charge_behaviour",
+            "/sys/class/power_supply/BAT1/battery_care_limit",
+            "/sys/devices/platform/huawei-wmi/charge_control_thresholds",
+            "/sys/bus/wmi/drivers/hp-bioscfg/battery_optimized_charge_level_max",
+            "/sys/bus/platform/devices/ACPI000D:00/charge_control_start_threshold",
+            "/etc/battery-manager/BAT0.conf",
+        ] {
+            assert!(is_allowed_path(Path::new(path)), "refused {path}");
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_values_and_args() {
+        let args = to_args(&[
+            "--write",
+            "/sys/class/power_supply/BAT0/alarm",
+            "1; rm -rf /",
+            "--service",
+            "keep",
+        ]);
+        assert!(parse_args(&args).is_err());
+
+        assert!(parse_args(&to_args(&["--service", "restart"])).is_err());
+        assert!(parse_args(&to_args(&["--write", "/sys/devices/x"])).is_err());
+        assert!(parse_args(&[]).is_err());
+
+        // charge_behaviour modes contain dashes
+        let args = to_args(&[
+            "--write",
+            "/sys/class/power_supply/BAT0/charge_behaviour",
+            "inhibit-charge",
+            "--service",
+            "keep",
+        ]);
+        assert!(parse_args(&args).is_ok());
+    }
+}
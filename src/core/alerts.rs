@@ -0,0 +1,391 @@
+//! Battery alerts for desktop notifications
+//!
+//! Detects when capacity falls below the low battery threshold, then below
+//! the critical level, while discharging, when charging stops at the stop
+//! threshold and when the charger is plugged or unplugged, with hysteresis so
+//! each event fires once per charge/discharge cycle instead of on every
+//! refresh. Each kind of event can be turned off.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::core::i18n::{duration_text, t};
+use crate::core::BatteryInfo;
+
+/// Default low battery notification threshold in percent
+pub const DEFAULT_LOW_THRESHOLD: u8 = 10;
+
+/// Capacity below which the critical battery notification fires, in percent
+pub const CRITICAL_THRESHOLD: u8 = 5;
+
+/// Charging counts as complete this close below the stop threshold, in percent
+///
+/// Firmwares often stop one or two percent short of the requested value.
+pub const CHARGE_COMPLETE_MARGIN: u8 = 2;
+
+/// Global notification preference (enabled by default)
+static NOTIFICATIONS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Capacity below which the low battery notification fires
+static LOW_THRESHOLD: AtomicU8 = AtomicU8::new(DEFAULT_LOW_THRESHOLD);
+
+/// Per-event preferences, indexed by `AlertKind`
+static EVENTS_ENABLED: [AtomicBool; 4] = [
+    AtomicBool::new(true),
+    AtomicBool::new(true),
+    AtomicBool::new(true),
+    AtomicBool::new(true),
+];
+
+/// Kind of notification, each one can be turned off separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    LowBattery,
+    CriticalBattery,
+    ChargeComplete,
+    PowerSource,
+}
+
+/// Enable or disable one kind of notification
+pub fn set_event_enabled(kind: AlertKind, enabled: bool) {
+    EVENTS_ENABLED[kind as usize].store(enabled, Ordering::Relaxed);
+}
+
+/// Check if a kind of notification is enabled
+pub fn event_enabled(kind: AlertKind) -> bool {
+    EVENTS_ENABLED[kind as usize].load(Ordering::Relaxed)
+}
+
+/// Enable or disable desktop notifications
+pub fn set_notifications_enabled(enabled: bool) {
+    NOTIFICATIONS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if desktop notifications are enabled
+pub fn notifications_enabled() -> bool {
+    NOTIFICATIONS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Sets the low battery notification threshold (clamped to 1–99%)
+pub fn set_low_threshold(percent: u8) {
+    LOW_THRESHOLD.store(percent.clamp(1, 99), Ordering::Relaxed);
+}
+
+/// Returns the low battery notification threshold in percent
+pub fn low_threshold() -> u8 {
+    LOW_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Parses the `notifications.conf` written by older versions
+///
+/// Accepts `enabled=on|off` and `low_threshold=N` lines, as well as the
+/// older single `on`/`off` value.
+///
+/// # Returns
+///
+/// Tuple of (enabled, low threshold), `None` for missing or invalid entries
+pub(crate) fn parse_preferences(text: &str) -> (Option<bool>, Option<u8>) {
+    let mut enabled = None;
+    let mut threshold = None;
+
+    for line in text.lines().map(str::trim) {
+        let (key, value) = line.split_once('=').unwrap_or(("enabled", line));
+        match (key.trim(), value.trim()) {
+            ("enabled", "on") => enabled = Some(true),
+            ("enabled", "off") => enabled = Some(false),
+            ("low_threshold", value) => {
+                threshold = value.parse().ok().filter(|v| (1..=99).contains(v));
+            }
+            _ => {}
+        }
+    }
+
+    (enabled, threshold)
+}
+
+/// Event worth a desktop notification
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatteryEvent {
+    /// Capacity fell below the low battery threshold while discharging
+    LowBattery {
+        capacity: u8,
+        time_remaining_minutes: Option<u32>,
+    },
+    /// Capacity fell below `CRITICAL_THRESHOLD` while discharging
+    CriticalBattery {
+        capacity: u8,
+        time_remaining_minutes: Option<u32>,
+    },
+    /// Charging stopped at (or just below) the stop threshold
+    ChargeComplete { capacity: u8, stop_threshold: u8 },
+    /// The charger was plugged in or unplugged
+    PowerSourceChanged { ac_online: bool, capacity: u8 },
+}
+
+impl BatteryEvent {
+    /// Preference controlling this event
+    pub const fn kind(&self) -> AlertKind {
+        match self {
+            Self::LowBattery { .. } => AlertKind::LowBattery,
+            Self::CriticalBattery { .. } => AlertKind::CriticalBattery,
+            Self::ChargeComplete { .. } => AlertKind::ChargeComplete,
+            Self::PowerSourceChanged { .. } => AlertKind::PowerSource,
+        }
+    }
+}
+
+/// Body of the low and critical battery notifications
+///
+/// # Returns
+///
+/// e.g. "9% · Time left: 25 min", or "9%" without an estimate
+pub fn discharge_body(capacity: u8, time_remaining_minutes: Option<u32>) -> String {
+    time_remaining_minutes.map_or_else(
+        || format!("{capacity}%"),
+        |minutes| {
+            format!(
+                "{capacity}% · {}: {}",
+                t("time_to_empty"),
+                duration_text(minutes)
+            )
+        },
+    )
+}
+
+/// Tracks battery state between refreshes to fire each event once per cycle
+#[derive(Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AlarmMonitor {
+    low_notified: bool,
+    critical_notified: bool,
+    charging_seen: bool,
+    full_notified: bool,
+    /// Charger state at the previous sample, `None` before the first one
+    ac_online: Option<bool>,
+}
+
+impl AlarmMonitor {
+    /// Feeds a new sample and returns the event to notify, if any
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Freshly read battery information
+    /// * `low_threshold` - Capacity in percent below which to warn
+    ///
+    /// # Returns
+    ///
+    /// * `Some(BatteryEvent)` - First crossing in the current cycle
+    /// * `None` - Nothing new to report
+    pub fn update(&mut self, info: &BatteryInfo, low_threshold: u8) -> Option<BatteryEvent> {
+        match info.status.as_str() {
+            "Discharging" => {
+                // New discharge cycle: re-arm the charge-complete event
+                self.charging_seen = false;
+                self.full_notified = false;
+
+                let capacity = info.capacity_percent;
+                let time_remaining_minutes = info.time_remaining_minutes;
+                if !self.critical_notified && capacity < CRITICAL_THRESHOLD {
+                    // A drop straight to critical doesn't also warn low later
+                    self.critical_notified = true;
+                    self.low_notified = true;
+                    return Some(BatteryEvent::CriticalBattery {
+                        capacity,
+                        time_remaining_minutes,
+                    });
+                }
+                if !self.low_notified && capacity < low_threshold {
+                    self.low_notified = true;
+                    return Some(BatteryEvent::LowBattery {
+                        capacity,
+                        time_remaining_minutes,
+                    });
+                }
+                None
+            }
+            status => {
+                // Plugged in: re-arm the low and critical battery events
+                self.low_notified = false;
+                self.critical_notified = false;
+                if status == "Charging" {
+                    self.charging_seen = true;
+                }
+
+                // Only once charging has actually stopped ("Not charging" or "Full")
+                let stop_threshold = info.charge_stop_threshold.unwrap_or(100);
+                if self.charging_seen
+                    && !self.full_notified
+                    && matches!(status, "Not charging" | "Full")
+                    && info.capacity_percent
+                        >= stop_threshold.saturating_sub(CHARGE_COMPLETE_MARGIN)
+                {
+                    self.full_notified = true;
+                    return Some(BatteryEvent::ChargeComplete {
+                        capacity: info.capacity_percent,
+                        stop_threshold,
+                    });
+                }
+                None
+            }
+        }
+    }
+
+    /// Feeds the charger state and returns an event when it changed
+    ///
+    /// The first sample only records the state: starting the app plugged in
+    /// doesn't notify.
+    ///
+    /// # Arguments
+    ///
+    /// * `ac_online` - Whether the charger is plugged in now
+    /// * `capacity` - Current capacity in percent, shown in the notification
+    pub fn update_power_source(&mut self, ac_online: bool, capacity: u8) -> Option<BatteryEvent> {
+        let previous = self.ac_online.replace(ac_online)?;
+        (previous != ac_online).then_some(BatteryEvent::PowerSourceChanged {
+            ac_online,
+            capacity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+
+    fn sample(status: &str, capacity: u8) -> BatteryInfo {
+        BatteryInfo {
+            status: status.to_string(),
+            capacity_percent: capacity,
+            charge_now: 0,
+            current_now: 0,
+            voltage_now: 0,
+            cycle_count: 0,
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_low_battery_fires_once_per_discharge() {
+        let mut monitor = AlarmMonitor::default();
+
+        assert_eq!(monitor.update(&sample("Discharging", 12), 10), None);
+        assert!(matches!(
+            monitor.update(&sample("Discharging", 9), 10),
+            Some(BatteryEvent::LowBattery { capacity: 9, .. })
+        ));
+        assert_eq!(monitor.update(&sample("Discharging", 8), 10), None);
+
+        // Plugging in re-arms the alarm for the next discharge
+        assert_eq!(monitor.update(&sample("Charging", 9), 10), None);
+        assert!(monitor.update(&sample("Discharging", 8), 10).is_some());
+    }
+
+    #[test]
+    fn test_critical_battery_after_low() {
+        let mut monitor = AlarmMonitor::default();
+
+        assert!(matches!(
+            monitor.update(&sample("Discharging", 9), 10),
+            Some(BatteryEvent::LowBattery { capacity: 9, .. })
+        ));
+        assert_eq!(monitor.update(&sample("Discharging", 5), 10), None);
+        assert!(matches!(
+            monitor.update(&sample("Discharging", 4), 10),
+            Some(BatteryEvent::CriticalBattery { capacity: 4, .. })
+        ));
+        assert_eq!(monitor.update(&sample("Discharging", 3), 10), None);
+
+        // Straight to critical: no low warning afterwards
+        let mut monitor = AlarmMonitor::default();
+        assert_eq!(
+            monitor
+                .update(&sample("Discharging", 3), 10)
+                .map(|e| e.kind()),
+            Some(AlertKind::CriticalBattery)
+        );
+        assert_eq!(monitor.update(&sample("Discharging", 2), 10), None);
+    }
+
+    #[test]
+    fn test_discharge_body() {
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        set_language("en");
+        assert_eq!(discharge_body(9, None), "9%");
+        assert_eq!(discharge_body(4, Some(75)), "4% · Time left: 1 hour 15 min");
+        assert_eq!(discharge_body(9, Some(120)), "9% · Time left: 2 hours");
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_parse_preferences() {
+        assert_eq!(
+            parse_preferences("enabled=off\nlow_threshold=15\n"),
+            (Some(false), Some(15))
+        );
+        // Older files only contain on/off
+        assert_eq!(parse_preferences("on\n"), (Some(true), None));
+        assert_eq!(parse_preferences("low_threshold=0\n"), (None, None));
+        assert_eq!(parse_preferences("garbage"), (None, None));
+    }
+
+    #[test]
+    fn test_charge_complete_requires_charging() {
+        let mut monitor = AlarmMonitor::default();
+
+        // Already at the threshold when the app starts: no notification
+        assert_eq!(monitor.update(&sample("Not charging", 80), 10), None);
+
+        assert_eq!(monitor.update(&sample("Charging", 79), 10), None);
+        assert_eq!(
+            monitor.update(&sample("Not charging", 80), 10),
+            Some(BatteryEvent::ChargeComplete {
+                capacity: 80,
+                stop_threshold: 80
+            })
+        );
+        assert_eq!(monitor.update(&sample("Not charging", 80), 10), None);
+    }
+
+    #[test]
+    fn test_charge_complete_margin() {
+        let mut monitor = AlarmMonitor::default();
+
+        // Still charging near the threshold: wait for it to stop
+        assert_eq!(monitor.update(&sample("Charging", 78), 10), None);
+        assert_eq!(monitor.update(&sample("Charging", 79), 10), None);
+        // Firmware stopped one percent short
+        assert_eq!(
+            monitor.update(&sample("Not charging", 79), 10),
+            Some(BatteryEvent::ChargeComplete {
+                capacity: 79,
+                stop_threshold: 80
+            })
+        );
+
+        // Stopped far from the threshold (e.g. charger unplugged then idle)
+        let mut monitor = AlarmMonitor::default();
+        assert_eq!(monitor.update(&sample("Charging", 60), 10), None);
+        assert_eq!(monitor.update(&sample("Not charging", 61), 10), None);
+    }
+
+    #[test]
+    fn test_power_source_changes() {
+        let mut monitor = AlarmMonitor::default();
+
+        // Initial state is not a change
+        assert_eq!(monitor.update_power_source(true, 50), None);
+        assert_eq!(monitor.update_power_source(true, 51), None);
+        assert_eq!(
+            monitor.update_power_source(false, 51),
+            Some(BatteryEvent::PowerSourceChanged {
+                ac_online: false,
+                capacity: 51
+            })
+        );
+        assert_eq!(monitor.update_power_source(false, 50), None);
+        assert_eq!(
+            monitor.update_power_source(true, 50).map(|e| e.kind()),
+            Some(AlertKind::PowerSource)
+        );
+    }
+}
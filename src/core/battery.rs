@@ -4,8 +4,15 @@
 //! by reading from `/sys/class/power_supply/` sysfs interface.
 
 use std::fs;
+use std::time::Duration;
 
-use crate::core::i18n::t;
+use crate::core::i18n::{duration_text, t};
+
+/// Reads attempted by `BatteryInfo::new_with_retry` before giving up
+pub const READ_ATTEMPTS: u32 = 3;
+
+/// Pause after the first failed read, doubled after each further failure
+pub const READ_RETRY_DELAY: Duration = Duration::from_millis(50);
 
 // Note: Markup functions are no longer used directly.
 // Colors are now dynamically managed via crate::ui::theme
@@ -41,6 +48,278 @@ impl From<std::io::Error> for BatteryError {
     }
 }
 
+/// Interface a battery reports its capacity through
+///
+/// The kernel exposes either `charge_*` attributes (µAh) or `energy_*`
+/// attributes (µWh); the `alarm` file uses the same unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapacityUnit {
+    /// `charge_*` attributes, in µAh
+    Charge,
+    /// `energy_*` attributes, in µWh
+    Energy,
+}
+
+impl CapacityUnit {
+    /// Detects the capacity interface of a battery
+    ///
+    /// `charge_full` is preferred when both interfaces are present, as the
+    /// alarm is then derived from the charge interface.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - Battery sysfs directory
+    ///
+    /// # Returns
+    ///
+    /// * `Some((unit, full))` - Unit and full capacity in that unit
+    /// * `None` - Neither `charge_full` nor `energy_full` is readable
+    pub fn detect(base_path: &str) -> Option<(Self, u64)> {
+        [("charge_full", Self::Charge), ("energy_full", Self::Energy)]
+            .into_iter()
+            .find_map(|(attribute, unit)| {
+                let full = fs::read_to_string(format!("{base_path}/{attribute}")).ok()?;
+                Some((unit, full.trim().parse().ok()?))
+            })
+    }
+}
+
+/// Alarm level as written to the `alarm` sysfs file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmValue {
+    /// Raw value, in µAh or µWh
+    pub raw: u64,
+    pub unit: CapacityUnit,
+}
+
+impl std::fmt::Display for AlarmValue {
+    /// Formats the value for display, e.g. "4 700 mAh" or "5.2 Wh"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.unit {
+            CapacityUnit::Charge => {
+                let digits = (self.raw / 1000).to_string();
+                let mut grouped = String::new();
+                for (index, digit) in digits.chars().enumerate() {
+                    if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                        grouped.push(' ');
+                    }
+                    grouped.push(digit);
+                }
+                write!(f, "{grouped} mAh")
+            }
+            CapacityUnit::Energy => {
+                #[allow(clippy::cast_precision_loss)]
+                let watt_hours = self.raw as f64 / 1_000_000.0;
+                write!(f, "{watt_hours:.1} Wh")
+            }
+        }
+    }
+}
+
+/// Computes the raw alarm value for a percentage of the full capacity
+///
+/// # Arguments
+///
+/// * `full_raw` - Full capacity read from `charge_full` or `energy_full`
+/// * `unit` - Unit of `full_raw`, see [`CapacityUnit::detect`]
+/// * `percent` - Alarm level in percent (clamped to 0–100)
+///
+/// # Returns
+///
+/// Alarm value in the same unit as the full capacity
+pub fn compute_alarm_raw(full_raw: u64, unit: CapacityUnit, percent: f32) -> AlarmValue {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    let raw = (full_raw as f64 * f64::from(percent.clamp(0.0, 100.0)) / 100.0).round() as u64;
+    AlarmValue { raw, unit }
+}
+
+/// A setting whose value read back from sysfs differs from the requested one
+///
+/// Some firmwares accept a write but clamp the value (e.g. 83% becomes 80%).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingMismatch {
+    /// Translation key of the setting name (e.g. "`threshold_stop`")
+    pub setting: &'static str,
+    /// Requested value, formatted for display
+    pub requested: String,
+    /// Value reported by the kernel, formatted for display
+    pub actual: String,
+}
+
+/// Alarm difference (in percentage points) below which the alarm is unchanged
+///
+/// The alarm is stored as a raw charge/energy value and the spinner shows one
+/// decimal, so smaller differences are only conversion noise.
+const ALARM_CHANGE_TOLERANCE: f32 = 0.05;
+
+/// Settings entered in the settings tab, about to be applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestedSettings {
+    /// Start threshold, `None` when unsupported
+    pub start: Option<u8>,
+    /// Stop threshold (or the care code on care-only vendors)
+    pub stop: u8,
+    /// Alarm as percentage of full capacity
+    pub alarm_pct: f32,
+    /// Whether the restore service should be enabled
+    pub service_enabled: bool,
+    /// Whether the udev rule should persist the stop threshold
+    pub udev_rule: bool,
+}
+
+/// One setting that an apply would change
+///
+/// `old` is `None` when the current value is unknown (not reported by the
+/// kernel, or service state not queried yet).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingChange {
+    Start { old: Option<u8>, new: u8 },
+    Stop { old: Option<u8>, new: u8 },
+    Alarm { old: Option<f32>, new: f32 },
+    Service { old: Option<bool>, new: bool },
+    UdevRule { old: bool, new: bool },
+}
+
+impl SettingChange {
+    /// Translation key of the setting name
+    pub const fn setting(self) -> &'static str {
+        match self {
+            Self::Start { .. } => "threshold_start",
+            Self::Stop { .. } => "threshold_stop",
+            Self::Alarm { .. } => "alarm",
+            Self::Service { .. } => "service",
+            Self::UdevRule { .. } => "udev_rule",
+        }
+    }
+}
+
+/// Differences between the current settings and the requested ones
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThresholdChangeSet {
+    /// Changed settings, in display order (start, stop, alarm, service, udev rule)
+    pub changes: Vec<SettingChange>,
+}
+
+impl ThresholdChangeSet {
+    /// Lists what applying `requested` would change
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Battery as currently read from sysfs
+    /// * `service_enabled` - Current restore service state, `None` if unknown
+    /// * `udev_rule_installed` - Whether the udev rule is installed
+    /// * `requested` - Values entered by the user
+    ///
+    /// # Returns
+    ///
+    /// The changed settings; unknown current values always count as changed
+    pub fn diff(
+        current: &BatteryInfo,
+        service_enabled: Option<bool>,
+        udev_rule_installed: bool,
+        requested: RequestedSettings,
+    ) -> Self {
+        let mut changes = Vec::new();
+
+        if let Some(new) = requested.start {
+            if current.charge_start_threshold != Some(new) {
+                changes.push(SettingChange::Start {
+                    old: current.charge_start_threshold,
+                    new,
+                });
+            }
+        }
+
+        if current.charge_stop_threshold != Some(requested.stop) {
+            changes.push(SettingChange::Stop {
+                old: current.charge_stop_threshold,
+                new: requested.stop,
+            });
+        }
+
+        let old_alarm = current.alarm_percent();
+        if old_alarm.is_none_or(|old| (old - requested.alarm_pct).abs() > ALARM_CHANGE_TOLERANCE) {
+            changes.push(SettingChange::Alarm {
+                old: old_alarm,
+                new: requested.alarm_pct,
+            });
+        }
+
+        if service_enabled != Some(requested.service_enabled) {
+            changes.push(SettingChange::Service {
+                old: service_enabled,
+                new: requested.service_enabled,
+            });
+        }
+
+        if udev_rule_installed != requested.udev_rule {
+            changes.push(SettingChange::UdevRule {
+                old: udev_rule_installed,
+                new: requested.udev_rule,
+            });
+        }
+
+        Self { changes }
+    }
+
+    /// Whether applying would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Combined figures across several batteries (e.g. BAT0 + BAT1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateInfo {
+    /// Number of batteries included in the weighting
+    pub battery_count: usize,
+    /// Sum of `charge_now` (µAh or µWh)
+    pub charge_now: u64,
+    /// Sum of `charge_full` (µAh or µWh)
+    pub charge_full: u64,
+    /// Capacity percent weighted by each battery's `charge_full`
+    pub capacity_percent: f64,
+    /// Summed power draw in watts
+    pub power_watts: f64,
+}
+
+/// Approximate charge of a `capacity_level` value
+///
+/// # Returns
+///
+/// A rough percentage, `None` for "Unknown" or an unexpected level
+pub fn level_percent(level: &str) -> Option<u8> {
+    match level {
+        "Critical" => Some(5),
+        "Low" => Some(15),
+        "Normal" => Some(50),
+        "High" => Some(80),
+        "Full" => Some(100),
+        _ => None,
+    }
+}
+
+/// Approximates the charge of a battery without a `capacity` file
+///
+/// Prefers the `charge_now`/`charge_full` ratio, then the `capacity_level`
+/// bucket.
+///
+/// # Arguments
+///
+/// * `charge_now` - Current charge (µAh or µWh)
+/// * `charge_full` - Full charge, `None` when the kernel doesn't report it
+/// * `level` - `capacity_level` value
+pub fn estimate_capacity(charge_now: u64, charge_full: Option<u64>, level: &str) -> Option<u8> {
+    charge_full
+        .filter(|&full| full > 0)
+        .and_then(|full| u8::try_from((charge_now.saturating_mul(100) / full).min(100)).ok())
+        .or_else(|| level_percent(level))
+}
+
 /// Detailed battery information
 ///
 /// Contains all battery metrics including status, capacity, health,
@@ -54,6 +333,8 @@ pub struct BatteryInfo {
     pub status: String,
     pub capacity_percent: u8,
     pub capacity_level: String,
+    /// `capacity_percent` approximated (no `capacity` file), shown with a "~"
+    pub capacity_estimated: bool,
     pub charge_now: u64,
     pub charge_full: u64,
     pub charge_full_design: u64,
@@ -66,10 +347,164 @@ pub struct BatteryInfo {
     pub charge_start_threshold: Option<u8>,
     pub charge_stop_threshold: Option<u8>,
     pub alarm: Option<u64>,
-    pub service_active: bool,
+    pub manufacture_date: Option<ManufactureDate>,
+    pub charge_behaviour: Option<ChargeBehaviour>,
+}
+
+/// Charging mode exposed by the `charge_behaviour` attribute
+///
+/// The kernel lists every supported mode and brackets the active one:
+/// `[auto] inhibit-charge force-discharge`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargeBehaviour {
+    /// Active mode (e.g., "auto")
+    pub active: String,
+    /// Supported modes, in kernel order
+    pub available: Vec<String>,
+}
+
+impl ChargeBehaviour {
+    /// Parses the content of `charge_behaviour`
+    ///
+    /// # Returns
+    ///
+    /// `None` if no mode is bracketed
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut active = None;
+        let available = raw
+            .split_whitespace()
+            .map(|mode| {
+                if let Some(mode) = mode.strip_prefix('[').and_then(|m| m.strip_suffix(']')) {
+                    active = Some(mode.to_string());
+                    mode.to_string()
+                } else {
+                    mode.to_string()
+                }
+            })
+            .collect();
+        Some(Self {
+            active: active?,
+            available,
+        })
+    }
+}
+
+/// Battery manufacture date reported by the firmware
+///
+/// Read from the `manufacture_year`, `manufacture_month` and
+/// `manufacture_day` sysfs attributes; month and day are often missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManufactureDate {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+impl ManufactureDate {
+    /// Reads the manufacture date of a battery
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - Battery sysfs directory
+    ///
+    /// # Returns
+    ///
+    /// `None` if the year is missing or implausible
+    fn read(base_path: &str) -> Option<Self> {
+        let read_number = |attribute: &str| -> Option<u16> {
+            BatteryInfo::read_sys_file(&format!("{base_path}/{attribute}"))?
+                .parse()
+                .ok()
+        };
+
+        let year = read_number("manufacture_year").filter(|y| (1990..=2100).contains(y))?;
+        let month = read_number("manufacture_month")
+            .filter(|m| (1..=12).contains(m))
+            .and_then(|m| u8::try_from(m).ok());
+        let day = read_number("manufacture_day")
+            .filter(|d| (1..=31).contains(d))
+            .and_then(|d| u8::try_from(d).ok());
+
+        Some(Self { year, month, day })
+    }
+
+    /// Computes the approximate age of the battery
+    ///
+    /// A missing month counts as January.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_year` - Current year
+    /// * `now_month` - Current month (1-12)
+    ///
+    /// # Returns
+    ///
+    /// Age in whole months, or `None` if the date lies in the future
+    pub fn age_months(self, now_year: u16, now_month: u8) -> Option<u32> {
+        let made = u32::from(self.year) * 12 + u32::from(self.month.unwrap_or(1)) - 1;
+        let now = (u32::from(now_year) * 12 + u32::from(now_month)).checked_sub(1)?;
+        now.checked_sub(made)
+    }
+}
+
+impl std::fmt::Display for ManufactureDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.month, self.day) {
+            (Some(month), Some(day)) => write!(f, "{:04}-{month:02}-{day:02}", self.year),
+            (Some(month), None) => write!(f, "{:04}-{month:02}", self.year),
+            _ => write!(f, "{:04}", self.year),
+        }
+    }
+}
+
+/// Runs `read` again while it fails with an I/O error
+///
+/// Sysfs reads can fail for a moment right after a resume from suspend.
+/// Invalid battery names fail straight away.
+///
+/// # Arguments
+///
+/// * `attempts` - Maximum number of calls to `read` (at least one)
+/// * `delay` - Pause after the first failure, doubled after each further one
+/// * `read` - Read to attempt
+///
+/// # Errors
+///
+/// Returns the last error once all attempts failed
+pub fn with_retry<T>(
+    attempts: u32,
+    delay: Duration,
+    mut read: impl FnMut() -> Result<T, BatteryError>,
+) -> Result<T, BatteryError> {
+    let mut pause = delay;
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(BatteryError::IoError(e)) if attempt < attempts => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [BATTERY] Read failed (attempt {attempt}/{attempts}), retrying: {e}"
+                ));
+                std::thread::sleep(pause);
+                pause *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
 }
 
 impl BatteryInfo {
+    /// Like `new`, retrying transient I/O errors (see `with_retry`)
+    ///
+    /// Used by the refresh timer; one-shot reads keep using `new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once `READ_ATTEMPTS` reads failed
+    pub fn new_with_retry(battery_name: &str) -> Result<Self, BatteryError> {
+        with_retry(READ_ATTEMPTS, READ_RETRY_DELAY, || Self::new(battery_name))
+    }
+
     /// Creates a new `BatteryInfo` instance by reading sysfs files
     ///
     /// # Arguments
@@ -127,25 +562,30 @@ impl BatteryInfo {
         let capacity_level = Self::read_sys_file(&format!("{base_path}/capacity_level"))
             .unwrap_or_else(|| t("unknown"));
 
-        let capacity_percent = Self::read_sys_file(&format!("{base_path}/capacity"))
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
-
-        if crate::core::debug::is_debug_enabled() {
-            crate::core::debug::debug_log_args(std::format_args!(
-                "🔋 [BATTERY] {battery_name}: status={status}, capacity={capacity_percent}%"
-            ));
-        }
+        let capacity = Self::read_sys_file(&format!("{base_path}/capacity"))
+            .and_then(|s| s.parse::<u8>().ok());
 
         let charge_now = Self::read_sys_file(&format!("{base_path}/charge_now"))
             .or_else(|| Self::read_sys_file(&format!("{base_path}/energy_now")))
             .and_then(|s| s.parse().ok())
             .unwrap_or(0);
 
-        let charge_full = Self::read_sys_file(&format!("{base_path}/charge_full"))
+        let charge_full_raw: Option<u64> = Self::read_sys_file(&format!("{base_path}/charge_full"))
             .or_else(|| Self::read_sys_file(&format!("{base_path}/energy_full")))
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(1);
+            .and_then(|s| s.parse().ok());
+        let charge_full = charge_full_raw.unwrap_or(1);
+
+        // Older batteries only report capacity_level
+        let capacity_estimated = capacity.is_none();
+        let capacity_percent = capacity.unwrap_or_else(|| {
+            estimate_capacity(charge_now, charge_full_raw, &capacity_level).unwrap_or(0)
+        });
+
+        if crate::core::debug::is_debug_enabled() {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔋 [BATTERY] {battery_name}: status={status}, capacity={capacity_percent}% (estimated: {capacity_estimated})"
+            ));
+        }
 
         let charge_full_design = Self::read_sys_file(&format!("{base_path}/charge_full_design"))
             .or_else(|| Self::read_sys_file(&format!("{base_path}/energy_full_design")))
@@ -174,18 +614,14 @@ impl BatteryInfo {
 
         let wear_percent = 100.0 - health_percent;
 
-        #[allow(
-            clippy::cast_precision_loss,
-            clippy::cast_possible_truncation,
-            clippy::cast_sign_loss
-        )]
-        let time_remaining_minutes = if current_now > 0 && status == "Discharging" {
-            Some((charge_now as f32 / current_now as f32 * 60.0) as u32)
-        } else if current_now > 0 && status == "Charging" {
-            Some(((charge_full - charge_now) as f32 / current_now as f32 * 60.0) as u32)
-        } else {
-            None
-        };
+        // Instantaneous estimate; the UI and watch mode smooth it across refreshes
+        #[allow(clippy::cast_precision_loss)]
+        let time_remaining_minutes = crate::core::estimator::eta_minutes(
+            charge_now,
+            charge_full,
+            &status,
+            current_now as f64,
+        );
 
         let charge_start_threshold =
             Self::read_sys_file(&format!("{base_path}/charge_start_threshold"))
@@ -203,16 +639,14 @@ impl BatteryInfo {
 
         let alarm = Self::read_sys_file(&format!("{base_path}/alarm")).and_then(|s| s.parse().ok());
 
-        // Vérifier si le service systemd battery-manager est actif
-        let service_active = std::process::Command::new("systemctl")
-            .args(["is-active", "battery-manager.service"])
-            .output()
-            .ok()
-            .is_some_and(|output| output.status.success());
+        let manufacture_date = ManufactureDate::read(&base_path);
+
+        let charge_behaviour = Self::read_sys_file(&format!("{base_path}/charge_behaviour"))
+            .and_then(|raw| ChargeBehaviour::parse(&raw));
 
         if crate::core::debug::is_debug_enabled() {
             crate::core::debug::debug_log_args(std::format_args!(
-                "🎯 [BATTERY] thresholds: start={charge_start_threshold:?} stop={charge_stop_threshold:?} alarm={alarm:?} service_active={service_active}"
+                "🎯 [BATTERY] thresholds: start={charge_start_threshold:?} stop={charge_stop_threshold:?} alarm={alarm:?}"
             ));
         }
 
@@ -224,6 +658,7 @@ impl BatteryInfo {
             status,
             capacity_percent,
             capacity_level,
+            capacity_estimated,
             charge_now,
             charge_full,
             charge_full_design,
@@ -236,7 +671,8 @@ impl BatteryInfo {
             charge_start_threshold,
             charge_stop_threshold,
             alarm,
-            service_active,
+            manufacture_date,
+            charge_behaviour,
         })
     }
 
@@ -283,6 +719,31 @@ impl BatteryInfo {
         batteries
     }
 
+    /// Whether critical fields were left at their defaults
+    ///
+    /// # Returns
+    ///
+    /// `true` if the status is unknown or neither capacity nor charge could be read
+    pub fn is_incomplete(&self) -> bool {
+        self.status == t("unknown") || (self.capacity_percent == 0 && self.charge_now == 0)
+    }
+
+    /// Returns the translated status text, without markup
+    ///
+    /// # Returns
+    ///
+    /// Status text (e.g., "En charge"); "Not charging" at 100% reads as full
+    pub fn status_text(&self) -> String {
+        match self.status.as_str() {
+            "Charging" => t("charging"),
+            "Discharging" => t("discharging"),
+            "Full" => t("full"),
+            "Not charging" if self.capacity_percent >= 100 => t("full"),
+            "Not charging" => t("not_charging"),
+            _ => t("unknown"),
+        }
+    }
+
     /// Returns formatted status text with markup for display
     ///
     /// # Returns
@@ -344,6 +805,54 @@ impl BatteryInfo {
         }
     }
 
+    /// Wear grade derived from the health percentage
+    ///
+    /// # Returns
+    ///
+    /// 'A' (≥90%), 'B' (75–89%), 'C' (60–74%) or 'D' (<60%)
+    pub fn health_grade(&self) -> char {
+        if self.health_percent >= 90.0 {
+            'A'
+        } else if self.health_percent >= 75.0 {
+            'B'
+        } else if self.health_percent >= 60.0 {
+            'C'
+        } else {
+            'D'
+        }
+    }
+
+    /// Returns CSS class for the wear grade color
+    ///
+    /// # Returns
+    ///
+    /// CSS class name ("color-success" A, "color-primary" B, "color-warning" C, "color-danger" D)
+    pub fn health_grade_css_class(&self) -> &str {
+        match self.health_grade() {
+            'A' => "color-success",
+            'B' => "color-primary",
+            'C' => "color-warning",
+            _ => "color-danger",
+        }
+    }
+
+    /// Translation key describing the wear grade (e.g., "`grade_a_description`")
+    pub fn health_grade_description_key(&self) -> String {
+        format!(
+            "grade_{}_description",
+            self.health_grade().to_ascii_lowercase()
+        )
+    }
+
+    /// Capacity shown to the user ("~50" when estimated), without the "%"
+    pub fn capacity_text(&self) -> String {
+        if self.capacity_estimated {
+            format!("~{}", self.capacity_percent)
+        } else {
+            self.capacity_percent.to_string()
+        }
+    }
+
     /// Calculates power consumption in watts
     ///
     /// # Returns
@@ -404,16 +913,15 @@ impl BatteryInfo {
     ///
     /// # Returns
     ///
-    /// * `Some(String)` - Time formatted as "Xh00 until full" or "Xh00 remaining"
+    /// * `Some(String)` - e.g. "⏱ 1 hour 5 min until full" or "⏱ Time left: 2 hours"
     /// * `None` - Time cannot be calculated
     pub fn time_remaining_formatted(&self) -> Option<String> {
         self.time_remaining_minutes.map(|minutes| {
-            let hours = minutes / 60;
-            let mins = minutes % 60;
+            let duration = duration_text(minutes);
             if self.status == "Charging" {
-                format!("⏱ {hours}h{mins:02} {}", t("time_until_full"))
+                format!("⏱ {duration} {}", t("time_until_full"))
             } else {
-                format!("⏱ {hours}h{mins:02} {}", t("time_remaining"))
+                format!("⏱ {}: {duration}", t("time_to_empty"))
             }
         })
     }
@@ -423,38 +931,137 @@ impl BatteryInfo {
     /// # Returns
     ///
     /// * `Some(f32)` - Alarm percentage
-    /// * `None` - No alarm configured
+    /// * `None` - No alarm configured (missing file, or 0 = driver default)
     #[allow(clippy::cast_precision_loss)]
     pub fn alarm_percent(&self) -> Option<f32> {
         self.alarm
+            .filter(|&a| a > 0 && self.charge_full > 0)
             .map(|a| (a as f32 / self.charge_full as f32) * 100.0)
     }
 
-    /// Returns formatted systemd service status with markup
+    /// Compares freshly read values with the requested settings
+    ///
+    /// Settings the battery doesn't expose (value read back as `None`) are
+    /// skipped, since the apply script only writes existing files.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Requested start threshold, if supported
+    /// * `stop` - Requested stop threshold
+    /// * `alarm_pct` - Requested alarm as percentage of full capacity
     ///
     /// # Returns
     ///
-    /// Pango markup string (green "Active" or red "Inactive")
-    pub fn service_status_markup(&self) -> String {
-        if self.service_active {
-            format!(
-                "<span size='xx-large' weight='bold'>{}</span>",
-                t("service_active")
-            )
-        } else {
-            format!(
-                "<span size='xx-large' weight='bold'>{}</span>",
-                t("service_inactive")
-            )
+    /// List of mismatching settings (empty when everything was applied as requested)
+    pub fn compare_with_requested(
+        &self,
+        start: Option<u8>,
+        stop: u8,
+        alarm_pct: Option<f32>,
+    ) -> Vec<SettingMismatch> {
+        let mut mismatches = Vec::new();
+
+        if let (Some(requested), Some(actual)) = (start, self.charge_start_threshold) {
+            if requested != actual {
+                mismatches.push(SettingMismatch {
+                    setting: "threshold_start",
+                    requested: format!("{requested}%"),
+                    actual: format!("{actual}%"),
+                });
+            }
+        }
+
+        if let Some(actual) = self.charge_stop_threshold {
+            if stop != actual {
+                mismatches.push(SettingMismatch {
+                    setting: "threshold_stop",
+                    requested: format!("{stop}%"),
+                    actual: format!("{actual}%"),
+                });
+            }
         }
+
+        // Alarm is stored as a raw charge/energy value: allow rounding noise
+        if let (Some(requested), Some(actual)) = (alarm_pct, self.alarm_percent()) {
+            if (requested - actual).abs() > 1.0 {
+                mismatches.push(SettingMismatch {
+                    setting: "alarm",
+                    requested: format!("{requested:.1}%"),
+                    actual: format!("{actual:.1}%"),
+                });
+            }
+        }
+
+        mismatches
     }
 
-    /// Returns CSS class for service status (active=success, inactive=danger)
-    pub const fn service_status_css_class(&self) -> &str {
-        if self.service_active {
-            "color-success"
+    /// Combines several batteries into one figure
+    ///
+    /// Batteries reporting `charge_full == 0` are left out of the weighting
+    /// (but their power draw is still counted).
+    ///
+    /// # Arguments
+    ///
+    /// * `infos` - Battery information for every detected battery
+    ///
+    /// # Returns
+    ///
+    /// `AggregateInfo` with summed charges, weighted capacity and total power
+    #[allow(clippy::cast_precision_loss)]
+    pub fn aggregate(infos: &[Self]) -> AggregateInfo {
+        let weighted: Vec<&Self> = infos.iter().filter(|i| i.charge_full > 0).collect();
+
+        let charge_now = weighted.iter().map(|i| i.charge_now).sum();
+        let charge_full: u64 = weighted.iter().map(|i| i.charge_full).sum();
+        let capacity_percent = if charge_full == 0 {
+            0.0
         } else {
-            "color-danger"
+            weighted
+                .iter()
+                .map(|i| f64::from(i.capacity_percent) * i.charge_full as f64)
+                .sum::<f64>()
+                / charge_full as f64
+        };
+
+        AggregateInfo {
+            battery_count: weighted.len(),
+            charge_now,
+            charge_full,
+            capacity_percent,
+            power_watts: infos.iter().map(Self::power_watts).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl BatteryInfo {
+    /// Half-charged 5 Ah battery at full health, discharging at 6 W
+    ///
+    /// Tests override the fields they care about with `..BatteryInfo::sample()`.
+    pub fn sample() -> Self {
+        Self {
+            name: "BAT0".to_string(),
+            manufacturer: "Test".to_string(),
+            model_name: "Test".to_string(),
+            technology: "Li-ion".to_string(),
+            status: "Discharging".to_string(),
+            capacity_percent: 50,
+            capacity_level: "Normal".to_string(),
+            capacity_estimated: false,
+            charge_now: 2_500_000,
+            charge_full: 5_000_000,
+            charge_full_design: 5_000_000,
+            current_now: 500_000,
+            voltage_now: 12_000_000,
+            cycle_count: 100,
+            health_percent: 100.0,
+            wear_percent: 0.0,
+            time_remaining_minutes: None,
+            charge_start_threshold: None,
+            charge_stop_threshold: Some(80),
+            alarm: None,
+            manufacture_date: None,
+            charge_behaviour: None,
         }
     }
 }
@@ -463,6 +1070,80 @@ impl BatteryInfo {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_level_percent() {
+        assert_eq!(level_percent("Critical"), Some(5));
+        assert_eq!(level_percent("Low"), Some(15));
+        assert_eq!(level_percent("Normal"), Some(50));
+        assert_eq!(level_percent("High"), Some(80));
+        assert_eq!(level_percent("Full"), Some(100));
+        assert_eq!(level_percent("Unknown"), None);
+    }
+
+    #[test]
+    fn test_estimate_capacity() {
+        // Charge ratio first
+        assert_eq!(
+            estimate_capacity(2_500_000, Some(5_000_000), "Low"),
+            Some(50)
+        );
+        assert_eq!(
+            estimate_capacity(5_200_000, Some(5_000_000), "Full"),
+            Some(100)
+        );
+        // No usable charge_full: capacity_level bucket
+        assert_eq!(estimate_capacity(2_500_000, None, "Low"), Some(15));
+        assert_eq!(estimate_capacity(0, Some(0), "Critical"), Some(5));
+        assert_eq!(estimate_capacity(0, None, "Unknown"), None);
+    }
+
+    #[test]
+    fn test_compute_alarm_raw() {
+        let alarm = compute_alarm_raw(47_000_000, CapacityUnit::Charge, 10.0);
+        assert_eq!(alarm.raw, 4_700_000);
+        assert_eq!(alarm.to_string(), "4 700 mAh");
+
+        let alarm = compute_alarm_raw(52_000_000, CapacityUnit::Energy, 10.0);
+        assert_eq!(alarm.raw, 5_200_000);
+        assert_eq!(alarm.to_string(), "5.2 Wh");
+
+        // Out of range percentages are clamped
+        assert_eq!(
+            compute_alarm_raw(1_000, CapacityUnit::Charge, 150.0).raw,
+            1_000
+        );
+        assert_eq!(compute_alarm_raw(1_000, CapacityUnit::Charge, -5.0).raw, 0);
+        assert_eq!(
+            compute_alarm_raw(999_000, CapacityUnit::Charge, 50.0).to_string(),
+            "499 mAh"
+        );
+    }
+
+    #[test]
+    fn test_capacity_unit_detect() {
+        let dir = std::env::temp_dir().join(format!("battery-manager-unit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let base_path = dir.to_string_lossy().to_string();
+
+        assert_eq!(CapacityUnit::detect(&base_path), None);
+
+        fs::write(dir.join("energy_full"), "52000000\n").unwrap();
+        assert_eq!(
+            CapacityUnit::detect(&base_path),
+            Some((CapacityUnit::Energy, 52_000_000))
+        );
+
+        // The charge interface wins when both are present
+        fs::write(dir.join("charge_full"), "4700000\n").unwrap();
+        assert_eq!(
+            CapacityUnit::detect(&base_path),
+            Some((CapacityUnit::Charge, 4_700_000))
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_battery_name_validation_valid() {
         // Les noms commençant par BAT sont valides (même si le fichier n'existe pas nécessairement)
@@ -479,6 +1160,49 @@ mod tests {
         }
     }
 
+    fn io_error() -> BatteryError {
+        BatteryError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "resuming",
+        ))
+    }
+
+    #[test]
+    fn test_with_retry_recovers() {
+        // Fails twice, then succeeds
+        let mut calls = 0;
+        let result = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            if calls < 3 {
+                Err(io_error())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_with_retry_gives_up() {
+        let mut calls = 0;
+        let result: Result<(), _> = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            Err(io_error())
+        });
+        assert!(matches!(result, Err(BatteryError::IoError(_))));
+        assert_eq!(calls, 3);
+
+        // An invalid name won't get better
+        let mut calls = 0;
+        let result: Result<(), _> = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            Err(BatteryError::InvalidBatteryName("AC0".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
     #[test]
     fn test_battery_name_validation_invalid() {
         // Noms invalides
@@ -500,26 +1224,13 @@ mod tests {
     fn test_status_markup_format() {
         // Verify get_status_markup returns colored markup
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
             status: "Charging".to_string(),
             capacity_percent: 80,
-            capacity_level: "Normal".to_string(),
             charge_now: 4_000_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 1_000_000,
-            voltage_now: 12_000_000,
             cycle_count: 50,
-            health_percent: 100.0,
-            wear_percent: 0.0,
             time_remaining_minutes: Some(120),
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         let markup = info.get_status_markup();
@@ -534,26 +1245,12 @@ mod tests {
     #[test]
     fn test_health_calculation() {
         let mut info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
             model_name: "Test Model".to_string(),
-            technology: "Li-ion".to_string(),
-            status: "Discharging".to_string(),
             capacity_percent: 80,
-            capacity_level: "Normal".to_string(),
             charge_now: 4_000_000,
             charge_full: 4_500_000,
-            charge_full_design: 5_000_000,
-            current_now: 500_000,
-            voltage_now: 12_000_000,
-            cycle_count: 100,
             health_percent: 0.0,
-            wear_percent: 0.0,
-            time_remaining_minutes: None,
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         // Calcul manuel
@@ -569,29 +1266,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_health_grade_boundaries() {
+        let mut info = BatteryInfo {
+            capacity_percent: 80,
+            charge_now: 4_000_000,
+            ..BatteryInfo::sample()
+        };
+        for (health, grade, class) in [
+            (100.0, 'A', "color-success"),
+            (90.0, 'A', "color-success"),
+            (89.9, 'B', "color-primary"),
+            (75.0, 'B', "color-primary"),
+            (74.9, 'C', "color-warning"),
+            (60.0, 'C', "color-warning"),
+            (59.9, 'D', "color-danger"),
+            (0.0, 'D', "color-danger"),
+        ] {
+            info.health_percent = health;
+            assert_eq!(info.health_grade(), grade, "health {health}");
+            assert_eq!(info.health_grade_css_class(), class, "health {health}");
+        }
+        info.health_percent = 80.0;
+        assert_eq!(info.health_grade_description_key(), "grade_b_description");
+    }
+
     #[test]
     fn test_power_watts_calculation() {
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
-            status: "Discharging".to_string(),
             capacity_percent: 80,
-            capacity_level: "Normal".to_string(),
             charge_now: 4_000_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 1_000_000,  // 1A
             voltage_now: 12_000_000, // 12V
             cycle_count: 50,
-            health_percent: 100.0,
-            wear_percent: 0.0,
-            time_remaining_minutes: None,
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         let power = info.power_watts();
@@ -601,26 +1309,14 @@ mod tests {
     #[test]
     fn test_voltage_conversion() {
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
             status: "Full".to_string(),
             capacity_percent: 100,
-            capacity_level: "Normal".to_string(),
             charge_now: 5_000_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 0,
             voltage_now: 12_600_000, // 12.6V
             cycle_count: 10,
-            health_percent: 100.0,
-            wear_percent: 0.0,
-            time_remaining_minutes: None,
             charge_start_threshold: Some(60),
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: true,
+            ..BatteryInfo::sample()
         };
 
         #[allow(clippy::float_cmp)]
@@ -632,26 +1328,12 @@ mod tests {
     #[test]
     fn test_current_conversion() {
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
             status: "Charging".to_string(),
-            capacity_percent: 50,
-            capacity_level: "Normal".to_string(),
-            charge_now: 2_500_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 2_500_000, // 2.5A = 2500mA
-            voltage_now: 12_000_000,
             cycle_count: 25,
-            health_percent: 100.0,
-            wear_percent: 0.0,
             time_remaining_minutes: Some(60),
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
             alarm: Some(500_000),
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         assert_eq!(info.current_ma(), 2500);
@@ -660,26 +1342,17 @@ mod tests {
     #[test]
     fn test_charge_conversions() {
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
-            status: "Discharging".to_string(),
             capacity_percent: 75,
-            capacity_level: "Normal".to_string(),
             charge_now: 3_750_000,         // 3750 mAh
             charge_full: 5_000_000,        // 5000 mAh
             charge_full_design: 5_500_000, // 5500 mAh
-            current_now: 500_000,
             voltage_now: 11_800_000,
             cycle_count: 150,
             health_percent: 90.9,
             wear_percent: 9.1,
             time_remaining_minutes: Some(450),
             charge_start_threshold: Some(40),
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: true,
+            ..BatteryInfo::sample()
         };
 
         assert_eq!(info.charge_now_mah(), 3750);
@@ -690,26 +1363,13 @@ mod tests {
     #[test]
     fn test_status_markup() {
         let mut info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
             status: "Charging".to_string(),
             capacity_percent: 60,
-            capacity_level: "Normal".to_string(),
             charge_now: 3_000_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 1_000_000,
-            voltage_now: 12_000_000,
             cycle_count: 50,
-            health_percent: 100.0,
-            wear_percent: 0.0,
             time_remaining_minutes: Some(120),
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         assert!(info.get_status_markup().contains('⚡'));
@@ -733,62 +1393,283 @@ mod tests {
     #[test]
     fn test_alarm_percent() {
         let info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
-            status: "Discharging".to_string(),
-            capacity_percent: 50,
-            capacity_level: "Normal".to_string(),
-            charge_now: 2_500_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
-            current_now: 500_000,
             voltage_now: 11_500_000,
-            cycle_count: 100,
-            health_percent: 100.0,
-            wear_percent: 0.0,
             time_remaining_minutes: Some(300),
-            charge_start_threshold: None,
-            charge_stop_threshold: Some(80),
             alarm: Some(500_000), // 500000 µAh = 10% de 5000000
-            service_active: false,
+            ..BatteryInfo::sample()
         };
 
         let alarm_pct = info.alarm_percent().unwrap();
         assert!((alarm_pct - 10.0).abs() < 0.1);
+
+        // 0 = alarm cleared (driver default)
+        let cleared = BatteryInfo {
+            alarm: Some(0),
+            ..info
+        };
+        assert_eq!(cleared.alarm_percent(), None);
+    }
+
+    fn aggregate_sample(
+        name: &str,
+        capacity: u8,
+        charge_now: u64,
+        charge_full: u64,
+    ) -> BatteryInfo {
+        BatteryInfo {
+            name: name.to_string(),
+            capacity_percent: capacity,
+            charge_now,
+            charge_full,
+            charge_full_design: charge_full,
+            cycle_count: 10,
+            charge_stop_threshold: None,
+            ..BatteryInfo::sample()
+        }
     }
 
     #[test]
-    fn test_service_status_markup() {
-        let mut info = BatteryInfo {
-            name: "BAT0".to_string(),
-            manufacturer: "Test".to_string(),
-            model_name: "Test".to_string(),
-            technology: "Li-ion".to_string(),
+    fn test_aggregate_unequal_batteries() {
+        // 2 Ah internal battery at 100%, 6 Ah external battery at 20%
+        let infos = [
+            aggregate_sample("BAT0", 100, 2_000_000, 2_000_000),
+            aggregate_sample("BAT1", 20, 1_200_000, 6_000_000),
+        ];
+
+        let aggregate = BatteryInfo::aggregate(&infos);
+        assert_eq!(aggregate.battery_count, 2);
+        assert_eq!(aggregate.charge_now, 3_200_000);
+        assert_eq!(aggregate.charge_full, 8_000_000);
+        assert!((aggregate.capacity_percent - 40.0).abs() < 0.01);
+        assert!((aggregate.power_watts - 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_aggregate_skips_zero_charge_full() {
+        let infos = [
+            aggregate_sample("BAT0", 80, 4_000_000, 5_000_000),
+            aggregate_sample("BAT1", 0, 0, 0),
+        ];
+
+        let aggregate = BatteryInfo::aggregate(&infos);
+        assert_eq!(aggregate.battery_count, 1);
+        assert!((aggregate.capacity_percent - 80.0).abs() < 0.01);
+
+        let empty = BatteryInfo::aggregate(&[aggregate_sample("BAT0", 0, 0, 0)]);
+        assert_eq!(empty.battery_count, 0);
+        assert!(empty.capacity_percent.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_compare_with_requested() {
+        let info = BatteryInfo {
+            voltage_now: 11_500_000,
+            time_remaining_minutes: Some(300),
+            charge_start_threshold: Some(60),
+            alarm: Some(500_000), // 10%
+            ..BatteryInfo::sample()
+        };
+
+        // Everything applied as requested (alarm within rounding tolerance)
+        assert!(info
+            .compare_with_requested(Some(60), 80, Some(10.4))
+            .is_empty());
+
+        // Firmware clamped 83% to 80%
+        let mismatches = info.compare_with_requested(Some(60), 83, Some(10.0));
+        assert_eq!(
+            mismatches,
+            vec![SettingMismatch {
+                setting: "threshold_stop",
+                requested: "83%".to_string(),
+                actual: "80%".to_string(),
+            }]
+        );
+
+        let mismatches = info.compare_with_requested(Some(55), 80, Some(20.0));
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].setting, "threshold_start");
+        assert_eq!(mismatches[1].setting, "alarm");
+    }
+
+    #[test]
+    fn test_compare_with_requested_skips_unsupported() {
+        let info = BatteryInfo {
             status: "Full".to_string(),
             capacity_percent: 100,
             capacity_level: "Full".to_string(),
             charge_now: 5_000_000,
-            charge_full: 5_000_000,
-            charge_full_design: 5_000_000,
             current_now: 0,
             voltage_now: 12_600_000,
             cycle_count: 5,
-            health_percent: 100.0,
-            wear_percent: 0.0,
-            time_remaining_minutes: None,
+            charge_stop_threshold: None,
+            ..BatteryInfo::sample()
+        };
+
+        assert!(info
+            .compare_with_requested(Some(40), 80, Some(10.0))
+            .is_empty());
+    }
+
+    fn applied_info() -> BatteryInfo {
+        BatteryInfo {
+            voltage_now: 11_500_000,
+            time_remaining_minutes: Some(300),
             charge_start_threshold: Some(60),
-            charge_stop_threshold: Some(80),
-            alarm: None,
-            service_active: true,
+            alarm: Some(500_000), // 10%
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_change_set_diff() {
+        let info = applied_info();
+        let requested = RequestedSettings {
+            start: Some(60),
+            stop: 80,
+            alarm_pct: 10.0,
+            service_enabled: true,
+            udev_rule: false,
         };
 
-        assert!(info.service_status_markup().contains(&t("service_active")));
+        // Same values: nothing to apply
+        assert!(ThresholdChangeSet::diff(&info, Some(true), false, requested).is_empty());
+
+        let changes = ThresholdChangeSet::diff(
+            &info,
+            Some(false),
+            false,
+            RequestedSettings {
+                stop: 90,
+                alarm_pct: 5.0,
+                ..requested
+            },
+        );
+        assert_eq!(
+            changes.changes,
+            vec![
+                SettingChange::Stop {
+                    old: Some(80),
+                    new: 90
+                },
+                SettingChange::Alarm {
+                    old: Some(10.0),
+                    new: 5.0
+                },
+                SettingChange::Service {
+                    old: Some(false),
+                    new: true
+                },
+            ]
+        );
+        assert_eq!(changes.changes[0].setting(), "threshold_stop");
+    }
 
-        info.service_active = false;
-        assert!(info
-            .service_status_markup()
-            .contains(&t("service_inactive")));
+    #[test]
+    fn test_change_set_diff_unknown_values() {
+        let mut info = applied_info();
+        info.charge_start_threshold = None;
+        info.alarm = Some(0);
+
+        let changes = ThresholdChangeSet::diff(
+            &info,
+            None,
+            false,
+            RequestedSettings {
+                start: Some(60),
+                stop: 80,
+                alarm_pct: 10.0,
+                service_enabled: false,
+                udev_rule: false,
+            },
+        );
+        assert_eq!(
+            changes.changes,
+            vec![
+                SettingChange::Start { old: None, new: 60 },
+                SettingChange::Alarm {
+                    old: None,
+                    new: 10.0
+                },
+                SettingChange::Service {
+                    old: None,
+                    new: false
+                },
+            ]
+        );
+
+        // Unsupported start threshold is never compared
+        let changes = ThresholdChangeSet::diff(
+            &applied_info(),
+            Some(false),
+            false,
+            RequestedSettings {
+                start: None,
+                stop: 80,
+                alarm_pct: 10.04,
+                service_enabled: false,
+                udev_rule: false,
+            },
+        );
+        assert!(changes.is_empty());
+
+        // Switching from the service to the udev rule alone is a change
+        let changes = ThresholdChangeSet::diff(
+            &applied_info(),
+            Some(false),
+            false,
+            RequestedSettings {
+                start: Some(60),
+                stop: 80,
+                alarm_pct: 10.0,
+                service_enabled: false,
+                udev_rule: true,
+            },
+        );
+        assert_eq!(
+            changes.changes,
+            vec![SettingChange::UdevRule {
+                old: false,
+                new: true
+            }]
+        );
+    }
+
+    #[test]
+    fn test_charge_behaviour_parse() {
+        let behaviour = ChargeBehaviour::parse("[auto] inhibit-charge force-discharge\n").unwrap();
+        assert_eq!(behaviour.active, "auto");
+        assert_eq!(
+            behaviour.available,
+            ["auto", "inhibit-charge", "force-discharge"]
+        );
+
+        let inhibited = ChargeBehaviour::parse("auto [inhibit-charge]").unwrap();
+        assert_eq!(inhibited.active, "inhibit-charge");
+        assert_eq!(inhibited.available, ["auto", "inhibit-charge"]);
+
+        assert_eq!(ChargeBehaviour::parse("auto inhibit-charge"), None);
+        assert_eq!(ChargeBehaviour::parse(""), None);
+    }
+
+    #[test]
+    fn test_manufacture_date_age() {
+        let date = ManufactureDate {
+            year: 2021,
+            month: Some(3),
+            day: Some(15),
+        };
+        assert_eq!(date.to_string(), "2021-03-15");
+        assert_eq!(date.age_months(2021, 3), Some(0));
+        assert_eq!(date.age_months(2024, 9), Some(42));
+        assert_eq!(date.age_months(2021, 2), None);
+
+        let year_only = ManufactureDate {
+            year: 2020,
+            month: None,
+            day: None,
+        };
+        assert_eq!(year_only.to_string(), "2020");
+        assert_eq!(year_only.age_months(2021, 1), Some(12));
     }
 }
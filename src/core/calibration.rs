@@ -0,0 +1,577 @@
+//! Guided calibration cycle
+//!
+//! Battery gauges drift over time; a full discharge followed by a full charge
+//! lets the firmware relearn the capacity. The calibration runs as a small
+//! state machine fed by the refresh timer:
+//!
+//! 1. `Discharging` - `charge_behaviour` is set to `force-discharge` when the
+//!    kernel offers it (otherwise the user unplugs the charger) until the
+//!    capacity reaches `DISCHARGE_TARGET`
+//! 2. `Charging` - the stop threshold is raised to 100% and charging resumes
+//! 3. `Done` - the thresholds and mode saved at the start are written back
+//!
+//! A phase is only saved once its writes succeeded; a failed or cancelled
+//! write is kept as pending and tried again at the next sample, so the cycle
+//! can't get stuck with the stop threshold left at 100%.
+//!
+//! The thresholds to restore are kept in memory and in
+//! `~/.config/battery-manager/calibration.conf`, so a crash or a reboot in
+//! the middle of the cycle doesn't lose them.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use crate::core::resume::SavedThresholds;
+use crate::core::threshold_apply::{self, ApplyEnvironment, ApplyPlan, ServiceAction};
+use crate::core::BatteryInfo;
+
+/// Capacity at which the discharge phase ends, in percent
+pub const DISCHARGE_TARGET: u8 = 5;
+
+/// `charge_behaviour` mode draining the battery on AC
+const FORCE_DISCHARGE: &str = "force-discharge";
+
+/// Step of the calibration cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationPhase {
+    /// Started, waiting for the first sample
+    Idle,
+    /// Draining down to `DISCHARGE_TARGET`
+    Discharging,
+    /// Charging up to 100%
+    Charging,
+    /// Full: the saved thresholds go back
+    Done,
+}
+
+impl CalibrationPhase {
+    /// Name used in the state file
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Discharging => "discharging",
+            Self::Charging => "charging",
+            Self::Done => "done",
+        }
+    }
+
+    /// Parses a name written by `as_str`
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::Idle, Self::Discharging, Self::Charging, Self::Done]
+            .into_iter()
+            .find(|phase| phase.as_str() == name)
+    }
+
+    /// Translation key describing the phase in the wizard
+    pub const fn message_key(self) -> &'static str {
+        match self {
+            Self::Idle => "calibration_idle",
+            Self::Discharging => "calibration_discharging",
+            Self::Charging => "calibration_charging",
+            Self::Done => "calibration_done",
+        }
+    }
+}
+
+/// Calibration in progress for one battery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    /// Battery name (e.g., "BAT0")
+    pub battery_name: String,
+    pub phase: CalibrationPhase,
+    /// Thresholds active when the calibration started
+    pub saved: SavedThresholds,
+    /// `charge_behaviour` mode active when the calibration started
+    pub saved_behaviour: Option<String>,
+    /// Whether the kernel can drain the battery on AC (`force-discharge`)
+    pub force_discharge: bool,
+    /// The writes of `phase` failed and must be tried again
+    pub pending_write: bool,
+}
+
+impl Calibration {
+    /// Prepares a calibration from the current battery state
+    ///
+    /// A battery without a stop threshold is restored to 100%.
+    pub fn new(info: &BatteryInfo) -> Self {
+        Self {
+            battery_name: info.name.clone(),
+            phase: CalibrationPhase::Idle,
+            saved: SavedThresholds {
+                start: info.charge_start_threshold,
+                stop: info.charge_stop_threshold.unwrap_or(100),
+            },
+            saved_behaviour: info
+                .charge_behaviour
+                .as_ref()
+                .map(|behaviour| behaviour.active.clone()),
+            force_discharge: info
+                .charge_behaviour
+                .as_ref()
+                .is_some_and(|behaviour| behaviour.available.iter().any(|m| m == FORCE_DISCHARGE)),
+            pending_write: false,
+        }
+    }
+
+    /// Phase a new sample of the calibrated battery leads to
+    ///
+    /// The calibration itself is left unchanged: the phase is only recorded
+    /// by [`Calibration::after_write`], once its writes went through.
+    ///
+    /// # Returns
+    ///
+    /// The phase to enter, `None` when the phase doesn't change
+    pub fn next_phase(&self, info: &BatteryInfo) -> Option<CalibrationPhase> {
+        let capacity = info.capacity_percent;
+        let next = match self.phase {
+            CalibrationPhase::Idle if capacity <= DISCHARGE_TARGET => CalibrationPhase::Charging,
+            CalibrationPhase::Idle => CalibrationPhase::Discharging,
+            CalibrationPhase::Discharging if capacity <= DISCHARGE_TARGET => {
+                CalibrationPhase::Charging
+            }
+            CalibrationPhase::Charging if capacity >= 100 || info.status == "Full" => {
+                CalibrationPhase::Done
+            }
+            phase => phase,
+        };
+        if next == self.phase {
+            return None;
+        }
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🎯 [CALIBRATION] {}: {:?} -> {next:?} at {capacity}%",
+            self.battery_name,
+            self.phase
+        ));
+        Some(next)
+    }
+
+    /// Phase whose writes must run for this sample
+    ///
+    /// # Returns
+    ///
+    /// The pending phase again after a failed write, else the next phase
+    pub fn step(&self, info: &BatteryInfo) -> Option<CalibrationPhase> {
+        if self.pending_write {
+            Some(self.phase)
+        } else {
+            self.next_phase(info)
+        }
+    }
+
+    /// State after the writes of `phase` ran
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - Phase whose writes ran
+    /// * `succeeded` - Whether they all went through
+    ///
+    /// # Returns
+    ///
+    /// `None` once the saved thresholds are back (calibration over)
+    pub fn after_write(&self, phase: CalibrationPhase, succeeded: bool) -> Option<Self> {
+        if succeeded && phase == CalibrationPhase::Done {
+            return None;
+        }
+        Some(Self {
+            phase,
+            pending_write: !succeeded,
+            ..self.clone()
+        })
+    }
+
+    /// Writes needed when entering `phase`
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - Phase just entered
+    /// * `env` - Detected threshold files
+    ///
+    /// # Returns
+    ///
+    /// A plan with no writes when there is nothing to change
+    pub fn plan_for(&self, phase: CalibrationPhase, env: &ApplyEnvironment<'_>) -> ApplyPlan {
+        let behaviour = |mode: &str| {
+            threshold_apply::build_charge_behaviour_plan(
+                &self.battery_name,
+                mode,
+                env.power_supply_root,
+            )
+        };
+        let plans = match phase {
+            CalibrationPhase::Idle => Vec::new(),
+            CalibrationPhase::Discharging => behaviour(FORCE_DISCHARGE)
+                .filter(|_| self.force_discharge)
+                .into_iter()
+                .collect(),
+            CalibrationPhase::Charging => {
+                let mut plans = vec![threshold_apply::build_full_charge_plan(
+                    self.saved.start,
+                    env,
+                )];
+                plans.extend(behaviour("auto").filter(|_| self.force_discharge));
+                plans
+            }
+            CalibrationPhase::Done => self.restore_plans(env),
+        };
+        merge_plans(plans)
+    }
+
+    /// Writes putting back the thresholds and mode saved at the start
+    fn restore_plans(&self, env: &ApplyEnvironment<'_>) -> Vec<ApplyPlan> {
+        let mut plans = vec![threshold_apply::build_reapply_plan(
+            self.saved.start,
+            self.saved.stop,
+            env,
+        )];
+        plans.extend(self.saved_behaviour.as_deref().and_then(|mode| {
+            threshold_apply::build_charge_behaviour_plan(
+                &self.battery_name,
+                mode,
+                env.power_supply_root,
+            )
+        }));
+        plans
+    }
+
+    /// Parses the state file (`key=value` lines)
+    ///
+    /// # Returns
+    ///
+    /// `None` without a battery, a known phase and a stop threshold
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut battery_name = None;
+        let mut phase = None;
+        let mut saved_behaviour = None;
+        let mut force_discharge = false;
+        let mut pending_write = false;
+        for line in text.lines() {
+            match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("battery", value)) => battery_name = Some(value.to_string()),
+                Some(("phase", value)) => phase = CalibrationPhase::parse(value),
+                Some(("behaviour", value)) => saved_behaviour = Some(value.to_string()),
+                Some(("force_discharge", value)) => force_discharge = value == "true",
+                Some(("pending_write", value)) => pending_write = value == "true",
+                _ => {}
+            }
+        }
+        Some(Self {
+            battery_name: battery_name?,
+            phase: phase?,
+            saved: SavedThresholds::parse(text)?,
+            saved_behaviour,
+            force_discharge,
+            pending_write,
+        })
+    }
+
+    /// Serializes the state file
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "battery={}\nphase={}\n",
+            self.battery_name,
+            self.phase.as_str()
+        );
+        if let Some(start) = self.saved.start {
+            let _ = writeln!(text, "START_THRESHOLD={start}");
+        }
+        let _ = writeln!(text, "STOP_THRESHOLD={}", self.saved.stop);
+        if let Some(mode) = &self.saved_behaviour {
+            let _ = writeln!(text, "behaviour={mode}");
+        }
+        let _ = writeln!(text, "force_discharge={}", self.force_discharge);
+        let _ = writeln!(text, "pending_write={}", self.pending_write);
+        text
+    }
+
+    /// Location of the state file
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("battery-manager").join("calibration.conf"))
+    }
+}
+
+/// Concatenates the writes of several plans, touching no service
+fn merge_plans(plans: Vec<ApplyPlan>) -> ApplyPlan {
+    ApplyPlan {
+        writes: plans.into_iter().flat_map(|plan| plan.writes).collect(),
+        install_unit: None,
+        udev_rule: None,
+        service_action: ServiceAction::Keep,
+    }
+}
+
+/// Calibration in progress, loaded from the state file on first use
+static ACTIVE: LazyLock<Mutex<Option<Calibration>>> =
+    LazyLock::new(|| Mutex::new(Calibration::default_path().and_then(|path| load(&path))));
+
+/// Reads the state file left by a previous run
+fn load(path: &Path) -> Option<Calibration> {
+    let calibration = Calibration::parse(&std::fs::read_to_string(path).ok()?)?;
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🎯 [CALIBRATION] Resuming {} in phase {:?}",
+        calibration.battery_name,
+        calibration.phase
+    ));
+    Some(calibration)
+}
+
+/// Writes (or removes, for `None`) the state file
+fn persist(calibration: Option<&Calibration>) {
+    let Some(path) = Calibration::default_path() else {
+        return;
+    };
+    let result = match calibration {
+        Some(calibration) => path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, calibration.to_text())),
+        None if path.exists() => std::fs::remove_file(&path),
+        None => Ok(()),
+    };
+    if let Err(e) = result {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [CALIBRATION] Cannot update {}: {e}",
+            path.display()
+        ));
+    }
+}
+
+/// Runs `f` on the calibration in progress, persisting any change
+fn with_active<R>(f: impl FnOnce(&mut Option<Calibration>) -> R) -> R {
+    let mut active = ACTIVE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let before = active.clone();
+    let result = f(&mut active);
+    if *active != before {
+        persist(active.as_ref());
+    }
+    result
+}
+
+/// Returns the calibration in progress, if any
+pub fn active() -> Option<Calibration> {
+    with_active(|active| active.clone())
+}
+
+/// Starts (`Some`) or ends (`None`) a calibration
+pub fn set_active(calibration: Option<Calibration>) {
+    with_active(|active| *active = calibration);
+}
+
+/// Feeds a refresh sample to the calibration in progress
+///
+/// Nothing is saved: call [`record_write`] once the writes of the returned
+/// phase ran.
+///
+/// # Returns
+///
+/// The calibration and the phase whose writes must run, `None` when there is
+/// nothing to do or another battery is calibrated
+pub fn step_active(info: &BatteryInfo) -> Option<(Calibration, CalibrationPhase)> {
+    let calibration = active().filter(|calibration| calibration.battery_name == info.name)?;
+    let phase = calibration.step(info)?;
+    Some((calibration, phase))
+}
+
+/// Saves the outcome of the writes of `phase`
+///
+/// Ignored when the calibration was stopped in the meantime.
+pub fn record_write(battery_name: &str, phase: CalibrationPhase, succeeded: bool) {
+    with_active(|active| {
+        if let Some(calibration) = active
+            .as_ref()
+            .filter(|calibration| calibration.battery_name == battery_name)
+        {
+            *active = calibration.after_write(phase, succeeded);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::battery::ChargeBehaviour;
+
+    fn sample(status: &str, capacity: u8) -> BatteryInfo {
+        BatteryInfo {
+            status: status.to_string(),
+            capacity_percent: capacity,
+            charge_start_threshold: Some(60),
+            charge_behaviour: Some(ChargeBehaviour {
+                active: "auto".to_string(),
+                available: vec![
+                    "auto".to_string(),
+                    "inhibit-charge".to_string(),
+                    "force-discharge".to_string(),
+                ],
+            }),
+            ..BatteryInfo::sample()
+        }
+    }
+
+    /// Steps through the phases as if every write succeeded
+    fn advance(calibration: &mut Calibration, info: &BatteryInfo) -> Option<CalibrationPhase> {
+        let phase = calibration.next_phase(info)?;
+        calibration.phase = phase;
+        Some(phase)
+    }
+
+    #[test]
+    fn test_calibration_cycle() {
+        let mut calibration = Calibration::new(&sample("Not charging", 80));
+        assert_eq!(calibration.phase, CalibrationPhase::Idle);
+        assert!(calibration.force_discharge);
+
+        assert_eq!(
+            advance(&mut calibration, &sample("Discharging", 80)),
+            Some(CalibrationPhase::Discharging)
+        );
+        assert_eq!(advance(&mut calibration, &sample("Discharging", 30)), None);
+        assert_eq!(advance(&mut calibration, &sample("Discharging", 6)), None);
+        assert_eq!(
+            advance(&mut calibration, &sample("Discharging", 5)),
+            Some(CalibrationPhase::Charging)
+        );
+        assert_eq!(advance(&mut calibration, &sample("Charging", 60)), None);
+        assert_eq!(
+            advance(&mut calibration, &sample("Charging", 100)),
+            Some(CalibrationPhase::Done)
+        );
+        assert_eq!(advance(&mut calibration, &sample("Full", 100)), None);
+    }
+
+    #[test]
+    fn test_calibration_shortcuts() {
+        // Already empty: straight to charging
+        let mut calibration = Calibration::new(&sample("Discharging", 4));
+        assert_eq!(
+            advance(&mut calibration, &sample("Discharging", 4)),
+            Some(CalibrationPhase::Charging)
+        );
+        // Some firmwares report "Full" below 100%
+        assert_eq!(
+            advance(&mut calibration, &sample("Full", 98)),
+            Some(CalibrationPhase::Done)
+        );
+    }
+
+    #[test]
+    fn test_calibration_failed_write_is_retried() {
+        let calibration = Calibration::new(&sample("Discharging", 4));
+        let empty = sample("Discharging", 4);
+        assert_eq!(calibration.step(&empty), Some(CalibrationPhase::Charging));
+
+        // A failed write keeps the phase pending, whatever the next sample
+        let failed = calibration
+            .after_write(CalibrationPhase::Charging, false)
+            .unwrap();
+        assert_eq!(failed.phase, CalibrationPhase::Charging);
+        assert!(failed.pending_write);
+        assert_eq!(
+            failed.step(&sample("Charging", 10)),
+            Some(CalibrationPhase::Charging)
+        );
+        assert_eq!(Calibration::parse(&failed.to_text()), Some(failed.clone()));
+
+        let charging = failed
+            .after_write(CalibrationPhase::Charging, true)
+            .unwrap();
+        assert!(!charging.pending_write);
+        assert_eq!(charging.step(&sample("Charging", 10)), None);
+
+        // Restoring the thresholds is retried until it succeeds
+        let full = sample("Full", 100);
+        assert_eq!(charging.step(&full), Some(CalibrationPhase::Done));
+        let restoring = charging.after_write(CalibrationPhase::Done, false).unwrap();
+        assert_eq!(restoring.step(&full), Some(CalibrationPhase::Done));
+        assert_eq!(restoring.after_write(CalibrationPhase::Done, true), None);
+    }
+
+    #[test]
+    fn test_calibration_state_round_trip() {
+        let mut calibration = Calibration::new(&sample("Discharging", 50));
+        calibration.phase = CalibrationPhase::Charging;
+        assert_eq!(
+            Calibration::parse(&calibration.to_text()),
+            Some(calibration)
+        );
+
+        let mut without_start = Calibration::new(&sample("Discharging", 50));
+        without_start.saved.start = None;
+        without_start.saved_behaviour = None;
+        assert_eq!(
+            Calibration::parse(&without_start.to_text()),
+            Some(without_start)
+        );
+
+        assert_eq!(Calibration::parse("battery=BAT0\nphase=charging\n"), None);
+        assert_eq!(
+            Calibration::parse("phase=unknown\nSTOP_THRESHOLD=80\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_calibration_plans() {
+        let dir = std::env::temp_dir().join(format!(
+            "battery-manager-calibration-{}",
+            std::process::id()
+        ));
+        let battery_dir = dir.join("BAT0");
+        std::fs::create_dir_all(&battery_dir).unwrap();
+        std::fs::write(
+            battery_dir.join("charge_behaviour"),
+            "[auto] force-discharge\n",
+        )
+        .unwrap();
+        let stop_paths = vec![battery_dir
+            .join("charge_control_end_threshold")
+            .display()
+            .to_string()];
+        let start_paths = vec![battery_dir
+            .join("charge_control_start_threshold")
+            .display()
+            .to_string()];
+        let env = ApplyEnvironment {
+            power_supply_root: &dir,
+            config_dir: &dir,
+            start_paths: &start_paths,
+            stop_paths: &stop_paths,
+            service_unit_exists: false,
+            udev_rule_exists: false,
+        };
+        let calibration = Calibration::new(&sample("Discharging", 50));
+        let values = |phase| -> Vec<String> {
+            calibration
+                .plan_for(phase, &env)
+                .writes
+                .into_iter()
+                .map(|(path, value)| {
+                    format!("{}={value}", path.file_name().unwrap().to_string_lossy())
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            values(CalibrationPhase::Discharging),
+            vec!["charge_behaviour=force-discharge"]
+        );
+        assert_eq!(
+            values(CalibrationPhase::Charging),
+            vec!["charge_control_end_threshold=100", "charge_behaviour=auto"]
+        );
+        let restore = values(CalibrationPhase::Done);
+        assert!(restore.contains(&"charge_control_start_threshold=60".to_string()));
+        assert!(restore.contains(&"charge_control_end_threshold=80".to_string()));
+        assert_eq!(restore.last().unwrap(), "charge_behaviour=auto");
+        assert_eq!(
+            calibration
+                .plan_for(CalibrationPhase::Done, &env)
+                .service_action,
+            ServiceAction::Keep
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,982 @@
+//! General application settings
+//!
+//! Every user preference is persisted in a single
+//! `~/.config/battery-manager/config.toml`:
+//!
+//! ```text
+//! language = "fr"
+//! theme = "dark"
+//! refresh_interval_secs = 30
+//! minimize_to_tray = false
+//! notifications_enabled = true
+//! notification_threshold = 10
+//! notify_low_battery = true
+//! notify_critical_battery = true
+//! notify_charge_complete = true
+//! notify_power_source = true
+//! reapply_on_resume = false
+//! accent_color = "#3584e4"
+//! ```
+//!
+//! Keys this version doesn't know (and any table) are written back unchanged,
+//! so a newer version's settings survive a downgrade.
+//!
+//! The older `language.conf`, `theme.conf`, `notifications.conf`,
+//! `behavior.conf` and `accent.conf` files are imported once: their values
+//! are written to `config.toml` and the files are removed.
+//!
+//! The main window size, maximized state and selected tab are kept apart in
+//! `window.conf` (see [`WindowState`]), rewritten each time the window closes.
+//! The older `geometry.conf` and `tab.conf` files are moved into it the same
+//! way.
+//!
+//! Power supply uevents already trigger immediate refreshes, so the timer
+//! only catches slow value drift (capacity, power draw).
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::core::alerts::{self, AlertKind, DEFAULT_LOW_THRESHOLD};
+use crate::core::i18n::normalize_language;
+
+/// Default interval between automatic refreshes, in seconds
+pub const DEFAULT_REFRESH_SECS: u64 = 30;
+
+/// Longest interval used when uevents are unavailable, so plugging AC
+/// still shows up quickly
+pub const POLL_ONLY_MAX_SECS: u64 = 5;
+
+/// Bounds of the interval offered in the UI tab
+pub const MIN_REFRESH_SECS: u64 = 1;
+pub const MAX_REFRESH_SECS: u64 = 60;
+
+/// Interval between automatic refreshes, read by the timer at each tick
+pub static AUTO_REFRESH_SECS: AtomicU64 = AtomicU64::new(DEFAULT_REFRESH_SECS);
+
+/// Re-apply the saved thresholds after a resume from suspend (opt-in)
+static REAPPLY_ON_RESUME: AtomicBool = AtomicBool::new(false);
+
+/// File name of the persisted settings
+const CONFIG_FILE: &str = "config.toml";
+
+/// Settings files written by older versions, imported once
+const LEGACY_LANGUAGE_FILE: &str = "language.conf";
+const LEGACY_THEME_FILE: &str = "theme.conf";
+const LEGACY_NOTIFICATIONS_FILE: &str = "notifications.conf";
+const LEGACY_BEHAVIOR_FILE: &str = "behavior.conf";
+const LEGACY_ACCENT_FILE: &str = "accent.conf";
+const LEGACY_FILES: [&str; 5] = [
+    LEGACY_LANGUAGE_FILE,
+    LEGACY_THEME_FILE,
+    LEGACY_NOTIFICATIONS_FILE,
+    LEGACY_BEHAVIOR_FILE,
+    LEGACY_ACCENT_FILE,
+];
+
+/// File name of the persisted window state
+const WINDOW_FILE: &str = "window.conf";
+
+/// Window state files written by older versions, imported once
+const LEGACY_GEOMETRY_FILE: &str = "geometry.conf";
+const LEGACY_TAB_FILE: &str = "tab.conf";
+
+/// Smallest restored window size, so a corrupt file cannot hide the window
+const MIN_WINDOW_WIDTH: i32 = 400;
+const MIN_WINDOW_HEIGHT: i32 = 200;
+
+/// Settings as last loaded or saved, updated by [`update`]
+static CURRENT: RwLock<Option<Config>> = RwLock::new(None);
+
+/// Errors that can occur when loading or saving `config.toml`
+#[derive(Debug)]
+pub enum ConfigError {
+    /// No user configuration directory (`$XDG_CONFIG_HOME`, `~/.config`)
+    NoConfigDir,
+    /// I/O error when reading or writing the file
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoConfigDir => write!(f, "No user configuration directory"),
+            Self::IoError(e) => write!(f, "I/O Error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+/// User preferences stored in `config.toml`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    /// Language code (one of `LANGUAGES`), empty to follow the system locale
+    pub language: String,
+    /// "light" or "dark"
+    pub theme: String,
+    /// Interval between automatic refreshes (1–60 s)
+    pub refresh_interval_secs: u64,
+    /// Hide the window to the tray instead of quitting
+    pub minimize_to_tray: bool,
+    /// Desktop notifications
+    pub notifications_enabled: bool,
+    /// Capacity below which the low battery notification fires (1–99%)
+    pub notification_threshold: u8,
+    /// Per-event notifications (low, critical, charge complete, charger)
+    pub notify_low_battery: bool,
+    pub notify_critical_battery: bool,
+    pub notify_charge_complete: bool,
+    pub notify_power_source: bool,
+    /// Re-apply the saved thresholds after a resume from suspend
+    pub reapply_on_resume: bool,
+    /// `#RRGGBB` accent color, empty for the theme defaults
+    pub accent_color: String,
+    /// Lines not understood by this version (unknown keys, tables), saved as is
+    pub extra: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            language: String::new(),
+            theme: "light".to_string(),
+            refresh_interval_secs: DEFAULT_REFRESH_SECS,
+            minimize_to_tray: false,
+            notifications_enabled: true,
+            notification_threshold: DEFAULT_LOW_THRESHOLD,
+            notify_low_battery: true,
+            notify_critical_battery: true,
+            notify_charge_complete: true,
+            notify_power_source: true,
+            reapply_on_resume: false,
+            accent_color: String::new(),
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Overrides the settings present in a `config.toml` text
+    ///
+    /// Only understands top-level `key = value` lines; unknown keys and
+    /// everything from the first table on are kept in `extra`, comments are
+    /// dropped, and invalid values keep the current setting.
+    fn merged(self, text: &str) -> Self {
+        let mut config = self;
+        let mut lines = text.lines();
+
+        while let Some(raw) = lines.next() {
+            let line = raw.trim();
+            if line.starts_with('[') {
+                config.extra.push(line.to_string());
+                config.extra.extend(lines.by_ref().map(str::to_string));
+                break;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            let text = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            match key.trim() {
+                "language" if text.is_empty() => config.language.clear(),
+                "language" => {
+                    if let Some(lang) = normalize_language(text) {
+                        config.language = lang.to_string();
+                    }
+                }
+                "theme" if text == "light" || text == "dark" => config.theme = text.to_string(),
+                // `auto_refresh_secs` was written before the other settings moved here
+                "refresh_interval_secs" | "auto_refresh_secs" => {
+                    if let Some(secs) = value
+                        .parse()
+                        .ok()
+                        .filter(|v| (MIN_REFRESH_SECS..=MAX_REFRESH_SECS).contains(v))
+                    {
+                        config.refresh_interval_secs = secs;
+                    }
+                }
+                "minimize_to_tray" => {
+                    config.minimize_to_tray = value.parse().unwrap_or(config.minimize_to_tray);
+                }
+                "notifications_enabled" => {
+                    config.notifications_enabled =
+                        value.parse().unwrap_or(config.notifications_enabled);
+                }
+                "notification_threshold" => {
+                    if let Some(percent) = value.parse().ok().filter(|v| (1..=99).contains(v)) {
+                        config.notification_threshold = percent;
+                    }
+                }
+                "notify_low_battery" => {
+                    config.notify_low_battery = value.parse().unwrap_or(config.notify_low_battery);
+                }
+                "notify_critical_battery" => {
+                    config.notify_critical_battery =
+                        value.parse().unwrap_or(config.notify_critical_battery);
+                }
+                "notify_charge_complete" => {
+                    config.notify_charge_complete =
+                        value.parse().unwrap_or(config.notify_charge_complete);
+                }
+                "notify_power_source" => {
+                    config.notify_power_source =
+                        value.parse().unwrap_or(config.notify_power_source);
+                }
+                "reapply_on_resume" => {
+                    config.reapply_on_resume = value.parse().unwrap_or(config.reapply_on_resume);
+                }
+                "accent_color" => config.accent_color = text.to_string(),
+                // Invalid theme
+                "theme" => {}
+                _ => config.extra.push(line.to_string()),
+            }
+        }
+
+        config
+    }
+
+    /// Serializes the settings as `config.toml`
+    pub fn to_toml(&self) -> String {
+        let extra: String = self
+            .extra
+            .iter()
+            .flat_map(|line| [line.as_str(), "\n"])
+            .collect();
+        format!(
+            "language = \"{}\"\n\
+             theme = \"{}\"\n\
+             refresh_interval_secs = {}\n\
+             minimize_to_tray = {}\n\
+             notifications_enabled = {}\n\
+             notification_threshold = {}\n\
+             notify_low_battery = {}\n\
+             notify_critical_battery = {}\n\
+             notify_charge_complete = {}\n\
+             notify_power_source = {}\n\
+             reapply_on_resume = {}\n\
+             accent_color = \"{}\"\n\
+             {extra}",
+            self.language,
+            self.theme,
+            self.refresh_interval_secs,
+            self.minimize_to_tray,
+            self.notifications_enabled,
+            self.notification_threshold,
+            self.notify_low_battery,
+            self.notify_critical_battery,
+            self.notify_charge_complete,
+            self.notify_power_source,
+            self.reapply_on_resume,
+            self.accent_color
+        )
+    }
+
+    /// Loads the settings from `~/.config/battery-manager`, migrating the
+    /// older per-setting files
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::NoConfigDir` without a configuration directory,
+    /// or `ConfigError::IoError` if `config.toml` exists but cannot be read
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::migrate(&app_config_dir().ok_or(ConfigError::NoConfigDir)?)
+    }
+
+    /// Loads the settings from a directory and moves the older per-setting
+    /// files into `config.toml`
+    ///
+    /// The older files are only removed once `config.toml` is written; a
+    /// failed write keeps them for the next start.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::IoError` if `config.toml` exists but cannot be read
+    pub fn migrate(dir: &Path) -> Result<Self, ConfigError> {
+        let config = Self::load_from(dir)?;
+        let legacy: Vec<PathBuf> = LEGACY_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        if legacy.is_empty() {
+            return Ok(config);
+        }
+
+        match config.save_to(dir) {
+            Ok(()) => {
+                for path in &legacy {
+                    let _ = std::fs::remove_file(path);
+                }
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "📦 [CONFIG] Moved {} legacy file(s) into {CONFIG_FILE}",
+                    legacy.len()
+                ));
+            }
+            Err(e) => crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [CONFIG] Cannot write {CONFIG_FILE}, keeping the legacy files: {e}"
+            )),
+        }
+        Ok(config)
+    }
+
+    /// Loads the settings from a directory
+    ///
+    /// The older per-setting files provide the settings `config.toml` lacks
+    /// (it used to hold only the refresh interval).
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory holding `config.toml`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::IoError` if `config.toml` exists but cannot be read
+    pub fn load_from(dir: &Path) -> Result<Self, ConfigError> {
+        let legacy = Self::import_legacy(dir);
+        match std::fs::read_to_string(dir.join(CONFIG_FILE)) {
+            Ok(text) => Ok(legacy.merged(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(legacy),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Builds the settings from the files written by older versions
+    fn import_legacy(dir: &Path) -> Self {
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        let mut config = Self::default();
+
+        if let Some(lang) = read(LEGACY_LANGUAGE_FILE)
+            .as_deref()
+            .and_then(|text| normalize_language(text.trim()))
+        {
+            config.language = lang.to_string();
+        }
+        if let Some(theme) = read(LEGACY_THEME_FILE)
+            .map(|text| text.trim().to_string())
+            .filter(|theme| theme == "light" || theme == "dark")
+        {
+            config.theme = theme;
+        }
+        if let Some(text) = read(LEGACY_NOTIFICATIONS_FILE) {
+            let (enabled, threshold) = alerts::parse_preferences(&text);
+            config.notifications_enabled = enabled.unwrap_or(config.notifications_enabled);
+            config.notification_threshold = threshold.unwrap_or(config.notification_threshold);
+        }
+        if let Some(text) = read(LEGACY_BEHAVIOR_FILE) {
+            for line in text.lines() {
+                match line.trim() {
+                    "minimize_to_tray=on" => config.minimize_to_tray = true,
+                    "minimize_to_tray=off" => config.minimize_to_tray = false,
+                    _ => {}
+                }
+            }
+        }
+        if let Some(color) = read(LEGACY_ACCENT_FILE) {
+            color.trim().clone_into(&mut config.accent_color);
+        }
+
+        config
+    }
+
+    /// Writes the settings to `~/.config/battery-manager/config.toml`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::NoConfigDir` without a configuration directory,
+    /// or `ConfigError::IoError` if the file cannot be written
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&app_config_dir().ok_or(ConfigError::NoConfigDir)?)
+    }
+
+    /// Writes the settings to `config.toml` in a directory (created if needed)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::IoError` if the file cannot be written
+    pub fn save_to(&self, dir: &Path) -> Result<(), ConfigError> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(CONFIG_FILE), self.to_toml())?;
+        Ok(())
+    }
+}
+
+/// Main window state stored in `window.conf`
+///
+/// Saved when the window is closed and restored at the next start:
+///
+/// ```text
+/// width=1024
+/// height=600
+/// maximized=off
+/// tab=2
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowState {
+    /// Unmaximized size, `None` until saved once (the window then opens at
+    /// 800×400)
+    pub size: Option<(i32, i32)>,
+    pub maximized: bool,
+    /// Selected notebook page
+    pub tab: u32,
+}
+
+impl WindowState {
+    /// Parses `key=value` lines
+    ///
+    /// Unknown keys and invalid values are ignored; the size is kept only
+    /// when both dimensions are valid, and raised to a visible minimum.
+    fn parse(text: &str) -> Self {
+        let mut state = Self::default();
+        let (mut width, mut height) = (None, None);
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "width" => width = value.parse::<i32>().ok().filter(|&w| w > 0),
+                "height" => height = value.parse::<i32>().ok().filter(|&h| h > 0),
+                "maximized" => state.maximized = value == "on",
+                "tab" => state.tab = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        state.size = width
+            .zip(height)
+            .map(|(w, h)| (w.max(MIN_WINDOW_WIDTH), h.max(MIN_WINDOW_HEIGHT)));
+        state
+    }
+
+    /// Serializes the state as `key=value` lines
+    fn to_conf(self) -> String {
+        let size = self.size.map_or_else(String::new, |(width, height)| {
+            format!("width={width}\nheight={height}\n")
+        });
+        format!(
+            "{size}maximized={}\ntab={}\n",
+            if self.maximized { "on" } else { "off" },
+            self.tab
+        )
+    }
+
+    /// Returns the page to select among the pages of this session
+    ///
+    /// A page that no longer exists (e.g. the peripherals tab is absent
+    /// this boot) falls back to the first one.
+    pub const fn tab_within(self, n_pages: u32) -> u32 {
+        if self.tab < n_pages {
+            self.tab
+        } else {
+            0
+        }
+    }
+
+    /// Loads the window state from `~/.config/battery-manager/window.conf`
+    ///
+    /// # Returns
+    ///
+    /// The default state (no size, first tab) when nothing was saved
+    pub fn load() -> Self {
+        app_config_dir().map_or_else(Self::default, |dir| Self::migrate(&dir))
+    }
+
+    /// Loads the window state from a directory and moves the `geometry.conf`
+    /// and `tab.conf` files of older versions into `window.conf`
+    ///
+    /// The older files are only removed once `window.conf` is written; a
+    /// failed write keeps them for the next start.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory holding `window.conf`
+    pub fn migrate(dir: &Path) -> Self {
+        let state = Self::load_from(dir);
+        let legacy: Vec<PathBuf> = [LEGACY_GEOMETRY_FILE, LEGACY_TAB_FILE]
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        if legacy.is_empty() {
+            return state;
+        }
+
+        // An existing window.conf already took precedence over these files
+        let written = if dir.join(WINDOW_FILE).is_file() {
+            Ok(())
+        } else {
+            state.save_to(dir)
+        };
+        match written {
+            Ok(()) => {
+                for path in &legacy {
+                    let _ = std::fs::remove_file(path);
+                }
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "📦 [CONFIG] Moved {} legacy file(s) into {WINDOW_FILE}",
+                    legacy.len()
+                ));
+            }
+            Err(e) => crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [CONFIG] Cannot write {WINDOW_FILE}, keeping the legacy files: {e}"
+            )),
+        }
+        state
+    }
+
+    /// Loads the window state from a directory
+    ///
+    /// Without `window.conf`, the `geometry.conf` and `tab.conf` files of
+    /// older versions are imported.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory holding `window.conf`
+    pub fn load_from(dir: &Path) -> Self {
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        if let Some(text) = read(WINDOW_FILE) {
+            return Self::parse(&text);
+        }
+
+        let mut state = read(LEGACY_GEOMETRY_FILE)
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default();
+        if let Some(tab) = read(LEGACY_TAB_FILE).and_then(|text| text.trim().parse().ok()) {
+            state.tab = tab;
+        }
+        state
+    }
+
+    /// Writes the window state to `~/.config/battery-manager/window.conf`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::NoConfigDir` without a configuration directory,
+    /// or `ConfigError::IoError` if the file cannot be written
+    pub fn save(self) -> Result<(), ConfigError> {
+        self.save_to(&app_config_dir().ok_or(ConfigError::NoConfigDir)?)
+    }
+
+    /// Writes the window state to `window.conf` in a directory (created if needed)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::IoError` if the file cannot be written
+    pub fn save_to(self, dir: &Path) -> Result<(), ConfigError> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(WINDOW_FILE), self.to_conf())?;
+        Ok(())
+    }
+}
+
+/// `~/.config/battery-manager`
+fn app_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("battery-manager"))
+}
+
+/// Sets the auto-refresh interval (clamped to 1–60 s)
+pub fn set_auto_refresh_secs(secs: u64) {
+    AUTO_REFRESH_SECS.store(
+        secs.clamp(MIN_REFRESH_SECS, MAX_REFRESH_SECS),
+        Ordering::Relaxed,
+    );
+}
+
+/// Returns the auto-refresh interval in seconds
+pub fn auto_refresh_secs() -> u64 {
+    AUTO_REFRESH_SECS.load(Ordering::Relaxed)
+}
+
+/// Enables or disables the threshold re-apply after resume
+pub fn set_reapply_on_resume(enabled: bool) {
+    REAPPLY_ON_RESUME.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the saved thresholds are re-applied after resume
+pub fn reapply_on_resume() -> bool {
+    REAPPLY_ON_RESUME.load(Ordering::Relaxed)
+}
+
+/// Returns the timer interval to use
+///
+/// # Arguments
+///
+/// * `event_driven` - Whether power supply uevents trigger refreshes
+pub fn timer_interval(event_driven: bool) -> u64 {
+    interval_for(auto_refresh_secs(), event_driven)
+}
+
+fn interval_for(configured: u64, event_driven: bool) -> u64 {
+    if event_driven {
+        configured
+    } else {
+        configured.min(POLL_ONLY_MAX_SECS)
+    }
+}
+
+/// Loads the saved settings and applies the core ones
+///
+/// Missing or unreadable settings fall back to the defaults. The UI settings
+/// (language, theme, tray) are applied by the caller.
+///
+/// # Returns
+///
+/// The loaded settings
+///
+/// # Panics
+///
+/// Panics if the settings `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn load_config() -> Config {
+    let config = Config::load().unwrap_or_else(|e| {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [CONFIG] Cannot load {CONFIG_FILE}, using defaults: {e}"
+        ));
+        Config::default()
+    });
+
+    set_auto_refresh_secs(config.refresh_interval_secs);
+    set_reapply_on_resume(config.reapply_on_resume);
+    alerts::set_notifications_enabled(config.notifications_enabled);
+    alerts::set_low_threshold(config.notification_threshold);
+    alerts::set_event_enabled(AlertKind::LowBattery, config.notify_low_battery);
+    alerts::set_event_enabled(AlertKind::CriticalBattery, config.notify_critical_battery);
+    alerts::set_event_enabled(AlertKind::ChargeComplete, config.notify_charge_complete);
+    alerts::set_event_enabled(AlertKind::PowerSource, config.notify_power_source);
+
+    *CURRENT.write().expect("Config RwLock poisoned") = Some(config.clone());
+    config
+}
+
+/// Changes the settings and persists them
+///
+/// # Arguments
+///
+/// * `change` - Edits the current settings
+///
+/// # Panics
+///
+/// Panics if the settings `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn update(change: impl FnOnce(&mut Config)) {
+    let mut current = CURRENT.write().expect("Config RwLock poisoned");
+    let config = current.get_or_insert_with(Config::default);
+    change(config);
+
+    match config.save() {
+        Ok(()) => crate::core::debug::debug_log_args(std::format_args!(
+            "💾 [CONFIG] Saved {CONFIG_FILE} -> {config:?}"
+        )),
+        Err(e) => crate::core::debug::terminal_error_args(std::format_args!(
+            "❌ [CONFIG] Cannot save {CONFIG_FILE}: {e}"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Config {
+        Config::default().merged(text)
+    }
+
+    #[test]
+    fn test_interval_without_uevents_is_capped() {
+        assert_eq!(interval_for(DEFAULT_REFRESH_SECS, true), 30);
+        assert_eq!(
+            interval_for(DEFAULT_REFRESH_SECS, false),
+            POLL_ONLY_MAX_SECS
+        );
+        assert_eq!(interval_for(2, false), 2);
+        assert_eq!(interval_for(2, true), 2);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse("language = \"en\"\ntheme = \"dark\"\nrefresh_interval_secs = 12\n");
+        assert_eq!(config.language, "en");
+        assert_eq!(config.theme, "dark");
+        assert_eq!(config.refresh_interval_secs, 12);
+        assert_eq!(
+            parse("# comment\nauto_refresh_secs=20\n[future]\nother = 1\n").refresh_interval_secs,
+            20
+        );
+        assert_eq!(parse(""), Config::default());
+        assert_eq!(
+            parse("refresh_interval_secs = 0\n").refresh_interval_secs,
+            DEFAULT_REFRESH_SECS
+        );
+        assert_eq!(
+            parse("refresh_interval_secs = 61\n").refresh_interval_secs,
+            DEFAULT_REFRESH_SECS
+        );
+        assert_eq!(parse("language = \"xx\"\n").language, "");
+        assert_eq!(parse("theme = \"blue\"\n").theme, "light");
+        assert_eq!(
+            parse("notification_threshold = 100\n").notification_threshold,
+            DEFAULT_LOW_THRESHOLD
+        );
+        assert!(parse("reapply_on_resume = true\n").reapply_on_resume);
+        assert!(!parse("reapply_on_resume = yes\n").reapply_on_resume);
+    }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "battery-manager-config-{tag}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let dir = temp_dir("round-trip");
+        let config = Config {
+            language: "pl".to_string(),
+            theme: "dark".to_string(),
+            refresh_interval_secs: 5,
+            minimize_to_tray: true,
+            notifications_enabled: false,
+            notification_threshold: 15,
+            notify_low_battery: true,
+            notify_critical_battery: false,
+            notify_charge_complete: true,
+            notify_power_source: false,
+            reapply_on_resume: true,
+            accent_color: "#3584e4".to_string(),
+            extra: vec!["future_key = 1".to_string()],
+        };
+
+        config.save_to(&dir).unwrap();
+        assert_eq!(Config::load_from(&dir).unwrap(), config);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_imports_legacy_files() {
+        let dir = temp_dir("legacy");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("language.conf"), "en\n").unwrap();
+        std::fs::write(dir.join("theme.conf"), "dark").unwrap();
+        std::fs::write(
+            dir.join("notifications.conf"),
+            "enabled=off\nlow_threshold=20\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("behavior.conf"), "minimize_to_tray=on\n").unwrap();
+        std::fs::write(dir.join("accent.conf"), "#3584e4\n").unwrap();
+
+        let config = Config::load_from(&dir).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                language: "en".to_string(),
+                theme: "dark".to_string(),
+                refresh_interval_secs: DEFAULT_REFRESH_SECS,
+                minimize_to_tray: true,
+                notifications_enabled: false,
+                notification_threshold: 20,
+                notify_low_battery: true,
+                notify_critical_battery: true,
+                notify_charge_complete: true,
+                notify_power_source: true,
+                reapply_on_resume: false,
+                accent_color: "#3584e4".to_string(),
+                extra: Vec::new(),
+            }
+        );
+
+        // A config.toml from before the move only overrides the refresh interval
+        std::fs::write(dir.join("config.toml"), "auto_refresh_secs = 12\n").unwrap();
+        let config = Config::load_from(&dir).unwrap();
+        assert_eq!(config.refresh_interval_secs, 12);
+        assert_eq!(config.language, "en");
+
+        // Once saved in full, the older files are ignored
+        Config::default().save_to(&dir).unwrap();
+        assert_eq!(Config::load_from(&dir).unwrap(), Config::default());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_migrate_moves_legacy_files() {
+        let dir = temp_dir("migrate");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("language.conf"), "pt\n").unwrap();
+        std::fs::write(dir.join("theme.conf"), "dark").unwrap();
+        std::fs::write(dir.join("geometry.conf"), "width=900\nheight=500\n").unwrap();
+        std::fs::write(dir.join("tab.conf"), "2").unwrap();
+
+        let config = Config::migrate(&dir).unwrap();
+        assert_eq!(config.language, "pt");
+        assert_eq!(config.theme, "dark");
+        assert!(!dir.join("language.conf").exists());
+        assert!(!dir.join("theme.conf").exists());
+
+        // The values now come from config.toml alone
+        assert_eq!(Config::load_from(&dir).unwrap(), config);
+        assert_eq!(Config::migrate(&dir).unwrap(), config);
+
+        let state = WindowState::migrate(&dir);
+        assert_eq!(state.size, Some((900, 500)));
+        assert_eq!(state.tab, 2);
+        assert!(dir.join("window.conf").is_file());
+        assert!(!dir.join("geometry.conf").exists());
+        assert!(!dir.join("tab.conf").exists());
+        assert_eq!(WindowState::migrate(&dir), state);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config = parse("theme = \"dark\"\nnotifications_enabled = false\n");
+        assert_eq!(
+            config,
+            Config {
+                theme: "dark".to_string(),
+                notifications_enabled: false,
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_preserved_on_save() {
+        let dir = temp_dir("unknown");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "# written by a newer version\n\
+             theme = \"dark\"\n\
+             graph_style = \"bars\"\n\
+             theme = \"blue\"\n\
+             [profiles.travel]\n\
+             stop = 100\n",
+        )
+        .unwrap();
+
+        let mut config = Config::load_from(&dir).unwrap();
+        assert_eq!(config.theme, "dark");
+        assert_eq!(
+            config.extra,
+            ["graph_style = \"bars\"", "[profiles.travel]", "stop = 100"]
+        );
+
+        config.language = "en".to_string();
+        config.save_to(&dir).unwrap();
+        let text = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+        assert!(text.contains("graph_style = \"bars\"\n"));
+        assert!(text.ends_with("[profiles.travel]\nstop = 100\n"));
+        assert_eq!(Config::load_from(&dir).unwrap(), config);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_window_state_round_trip() {
+        let dir = temp_dir("window");
+        let state = WindowState {
+            size: Some((1024, 600)),
+            maximized: true,
+            tab: 2,
+        };
+        assert_eq!(
+            state.to_conf(),
+            "width=1024\nheight=600\nmaximized=on\ntab=2\n"
+        );
+
+        state.save_to(&dir).unwrap();
+        assert_eq!(WindowState::load_from(&dir), state);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_window_state_malformed() {
+        // Unknown keys are ignored; tiny sizes are raised to the minimum
+        assert_eq!(
+            WindowState::parse("future=1\nheight=50\nwidth=90\ntab=1\n"),
+            WindowState {
+                size: Some((MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT)),
+                maximized: false,
+                tab: 1,
+            }
+        );
+
+        assert_eq!(WindowState::parse(""), WindowState::default());
+        assert_eq!(WindowState::parse("width=800\n").size, None);
+        assert_eq!(WindowState::parse("width=abc\nheight=400\n").size, None);
+        assert_eq!(WindowState::parse("width=-800\nheight=400\n").size, None);
+        assert_eq!(
+            WindowState::parse("garbage\ntab=-1\nmaximized=on\n"),
+            WindowState {
+                size: None,
+                maximized: true,
+                tab: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_window_tab_fallback() {
+        let state = WindowState {
+            tab: 3,
+            ..WindowState::default()
+        };
+        assert_eq!(state.tab_within(4), 3);
+        // Saved on the last tab while the peripherals tab existed
+        assert_eq!(state.tab_within(3), 0);
+        assert_eq!(state.tab_within(0), 0);
+    }
+
+    #[test]
+    fn test_window_state_imports_legacy_files() {
+        let dir = temp_dir("window-legacy");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("geometry.conf"),
+            "width=900\nheight=500\nx=10\ny=20\nmaximized=off\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("tab.conf"), "1").unwrap();
+
+        assert_eq!(
+            WindowState::load_from(&dir),
+            WindowState {
+                size: Some((900, 500)),
+                maximized: false,
+                tab: 1,
+            }
+        );
+
+        // window.conf wins once written
+        WindowState::default().save_to(&dir).unwrap();
+        assert_eq!(WindowState::load_from(&dir), WindowState::default());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_without_files_gives_defaults() {
+        let dir = temp_dir("empty");
+        assert_eq!(Config::load_from(&dir).unwrap(), Config::default());
+    }
+}
@@ -0,0 +1,227 @@
+//! CSV export of battery data
+//!
+//! One row per history sample, followed by the current snapshot, under a
+//! fixed header. History samples only fill the columns they record; the
+//! snapshot fills them all. Values are raw sysfs units (µAh, µA, µV) plus
+//! the derived volts and watts, always with a `.` decimal separator
+//! whatever the UI language, and text fields are quoted per RFC 4180.
+
+use std::borrow::Cow;
+
+use crate::core::traits::HistorySample;
+use crate::core::BatteryInfo;
+
+/// Header row, in column order
+pub const COLUMNS: [&str; 19] = [
+    "timestamp",
+    "battery",
+    "status",
+    "capacity_percent",
+    "health_percent",
+    "wear_percent",
+    "charge_now",
+    "charge_full",
+    "charge_full_design",
+    "current_now",
+    "voltage_now",
+    "voltage_v",
+    "power_watts",
+    "cycle_count",
+    "time_remaining_minutes",
+    "charge_start_threshold",
+    "charge_stop_threshold",
+    "alarm",
+    "source",
+];
+
+/// Quotes a field containing a separator, a quote or a line break
+pub fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Formats a float with two decimals (empty for NaN and infinities)
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        format!("{value:.2}")
+    } else {
+        String::new()
+    }
+}
+
+/// Formats an optional value (empty when absent)
+fn optional<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Joins fields into one CSV line, quoting them as needed
+fn line(fields: &[String]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| escape(field))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// Columns recorded by a history sample, with their values
+fn sample_fields(battery: &str, sample: &HistorySample) -> [(&'static str, String); 7] {
+    [
+        ("timestamp", sample.timestamp.to_string()),
+        ("battery", battery.to_string()),
+        ("status", sample.status.clone()),
+        ("capacity_percent", sample.capacity_percent.to_string()),
+        ("health_percent", number(f64::from(sample.health_percent))),
+        (
+            "power_watts",
+            optional(sample.power_watts.map(|watts| number(f64::from(watts)))),
+        ),
+        ("source", "history".to_string()),
+    ]
+}
+
+/// Row of a recorded history sample, other columns left empty
+fn sample_row(battery: &str, sample: &HistorySample) -> Vec<String> {
+    let mut row = vec![String::new(); COLUMNS.len()];
+    for (name, value) in sample_fields(battery, sample) {
+        if let Some(index) = COLUMNS.iter().position(|column| *column == name) {
+            row[index] = value;
+        }
+    }
+    row
+}
+
+/// Row of the current battery state
+fn snapshot_row(info: &BatteryInfo, timestamp: u64) -> Vec<String> {
+    vec![
+        timestamp.to_string(),
+        info.name.clone(),
+        info.status.clone(),
+        info.capacity_percent.to_string(),
+        number(f64::from(info.health_percent)),
+        number(f64::from(info.wear_percent)),
+        info.charge_now.to_string(),
+        info.charge_full.to_string(),
+        info.charge_full_design.to_string(),
+        info.current_now.to_string(),
+        info.voltage_now.to_string(),
+        number(info.voltage_v()),
+        number(info.power_watts()),
+        info.cycle_count.to_string(),
+        optional(info.time_remaining_minutes),
+        optional(info.charge_start_threshold),
+        optional(info.charge_stop_threshold),
+        optional(info.alarm),
+        "snapshot".to_string(),
+    ]
+}
+
+/// Builds the CSV document
+///
+/// # Arguments
+///
+/// * `info` - Current battery state, written last
+/// * `samples` - Recorded history of the same battery, oldest first
+/// * `timestamp` - Unix timestamp of the snapshot
+pub fn export(info: &BatteryInfo, samples: &[HistorySample], timestamp: u64) -> String {
+    let mut csv = line(&COLUMNS.map(str::to_string));
+    for sample in samples {
+        csv.push_str(&line(&sample_row(&info.name, sample)));
+    }
+    csv.push_str(&line(&snapshot_row(info, timestamp)));
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_info() -> BatteryInfo {
+        BatteryInfo {
+            manufacturer: "SMP".to_string(),
+            model_name: "5B10".to_string(),
+            capacity_percent: 77,
+            charge_now: 3_850_000,
+            charge_full_design: 5_500_000,
+            cycle_count: 120,
+            health_percent: 90.9,
+            wear_percent: 9.1,
+            time_remaining_minutes: Some(95),
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Discharging"), "Discharging");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_export_snapshot() {
+        let csv = export(&sample_info(), &[], 1_700_000_000);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert_eq!(
+            lines[1],
+            "1700000000,BAT0,Discharging,77,90.90,9.10,3850000,5000000,5500000,\
+             500000,12000000,12.00,6.00,120,95,,80,,snapshot"
+        );
+        // Every row has every column
+        assert!(lines
+            .iter()
+            .all(|line| line.split(',').count() == COLUMNS.len()));
+    }
+
+    #[test]
+    fn test_export_history_and_quoting() {
+        let mut info = sample_info();
+        info.status = "Not charging, idle".to_string();
+        let samples = [HistorySample {
+            timestamp: 1_699_999_000,
+            capacity_percent: 80,
+            health_percent: 90.9,
+            status: "Charging".to_string(),
+            power_watts: Some(12.5),
+        }];
+
+        let csv = export(&info, &samples, 1_700_000_000);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "1699999000,BAT0,Charging,80,90.90,,,,,,,,12.50,,,,,,history"
+        );
+        assert!(lines[2].starts_with("1700000000,BAT0,\"Not charging, idle\",77,"));
+    }
+
+    #[test]
+    fn test_sample_fields_are_columns() {
+        let sample = HistorySample {
+            timestamp: 0,
+            capacity_percent: 50,
+            health_percent: 100.0,
+            status: "Charging".to_string(),
+            power_watts: None,
+        };
+        for (name, _) in sample_fields("BAT0", &sample) {
+            assert!(COLUMNS.contains(&name), "unknown column {name}");
+        }
+    }
+
+    #[test]
+    fn test_export_ignores_ui_locale() {
+        let _lock = crate::core::i18n::TEST_LANG_LOCK.lock().unwrap();
+        crate::core::i18n::set_language("fr");
+        let csv = export(&sample_info(), &[], 0);
+        assert!(csv.contains(",90.90,9.10,"));
+        assert!(!csv.contains("90,90"));
+    }
+}
@@ -0,0 +1,382 @@
+//! Background daemon mode (`--daemon`)
+//!
+//! Runs without GTK, reads every battery at a fixed interval and writes the
+//! thresholds saved in `/etc/battery-manager/<BAT>.conf` again when the
+//! kernel no longer reports them. A lightweight userspace alternative to the
+//! systemd restore service; status changes and re-applies are logged with
+//! `--debug`.
+//!
+//! Writing needs root: there is no polkit agent to answer pkexec without a
+//! session, so the daemon runs the helper directly when started as root and
+//! otherwise only reports the drifted thresholds, once per change.
+//!
+//! The process id is written to `/run/battery-manager.pid` as root, or to
+//! `$XDG_RUNTIME_DIR/battery-manager.pid` otherwise, which also prevents two
+//! daemons from running at once.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::resume::{ReapplyOutcome, SavedThresholds};
+use crate::core::threshold_apply::CONFIG_DIR;
+use crate::core::watch::{install_stop_handlers, sleep_unless_stopped, stop_requested};
+use crate::core::writer::{execute_as_root, reapply_saved_thresholds, running_as_root};
+use crate::core::BatteryInfo;
+
+/// Default interval between two readings, in seconds
+pub const DEFAULT_DAEMON_INTERVAL_SECS: u64 = 30;
+
+/// Bounds accepted by `--interval=`
+const MIN_DAEMON_INTERVAL_SECS: u64 = 5;
+const MAX_DAEMON_INTERVAL_SECS: u64 = 3600;
+
+/// File name of the PID file, in the runtime directory
+const PID_FILE: &str = "battery-manager.pid";
+
+/// Runtime directory of the PID file when running as root
+const ROOT_RUNTIME_DIR: &str = "/run";
+
+/// Parses the value of `--interval=` (5–3600 s)
+pub fn parse_interval(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|secs| (MIN_DAEMON_INTERVAL_SECS..=MAX_DAEMON_INTERVAL_SECS).contains(secs))
+}
+
+/// PID file path of the current process
+///
+/// # Returns
+///
+/// `/run/battery-manager.pid` as root, `$XDG_RUNTIME_DIR/battery-manager.pid`
+/// otherwise (`/run/user/<uid>` when the variable is unset)
+pub fn pid_file_path() -> PathBuf {
+    // SAFETY: getuid has no preconditions and cannot fail.
+    let uid = unsafe { libc::getuid() };
+    pid_file_path_for(
+        running_as_root(),
+        std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from),
+        uid,
+    )
+}
+
+/// Chooses the PID file path for a user and runtime directory
+fn pid_file_path_for(as_root: bool, runtime_dir: Option<PathBuf>, uid: u32) -> PathBuf {
+    let dir = if as_root {
+        PathBuf::from(ROOT_RUNTIME_DIR)
+    } else {
+        runtime_dir
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(|| Path::new("/run/user").join(uid.to_string()))
+    };
+    dir.join(PID_FILE)
+}
+
+/// PID file held while the daemon runs, removed on drop
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the current process id to `path`
+    ///
+    /// A file left by a daemon that no longer runs is replaced, and a
+    /// missing parent directory is created.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ErrorKind::AlreadyExists` while another daemon is running, or
+    /// the I/O error if the file cannot be written
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(pid) = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| text.trim().parse::<u32>().ok())
+        {
+            if pid != std::process::id() && Path::new(&format!("/proc/{pid}")).exists() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("already running (pid {pid})"),
+                ));
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(path)?;
+        writeln!(file, "{}", std::process::id())?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Last reading of a battery, so only changes are logged
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BatteryState {
+    status: String,
+    start: Option<u8>,
+    stop: Option<u8>,
+}
+
+impl BatteryState {
+    fn of(info: &BatteryInfo) -> Self {
+        Self {
+            status: info.status.clone(),
+            start: info.charge_start_threshold,
+            stop: info.charge_stop_threshold,
+        }
+    }
+}
+
+/// Describes what changed since the previous reading of a battery
+///
+/// # Returns
+///
+/// `None` when nothing changed, e.g. "BAT0: Discharging → Charging"
+fn describe_change(previous: Option<&BatteryState>, info: &BatteryInfo) -> Option<String> {
+    let current = BatteryState::of(info);
+    let Some(previous) = previous else {
+        return Some(format!(
+            "{}: {}, thresholds {:?}/{:?}",
+            info.name, current.status, current.start, current.stop
+        ));
+    };
+
+    let mut changes = Vec::new();
+    if previous.status != current.status {
+        changes.push(format!("{} → {}", previous.status, current.status));
+    }
+    if (previous.start, previous.stop) != (current.start, current.stop) {
+        changes.push(format!(
+            "thresholds {:?}/{:?} → {:?}/{:?}",
+            previous.start, previous.stop, current.start, current.stop
+        ));
+    }
+    (!changes.is_empty()).then(|| format!("{}: {}", info.name, changes.join(", ")))
+}
+
+/// Daemon memory of one battery
+#[derive(Debug)]
+struct Tracked {
+    state: BatteryState,
+    /// Last re-apply problem reported, so a failure retried at every reading
+    /// is only logged again when it changes
+    reported: Option<String>,
+}
+
+/// Reads one battery, logs its changes and re-applies drifted thresholds
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `tracked` - Previous readings
+/// * `as_root` - Whether the helper can be run to write the thresholds
+fn check_battery(battery_name: &str, tracked: &mut HashMap<String, Tracked>, as_root: bool) {
+    let info = match BatteryInfo::new(battery_name) {
+        Ok(info) => info,
+        Err(e) => {
+            crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [DAEMON] Cannot read {battery_name}: {e}"
+            ));
+            return;
+        }
+    };
+
+    let previous = tracked.get(battery_name);
+    if let Some(change) = describe_change(previous.map(|t| &t.state), &info) {
+        crate::core::debug::debug_log_args(std::format_args!("🔋 [DAEMON] {change}"));
+    }
+    let state = BatteryState::of(&info);
+    let mut reported = previous
+        .filter(|previous| previous.state == state)
+        .and_then(|previous| previous.reported.clone());
+
+    let drifted = SavedThresholds::load(Path::new(CONFIG_DIR), battery_name)
+        .is_some_and(|saved| !saved.matches(&info));
+    if !drifted {
+        reported = None;
+    } else if !as_root {
+        let problem = "not running as root".to_string();
+        if reported.as_ref() != Some(&problem) {
+            crate::core::debug::terminal_warn_args(std::format_args!(
+                "⚠️ [DAEMON] {battery_name}: thresholds drifted from the saved ones, cannot re-apply them without root"
+            ));
+            reported = Some(problem);
+        }
+    } else {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🔁 [DAEMON] {battery_name}: thresholds drifted from the saved ones, re-applying"
+        ));
+        match reapply_saved_thresholds(battery_name, execute_as_root) {
+            Some(ReapplyOutcome::Failed(error)) => {
+                if reported.as_ref() != Some(&error) {
+                    crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [DAEMON] {battery_name}: re-apply failed, retrying at the next reading: {error}"
+                    ));
+                }
+                reported = Some(error);
+            }
+            outcome => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "✅ [DAEMON] {battery_name}: {outcome:?}"
+                ));
+                reported = None;
+            }
+        }
+    }
+
+    tracked.insert(battery_name.to_string(), Tracked { state, reported });
+}
+
+/// Runs the daemon loop until SIGTERM or SIGINT
+///
+/// # Arguments
+///
+/// * `interval` - Time between two readings
+///
+/// # Returns
+///
+/// Process exit code (0 on clean stop, 1 when the PID file cannot be created)
+pub fn run(interval: Duration) -> i32 {
+    let pid_path = pid_file_path();
+    let _pid_file = match PidFile::create(&pid_path) {
+        Ok(pid_file) => pid_file,
+        Err(e) => {
+            crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [DAEMON] Cannot create {}: {e}",
+                pid_path.display()
+            ));
+            return 1;
+        }
+    };
+    install_stop_handlers();
+
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🛡️ [DAEMON] Started (pid {}), checking every {}s",
+        std::process::id(),
+        interval.as_secs()
+    ));
+
+    let as_root = running_as_root();
+    if !as_root {
+        crate::core::debug::terminal_warn_args(std::format_args!(
+            "⚠️ [DAEMON] Not running as root: drifted thresholds will be reported, not re-applied"
+        ));
+    }
+
+    let mut tracked = HashMap::new();
+    while !stop_requested() {
+        for battery_name in BatteryInfo::get_battery_list() {
+            check_battery(&battery_name, &mut tracked, as_root);
+        }
+        sleep_unless_stopped(interval);
+    }
+
+    crate::core::debug::debug_log("👋 [DAEMON] Stop requested, exiting");
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(status: &str, stop: Option<u8>) -> BatteryInfo {
+        BatteryInfo {
+            status: status.to_string(),
+            charge_stop_threshold: stop,
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("30"), Some(30));
+        assert_eq!(parse_interval("5"), Some(5));
+        assert_eq!(parse_interval("4"), None);
+        assert_eq!(parse_interval("3601"), None);
+        assert_eq!(parse_interval("soon"), None);
+    }
+
+    #[test]
+    fn test_describe_change() {
+        let discharging = BatteryState::of(&info("Discharging", Some(80)));
+        assert_eq!(
+            describe_change(None, &info("Discharging", Some(80))).as_deref(),
+            Some("BAT0: Discharging, thresholds None/Some(80)")
+        );
+        assert_eq!(
+            describe_change(Some(&discharging), &info("Discharging", Some(80))),
+            None
+        );
+        assert_eq!(
+            describe_change(Some(&discharging), &info("Charging", Some(100))).as_deref(),
+            Some("BAT0: Discharging → Charging, thresholds None/Some(80) → None/Some(100)")
+        );
+    }
+
+    #[test]
+    fn test_pid_file_path() {
+        let runtime = Some(PathBuf::from("/run/user/1000"));
+        assert_eq!(
+            pid_file_path_for(true, runtime.clone(), 0),
+            Path::new("/run/battery-manager.pid")
+        );
+        assert_eq!(
+            pid_file_path_for(false, runtime, 1000),
+            Path::new("/run/user/1000/battery-manager.pid")
+        );
+        assert_eq!(
+            pid_file_path_for(false, Some(PathBuf::from("/tmp/xdg")), 1000),
+            Path::new("/tmp/xdg/battery-manager.pid")
+        );
+        assert_eq!(
+            pid_file_path_for(false, None, 1000),
+            Path::new("/run/user/1000/battery-manager.pid")
+        );
+        assert_eq!(
+            pid_file_path_for(false, Some(PathBuf::from("relative")), 1000),
+            Path::new("/run/user/1000/battery-manager.pid")
+        );
+    }
+
+    #[test]
+    fn test_pid_file() {
+        let dir =
+            std::env::temp_dir().join(format!("battery-manager-daemon-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join(PID_FILE);
+
+        // The parent directory is created when missing
+        drop(PidFile::create(&path).unwrap());
+        assert!(dir.is_dir());
+
+        // Stale file from a process that no longer exists
+        std::fs::write(&path, "4194305\n").unwrap();
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+        drop(pid_file);
+        assert!(!path.exists());
+
+        // PID 1 always runs
+        std::fs::write(&path, "1\n").unwrap();
+        assert_eq!(
+            PidFile::create(&path).unwrap_err().kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
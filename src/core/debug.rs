@@ -4,12 +4,19 @@
 //! Traces UI events and core operations.
 
 use std::fmt;
-use std::io::IsTerminal;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Global debug flag
 static DEBUG_ENABLED: AtomicBool = AtomicBool::new(false);
 
+/// Global timestamp flag (independent of debug mode)
+static ENABLE_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+
 /// Enable debug mode
 pub fn enable_debug() {
     DEBUG_ENABLED.store(true, Ordering::Relaxed);
@@ -26,9 +33,361 @@ pub fn is_debug_enabled() -> bool {
     DEBUG_ENABLED.load(Ordering::Relaxed)
 }
 
+/// Severity of a log message, from most to least important
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// Parses a `BATTERY_MANAGER_LOG_LEVEL` value
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - "error", "warn", "info" or "debug"
+    ///
+    /// # Returns
+    ///
+    /// * `Some(LogLevel)` - Known level
+    /// * `None` - Unknown value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "warn" | "warning" => Some(Self::Warn),
+            "info" => Some(Self::Info),
+            "debug" | "trace" | "all" => Some(Self::Debug),
+            _ => None,
+        }
+    }
+
+    /// Classifies a debug message
+    ///
+    /// Errors and warnings are detected like their terminal color; trace
+    /// messages from the `debug!`, `debug_ui!` and `debug_core!` macros are
+    /// `Debug`, everything else is `Info`.
+    fn classify(message: &str) -> Self {
+        match detect_color_from_text(message) {
+            LogColor::Error => Self::Error,
+            LogColor::Warning => Self::Warn,
+            LogColor::None | LogColor::Success => {
+                if ["[LOG]", "[UI]", "[CORE]"]
+                    .iter()
+                    .any(|tag| message.contains(tag))
+                {
+                    Self::Debug
+                } else {
+                    Self::Info
+                }
+            }
+        }
+    }
+
+    const fn to_u8(self) -> u8 {
+        match self {
+            Self::Error => 0,
+            Self::Warn => 1,
+            Self::Info => 2,
+            Self::Debug => 3,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Error,
+            1 => Self::Warn,
+            2 => Self::Info,
+            _ => Self::Debug,
+        }
+    }
+}
+
+/// Environment variable selecting the most verbose level shown with `--debug`
+const LOG_LEVEL_ENV: &str = "BATTERY_MANAGER_LOG_LEVEL";
+
+/// Not yet read from the environment
+const LOG_LEVEL_UNSET: u8 = u8::MAX;
+
+/// Global log level (`LOG_LEVEL_UNSET` until first use)
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LOG_LEVEL_UNSET);
+
+/// Select the most verbose level shown with `--debug`
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level.to_u8(), Ordering::Relaxed);
+}
+
+/// Returns the active log level
+///
+/// Read once from `BATTERY_MANAGER_LOG_LEVEL`; defaults to `Debug` (everything).
+pub fn log_level() -> LogLevel {
+    let value = LOG_LEVEL.load(Ordering::Relaxed);
+    if value != LOG_LEVEL_UNSET {
+        return LogLevel::from_u8(value);
+    }
+
+    let level = std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .map_or(LogLevel::Debug, |v| {
+            LogLevel::parse(&v).unwrap_or_else(|| {
+                terminal_warn_args(std::format_args!(
+                    "⚠️ [LOG] Unknown {LOG_LEVEL_ENV}={v}, showing all messages"
+                ));
+                LogLevel::Debug
+            })
+        });
+    set_log_level(level);
+    level
+}
+
+/// Whether a debug message passes the configured level
+///
+/// # Arguments
+///
+/// * `max_level` - Most verbose level to show
+/// * `message` - Message text
+fn passes_level(max_level: LogLevel, message: &str) -> bool {
+    LogLevel::classify(message) <= max_level
+}
+
+/// Output format of log lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines (optionally colored)
+    Plain,
+    /// One JSON object per line (`level`, `ts`, `msg`, `tag`)
+    Json,
+}
+
+impl LogFormat {
+    /// Parses a `--log-format=` value
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - "plain" or "json"
+    ///
+    /// # Returns
+    ///
+    /// * `Some(LogFormat)` - Known format
+    /// * `None` - Unknown value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "plain" | "text" => Some(Self::Plain),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Global log format (0 = plain, 1 = JSON)
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Select the log output format
+pub fn set_log_format(format: LogFormat) {
+    let value = match format {
+        LogFormat::Plain => 0,
+        LogFormat::Json => 1,
+    };
+    LOG_FORMAT.store(value, Ordering::Relaxed);
+}
+
+/// Returns the active log output format
+pub fn log_format() -> LogFormat {
+    match LOG_FORMAT.load(Ordering::Relaxed) {
+        1 => LogFormat::Json,
+        _ => LogFormat::Plain,
+    }
+}
+
+/// Extracts the first `[TAG]` (uppercase letters, digits, `_`) from a message
+fn extract_tag(message: &str) -> Option<&str> {
+    let mut rest = message;
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let close = after.find(']')?;
+        let candidate = &after[..close];
+        if !candidate.is_empty()
+            && candidate
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Some(candidate);
+        }
+        rest = after;
+    }
+    None
+}
+
+/// Formats a message as a single-line JSON object
+fn format_json_line(level: &str, message: &str) -> String {
+    let tag = extract_tag(message).map_or_else(|| "null".to_string(), |t| format!("\"{t}\""));
+    format!(
+        "{{\"level\":\"{level}\",\"ts\":\"{}\",\"msg\":\"{}\",\"tag\":{tag}}}",
+        format_iso8601(SystemTime::now()),
+        crate::core::json::escape(message)
+    )
+}
+
+/// Size above which the log file is rotated to `PATH.1`
+const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Log file opened in append mode, with its current size
+struct LogFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            size,
+        })
+    }
+
+    /// Appends a line, rotating to `PATH.1` first if it would exceed `max_bytes`
+    fn write_line(&mut self, line: &str, max_bytes: u64) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, rotated)?;
+        *self = Self::open(&self.path.clone())?;
+        Ok(())
+    }
+}
+
+/// Optional log file receiving a copy of every log line
+static LOG_FILE: LazyLock<Mutex<Option<LogFile>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Also write log lines to a file (append mode, rotated above 1 MB)
+///
+/// # Arguments
+///
+/// * `path` - Log file path
+///
+/// # Errors
+///
+/// Returns the I/O error if the file cannot be opened
+pub fn set_log_file(path: &Path) -> Result<(), std::io::Error> {
+    let log_file = LogFile::open(path)?;
+    *LOG_FILE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(log_file);
+    Ok(())
+}
+
+/// Writes an (uncolored) line to the log file, if one is set
+fn write_to_log_file(line: &str) {
+    let mut guard = LOG_FILE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(log_file) = guard.as_mut() {
+        if let Err(e) = log_file.write_line(line, LOG_FILE_MAX_BYTES) {
+            // Don't recurse into the logger; disable the file and report once
+            eprintln!("[ERROR] ❌ Log file write failed, disabling: {e}");
+            *guard = None;
+        }
+    }
+}
+
+/// Enable `[HH:MM:SS.mmm]` timestamps on log lines
+pub fn enable_timestamps() {
+    ENABLE_TIMESTAMPS.store(true, Ordering::Relaxed);
+}
+
+/// Check if log timestamps are enabled
+pub fn are_timestamps_enabled() -> bool {
+    ENABLE_TIMESTAMPS.load(Ordering::Relaxed)
+}
+
+/// Formats a point in time as local `HH:MM:SS.mmm`
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let millis = since_epoch.subsec_millis();
+    let secs = libc::time_t::try_from(since_epoch.as_secs()).unwrap_or_default();
+
+    // SAFETY: localtime_r only writes into the provided `tm` struct.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let local = unsafe { !libc::localtime_r(&raw const secs, &raw mut tm).is_null() };
+
+    if local {
+        format!(
+            "{:02}:{:02}:{:02}.{millis:03}",
+            tm.tm_hour, tm.tm_min, tm.tm_sec
+        )
+    } else {
+        // Fall back to UTC if the local time conversion fails
+        let day_secs = since_epoch.as_secs() % 86_400;
+        format!(
+            "{:02}:{:02}:{:02}.{millis:03}",
+            day_secs / 3600,
+            (day_secs / 60) % 60,
+            day_secs % 60
+        )
+    }
+}
+
+/// Formats a point in time as local ISO-8601 (`2026-01-02T13:45:07.123+01:00`)
+fn format_iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let millis = since_epoch.subsec_millis();
+    let secs = libc::time_t::try_from(since_epoch.as_secs()).unwrap_or_default();
+
+    // SAFETY: localtime_r/gmtime_r only write into the provided `tm` struct.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let local = unsafe { !libc::localtime_r(&raw const secs, &raw mut tm).is_null() };
+    if !local && unsafe { libc::gmtime_r(&raw const secs, &raw mut tm).is_null() } {
+        return format!("{}.{millis:03}", since_epoch.as_secs());
+    }
+
+    let offset = if local {
+        let minutes = tm.tm_gmtoff / 60;
+        let sign = if minutes < 0 { '-' } else { '+' };
+        format!("{sign}{:02}:{:02}", minutes.abs() / 60, minutes.abs() % 60)
+    } else {
+        "Z".to_string()
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{millis:03}{offset}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Prepends the current timestamp to a log line when timestamps are enabled
+fn with_timestamp(line: String) -> String {
+    if are_timestamps_enabled() {
+        format!("[{}] {line}", format_timestamp(SystemTime::now()))
+    } else {
+        line
+    }
+}
+
+/// Terminal color applied to an output line
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum LogColor {
+pub enum LogColor {
     None,
+    Success,
     Warning,
     Error,
 }
@@ -67,23 +426,33 @@ fn should_colorize_stderr() -> bool {
     }
 }
 
+/// Whether stdout output should be colorized (honors `NO_COLOR` and `BATTERY_MANAGER_COLOR`)
+pub fn should_colorize_stdout() -> bool {
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+    }
+}
+
+/// Detects the color of a line from its markers, then from its wording
+///
+/// An explicit marker wins: "⚠️ Failed to …" is a warning, not an error.
 fn detect_color_from_text(text: &str) -> LogColor {
-    let lower = text.to_ascii_lowercase();
+    if text.contains("[ERROR]") || text.contains('❌') {
+        return LogColor::Error;
+    }
 
-    if text.contains("[ERROR]")
-        || text.contains('❌')
-        || lower.contains(" error")
-        || lower.contains("error:")
-        || lower.contains("failed")
-    {
+    if text.contains("[WARN]") || text.contains("[WARNING]") || text.contains('⚠') {
+        return LogColor::Warning;
+    }
+
+    let lower = text.to_ascii_lowercase();
+    if lower.contains(" error") || lower.contains("error:") || lower.contains("failed") {
         return LogColor::Error;
     }
 
-    if text.contains("[WARN]")
-        || text.contains("[WARNING]")
-        || text.contains("WARNING")
-        || text.contains('⚠')
-    {
+    if text.contains("WARNING") {
         return LogColor::Warning;
     }
 
@@ -112,64 +481,106 @@ fn ensure_marker(line: String, color: LogColor) -> String {
                 }
             }
         }
-        LogColor::None => {}
+        LogColor::None | LogColor::Success => {}
     }
 
     line
 }
 
-fn colorize_line(color: LogColor, line: &str) -> String {
+/// Wraps a line in the ANSI escape sequence for the given color
+pub fn colorize_line(color: LogColor, line: &str) -> String {
     match color {
         LogColor::None => line.to_string(),
+        LogColor::Success => format!("\u{001b}[32m{line}\u{001b}[0m"),
         LogColor::Error => format!("\u{001b}[31m{line}\u{001b}[0m"),
         // "Orange" terminal-friendly: usually renders as yellow; avoids relying on 256-color.
         LogColor::Warning => format!("\u{001b}[33m{line}\u{001b}[0m"),
     }
 }
 
-/// Log a debug message (function version for easier use)
-pub fn debug_log(message: &str) {
-    if is_debug_enabled() {
-        let line = format!("[DEBUG] {message}");
-        let color = detect_color_from_text(&line);
-        let line = ensure_marker(line, color);
+/// Prints a finished log line to stderr and to the log file
+///
+/// Plain lines always get an ISO-8601 timestamp in the file, and the short
+/// terminal timestamp on stderr only when enabled; JSON lines carry their own.
+fn emit_line(color: LogColor, line: &str) {
+    let plain = log_format() == LogFormat::Plain;
+    if plain {
+        write_to_log_file(&format!("[{}] {line}", format_iso8601(SystemTime::now())));
+    } else {
+        write_to_log_file(line);
+    }
 
-        if should_colorize_stderr() {
-            eprintln!("{}", colorize_line(color, &line));
-            return;
-        }
+    let line = if plain {
+        with_timestamp(line.to_string())
+    } else {
+        line.to_string()
+    };
+    if should_colorize_stderr() {
+        eprintln!("{}", colorize_line(color, &line));
+        return;
+    }
+
+    eprintln!("{line}");
+}
+
+/// Formats a message for the active log format
+///
+/// # Arguments
+///
+/// * `level` - "debug", "warn" or "error"
+/// * `message` - Message text (usually `emoji [TAG] ...`)
+///
+/// # Returns
+///
+/// Tuple of (color, finished line)
+fn format_message(level: &str, message: &str) -> (LogColor, String) {
+    if log_format() == LogFormat::Json {
+        return (LogColor::None, format_json_line(level, message));
+    }
+
+    let line = format!("[{}] {message}", level.to_ascii_uppercase());
+    let color = match level {
+        "error" => LogColor::Error,
+        "warn" => LogColor::Warning,
+        _ => detect_color_from_text(&line),
+    };
+    (color, ensure_marker(line, color))
+}
 
-        eprintln!("{line}");
+/// Log a debug message (function version for easier use)
+pub fn debug_log(message: &str) {
+    if is_debug_enabled() && passes_level(log_level(), message) {
+        let (color, line) = format_message("debug", message);
+        emit_line(color, &line);
     }
 }
 
 /// Log a debug message without allocating a temporary `String`.
 pub fn debug_log_args(args: fmt::Arguments<'_>) {
     if is_debug_enabled() {
-        let line = format!("[DEBUG] {args}");
-        let color = detect_color_from_text(&line);
-        let line = ensure_marker(line, color);
-
-        if should_colorize_stderr() {
-            eprintln!("{}", colorize_line(color, &line));
-            return;
+        let message = args.to_string();
+        if passes_level(log_level(), &message) {
+            let (color, line) = format_message("debug", &message);
+            emit_line(color, &line);
         }
-
-        eprintln!("{line}");
     }
 }
 
 /// Log an error to stderr (always) without forcing a temporary `String` at callsite.
 pub fn terminal_error_args(args: fmt::Arguments<'_>) {
-    let line = format!("[ERROR] {args}");
-    let line = ensure_marker(line, LogColor::Error);
+    let (color, line) = format_message("error", &args.to_string());
+    emit_line(color, &line);
+}
 
-    if should_colorize_stderr() {
-        eprintln!("{}", colorize_line(LogColor::Error, &line));
-        return;
-    }
+/// Log a warning to stderr (always)
+pub fn terminal_warn(message: &str) {
+    let (color, line) = format_message("warn", message);
+    emit_line(color, &line);
+}
 
-    eprintln!("{line}");
+/// Log a warning to stderr (always) without forcing a temporary `String` at callsite.
+pub fn terminal_warn_args(args: fmt::Arguments<'_>) {
+    terminal_warn(&args.to_string());
 }
 
 /// Debug macro - only prints when debug is enabled
@@ -224,6 +635,211 @@ mod tests {
         assert!(is_debug_enabled());
     }
 
+    #[test]
+    fn test_timestamp_prefix_format() {
+        enable_timestamps();
+        assert!(are_timestamps_enabled());
+
+        let line = with_timestamp("[DEBUG] 🔧 [LOG] hello".to_string());
+        let bytes = line.as_bytes();
+        assert_eq!(bytes[0], b'[');
+        assert_eq!(&line[13..15], "] ");
+        for (i, b) in bytes[1..13].iter().enumerate() {
+            match i {
+                2 | 5 => assert_eq!(*b, b':', "unexpected separator in {line}"),
+                8 => assert_eq!(*b, b'.', "unexpected separator in {line}"),
+                _ => assert!(b.is_ascii_digit(), "unexpected char in {line}"),
+            }
+        }
+        assert!(line.ends_with("[DEBUG] 🔧 [LOG] hello"));
+    }
+
+    #[test]
+    fn test_format_timestamp_millis() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(45_296_007);
+        let formatted = format_timestamp(time);
+        assert_eq!(formatted.len(), 12);
+        assert!(formatted.ends_with(".007"));
+    }
+
+    /// Serializes the tests that swap the global log file
+    static LOG_FILE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    fn temp_log_path(tag: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "battery-manager-log-{tag}-{}.log",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(path.with_extension("log.1"));
+        path
+    }
+
+    #[test]
+    fn test_set_log_file_appends_lines() {
+        let _guard = LOG_FILE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let path = temp_log_path("append");
+        fs::write(&path, "existing\n").unwrap();
+
+        set_log_file(&path).unwrap();
+        terminal_error_args(std::format_args!("[TEST] log file line"));
+        terminal_warn("[TEST] failed, retrying");
+        *LOG_FILE.lock().unwrap() = None;
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("existing\n"));
+        assert!(text.contains("[ERROR] ❌ [TEST] log file line"));
+        assert!(text.contains("[WARN] ⚠️ [TEST] failed, retrying"));
+        assert!(!text.contains('\u{001b}'));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_log_file_lines_are_timestamped_and_untinted() {
+        let _guard = LOG_FILE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let path = temp_log_path("iso");
+
+        set_log_file(&path).unwrap();
+        for i in 0..3 {
+            terminal_error_args(std::format_args!("[TEST] iso line {i} failed"));
+        }
+        *LOG_FILE.lock().unwrap() = None;
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text
+            .lines()
+            .filter(|line| line.contains("[TEST] iso line"))
+            .collect();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            // [YYYY-MM-DDTHH:MM:SS.mmm+HH:MM] or [...Z]
+            let (stamp, rest) = line[1..].split_once("] ").unwrap();
+            assert!(rest.starts_with("[ERROR] ❌ [TEST] iso line"), "{line}");
+            assert_eq!(&stamp[4..5], "-");
+            assert_eq!(&stamp[10..11], "T");
+            assert_eq!(&stamp[19..20], ".");
+            assert!(stamp.len() == 24 || stamp.len() == 29, "{line}");
+            assert!(!line.contains('\u{001b}'));
+        }
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_format_iso8601() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_042);
+        let formatted = format_iso8601(time);
+        assert!(formatted.starts_with("2023-11-1"), "{formatted}");
+        assert_eq!(&formatted[19..23], ".042");
+    }
+
+    #[test]
+    fn test_log_file_rotation() {
+        let path = temp_log_path("rotate");
+        let rotated = path.with_extension("log.1");
+
+        let mut log_file = LogFile::open(&path).unwrap();
+        log_file.write_line("0123456789", 32).unwrap();
+        log_file.write_line("0123456789", 32).unwrap();
+        assert!(!rotated.exists());
+
+        log_file.write_line("abcdefghij", 32).unwrap();
+        assert_eq!(
+            fs::read_to_string(&rotated).unwrap(),
+            "0123456789\n0123456789\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abcdefghij\n");
+
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&rotated);
+    }
+
+    #[test]
+    fn test_json_log_line() {
+        let line = format_json_line("debug", "🔋 [APP] Detected \"2\" batteries");
+        let parsed = crate::core::json::parse(&line).unwrap();
+        let field = |name: &str| {
+            parsed
+                .get(name)
+                .and_then(|v| v.as_str())
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(field("level"), "debug");
+        assert_eq!(field("msg"), "🔋 [APP] Detected \"2\" batteries");
+        // YYYY-MM-DDTHH:MM:SS.mmm+HH:MM or ...Z
+        let ts = field("ts");
+        assert_eq!(&ts[10..11], "T");
+        assert!(ts.len() == 24 || ts.len() == 29, "{ts}");
+        assert!(line.ends_with("\"tag\":\"APP\"}"));
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn test_log_level_parse() {
+        assert_eq!(LogLevel::parse("error"), Some(LogLevel::Error));
+        assert_eq!(LogLevel::parse(" WARN "), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("warning"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("Info"), Some(LogLevel::Info));
+        assert_eq!(LogLevel::parse("debug"), Some(LogLevel::Debug));
+        assert_eq!(LogLevel::parse("verbose"), None);
+        assert!(LogLevel::Error < LogLevel::Debug);
+        for level in [
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Info,
+            LogLevel::Debug,
+        ] {
+            assert_eq!(LogLevel::from_u8(level.to_u8()), level);
+        }
+    }
+
+    #[test]
+    fn test_log_level_suppression() {
+        let info = "🔋 [APP] Detected 2 batteries";
+        let warning = "⚠️ [UPDATE] Failed to prune history";
+        let trace = "🧭 [UI] Switched tab -> #1";
+
+        assert_eq!(LogLevel::classify(info), LogLevel::Info);
+        assert_eq!(LogLevel::classify(warning), LogLevel::Warn);
+        assert_eq!(
+            LogLevel::classify("🔁 [UPDATE] Failed to prune history"),
+            LogLevel::Error
+        );
+        assert_eq!(
+            LogLevel::classify("⚠️ [SETTINGS_TAB] Service disabled"),
+            LogLevel::Warn
+        );
+        assert_eq!(LogLevel::classify(trace), LogLevel::Debug);
+
+        assert!(!passes_level(LogLevel::Warn, info));
+        assert!(passes_level(
+            LogLevel::Warn,
+            "⚠️ [SETTINGS_TAB] Service disabled"
+        ));
+        assert!(!passes_level(LogLevel::Error, warning));
+        assert!(passes_level(LogLevel::Warn, warning));
+        assert!(passes_level(LogLevel::Info, info));
+        assert!(!passes_level(LogLevel::Info, trace));
+        assert!(passes_level(LogLevel::Debug, trace));
+    }
+
+    #[test]
+    fn test_extract_tag() {
+        assert_eq!(extract_tag("🚀 [MAIN] Debug mode"), Some("MAIN"));
+        assert_eq!(
+            extract_tag("[x] then [SETTINGS_TAB] y"),
+            Some("SETTINGS_TAB")
+        );
+        assert_eq!(extract_tag("no tag here"), None);
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+
     #[test]
     fn test_source_debug_log_literals_are_tagged() {
         fn visit_rs_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
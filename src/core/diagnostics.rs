@@ -0,0 +1,312 @@
+//! Plain-text diagnostic report for bug reports
+//!
+//! Gathers the application version, the detected vendor support, every
+//! battery, the power supplies, the threshold files (and whether they still
+//! exist) and the peripherals into one text, copied to the clipboard or
+//! saved to a file from the Help menu. Serial numbers and Bluetooth
+//! addresses (also found in `BlueZ` and `UPower` device names) can be masked
+//! before the report is pasted into a public issue.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::core::kernel::{self, KernelVersion};
+use crate::core::traits::BatteryService;
+use crate::core::{BatteryInfo, PeripheralBattery, PowerSupplyInfo, VendorInfo};
+
+/// Replaces serial numbers when redaction is requested
+const REDACTED: &str = "[redacted]";
+
+/// Length of a Bluetooth address (`AC:80:0A:12:34:56`)
+const ADDRESS_LEN: usize = 17;
+
+/// Whether `bytes` is a Bluetooth address, separated by `:`, `_` or `-`
+fn is_address(bytes: &[u8]) -> bool {
+    bytes.len() == ADDRESS_LEN
+        && bytes.iter().enumerate().all(|(i, byte)| {
+            if i % 3 == 2 {
+                matches!(byte, b':' | b'_' | b'-')
+            } else {
+                byte.is_ascii_hexdigit()
+            }
+        })
+}
+
+/// Masks the Bluetooth addresses and the serial number found in a text
+///
+/// # Arguments
+///
+/// * `text` - Device name or model (e.g., `bluez_ac_80_0a_12_34_56`)
+/// * `serial` - Serial number of the device, matched case-insensitively
+fn redact(text: &str, serial: Option<&str>) -> String {
+    let serial = serial
+        .map(|serial| serial.trim().to_ascii_lowercase())
+        .filter(|serial| !serial.is_empty());
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let head = rest.as_bytes();
+        let matched = if head.len() >= ADDRESS_LEN && is_address(&head[..ADDRESS_LEN]) {
+            Some(ADDRESS_LEN)
+        } else {
+            serial
+                .as_ref()
+                .filter(|serial| {
+                    rest.get(..serial.len())
+                        .is_some_and(|start| start.eq_ignore_ascii_case(serial))
+                })
+                .map(String::len)
+        };
+        if let Some(len) = matched {
+            redacted.push_str(REDACTED);
+            rest = &rest[len..];
+        } else {
+            let mut chars = rest.chars();
+            if let Some(c) = chars.next() {
+                redacted.push(c);
+            }
+            rest = chars.as_str();
+        }
+    }
+    redacted
+}
+
+/// Everything the report shows, read once
+#[derive(Debug, Clone)]
+pub struct DiagnosticReport {
+    pub version: String,
+    pub kernel: Option<KernelVersion>,
+    pub vendor: Option<VendorInfo>,
+    pub batteries: Vec<BatteryInfo>,
+    pub power_supply: PowerSupplyInfo,
+    /// Threshold file paths with whether they exist now
+    pub threshold_files: Vec<(String, bool)>,
+    pub peripherals: Vec<PeripheralBattery>,
+}
+
+impl DiagnosticReport {
+    /// Reads the current state of the system
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - Battery data source
+    /// * `peripherals` - Peripherals detected now
+    pub fn collect(service: &dyn BatteryService, peripherals: Vec<PeripheralBattery>) -> Self {
+        let names = service.list_batteries();
+        let vendor = names
+            .first()
+            .map(|name| VendorInfo::detect_cached(name).clone());
+        let threshold_files = names
+            .iter()
+            .flat_map(|name| {
+                let files = &VendorInfo::detect_cached(name).threshold_files;
+                files.start_paths.iter().chain(&files.stop_paths).cloned()
+            })
+            .map(|path| {
+                let exists = Path::new(&path).exists();
+                (path, exists)
+            })
+            .collect();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            kernel: kernel::running_version(),
+            vendor,
+            batteries: names
+                .iter()
+                .filter_map(|name| service.get_info(name).ok())
+                .collect(),
+            power_supply: PowerSupplyInfo::new(),
+            threshold_files,
+            peripherals,
+        }
+    }
+
+    /// Formats the report as plain text (no Pango markup)
+    ///
+    /// # Arguments
+    ///
+    /// * `redact_serials` - Replace peripheral serial numbers and Bluetooth
+    ///   addresses with "[redacted]"
+    pub fn to_text(&self, redact_serials: bool) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "Battery Manager {}", self.version);
+        let _ = writeln!(
+            text,
+            "Kernel: {}",
+            self.kernel
+                .map_or_else(|| "unknown".to_string(), |version| version.to_string())
+        );
+
+        let _ = writeln!(text, "\n== Vendor ==");
+        match &self.vendor {
+            Some(vendor) => {
+                let _ = writeln!(text, "{vendor:#?}");
+            }
+            None => text.push_str("No battery detected\n"),
+        }
+
+        let _ = writeln!(text, "\n== Threshold files ==");
+        if self.threshold_files.is_empty() {
+            text.push_str("none\n");
+        }
+        for (path, exists) in &self.threshold_files {
+            let _ = writeln!(
+                text,
+                "{path} ({})",
+                if *exists { "present" } else { "missing" }
+            );
+        }
+
+        let _ = writeln!(text, "\n== Batteries ==");
+        for info in &self.batteries {
+            let _ = writeln!(text, "{info:#?}");
+        }
+
+        let _ = writeln!(text, "\n== Power supply ==");
+        let _ = writeln!(text, "{:#?}", self.power_supply);
+
+        let _ = writeln!(text, "\n== Peripherals ==");
+        if self.peripherals.is_empty() {
+            text.push_str("none\n");
+        }
+        for peripheral in &self.peripherals {
+            let mut peripheral = peripheral.clone();
+            if redact_serials {
+                let serial = peripheral.serial_number.as_deref();
+                peripheral.name = redact(&peripheral.name, serial);
+                peripheral.model_name = redact(&peripheral.model_name, serial);
+                if serial.is_some() {
+                    peripheral.serial_number = Some(REDACTED.to_string());
+                }
+            }
+            let _ = writeln!(text, "{peripheral:#?}");
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::vendor_detection::{ThresholdFiles, VendorType};
+
+    fn report() -> DiagnosticReport {
+        DiagnosticReport {
+            version: "1.0.0".to_string(),
+            kernel: Some(KernelVersion { major: 6, minor: 8 }),
+            vendor: Some(VendorInfo {
+                manufacturer: "LENOVO".to_string(),
+                product_name: "20XY".to_string(),
+                supports_start_threshold: true,
+                supports_stop_threshold: true,
+                threshold_files: ThresholdFiles::default(),
+                vendor_type: VendorType::Lenovo,
+            }),
+            batteries: vec![BatteryInfo {
+                manufacturer: "SMP".to_string(),
+                model_name: "5B10".to_string(),
+                capacity_percent: 77,
+                charge_now: 3_850_000,
+                charge_full_design: 5_500_000,
+                cycle_count: 120,
+                health_percent: 90.9,
+                wear_percent: 9.1,
+                time_remaining_minutes: Some(95),
+                charge_start_threshold: Some(40),
+                ..BatteryInfo::sample()
+            }],
+            power_supply: PowerSupplyInfo {
+                ac_online: false,
+                ac_name: "AC".to_string(),
+                ups: None,
+            },
+            threshold_files: vec![(
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold".to_string(),
+                true,
+            )],
+            peripherals: vec![PeripheralBattery {
+                name: "hidpp_battery_0".to_string(),
+                manufacturer: "Logitech".to_string(),
+                model_name: "MX Master 3".to_string(),
+                status: "Discharging".to_string(),
+                capacity_percent: 55,
+                voltage_now: None,
+                serial_number: Some("4082-1234-ABCD".to_string()),
+                online: true,
+                device_type: "Mouse".to_string(),
+                scope: "Device".to_string(),
+                connected: Some(true),
+            }],
+        }
+    }
+
+    /// Bluetooth headset as listed by `BlueZ`, then by `UPower`
+    fn bluetooth_peripherals() -> Vec<PeripheralBattery> {
+        let bluez = PeripheralBattery {
+            name: "bluez_ac_80_0a_12_34_56".to_string(),
+            manufacturer: "Bluetooth".to_string(),
+            // No alias: BlueZ falls back to the address
+            model_name: "AC:80:0A:12:34:56".to_string(),
+            status: "Unknown".to_string(),
+            capacity_percent: 70,
+            voltage_now: None,
+            serial_number: Some("ac:80:0a:12:34:56".to_string()),
+            online: true,
+            device_type: "Headset".to_string(),
+            scope: "Device".to_string(),
+            connected: Some(true),
+        };
+        let upower = PeripheralBattery {
+            name: "/org/bluez/hci0/dev_AC_80_0A_12_34_56".to_string(),
+            model_name: "WH-1000XM4".to_string(),
+            serial_number: Some("AC:80:0A:12:34:56".to_string()),
+            ..bluez.clone()
+        };
+        vec![bluez, upower]
+    }
+
+    #[test]
+    fn test_report_contents() {
+        let text = report().to_text(false);
+        assert!(text.starts_with("Battery Manager 1.0.0\nKernel: 6.8\n"));
+        for expected in [
+            "LENOVO",
+            "charge_stop_threshold: Some(\n        80,",
+            "charge_control_end_threshold (present)",
+            "ac_online: false",
+            "MX Master 3",
+            "4082-1234-ABCD",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} in:\n{text}");
+        }
+        // Plain text, safe to paste anywhere
+        assert!(!text.contains("<span") && !text.contains("<b>"));
+    }
+
+    #[test]
+    fn test_report_redacts_serials() {
+        let text = report().to_text(true);
+        assert!(!text.contains("4082-1234-ABCD"));
+        assert!(text.contains(REDACTED));
+        // Names without an address are kept
+        assert!(text.contains("hidpp_battery_0"));
+    }
+
+    #[test]
+    fn test_report_redacts_bluetooth_addresses() {
+        let mut report = report();
+        report.peripherals = bluetooth_peripherals();
+
+        let text = report.to_text(false);
+        assert!(text.contains("bluez_ac_80_0a_12_34_56"));
+
+        let text = report.to_text(true);
+        for address in ["ac:80", "AC:80", "ac_80", "AC_80"] {
+            assert!(!text.contains(address), "{address:?} leaked in:\n{text}");
+        }
+        assert!(text.contains("bluez_[redacted]"));
+        assert!(text.contains("/org/bluez/hci0/dev_[redacted]"));
+        assert!(text.contains("WH-1000XM4"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Charge-rate smoothing for time estimates
+//!
+//! `current_now` is an instantaneous reading that spikes with load, which
+//! makes the time remaining jump between refreshes. The estimator keeps an
+//! exponential moving average (EMA) of the current across refreshes and the
+//! displayed ETA is computed from the smoothed value.
+
+use crate::core::BatteryInfo;
+
+/// Default EMA weight of the newest sample
+const DEFAULT_ALPHA: f64 = 0.3;
+
+/// Exponential moving average of the battery current
+#[derive(Debug, Clone)]
+pub struct Estimator {
+    /// Weight of the newest sample, in `(0, 1]`
+    alpha: f64,
+    smoothed: Option<f64>,
+    /// Battery and status the average belongs to (reset when either changes)
+    context: Option<(String, String)>,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_ALPHA)
+    }
+}
+
+impl Estimator {
+    /// Creates an estimator with the given smoothing factor
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Weight of the newest sample, clamped to `(0, 1]`
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            smoothed: None,
+            context: None,
+        }
+    }
+
+    /// Feeds a new current reading
+    ///
+    /// # Arguments
+    ///
+    /// * `current_now` - Instantaneous current in µA
+    ///
+    /// # Returns
+    ///
+    /// Smoothed current in µA (the first sample is returned unchanged)
+    #[allow(clippy::cast_precision_loss)]
+    pub fn update(&mut self, current_now: u64) -> f64 {
+        let sample = current_now as f64;
+        let smoothed = self
+            .smoothed
+            .map_or(sample, |prev| self.alpha.mul_add(sample - prev, prev));
+        self.smoothed = Some(smoothed);
+        smoothed
+    }
+
+    /// Forgets the running average
+    pub fn reset(&mut self) {
+        self.smoothed = None;
+    }
+
+    /// Replaces the instantaneous ETA of `info` with one from the smoothed current
+    ///
+    /// The average restarts when the battery or its status changes, since
+    /// charge and discharge currents are unrelated.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Freshly read battery information, updated in place
+    pub fn smooth_info(&mut self, info: &mut BatteryInfo) {
+        let context = (info.name.clone(), info.status.clone());
+        if self.context.as_ref() != Some(&context) {
+            self.reset();
+            self.context = Some(context);
+        }
+
+        let smoothed = self.update(info.current_now);
+        info.time_remaining_minutes =
+            eta_minutes(info.charge_now, info.charge_full, &info.status, smoothed);
+    }
+}
+
+/// Computes the time remaining from a charge rate
+///
+/// # Arguments
+///
+/// * `charge_now` - Current charge in µAh
+/// * `charge_full` - Full charge capacity in µAh
+/// * `status` - Sysfs status (`Charging`, `Discharging`, ...)
+/// * `current` - Current in µA (instantaneous or smoothed)
+///
+/// # Returns
+///
+/// * `Some(minutes)` - Time until empty (discharging) or full (charging)
+/// * `None` - No current flowing or battery idle
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn eta_minutes(charge_now: u64, charge_full: u64, status: &str, current: f64) -> Option<u32> {
+    if current <= 0.0 {
+        return None;
+    }
+
+    let remaining = match status {
+        "Discharging" => charge_now,
+        "Charging" => charge_full.saturating_sub(charge_now),
+        _ => return None,
+    };
+
+    Some((remaining as f64 / current * 60.0) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_math() {
+        let mut estimator = Estimator::new(0.5);
+        assert!((estimator.update(1000) - 1000.0).abs() < f64::EPSILON);
+        assert!((estimator.update(2000) - 1500.0).abs() < f64::EPSILON);
+        assert!((estimator.update(2000) - 1750.0).abs() < f64::EPSILON);
+
+        estimator.reset();
+        assert!((estimator.update(400) - 400.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ema_damps_spikes() {
+        let mut estimator = Estimator::default();
+        estimator.update(1_000_000);
+        let smoothed = estimator.update(5_000_000);
+        assert!(smoothed < 3_000_000.0, "spike not damped: {smoothed}");
+    }
+
+    #[test]
+    fn test_eta_branch_selection() {
+        // Discharging: time until empty from charge_now
+        assert_eq!(
+            eta_minutes(2_000_000, 5_000_000, "Discharging", 1_000_000.0),
+            Some(120)
+        );
+        // Charging: time until full from the missing charge
+        assert_eq!(
+            eta_minutes(2_000_000, 5_000_000, "Charging", 1_000_000.0),
+            Some(180)
+        );
+        assert_eq!(
+            eta_minutes(2_000_000, 5_000_000, "Not charging", 1_000_000.0),
+            None
+        );
+        assert_eq!(eta_minutes(2_000_000, 5_000_000, "Discharging", 0.0), None);
+    }
+}
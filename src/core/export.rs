@@ -0,0 +1,461 @@
+//! Machine-readable battery status (`--json`)
+//!
+//! Prints every detected battery and the power supplies as one JSON object,
+//! without starting GTK, for scripts and headless machines:
+//!
+//! ```text
+//! {"batteries": [{"name": "BAT0", ...}], "power_supply": {"ac_online": true, ...}}
+//! ```
+//!
+//! Values are the raw sysfs ones (µAh, µA, µV); missing values are `null`.
+//!
+//! `--list-batteries` prints one line per battery (status, capacity, health),
+//! or with `--json` a bare array of the same battery objects.
+
+use std::fmt::Write;
+
+use crate::core::battery::{ChargeBehaviour, ManufactureDate};
+use crate::core::i18n::t;
+use crate::core::json;
+use crate::core::power_supply::UpsInfo;
+use crate::core::traits::BatteryService;
+use crate::core::upower;
+use crate::core::{BatteryInfo, PowerSupplyInfo};
+
+/// Formats an optional value as a JSON literal (`null` when absent)
+fn optional<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "null".to_string(), |v| v.to_string())
+}
+
+/// Formats a float with two decimals (`null` for NaN and infinities)
+fn number(value: f32) -> String {
+    if value.is_finite() {
+        format!("{value:.2}")
+    } else {
+        "null".to_string()
+    }
+}
+
+fn string(value: &str) -> String {
+    format!("\"{}\"", json::escape(value))
+}
+
+fn manufacture_date_json(date: Option<&ManufactureDate>) -> String {
+    date.map_or_else(
+        || "null".to_string(),
+        |date| {
+            format!(
+                "{{\"year\": {}, \"month\": {}, \"day\": {}}}",
+                date.year,
+                optional(date.month),
+                optional(date.day)
+            )
+        },
+    )
+}
+
+fn charge_behaviour_json(behaviour: Option<&ChargeBehaviour>) -> String {
+    behaviour.map_or_else(
+        || "null".to_string(),
+        |behaviour| {
+            let available: Vec<String> = behaviour.available.iter().map(|m| string(m)).collect();
+            format!(
+                "{{\"active\": {}, \"available\": [{}]}}",
+                string(&behaviour.active),
+                available.join(", ")
+            )
+        },
+    )
+}
+
+/// Serializes every field of a battery as a JSON object
+///
+/// # Arguments
+///
+/// * `info` - Battery information
+/// * `indent` - Indentation of the members
+pub fn battery_json(info: &BatteryInfo, indent: &str) -> String {
+    let members = [
+        ("name", string(&info.name)),
+        ("manufacturer", string(&info.manufacturer)),
+        ("model_name", string(&info.model_name)),
+        ("technology", string(&info.technology)),
+        ("status", string(&info.status)),
+        ("capacity_percent", info.capacity_percent.to_string()),
+        ("capacity_level", string(&info.capacity_level)),
+        ("capacity_estimated", info.capacity_estimated.to_string()),
+        ("charge_now", info.charge_now.to_string()),
+        ("charge_full", info.charge_full.to_string()),
+        ("charge_full_design", info.charge_full_design.to_string()),
+        ("current_now", info.current_now.to_string()),
+        ("voltage_now", info.voltage_now.to_string()),
+        ("cycle_count", info.cycle_count.to_string()),
+        ("health_percent", number(info.health_percent)),
+        ("wear_percent", number(info.wear_percent)),
+        (
+            "time_remaining_minutes",
+            optional(info.time_remaining_minutes),
+        ),
+        (
+            "charge_start_threshold",
+            optional(info.charge_start_threshold),
+        ),
+        (
+            "charge_stop_threshold",
+            optional(info.charge_stop_threshold),
+        ),
+        ("alarm", optional(info.alarm)),
+        (
+            "manufacture_date",
+            manufacture_date_json(info.manufacture_date.as_ref()),
+        ),
+        (
+            "charge_behaviour",
+            charge_behaviour_json(info.charge_behaviour.as_ref()),
+        ),
+    ];
+    object(&members, indent)
+}
+
+fn ups_json(ups: Option<&UpsInfo>) -> String {
+    ups.map_or_else(
+        || "null".to_string(),
+        |ups| {
+            format!(
+                "{{\"name\": {}, \"capacity_percent\": {}, \"status\": {}, \"online\": {}}}",
+                string(&ups.name),
+                optional(ups.capacity_percent),
+                string(&ups.status),
+                ups.online
+            )
+        },
+    )
+}
+
+/// Serializes the power supplies as a JSON object
+///
+/// # Arguments
+///
+/// * `power_supply` - AC and UPS information
+/// * `indent` - Indentation of the members
+pub fn power_supply_json(power_supply: &PowerSupplyInfo, indent: &str) -> String {
+    let members = [
+        ("ac_online", power_supply.ac_online.to_string()),
+        ("ac_name", string(&power_supply.ac_name)),
+        ("ups", ups_json(power_supply.ups.as_ref())),
+    ];
+    object(&members, indent)
+}
+
+/// Writes `{ "key": value, ... }` with one member per line
+fn object(members: &[(&str, String)], indent: &str) -> String {
+    let closing = indent.strip_suffix("  ").unwrap_or_default();
+    let mut out = String::from("{");
+    for (i, (key, value)) in members.iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let _ = write!(out, "{separator}\n{indent}\"{key}\": {value}");
+    }
+    let _ = write!(out, "\n{closing}}}");
+    out
+}
+
+/// Writes `[ {...}, ... ]` with one battery object per line
+///
+/// # Arguments
+///
+/// * `batteries` - Batteries, in order
+/// * `indent` - Indentation of the array items
+fn battery_array(batteries: &[BatteryInfo], indent: &str) -> String {
+    if batteries.is_empty() {
+        return "[]".to_string();
+    }
+    let closing = indent.strip_suffix("  ").unwrap_or_default();
+    let members = format!("{indent}  ");
+    let items: Vec<String> = batteries
+        .iter()
+        .map(|info| format!("\n{indent}{}", battery_json(info, &members)))
+        .collect();
+    format!("[{}\n{closing}]", items.join(","))
+}
+
+/// Builds the `--json` document
+///
+/// # Arguments
+///
+/// * `batteries` - Batteries to list, in order
+/// * `power_supply` - AC and UPS information
+///
+/// # Returns
+///
+/// `{"batteries": [...], "power_supply": {...}}`, indented, with a final newline
+pub fn status_json(batteries: &[BatteryInfo], power_supply: &PowerSupplyInfo) -> String {
+    let batteries = battery_array(batteries, "    ");
+    format!(
+        "{{\n  \"batteries\": {batteries},\n  \"power_supply\": {}\n}}\n",
+        power_supply_json(power_supply, "    ")
+    )
+}
+
+/// Formats one line of `--list-batteries`
+///
+/// # Returns
+///
+/// e.g. "BAT0: Décharge, 76%, Santé 89.3%"
+pub fn battery_list_line(info: &BatteryInfo) -> String {
+    format!(
+        "{}: {}, {}%, {} {:.1}%",
+        info.name,
+        info.status_text(),
+        info.capacity_percent,
+        t("card_health"),
+        info.health_percent
+    )
+}
+
+/// Builds the `--list-batteries` output
+///
+/// Batteries whose information cannot be read are left out (and logged).
+///
+/// # Arguments
+///
+/// * `service` - Battery data source
+/// * `json` - JSON array (`--json`) instead of one line per battery
+///
+/// # Returns
+///
+/// The text to print, with a final newline (empty without battery in text mode)
+pub fn battery_list(service: &dyn BatteryService, json: bool) -> String {
+    let batteries: Vec<BatteryInfo> = service
+        .list_batteries()
+        .iter()
+        .filter_map(|name| match service.get_info(name) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [EXPORT] Cannot read {name}: {e}"
+                ));
+                None
+            }
+        })
+        .collect();
+
+    if json {
+        format!("{}\n", battery_array(&batteries, "  "))
+    } else {
+        batteries.iter().fold(String::new(), |mut out, info| {
+            let _ = writeln!(out, "{}", battery_list_line(info));
+            out
+        })
+    }
+}
+
+/// Prints the detected batteries (`--list-batteries`)
+///
+/// # Arguments
+///
+/// * `json` - Print a JSON array instead of one line per battery
+///
+/// # Returns
+///
+/// Process exit code (0, even without battery: the list is then empty)
+pub fn run_list(json: bool) -> i32 {
+    let service = upower::select_battery_service();
+    let output = battery_list(service.as_ref(), json);
+    if output.is_empty() {
+        eprintln!("{}", t("no_battery"));
+    }
+    print!("{output}");
+    0
+}
+
+/// Prints the battery status as JSON (`--json`)
+///
+/// # Arguments
+///
+/// * `battery_filter` - Only this battery (`--battery=BAT0`), if given
+///
+/// # Returns
+///
+/// Process exit code (0 on success, 1 when the filtered battery is not found)
+pub fn run(battery_filter: Option<&str>) -> i32 {
+    let names: Vec<String> = BatteryInfo::get_battery_list()
+        .into_iter()
+        .filter(|name| battery_filter.is_none_or(|wanted| name == wanted))
+        .collect();
+    if let (Some(wanted), true) = (battery_filter, names.is_empty()) {
+        eprintln!("{}: {wanted}", t("no_battery"));
+        return 1;
+    }
+
+    let batteries: Vec<BatteryInfo> = names
+        .iter()
+        .filter_map(|name| match BatteryInfo::new(name) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [EXPORT] Cannot read {name}: {e}"
+                ));
+                None
+            }
+        })
+        .collect();
+
+    print!("{}", status_json(&batteries, &PowerSupplyInfo::new()));
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+    use crate::core::traits::MockBatteryService;
+
+    fn sample_info() -> BatteryInfo {
+        BatteryInfo {
+            manufacturer: "LGC".to_string(),
+            model_name: "5B10W13930 \"X1\"".to_string(),
+            technology: "Li-poly".to_string(),
+            capacity_percent: 76,
+            charge_now: 3_800_000,
+            charge_full_design: 5_600_000,
+            current_now: 1_200_000,
+            voltage_now: 11_900_000,
+            cycle_count: 142,
+            health_percent: 89.285_71,
+            wear_percent: 10.714_29,
+            time_remaining_minutes: Some(190),
+            manufacture_date: Some(ManufactureDate {
+                year: 2021,
+                month: Some(3),
+                day: None,
+            }),
+            charge_behaviour: ChargeBehaviour::parse("[auto] inhibit-charge"),
+            ..BatteryInfo::sample()
+        }
+    }
+
+    fn power_supply() -> PowerSupplyInfo {
+        PowerSupplyInfo {
+            ac_online: false,
+            ac_name: "AC".to_string(),
+            ups: None,
+        }
+    }
+
+    #[test]
+    fn test_status_json_round_trip() {
+        let text = status_json(&[sample_info()], &power_supply());
+        let value = json::parse(&text).unwrap();
+
+        let battery = &value.get("batteries").unwrap().as_array().unwrap()[0];
+        assert_eq!(battery.get("name").unwrap().as_str(), Some("BAT0"));
+        assert_eq!(
+            battery.get("model_name").unwrap().as_str(),
+            Some("5B10W13930 \"X1\"")
+        );
+        assert_eq!(battery.get("capacity_percent").unwrap().as_u64(), Some(76));
+        assert_eq!(battery.get("health_percent").unwrap().as_f64(), Some(89.29));
+        assert_eq!(
+            battery.get("charge_stop_threshold").unwrap().as_u64(),
+            Some(80)
+        );
+        assert_eq!(
+            battery.get("charge_start_threshold"),
+            Some(&json::JsonValue::Null)
+        );
+        assert_eq!(
+            battery
+                .get("manufacture_date")
+                .and_then(|d| d.get("month"))
+                .and_then(json::JsonValue::as_u64),
+            Some(3)
+        );
+        assert_eq!(
+            battery
+                .get("charge_behaviour")
+                .and_then(|b| b.get("active"))
+                .and_then(json::JsonValue::as_str),
+            Some("auto")
+        );
+
+        let power = value.get("power_supply").unwrap();
+        assert_eq!(power.get("ac_online"), Some(&json::JsonValue::Bool(false)));
+        assert_eq!(power.get("ups"), Some(&json::JsonValue::Null));
+    }
+
+    #[test]
+    fn test_status_json_every_field() {
+        let text = status_json(&[sample_info()], &power_supply());
+        let value = json::parse(&text).unwrap();
+        let battery = &value.get("batteries").unwrap().as_array().unwrap()[0];
+        assert_eq!(battery.as_object().unwrap().len(), 22);
+    }
+
+    #[test]
+    fn test_status_json_without_battery() {
+        let mut power = power_supply();
+        power.ups = Some(UpsInfo {
+            name: "ups0".to_string(),
+            capacity_percent: Some(97),
+            status: "Charging".to_string(),
+            online: true,
+        });
+        let value = json::parse(&status_json(&[], &power)).unwrap();
+        assert_eq!(value.get("batteries").unwrap().as_array(), Some(&[][..]));
+        assert_eq!(
+            value
+                .get("power_supply")
+                .and_then(|p| p.get("ups"))
+                .and_then(|u| u.get("capacity_percent"))
+                .and_then(json::JsonValue::as_u64),
+            Some(97)
+        );
+    }
+
+    #[test]
+    fn test_battery_list_text() {
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        set_language("en");
+
+        let mut second = sample_info();
+        second.name = "BAT1".to_string();
+        second.status = "Charging".to_string();
+        second.capacity_percent = 100;
+        let service = MockBatteryService::with_infos(vec![sample_info(), second]);
+        assert_eq!(
+            battery_list(&service, false),
+            "BAT0: Discharging, 76%, Health 89.3%\nBAT1: Charging, 100%, Health 89.3%\n"
+        );
+
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_battery_list_json() {
+        let service = MockBatteryService::with_infos(vec![sample_info()]);
+        let value = json::parse(&battery_list(&service, true)).unwrap();
+        let batteries = value.as_array().unwrap();
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(
+            batteries[0].get("capacity_percent").unwrap().as_u64(),
+            Some(76)
+        );
+        assert_eq!(batteries[0].as_object().unwrap().len(), 22);
+    }
+
+    #[test]
+    fn test_battery_list_skips_unreadable() {
+        let service = MockBatteryService::new(vec!["BAT0".to_string()]);
+        assert_eq!(battery_list(&service, false), "");
+        assert_eq!(battery_list(&service, true), "[]\n");
+    }
+
+    #[test]
+    fn test_non_finite_numbers_are_null() {
+        let mut info = sample_info();
+        info.health_percent = f32::NAN;
+        let value = json::parse(&status_json(&[info], &power_supply())).unwrap();
+        let battery = &value.get("batteries").unwrap().as_array().unwrap()[0];
+        assert_eq!(battery.get("health_percent"), Some(&json::JsonValue::Null));
+    }
+}
@@ -0,0 +1,427 @@
+//! Long-term battery health tracking
+//!
+//! Stores at most one snapshot per battery and calendar day in
+//! `~/.local/share/battery-manager/health.json` and computes the wear over
+//! the last 30/90/365 days:
+//!
+//! ```json
+//! {
+//!   "BAT0": [
+//!     {"date": "2026-10-16", "charge_full": 4510000, "charge_full_design": 5000000,
+//!      "cycle_count": 212, "health_percent": 90.2}
+//!   ]
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::json::{self, JsonValue};
+use crate::core::BatteryInfo;
+
+/// Trend windows shown in the health card, in days
+pub const TREND_WINDOWS: [u32; 3] = [30, 90, 365];
+
+/// Snapshots kept per battery (two years of daily snapshots)
+const MAX_SNAPSHOTS: usize = 730;
+
+/// Daily health snapshot of one battery
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSnapshot {
+    /// Local calendar date (`YYYY-MM-DD`)
+    pub date: String,
+    pub charge_full: u64,
+    pub charge_full_design: u64,
+    pub cycle_count: u32,
+    pub health_percent: f32,
+}
+
+impl HealthSnapshot {
+    /// Builds a snapshot from freshly read battery information
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Battery information
+    /// * `date` - Local calendar date (`YYYY-MM-DD`)
+    pub fn from_info(info: &BatteryInfo, date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            charge_full: info.charge_full,
+            charge_full_design: info.charge_full_design,
+            cycle_count: info.cycle_count,
+            health_percent: info.health_percent,
+        }
+    }
+
+    /// Reads a snapshot from a JSON object, ignoring unknown fields
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        let date = value.get("date")?.as_str()?;
+        day_number(date)?;
+        Some(Self {
+            date: date.to_string(),
+            charge_full: value.get("charge_full")?.as_u64()?,
+            charge_full_design: value.get("charge_full_design")?.as_u64()?,
+            cycle_count: u32::try_from(value.get("cycle_count")?.as_u64()?).ok()?,
+            health_percent: value.get("health_percent")?.as_f64()? as f32,
+        })
+    }
+}
+
+/// Wear measured over a trend window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WearTrend {
+    /// Health points lost (negative when the reported health went up)
+    pub wear_percent: f32,
+    /// Days actually covered by the history (at most the window)
+    pub days_covered: u32,
+}
+
+/// Health snapshots of every battery ever seen, by battery name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthHistory {
+    batteries: BTreeMap<String, Vec<HealthSnapshot>>,
+}
+
+impl HealthHistory {
+    /// Returns the default health file path
+    ///
+    /// # Returns
+    ///
+    /// `~/.local/share/battery-manager/health.json`, or `None` without a data dir
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|d| d.join("battery-manager").join("health.json"))
+    }
+
+    /// Loads the history, starting empty if the file is missing or corrupt
+    ///
+    /// Invalid snapshots are skipped; batteries that are not present anymore
+    /// are kept so they survive the next save.
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        match json::parse(&text) {
+            Ok(value) => Self::from_json(&value),
+            Err(e) => {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [HEALTH] Ignoring corrupt {}: {e}",
+                    path.display()
+                ));
+                Self::default()
+            }
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Self {
+        let mut history = Self::default();
+        for (battery, snapshots) in value.as_object().unwrap_or_default() {
+            let mut snapshots: Vec<HealthSnapshot> = snapshots
+                .as_array()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(HealthSnapshot::from_json)
+                .collect();
+            snapshots.sort_by(|a, b| a.date.cmp(&b.date));
+            snapshots.dedup_by(|later, earlier| later.date == earlier.date);
+            history.batteries.insert(battery.clone(), snapshots);
+        }
+        history
+    }
+
+    /// Serializes the history to JSON (one snapshot per line)
+    fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        for (i, (battery, snapshots)) in self.batteries.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(out, "{separator}\n  \"{}\": [", json::escape(battery));
+            for (j, s) in snapshots.iter().enumerate() {
+                let separator = if j == 0 { "" } else { "," };
+                let _ = write!(
+                    out,
+                    "{separator}\n    {{\"date\": \"{}\", \"charge_full\": {}, \"charge_full_design\": {}, \"cycle_count\": {}, \"health_percent\": {:.2}}}",
+                    s.date, s.charge_full, s.charge_full_design, s.cycle_count, s.health_percent
+                );
+            }
+            out.push_str("\n  ]");
+        }
+        out.push_str("\n}\n");
+        out
+    }
+
+    /// Writes the history to disk, creating the parent directory if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the file cannot be written
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, self.to_json())?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// Adds a snapshot unless the battery already has one for that day
+    ///
+    /// # Returns
+    ///
+    /// `true` if the snapshot was added
+    pub fn record(&mut self, battery: &str, snapshot: HealthSnapshot) -> bool {
+        let snapshots = self.batteries.entry(battery.to_string()).or_default();
+        if snapshots
+            .last()
+            .is_some_and(|last| last.date >= snapshot.date)
+        {
+            return false;
+        }
+
+        snapshots.push(snapshot);
+        let excess = snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+        snapshots.drain(..excess);
+        true
+    }
+
+    /// Iterates over every battery and its snapshots, by battery name
+    pub fn batteries(&self) -> impl Iterator<Item = (&str, &[HealthSnapshot])> {
+        self.batteries
+            .iter()
+            .map(|(battery, snapshots)| (battery.as_str(), snapshots.as_slice()))
+    }
+
+    /// Returns the snapshots of a battery, oldest first
+    pub fn snapshots(&self, battery: &str) -> &[HealthSnapshot] {
+        self.batteries.get(battery).map_or(&[], Vec::as_slice)
+    }
+
+    /// Computes the wear of a battery over the last `days` days
+    ///
+    /// Compares the latest snapshot with the oldest one inside the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `battery` - Battery name
+    /// * `days` - Window length in days
+    ///
+    /// # Returns
+    ///
+    /// `None` when the window holds fewer than two snapshots
+    pub fn wear_trend(&self, battery: &str, days: u32) -> Option<WearTrend> {
+        let snapshots = self.snapshots(battery);
+        let latest = snapshots.last()?;
+        let latest_day = day_number(&latest.date)?;
+        let oldest = snapshots
+            .iter()
+            .find(|s| day_number(&s.date).is_some_and(|d| latest_day - d <= i64::from(days)))?;
+        let oldest_day = day_number(&oldest.date)?;
+
+        (oldest_day < latest_day).then(|| WearTrend {
+            wear_percent: oldest.health_percent - latest.health_percent,
+            days_covered: u32::try_from(latest_day - oldest_day).unwrap_or(days),
+        })
+    }
+}
+
+/// Records today's snapshot of a battery in the health file
+///
+/// # Arguments
+///
+/// * `path` - Health file path
+/// * `info` - Freshly read battery information
+/// * `date` - Local calendar date (`YYYY-MM-DD`)
+///
+/// # Returns
+///
+/// The updated history when a snapshot was written, `None` if today was already recorded
+///
+/// # Errors
+///
+/// Returns the I/O error if the file cannot be written
+pub fn record_daily_snapshot(
+    path: &Path,
+    info: &BatteryInfo,
+    date: &str,
+) -> io::Result<Option<HealthHistory>> {
+    let mut history = HealthHistory::load(path);
+    if !history.record(&info.name, HealthSnapshot::from_info(info, date)) {
+        return Ok(None);
+    }
+
+    history.save(path)?;
+    crate::core::debug::debug_log_args(std::format_args!(
+        "💾 [HEALTH] Recorded {date} snapshot for {} ({:.1}%)",
+        info.name,
+        info.health_percent
+    ));
+    Ok(Some(history))
+}
+
+/// Returns today's local calendar date (`YYYY-MM-DD`), UTC if the conversion fails
+pub fn local_date() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let time = libc::time_t::try_from(secs).unwrap_or_default();
+
+    // SAFETY: localtime_r only writes into the provided `tm` struct.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&raw const time, &raw mut tm).is_null() } {
+        let (year, month, day) = civil_from_days(i64::try_from(secs / 86_400).unwrap_or(0));
+        return format!("{year:04}-{month:02}-{day:02}");
+    }
+    format!(
+        "{:04}-{:02}-{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday
+    )
+}
+
+/// Converts a `YYYY-MM-DD` date to a day number (days since 1970-01-01)
+pub(crate) fn day_number(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || date.len() != 10 {
+        return None;
+    }
+
+    // Howard Hinnant's days_from_civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// Converts a day number (days since 1970-01-01) to (year, month, day)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(date: &str, health: f32) -> HealthSnapshot {
+        HealthSnapshot {
+            date: date.to_string(),
+            charge_full: 4_500_000,
+            charge_full_design: 5_000_000,
+            cycle_count: 100,
+            health_percent: health,
+        }
+    }
+
+    #[test]
+    fn test_day_number_round_trip() {
+        assert_eq!(day_number("1970-01-01"), Some(0));
+        assert_eq!(day_number("2024-03-01"), Some(19_783));
+        assert_eq!(civil_from_days(19_783), (2024, 3, 1));
+        assert_eq!(day_number("2024-13-01"), None);
+        assert_eq!(day_number("2024-1-1"), None);
+    }
+
+    #[test]
+    fn test_record_dedups_by_day() {
+        let mut history = HealthHistory::default();
+        assert!(history.record("BAT0", snapshot("2026-01-01", 95.0)));
+        assert!(!history.record("BAT0", snapshot("2026-01-01", 94.0)));
+        assert!(!history.record("BAT0", snapshot("2025-12-31", 94.0)));
+        assert!(history.record("BAT0", snapshot("2026-01-02", 94.9)));
+        assert_eq!(history.snapshots("BAT0").len(), 2);
+        assert!((history.snapshots("BAT0")[0].health_percent - 95.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = HealthHistory::default();
+        for day in 0..(i64::try_from(MAX_SNAPSHOTS).unwrap() + 10) {
+            let (y, m, d) = civil_from_days(20_000 + day);
+            history.record("BAT0", snapshot(&format!("{y:04}-{m:02}-{d:02}"), 90.0));
+        }
+        assert_eq!(history.snapshots("BAT0").len(), MAX_SNAPSHOTS);
+    }
+
+    #[test]
+    fn test_wear_trend() {
+        let mut history = HealthHistory::default();
+        history.record("BAT0", snapshot("2025-01-01", 99.0));
+        history.record("BAT0", snapshot("2025-11-01", 97.0));
+        history.record("BAT0", snapshot("2025-12-15", 96.5));
+        history.record("BAT0", snapshot("2026-01-01", 96.0));
+
+        let month = history.wear_trend("BAT0", 30).unwrap();
+        assert!((month.wear_percent - 0.5).abs() < 1e-4);
+        assert_eq!(month.days_covered, 17);
+
+        let quarter = history.wear_trend("BAT0", 90).unwrap();
+        assert!((quarter.wear_percent - 1.0).abs() < 1e-4);
+
+        let year = history.wear_trend("BAT0", 365).unwrap();
+        assert!((year.wear_percent - 3.0).abs() < 1e-4);
+        assert_eq!(year.days_covered, 365);
+
+        assert_eq!(history.wear_trend("BAT1", 30), None);
+        let mut single = HealthHistory::default();
+        single.record("BAT0", snapshot("2026-01-01", 96.0));
+        assert_eq!(single.wear_trend("BAT0", 30), None);
+    }
+
+    #[test]
+    fn test_json_round_trip_keeps_unknown_batteries() {
+        let text = r#"{
+          "BAT0": [{"date": "2026-01-02", "charge_full": 1, "charge_full_design": 2,
+                    "cycle_count": 3, "health_percent": 50.0, "future_field": true},
+                   {"date": "garbage"}],
+          "OLD_BAT": [{"date": "2020-05-05", "charge_full": 4, "charge_full_design": 5,
+                       "cycle_count": 6, "health_percent": 80.0}]
+        }"#;
+        let history = HealthHistory::from_json(&json::parse(text).unwrap());
+        assert_eq!(history.snapshots("BAT0").len(), 1);
+        assert_eq!(history.snapshots("OLD_BAT")[0].cycle_count, 6);
+
+        let reparsed = HealthHistory::from_json(&json::parse(&history.to_json()).unwrap());
+        assert_eq!(reparsed, history);
+    }
+
+    #[test]
+    fn test_load_missing_or_corrupt_file() {
+        let dir =
+            std::env::temp_dir().join(format!("battery-manager-health-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("health.json");
+
+        assert_eq!(HealthHistory::load(&path), HealthHistory::default());
+
+        let mut history = HealthHistory::default();
+        history.record("BAT0", snapshot("2026-01-02", 91.0));
+        history.save(&path).unwrap();
+        assert_eq!(HealthHistory::load(&path), history);
+
+        fs::write(&path, "{not json").unwrap();
+        assert_eq!(HealthHistory::load(&path), HealthHistory::default());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,220 @@
+//! Scriptable battery health check (`--check-health`)
+//!
+//! Prints a one-line health summary of the first battery, without starting
+//! GTK, and exits with a code scripts can test:
+//!
+//! | Code | Meaning                  |
+//! |------|--------------------------|
+//! | 0    | health ≥ 80%             |
+//! | 1    | 60% ≤ health < 80%       |
+//! | 2    | health < 60%             |
+//! | 3    | no battery detected      |
+
+use crate::core::i18n::{t, tn};
+use crate::core::traits::BatteryService;
+use crate::core::upower;
+use crate::core::BatteryInfo;
+
+/// Health (in %) below which the battery is reported as degraded
+pub const HEALTH_WARN_PERCENT: f32 = 80.0;
+
+/// Health (in %) below which the battery is reported as critical
+pub const HEALTH_CRIT_PERCENT: f32 = 60.0;
+
+/// Exit code of `--check-health`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Health at or above the warning threshold
+    Healthy,
+    /// Health between the critical and the warning threshold
+    Degraded,
+    /// Health below the critical threshold
+    Critical,
+    /// No readable battery
+    NoBattery,
+}
+
+impl ExitCode {
+    /// Classifies a health percentage
+    ///
+    /// # Arguments
+    ///
+    /// * `health_percent` - Full capacity relative to the design capacity
+    /// * `threshold_warn` - Degraded below this value
+    /// * `threshold_crit` - Critical below this value
+    pub fn from_health(health_percent: f32, threshold_warn: f32, threshold_crit: f32) -> Self {
+        if health_percent >= threshold_warn {
+            Self::Healthy
+        } else if health_percent >= threshold_crit {
+            Self::Degraded
+        } else {
+            Self::Critical
+        }
+    }
+
+    /// Process exit code
+    pub const fn code(self) -> i32 {
+        match self {
+            Self::Healthy => 0,
+            Self::Degraded => 1,
+            Self::Critical => 2,
+            Self::NoBattery => 3,
+        }
+    }
+
+    /// Translation key of the verdict shown in the summary
+    const fn verdict_key(self) -> &'static str {
+        match self {
+            Self::Healthy => "health_verdict_good",
+            Self::Degraded => "health_verdict_degraded",
+            Self::Critical | Self::NoBattery => "health_verdict_critical",
+        }
+    }
+}
+
+/// Formats the summary line printed by `--check-health`
+///
+/// # Arguments
+///
+/// * `info` - Battery information
+/// * `code` - Verdict for this battery
+///
+/// # Returns
+///
+/// e.g. "BAT0: Santé 89.3% (bonne), Usure 10.7%, 142 cycles"
+pub fn format_health_summary(info: &BatteryInfo, code: ExitCode) -> String {
+    format!(
+        "{}: {} {:.1}% ({}), {} {:.1}%, {}",
+        info.name,
+        t("card_health"),
+        info.health_percent,
+        t(code.verdict_key()),
+        t("wear"),
+        info.wear_percent,
+        tn(
+            "cycle_count_singular",
+            "cycle_count_plural",
+            u64::from(info.cycle_count)
+        )
+    )
+}
+
+/// Checks the health of the first battery of a service
+///
+/// # Arguments
+///
+/// * `service` - Battery data source
+/// * `threshold_warn` - Degraded below this health percentage
+/// * `threshold_crit` - Critical below this health percentage
+///
+/// # Returns
+///
+/// The verdict and the line to print (the error message without battery)
+pub fn evaluate(
+    service: &dyn BatteryService,
+    threshold_warn: f32,
+    threshold_crit: f32,
+) -> (ExitCode, String) {
+    let Some(battery_name) = service.list_batteries().into_iter().next() else {
+        return (ExitCode::NoBattery, t("no_battery"));
+    };
+
+    match service.get_info(&battery_name) {
+        Ok(info) => {
+            let code = ExitCode::from_health(info.health_percent, threshold_warn, threshold_crit);
+            (code, format_health_summary(&info, code))
+        }
+        Err(e) => (ExitCode::NoBattery, format!("{battery_name}: {e}")),
+    }
+}
+
+/// Prints the health summary of the first battery (`--check-health`)
+///
+/// # Arguments
+///
+/// * `threshold_warn` - Degraded below this health percentage
+/// * `threshold_crit` - Critical below this health percentage
+///
+/// # Returns
+///
+/// The exit code matching the health (see the module documentation)
+pub fn check_health(threshold_warn: f32, threshold_crit: f32) -> ExitCode {
+    let service = upower::select_battery_service();
+    let (code, summary) = evaluate(service.as_ref(), threshold_warn, threshold_crit);
+    if code == ExitCode::NoBattery {
+        eprintln!("{summary}");
+    } else {
+        println!("{summary}");
+    }
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🩺 [HEALTH_CHECK] {code:?} (exit code {})",
+        code.code()
+    ));
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+    use crate::core::traits::MockBatteryService;
+
+    fn battery(name: &str, health_percent: f32) -> BatteryInfo {
+        BatteryInfo {
+            name: name.to_string(),
+            voltage_now: 11_500_000,
+            cycle_count: 142,
+            health_percent,
+            wear_percent: 100.0 - health_percent,
+            charge_stop_threshold: None,
+            ..BatteryInfo::sample()
+        }
+    }
+
+    fn check(batteries: Vec<BatteryInfo>) -> (ExitCode, String) {
+        evaluate(
+            &MockBatteryService::with_infos(batteries),
+            HEALTH_WARN_PERCENT,
+            HEALTH_CRIT_PERCENT,
+        )
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(ExitCode::Healthy.code(), 0);
+        assert_eq!(ExitCode::Degraded.code(), 1);
+        assert_eq!(ExitCode::Critical.code(), 2);
+        assert_eq!(ExitCode::NoBattery.code(), 3);
+
+        assert_eq!(ExitCode::from_health(80.0, 80.0, 60.0), ExitCode::Healthy);
+        assert_eq!(ExitCode::from_health(79.9, 80.0, 60.0), ExitCode::Degraded);
+        assert_eq!(ExitCode::from_health(60.0, 80.0, 60.0), ExitCode::Degraded);
+        assert_eq!(ExitCode::from_health(59.9, 80.0, 60.0), ExitCode::Critical);
+    }
+
+    #[test]
+    fn test_check_first_battery() {
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        set_language("en");
+
+        let (code, summary) = check(vec![battery("BAT0", 89.3), battery("BAT1", 40.0)]);
+        assert_eq!(code, ExitCode::Healthy);
+        assert_eq!(summary, "BAT0: Health 89.3% (good), Wear 10.7%, 142 cycles");
+
+        assert_eq!(check(vec![battery("BAT0", 72.0)]).0, ExitCode::Degraded);
+        assert_eq!(check(vec![battery("BAT0", 55.0)]).0, ExitCode::Critical);
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_check_without_battery() {
+        assert_eq!(check(Vec::new()).0, ExitCode::NoBattery);
+
+        // Listed but unreadable
+        let service = MockBatteryService::new(vec!["BAT0".to_string()]);
+        assert_eq!(
+            evaluate(&service, HEALTH_WARN_PERCENT, HEALTH_CRIT_PERCENT).0,
+            ExitCode::NoBattery
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Capacity trend and spreadsheet export
+//!
+//! Derives how fast a battery is losing capacity from the daily snapshots
+//! of [`crate::core::health`], and exports them to
+//! `~/.config/battery-manager/health-history.csv` so they are easy to open
+//! in a spreadsheet:
+//!
+//! ```text
+//! date,battery,charge_full,charge_full_design
+//! 2026-09-01,BAT0,4510000,5000000
+//! 2026-09-02,BAT0,4509000,5000000
+//! ```
+//!
+//! The CSV is rewritten from `health.json` each time; it is never read back.
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::core::health::{day_number, HealthHistory, HealthSnapshot};
+
+/// CSV file name in the configuration directory
+pub const HISTORY_FILE: &str = "health-history.csv";
+
+/// CSV header line
+const HEADER: &str = "date,battery,charge_full,charge_full_design";
+
+/// Snapshots must span at least this many days before a trend is shown
+pub const MIN_TREND_SPAN_DAYS: i64 = 14;
+
+/// Below this loss (% of design capacity per month), capacity is considered stable
+pub const STABLE_LOSS_PERCENT_PER_MONTH: f64 = 0.05;
+
+/// Average month length used for the monthly estimate
+const DAYS_PER_MONTH: f64 = 30.44;
+
+/// Direction of the capacity trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendDirection {
+    /// The battery is losing capacity (↓)
+    Losing,
+    /// Capacity is stable, or went up after a recalibration (→)
+    Stable,
+}
+
+impl TrendDirection {
+    /// Arrow shown next to the health percentage
+    pub const fn arrow(self) -> &'static str {
+        match self {
+            Self::Losing => "↓",
+            Self::Stable => "→",
+        }
+    }
+}
+
+/// Capacity loss estimated from the health snapshots
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapacityTrend {
+    pub direction: TrendDirection,
+    /// Capacity lost per month, in % of the design capacity (negative when gaining)
+    pub loss_percent_per_month: f64,
+}
+
+/// Returns the default CSV path
+///
+/// # Returns
+///
+/// `~/.config/battery-manager/health-history.csv`, or `None` without a config dir
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("battery-manager").join(HISTORY_FILE))
+}
+
+/// Formats the snapshots of every battery as CSV, header included
+pub fn to_csv(history: &HealthHistory) -> String {
+    let mut csv = format!("{HEADER}\n");
+    for (battery, snapshots) in history.batteries() {
+        for s in snapshots {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{}",
+                s.date,
+                crate::core::csv::escape(battery),
+                s.charge_full,
+                s.charge_full_design
+            );
+        }
+    }
+    csv
+}
+
+/// Writes the CSV export of the health history
+///
+/// # Arguments
+///
+/// * `path` - CSV path
+/// * `history` - Health history to export
+///
+/// # Errors
+///
+/// Returns the I/O error if the file cannot be written
+pub fn export(path: &Path, history: &HealthHistory) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("csv.tmp");
+    fs::write(&tmp_path, to_csv(history))?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Computes the capacity trend of a battery
+///
+/// Fits a least-squares line through `charge_full` over time, so a single
+/// noisy snapshot doesn't flip the arrow.
+///
+/// # Arguments
+///
+/// * `snapshots` - Snapshots of the battery, oldest first
+///
+/// # Returns
+///
+/// `None` until the snapshots span [`MIN_TREND_SPAN_DAYS`]
+#[allow(clippy::cast_precision_loss)]
+pub fn compute_trend(snapshots: &[HealthSnapshot]) -> Option<CapacityTrend> {
+    let points: Vec<(f64, f64)> = snapshots
+        .iter()
+        .filter_map(|s| Some((day_number(&s.date)? as f64, s.charge_full as f64)))
+        .collect();
+    let design = snapshots
+        .last()
+        .map(|s| s.charge_full_design as f64)
+        .filter(|&design| design > 0.0)?;
+
+    let first_day = points
+        .iter()
+        .map(|&(day, _)| day)
+        .fold(f64::INFINITY, f64::min);
+    let last_day = points
+        .iter()
+        .map(|&(day, _)| day)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if points.len() < 2 || last_day - first_day < MIN_TREND_SPAN_DAYS as f64 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_day = points.iter().map(|&(day, _)| day).sum::<f64>() / n;
+    let mean_full = points.iter().map(|&(_, full)| full).sum::<f64>() / n;
+    let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), &(day, full)| {
+        let dx = day - mean_day;
+        (cov + dx * (full - mean_full), var + dx * dx)
+    });
+    let slope_per_day = covariance / variance;
+
+    let loss_percent_per_month = -slope_per_day * DAYS_PER_MONTH / design * 100.0;
+    let direction = if loss_percent_per_month >= STABLE_LOSS_PERCENT_PER_MONTH {
+        TrendDirection::Losing
+    } else {
+        TrendDirection::Stable
+    };
+    Some(CapacityTrend {
+        direction,
+        loss_percent_per_month,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(date: &str, charge_full: u64) -> HealthSnapshot {
+        HealthSnapshot {
+            date: date.to_string(),
+            charge_full,
+            charge_full_design: 5_000_000,
+            cycle_count: 100,
+            health_percent: 90.0,
+        }
+    }
+
+    #[test]
+    fn test_trend_losing() {
+        // 5 000 µAh lost per week = 0.435 % of design per month
+        let snapshots: Vec<HealthSnapshot> =
+            ["2026-09-01", "2026-09-08", "2026-09-15", "2026-09-22"]
+                .iter()
+                .enumerate()
+                .map(|(i, date)| snapshot(date, 4_500_000 - 5_000 * i as u64))
+                .collect();
+
+        let trend = compute_trend(&snapshots).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Losing);
+        assert!((trend.loss_percent_per_month - 0.4349).abs() < 0.001);
+        assert_eq!(trend.direction.arrow(), "↓");
+    }
+
+    #[test]
+    fn test_trend_stable_and_recalibration() {
+        let flat = [
+            snapshot("2026-09-01", 4_500_000),
+            snapshot("2026-09-08", 4_500_100),
+            snapshot("2026-09-15", 4_499_900),
+        ];
+        let trend = compute_trend(&flat).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Stable);
+
+        // Capacity going up after a calibration cycle is not a loss
+        let gaining = [
+            snapshot("2026-09-01", 4_400_000),
+            snapshot("2026-09-22", 4_500_000),
+        ];
+        let trend = compute_trend(&gaining).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Stable);
+        assert!(trend.loss_percent_per_month < 0.0);
+    }
+
+    #[test]
+    fn test_trend_needs_enough_history() {
+        assert_eq!(compute_trend(&[]), None);
+        assert_eq!(compute_trend(&[snapshot("2026-09-01", 4_500_000)]), None);
+        let one_week = [
+            snapshot("2026-09-01", 4_500_000),
+            snapshot("2026-09-08", 4_400_000),
+        ];
+        assert_eq!(compute_trend(&one_week), None);
+    }
+
+    #[test]
+    fn test_csv_export_of_health_history() {
+        let mut history = HealthHistory::default();
+        history.record("BAT0", snapshot("2026-09-01", 4_510_000));
+        history.record("BAT0", snapshot("2026-09-02", 4_509_000));
+        history.record("BAT1", snapshot("2026-09-01", 4_000_000));
+
+        assert_eq!(
+            to_csv(&history),
+            "date,battery,charge_full,charge_full_design\n\
+             2026-09-01,BAT0,4510000,5000000\n\
+             2026-09-02,BAT0,4509000,5000000\n\
+             2026-09-01,BAT1,4000000,5000000\n"
+        );
+        assert_eq!(to_csv(&HealthHistory::default()), format!("{HEADER}\n"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Charge history for the info tab graph
+//!
+//! Keeps the most recent samples in a bounded ring buffer for drawing,
+//! and caps the on-disk history file (see `FlatFileBatteryHistory`) to a
+//! fixed retention period.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::traits::HistorySample;
+
+/// Samples kept in memory (4 hours at one sample every 5 seconds)
+pub const BUFFER_CAPACITY: usize = 2880;
+
+/// Time between samples above which the graph line is broken (suspend/resume)
+pub const MAX_GAP_SECS: u64 = 30;
+
+/// How long samples are kept in the history file
+pub const RETENTION: Duration = Duration::from_hours(7 * 24);
+
+/// Returns the current Unix timestamp in seconds
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Bounded ring buffer of history samples, oldest first
+#[derive(Debug, Clone)]
+pub struct HistoryBuffer {
+    samples: VecDeque<HistorySample>,
+    capacity: usize,
+}
+
+impl Default for HistoryBuffer {
+    fn default() -> Self {
+        Self::new(BUFFER_CAPACITY)
+    }
+}
+
+impl HistoryBuffer {
+    /// Creates an empty buffer holding at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(BUFFER_CAPACITY)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Appends a sample, dropping the oldest one when full
+    ///
+    /// Samples older than the newest one are ignored, so the buffer stays
+    /// sorted by timestamp.
+    pub fn push(&mut self, sample: HistorySample) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| sample.timestamp < last.timestamp)
+        {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Returns the number of stored samples
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the stored samples, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = &HistorySample> {
+        self.samples.iter()
+    }
+
+    /// Splits the samples into continuous runs
+    ///
+    /// # Arguments
+    ///
+    /// * `max_gap` - Largest time between two samples of the same run, in seconds
+    ///
+    /// # Returns
+    ///
+    /// Runs of samples to draw as separate lines, oldest first
+    pub fn segments(&self, max_gap: u64) -> Vec<Vec<&HistorySample>> {
+        let mut segments: Vec<Vec<&HistorySample>> = Vec::new();
+        let mut previous: Option<u64> = None;
+
+        for sample in &self.samples {
+            match (previous, segments.last_mut()) {
+                (Some(prev), Some(current)) if sample.timestamp - prev <= max_gap => {
+                    current.push(sample);
+                }
+                _ => segments.push(vec![sample]),
+            }
+            previous = Some(sample.timestamp);
+        }
+
+        segments
+    }
+}
+
+/// Keeps only history lines recorded at or after `cutoff`
+///
+/// # Arguments
+///
+/// * `text` - History file content
+/// * `cutoff` - Oldest Unix timestamp to keep
+///
+/// # Returns
+///
+/// Tuple of (kept content, number of removed lines); malformed lines are removed
+pub fn prune_lines(text: &str, cutoff: u64) -> (String, usize) {
+    let mut kept = String::with_capacity(text.len());
+    let mut removed = 0;
+
+    for line in text.lines() {
+        let recent = line
+            .split(',')
+            .next()
+            .and_then(|ts| ts.parse::<u64>().ok())
+            .is_some_and(|ts| ts >= cutoff);
+        if recent {
+            kept.push_str(line);
+            kept.push('\n');
+        } else {
+            removed += 1;
+        }
+    }
+
+    (kept, removed)
+}
+
+/// Removes samples older than `max_age` from the history file
+///
+/// # Arguments
+///
+/// * `path` - History CSV file
+/// * `max_age` - Retention period
+/// * `now` - Current Unix timestamp
+///
+/// # Returns
+///
+/// Number of removed lines (the file is left untouched when nothing is removed)
+///
+/// # Errors
+///
+/// Returns the I/O error if the file cannot be read or rewritten
+pub fn prune_file(path: &Path, max_age: Duration, now: u64) -> io::Result<usize> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let (kept, removed) = prune_lines(&text, now.saturating_sub(max_age.as_secs()));
+    if removed > 0 {
+        // Write next to the file and rename, so a crash never truncates history
+        let tmp_path = path.with_extension("csv.tmp");
+        fs::write(&tmp_path, kept)?;
+        fs::rename(&tmp_path, path)?;
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🧹 [HISTORY] Pruned {removed} old sample(s) from {}",
+            path.display()
+        ));
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, capacity: u8) -> HistorySample {
+        HistorySample {
+            timestamp,
+            capacity_percent: capacity,
+            health_percent: 100.0,
+            status: "Discharging".to_string(),
+            power_watts: Some(8.5),
+        }
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let mut buffer = HistoryBuffer::new(3);
+        for i in 0..5_u8 {
+            buffer.push(sample(u64::from(i) * 5, 90 - i));
+        }
+
+        assert_eq!(buffer.len(), 3);
+        let capacities: Vec<u8> = buffer.samples().map(|s| s.capacity_percent).collect();
+        assert_eq!(capacities, vec![88, 87, 86]);
+
+        // Out-of-order samples are ignored
+        buffer.push(sample(0, 50));
+        assert_eq!(buffer.samples().last().unwrap().capacity_percent, 86);
+    }
+
+    #[test]
+    fn test_segments_break_on_gaps() {
+        let mut buffer = HistoryBuffer::default();
+        for ts in [0, 5, 10, 3600, 3605, 7200] {
+            buffer.push(sample(ts, 50));
+        }
+
+        let lengths: Vec<usize> = buffer.segments(MAX_GAP_SECS).iter().map(Vec::len).collect();
+        assert_eq!(lengths, vec![3, 2, 1]);
+        assert!(HistoryBuffer::default().segments(MAX_GAP_SECS).is_empty());
+    }
+
+    #[test]
+    fn test_prune_lines() {
+        let text = "100,BAT0,80,99.0,Discharging,7.50\n\
+                    200,BAT0,79,99.0,Discharging,7.40\n\
+                    garbage\n\
+                    300,BAT1,50,95.0,Charging\n";
+
+        let (kept, removed) = prune_lines(text, 200);
+        assert_eq!(removed, 2);
+        assert_eq!(
+            kept,
+            "200,BAT0,79,99.0,Discharging,7.40\n300,BAT1,50,95.0,Charging\n"
+        );
+    }
+
+    #[test]
+    fn test_prune_file() {
+        let dir =
+            std::env::temp_dir().join(format!("battery-manager-prune-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.csv");
+
+        // Missing file is not an error
+        assert_eq!(prune_file(&path, RETENTION, 1_000_000).unwrap(), 0);
+
+        let now = 10 * 24 * 3600;
+        let old = now - 8 * 24 * 3600;
+        let recent = now - 3600;
+        fs::write(
+            &path,
+            format!("{old},BAT0,80,99.0,Full,0.00\n{recent},BAT0,70,99.0,Discharging,6.00\n"),
+        )
+        .unwrap();
+
+        assert_eq!(prune_file(&path, RETENTION, now).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{recent},BAT0,70,99.0,Discharging,6.00\n")
+        );
+        assert_eq!(prune_file(&path, RETENTION, now).unwrap(), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
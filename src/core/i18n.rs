@@ -1,14 +1,66 @@
 //! Internationalization module for Battery Manager
 //!
-//! Provides translation support for English and French languages.
-//! The language is set at runtime via command-line argument.
+//! Provides translation support for French, English, Brazilian Portuguese
+//! and Polish. The language is set at runtime via command-line argument or
+//! from the system locale.
+//!
+//! Lookups walk a fallback chain: a locale such as `de_AT` is tried as
+//! `de_AT`, then `de`, then `en` (see [`set_locale`]).
+//!
+//! Built-in translations can be overridden at runtime from JSON files
+//! (see [`load_extra_translations`]).
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::RwLock;
 
-/// Current language setting (default: "fr")
-static CURRENT_LANG: std::sync::LazyLock<RwLock<String>> =
-    std::sync::LazyLock::new(|| RwLock::new("fr".to_string()));
+use crate::core::json::{self, JsonError};
+
+/// Errors that can occur when loading external translation files
+#[derive(Debug)]
+pub enum I18nError {
+    /// I/O error when reading the translation file
+    IoError(std::io::Error),
+    /// Malformed JSON or unexpected structure
+    ParseError(String),
+}
+
+impl std::fmt::Display for I18nError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "I/O Error: {e}"),
+            Self::ParseError(msg) => write!(f, "Invalid translation file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for I18nError {}
+
+impl From<std::io::Error> for I18nError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+impl From<JsonError> for I18nError {
+    fn from(error: JsonError) -> Self {
+        Self::ParseError(error.0)
+    }
+}
+
+/// Runtime translation overrides, checked before the built-in dictionary
+static OVERRIDES: std::sync::LazyLock<RwLock<HashMap<String, HashMap<String, String>>>> =
+    std::sync::LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Current language fallback chain, most specific first (default: "fr", then "en")
+static CURRENT_LANG: std::sync::LazyLock<RwLock<Vec<String>>> =
+    std::sync::LazyLock::new(|| RwLock::new(vec!["fr".to_string(), FALLBACK_LANG.to_string()]));
+
+/// Last language of every fallback chain
+const FALLBACK_LANG: &str = "en";
+
+/// Supported language codes, in the order shown by the language selector
+pub const LANGUAGES: &[&str] = &["fr", "en", "pt", "pl"];
 
 /// Translation dictionary
 static TRANSLATIONS: std::sync::LazyLock<
@@ -75,7 +127,7 @@ static TRANSLATIONS: std::sync::LazyLock<
     fr.insert("charge_100", "Charger à 100%");
     fr.insert(
         "settings_applied",
-        "✓ Réglages appliqués (redémarrage requis)",
+        "✓ Réglages appliqués (redémarrage requis) : {thresholds}, alarme : {alarm}, service : {service}",
     );
     fr.insert("alarm", "Alarme");
     fr.insert("service", "Service");
@@ -148,10 +200,288 @@ static TRANSLATIONS: std::sync::LazyLock<
         "Erreur: le seuil de début doit être inférieur au seuil de fin",
     );
     fr.insert("success_applied", "Réglages appliqués avec succès");
+    fr.insert(
+        "settings_mismatch",
+        "Réglages appliqués, mais le matériel rapporte des valeurs différentes",
+    );
+    fr.insert(
+        "error_no_pkexec",
+        "pkexec introuvable, installez polkit (policykit-1)",
+    );
+    fr.insert(
+        "error_no_helper",
+        "assistant battery-manager-apply introuvable, réinstallez l'application",
+    );
+    fr.insert("select_battery", "Choisir la batterie");
+    fr.insert("preset_lifespan", "Longévité");
+    fr.insert("preset_balanced", "Équilibré");
+    fr.insert("preset_travel", "Voyage");
+    fr.insert("aggregate_capacity", "Capacité combinée");
+    fr.insert("card_profiles", "Profils");
+    fr.insert("profile_none", "— Aucun profil —");
+    fr.insert("profile_name", "Nom du profil");
+    fr.insert("profile_save", "Enregistrer");
+    fr.insert("profile_delete", "Supprimer");
+    fr.insert("profile_saved", "Profil enregistré");
+    fr.insert("profile_name_required", "Saisissez un nom de profil");
+    fr.insert(
+        "charge_100_tooltip",
+        "Porte le seuil d'arrêt à 100% pour la prochaine charge, sans l'enregistrer",
+    );
+    fr.insert(
+        "charge_100_applied",
+        "Seuil d'arrêt porté à 100% pour cette charge",
+    );
+    fr.insert(
+        "charge_100_temporary",
+        "Temporaire : le seuil enregistré reviendra au redémarrage ou au redémarrage du service",
+    );
+    fr.insert("notifications_setting", "Notifications");
+    fr.insert("notifications_enable", "Notifications de bureau");
+    fr.insert(
+        "notifications_tooltip",
+        "Prévenir quand la batterie passe sous le seuil d'alerte ou atteint le seuil de fin de charge",
+    );
+    fr.insert("notif_low_title", "Batterie faible");
+    fr.insert("notif_charged_title", "Charge terminée");
+    fr.insert(
+        "notif_charged_body",
+        "Batterie chargée à {stop} % — seuil atteint",
+    );
+    fr.insert("notif_ac_plugged_title", "Chargeur branché");
+    fr.insert("notif_ac_unplugged_title", "Chargeur débranché");
+    fr.insert("notif_applied_title", "Seuils appliqués");
+    fr.insert("notif_error_title", "Battery Manager : erreur");
+    fr.insert("notifications_low_threshold", "Alerte batterie faible (%)");
+    fr.insert("history_graph", "Historique de charge");
+    fr.insert("tray_open", "Ouvrir");
+    fr.insert("tray_apply_thresholds", "Appliquer les seuils…");
+    fr.insert("tray_quit", "Quitter");
+    fr.insert("card_behavior", "Comportement");
+    fr.insert(
+        "minimize_to_tray",
+        "Réduire dans la barre système à la fermeture",
+    );
+    fr.insert(
+        "minimize_to_tray_tooltip",
+        "Nécessite une barre système compatible StatusNotifierItem",
+    );
+    fr.insert("wear_over", "Usure sur");
+    fr.insert("days", "jours");
+    fr.insert("wear_trend_pending", "en cours de mesure");
+    fr.insert("manufacture_date", "Fabrication");
+    fr.insert("months", "mois");
+    fr.insert("keyboard_shortcuts", "Raccourcis clavier");
+    fr.insert("shortcuts_general", "Général");
+    fr.insert("shortcuts_navigation", "Navigation");
+    fr.insert("shortcut_refresh", "Actualiser les données");
+    fr.insert("shortcut_apply", "Appliquer les réglages");
+    fr.insert("shortcut_quit", "Quitter");
+    fr.insert("shortcut_preferences", "Préférences de l'interface");
+    fr.insert("shortcut_tab", "Onglet");
+    fr.insert("title_format_charging", "{app} – {percent}% ⚡ En charge");
+    fr.insert(
+        "title_format_discharging",
+        "{app} – {percent}% 🔋 Sur batterie",
+    );
+    fr.insert("title_format_full", "{app} – {percent}% ✓ Chargée");
+    fr.insert("battery_care", "Mode conservation");
+    fr.insert(
+        "battery_care_tooltip",
+        "Limite la charge pour préserver la batterie (fichier constructeur on/off)",
+    );
+    fr.insert("auto_refresh_interval", "Actualisation automatique (s)");
+    fr.insert(
+        "auto_refresh_tooltip",
+        "Intervalle entre deux lectures de la batterie (branchement secteur et changement d'état affichés immédiatement)",
+    );
+    fr.insert("accent_color", "Couleur d'accent");
+    fr.insert(
+        "accent_color_tooltip",
+        "Couleur #RRGGBB des interrupteurs et messages de réussite (Entrée pour appliquer)",
+    );
+    fr.insert("accent_reset", "Par défaut");
+    fr.insert(
+        "accent_invalid",
+        "Couleur invalide : utilisez le format #RRGGBB",
+    );
+    fr.insert("health_trend", "Évolution de la santé (historique récent)");
+    fr.insert(
+        "error_stop_out_of_range",
+        "Erreur: le seuil de fin ne peut pas dépasser 100 %",
+    );
+    fr.insert(
+        "error_start_out_of_range",
+        "Erreur: le seuil de début doit être compris entre 0 et 100 %",
+    );
+    fr.insert("confirm_apply_title", "Appliquer ces réglages ?");
+    fr.insert(
+        "confirm_apply_body",
+        "{changes}\n\nCes valeurs sont écrites avec les droits administrateur. Continuer ?",
+    );
+    fr.insert("confirm_apply_button", "Appliquer");
+    fr.insert("cancel", "Annuler");
+    fr.insert("category_mouse", "Souris");
+    fr.insert("category_keyboard", "Claviers");
+    fr.insert("category_headset", "Casques et écouteurs");
+    fr.insert("category_gamepad", "Manettes");
+    fr.insert("category_other", "Autres appareils");
+    fr.insert(
+        "capacity_trend",
+        "Charge récente (vert : en charge, orange : en décharge)",
+    );
+    fr.insert(
+        "threshold_not_applied",
+        "Seuils appliqués, mais le noyau rapporte {actual}% — le matériel ne prend peut-être pas en charge cette valeur",
+    );
+    fr.insert("charge_rate", "Évolution");
+    fr.insert("hp_bioscfg", "Pilote BIOS HP (hp-bioscfg)");
+    fr.insert(
+        "hp_bioscfg_missing",
+        "non chargé, seuls les fichiers ACPI sont utilisés",
+    );
+    fr.insert("time_to_empty", "Autonomie");
+    fr.insert(
+        "error_threshold_gap",
+        "Le seuil de début doit être au moins {gap}% sous le seuil de fin sur ce matériel",
+    );
+    fr.insert("learn_more", "En savoir plus");
+    fr.insert(
+        "recommended_defaults",
+        "Valeurs recommandées pour votre matériel",
+    );
+    fr.insert("alarm_reset", "Réinitialiser");
+    fr.insert(
+        "alarm_reset_tooltip",
+        "Efface l'alarme de décharge (0) pour revenir à la valeur par défaut du pilote",
+    );
+    fr.insert(
+        "alarm_reset_done",
+        "Alarme réinitialisée (valeur par défaut du pilote)",
+    );
+    fr.insert("card_ups", "Onduleur");
+    fr.insert("ups_outage", "Coupure secteur, sur batterie");
+    fr.insert("capacity_loss_month", "perte de {loss} % par mois");
+    fr.insert("capacity_stable", "capacité stable");
+    fr.insert(
+        "capacity_trend_pending",
+        "tendance après 2 semaines de relevés",
+    );
+    fr.insert(
+        "capacity_trend_tooltip",
+        "Capacité pleine relevée chaque jour (exportée dans health-history.csv), en % de la capacité d'origine",
+    );
+    fr.insert("service_not_installed", "Non installé");
+    fr.insert(
+        "service_missing_note",
+        "Le service battery-manager.service n'est pas installé : les seuils ne seront pas restaurés au démarrage.",
+    );
+    fr.insert("install_service", "Installer le service");
+    fr.insert(
+        "install_service_tooltip",
+        "Installe battery-manager.service et l'active avec les seuils actuels",
+    );
+    fr.insert("service_installed", "Service installé et activé");
+    fr.insert("cycle_count_singular", "{n} cycle");
+    fr.insert("cycle_count_plural", "{n} cycles");
+    fr.insert("charge_behaviour", "Mode de charge");
+    fr.insert("charge_behaviour_tooltip", "Mode du noyau (charge_behaviour) : suspendre la charge ou forcer la décharge, par exemple pour calibrer la batterie");
+    fr.insert("behaviour_auto", "Auto");
+    fr.insert("behaviour_inhibit_charge", "Suspendre la charge");
+    fr.insert("behaviour_force_discharge", "Forcer la décharge");
+    fr.insert("charge_behaviour_applied", "Mode de charge : {mode}");
+    fr.insert(
+        "reapply_on_resume",
+        "Réappliquer les seuils après la mise en veille",
+    );
+    fr.insert("reapply_on_resume_tooltip", "Certains firmwares oublient les seuils pendant la veille : au réveil, les seuils enregistrés sont réécrits s'ils ont changé (mot de passe administrateur demandé)");
+    fr.insert("resume_never", "Aucun réveil depuis le lancement");
+    fr.insert("resume_last", "Dernier réveil ({time}) : {result}");
+    fr.insert("resume_kept", "seuils conservés par le firmware");
+    fr.insert("resume_reapplied", "seuils réappliqués");
+    fr.insert("resume_failed", "échec de la réapplication ({error})");
+    fr.insert("applying", "Application des réglages…");
+    fr.insert("change_line", "• {setting} : {old} → {new}");
+    fr.insert("no_changes", "Aucun changement");
+    fr.insert("health_verdict_good", "bonne");
+    fr.insert("health_verdict_degraded", "dégradée");
+    fr.insert("health_verdict_critical", "critique");
+    fr.insert("kernel_too_old", "Le pilote de seuils de ce constructeur demande un noyau {required} ou plus récent (noyau actuel : {running}) : les fichiers de seuils peuvent être absents.");
+    fr.insert("udev_rule", "Règle udev");
+    fr.insert("persist_udev_rule", "Règle udev (sans service systemd)");
+    fr.insert("persist_udev_rule_tooltip", "Installe /etc/udev/rules.d/99-battery-manager.rules, qui réécrit le seuil de fin à chaque apparition de la batterie (démarrage compris). Remplace le service systemd.");
+    fr.insert("updated_at", "Mis à jour à {time}");
+    fr.insert(
+        "reset_done",
+        "Seuils de {battery} remis à 0 / 100 % (non enregistrés)",
+    );
+    fr.insert(
+        "reset_unsupported",
+        "{battery} : aucun fichier de seuil détecté",
+    );
+    fr.insert(
+        "notif_critical_title",
+        "Batterie critique : branchez le chargeur",
+    );
+    fr.insert("notifications_critical", "Batterie critique (< 5 %)");
+    fr.insert("notifications_events", "Notifier :");
+    fr.insert("hours_singular", "{n} heure");
+    fr.insert("hours_plural", "{n} heures");
+    fr.insert("diagnostic_report", "Rapport de diagnostic");
+    fr.insert(
+        "diagnostic_redact",
+        "Masquer les numéros de série et adresses Bluetooth",
+    );
+    fr.insert("diagnostic_copy", "Copier");
+    fr.insert("diagnostic_save", "Enregistrer…");
+    fr.insert("diagnostic_copied", "Rapport copié dans le presse-papiers");
+    fr.insert("diagnostic_saved", "Rapport enregistré : {path}");
+    fr.insert(
+        "diagnostic_save_failed",
+        "Impossible d'enregistrer le rapport : {error}",
+    );
+    fr.insert("notifications_power_source", "Branchement du chargeur");
+    fr.insert("calibration_button", "Calibrer…");
+    fr.insert(
+        "calibration_tooltip",
+        "Cycle complet guidé (décharge à 5 %, puis charge à 100 %) pour recaler la jauge",
+    );
+    fr.insert("calibration_title", "Calibration de la batterie");
+    fr.insert("calibration_intro", "La jauge de la batterie dérive avec le temps. La calibration la décharge jusqu'à 5 % (débranchez le chargeur si le mode « force-discharge » n'est pas disponible), puis la recharge à 100 %. Vos seuils sont ensuite rétablis automatiquement.");
+    fr.insert("calibration_idle", "Démarrage…");
+    fr.insert("calibration_discharging", "Décharge jusqu'à 5 % en cours…");
+    fr.insert("calibration_charging", "Charge jusqu'à 100 % en cours…");
+    fr.insert("calibration_done", "Calibration terminée, seuils rétablis");
+    fr.insert("calibration_not_running", "Aucune calibration en cours");
+    fr.insert(
+        "calibration_other_battery",
+        "Calibration en cours sur {battery}",
+    );
+    fr.insert("calibration_start", "Démarrer");
+    fr.insert("calibration_stop", "Arrêter et rétablir les seuils");
+    fr.insert("calibration_failed", "Échec : {error}");
+    fr.insert("grade_a_description", "Excellent état, très peu d'usure");
+    fr.insert("grade_b_description", "Bon état, usure normale");
+    fr.insert("grade_c_description", "Usure marquée, autonomie réduite");
+    fr.insert(
+        "grade_d_description",
+        "Batterie très usée, remplacement conseillé",
+    );
+    fr.insert(
+        "health_grade_tooltip",
+        "Note d'usure : A ≥ 90 %, B 75–89 %, C 60–74 %, D < 60 % de la capacité d'origine",
+    );
+    fr.insert("export_csv", "Exporter en CSV");
+    fr.insert("export_csv_saved", "Exporté vers {path}");
+    fr.insert("export_csv_failed", "Échec de l'export CSV : {error}");
+    fr.insert("requested", "demandé");
+    fr.insert("actual", "obtenu");
     fr.insert("error_execution", "Erreur lors de l'exécution");
     fr.insert("language_setting", "Langue de l'interface");
     fr.insert("language_fr", "Français");
     fr.insert("language_en", "English");
+    fr.insert("language_pt", "Português");
+    fr.insert("language_pl", "Polski");
     fr.insert(
         "language_changed",
         "Langue modifiée. Redémarrez l'application pour appliquer le changement.",
@@ -243,7 +573,10 @@ static TRANSLATIONS: std::sync::LazyLock<
     en.insert("service_settings", "🔧 Systemd Service");
     en.insert("enable_service", "Enable automatic restoration at boot");
     en.insert("charge_100", "Charge to 100%");
-    en.insert("settings_applied", "✓ Settings applied (reboot required)");
+    en.insert(
+        "settings_applied",
+        "✓ Settings applied (reboot required): {thresholds}, alarm: {alarm}, service: {service}",
+    );
     en.insert("alarm", "Alarm");
     en.insert("service", "Service");
     en.insert("enabled", "enabled");
@@ -312,6 +645,261 @@ static TRANSLATIONS: std::sync::LazyLock<
         "Error: start threshold must be lower than stop threshold",
     );
     en.insert("success_applied", "Settings applied successfully");
+    en.insert(
+        "settings_mismatch",
+        "Settings applied, but the hardware reports different values",
+    );
+    en.insert(
+        "error_no_pkexec",
+        "pkexec not installed, install polkit (policykit-1)",
+    );
+    en.insert(
+        "error_no_helper",
+        "battery-manager-apply helper not found, reinstall the application",
+    );
+    en.insert("select_battery", "Select battery");
+    en.insert("preset_lifespan", "Lifespan");
+    en.insert("preset_balanced", "Balanced");
+    en.insert("preset_travel", "Travel");
+    en.insert("aggregate_capacity", "Combined capacity");
+    en.insert("card_profiles", "Profiles");
+    en.insert("profile_none", "— No profile —");
+    en.insert("profile_name", "Profile name");
+    en.insert("profile_save", "Save");
+    en.insert("profile_delete", "Delete");
+    en.insert("profile_saved", "Profile saved");
+    en.insert("profile_name_required", "Enter a profile name");
+    en.insert(
+        "charge_100_tooltip",
+        "Raises the stop threshold to 100% for the next charge, without saving it",
+    );
+    en.insert(
+        "charge_100_applied",
+        "Stop threshold raised to 100% for this charge",
+    );
+    en.insert(
+        "charge_100_temporary",
+        "Temporary: the saved threshold comes back on reboot or service restart",
+    );
+    en.insert("notifications_setting", "Notifications");
+    en.insert("notifications_enable", "Desktop notifications");
+    en.insert(
+        "notifications_tooltip",
+        "Notify when the battery drops below the alert level or reaches the charge stop threshold",
+    );
+    en.insert("notif_low_title", "Battery low");
+    en.insert("notif_charged_title", "Charging complete");
+    en.insert(
+        "notif_charged_body",
+        "Battery charged to {stop}% — threshold reached",
+    );
+    en.insert("notif_ac_plugged_title", "Charger plugged in");
+    en.insert("notif_ac_unplugged_title", "Charger unplugged");
+    en.insert("notif_applied_title", "Thresholds applied");
+    en.insert("notif_error_title", "Battery Manager: error");
+    en.insert("notifications_low_threshold", "Low battery alert (%)");
+    en.insert("history_graph", "Charge history");
+    en.insert("tray_open", "Open");
+    en.insert("tray_apply_thresholds", "Apply thresholds…");
+    en.insert("tray_quit", "Quit");
+    en.insert("card_behavior", "Behavior");
+    en.insert("minimize_to_tray", "Minimize to tray on close");
+    en.insert(
+        "minimize_to_tray_tooltip",
+        "Requires a system tray supporting StatusNotifierItem",
+    );
+    en.insert("wear_over", "Wear over");
+    en.insert("days", "days");
+    en.insert("wear_trend_pending", "measuring");
+    en.insert("manufacture_date", "Manufactured");
+    en.insert("months", "months");
+    en.insert("keyboard_shortcuts", "Keyboard Shortcuts");
+    en.insert("shortcuts_general", "General");
+    en.insert("shortcuts_navigation", "Navigation");
+    en.insert("shortcut_refresh", "Refresh battery data");
+    en.insert("shortcut_apply", "Apply settings");
+    en.insert("shortcut_quit", "Quit");
+    en.insert("shortcut_preferences", "Interface preferences");
+    en.insert("shortcut_tab", "Tab");
+    en.insert("title_format_charging", "{app} – {percent}% ⚡ Charging");
+    en.insert(
+        "title_format_discharging",
+        "{app} – {percent}% 🔋 Discharging",
+    );
+    en.insert("title_format_full", "{app} – {percent}% ✓ Full");
+    en.insert("battery_care", "Conservation mode");
+    en.insert(
+        "battery_care_tooltip",
+        "Limits charging to preserve the battery (vendor on/off file)",
+    );
+    en.insert("auto_refresh_interval", "Auto-refresh interval (s)");
+    en.insert(
+        "auto_refresh_tooltip",
+        "Time between two battery readings (AC plug and status changes show up immediately)",
+    );
+    en.insert("accent_color", "Accent color");
+    en.insert(
+        "accent_color_tooltip",
+        "#RRGGBB color of switches and success messages (press Enter to apply)",
+    );
+    en.insert("accent_reset", "Default");
+    en.insert("accent_invalid", "Invalid color: use the #RRGGBB format");
+    en.insert("health_trend", "Health trend (recent history)");
+    en.insert(
+        "error_stop_out_of_range",
+        "Error: stop threshold cannot exceed 100%",
+    );
+    en.insert(
+        "error_start_out_of_range",
+        "Error: start threshold must be between 0 and 100%",
+    );
+    en.insert("confirm_apply_title", "Apply these settings?");
+    en.insert(
+        "confirm_apply_body",
+        "{changes}\n\nThese values are written with administrator rights. Continue?",
+    );
+    en.insert("confirm_apply_button", "Apply");
+    en.insert("cancel", "Cancel");
+    en.insert("category_mouse", "Mice");
+    en.insert("category_keyboard", "Keyboards");
+    en.insert("category_headset", "Headsets and earbuds");
+    en.insert("category_gamepad", "Gamepads");
+    en.insert("category_other", "Other devices");
+    en.insert(
+        "capacity_trend",
+        "Recent charge (green: charging, orange: discharging)",
+    );
+    en.insert(
+        "threshold_not_applied",
+        "Thresholds applied but kernel reports {actual}% — hardware may not support this value",
+    );
+    en.insert("charge_rate", "Rate");
+    en.insert("hp_bioscfg", "HP BIOS driver (hp-bioscfg)");
+    en.insert("hp_bioscfg_missing", "not loaded, only ACPI files are used");
+    en.insert("time_to_empty", "Time left");
+    en.insert(
+        "error_threshold_gap",
+        "Start threshold must be at least {gap}% below the stop threshold on this hardware",
+    );
+    en.insert("learn_more", "Learn more");
+    en.insert(
+        "recommended_defaults",
+        "Recommended defaults for your hardware",
+    );
+    en.insert("alarm_reset", "Reset");
+    en.insert(
+        "alarm_reset_tooltip",
+        "Clears the discharge alarm (0) to go back to the driver default",
+    );
+    en.insert("alarm_reset_done", "Alarm reset (driver default)");
+    en.insert("card_ups", "UPS");
+    en.insert("ups_outage", "Mains outage, on battery");
+    en.insert("capacity_loss_month", "{loss}% lost per month");
+    en.insert("capacity_stable", "capacity stable");
+    en.insert("capacity_trend_pending", "trend after 2 weeks of records");
+    en.insert(
+        "capacity_trend_tooltip",
+        "Full capacity recorded daily (exported to health-history.csv), in % of the design capacity",
+    );
+    en.insert("service_not_installed", "Not installed");
+    en.insert(
+        "service_missing_note",
+        "battery-manager.service is not installed: thresholds won't be restored at boot.",
+    );
+    en.insert("install_service", "Install the service");
+    en.insert(
+        "install_service_tooltip",
+        "Installs battery-manager.service and enables it with the current thresholds",
+    );
+    en.insert("service_installed", "Service installed and enabled");
+    en.insert("cycle_count_singular", "{n} cycle");
+    en.insert("cycle_count_plural", "{n} cycles");
+    en.insert("charge_behaviour", "Charge behaviour");
+    en.insert("charge_behaviour_tooltip", "Kernel mode (charge_behaviour): pause charging or force a discharge, e.g. to calibrate the battery");
+    en.insert("behaviour_auto", "Auto");
+    en.insert("behaviour_inhibit_charge", "Inhibit charge");
+    en.insert("behaviour_force_discharge", "Force discharge");
+    en.insert("charge_behaviour_applied", "Charge behaviour: {mode}");
+    en.insert("reapply_on_resume", "Re-apply thresholds after suspend");
+    en.insert("reapply_on_resume_tooltip", "Some firmwares forget the thresholds while suspended: on resume, the saved thresholds are written again if they changed (asks for the administrator password)");
+    en.insert("resume_never", "No resume since the app started");
+    en.insert("resume_last", "Last resume ({time}): {result}");
+    en.insert("resume_kept", "thresholds kept by the firmware");
+    en.insert("resume_reapplied", "thresholds re-applied");
+    en.insert("resume_failed", "re-apply failed ({error})");
+    en.insert("applying", "Applying settings…");
+    en.insert("change_line", "• {setting}: {old} → {new}");
+    en.insert("no_changes", "No changes");
+    en.insert("health_verdict_good", "good");
+    en.insert("health_verdict_degraded", "degraded");
+    en.insert("health_verdict_critical", "critical");
+    en.insert("kernel_too_old", "This vendor's threshold driver needs kernel {required} or newer (running: {running}), so the threshold files may be missing.");
+    en.insert("udev_rule", "udev rule");
+    en.insert("persist_udev_rule", "udev rule (no systemd service)");
+    en.insert("persist_udev_rule_tooltip", "Installs /etc/udev/rules.d/99-battery-manager.rules, which writes the stop threshold again each time the battery appears (including at boot). Replaces the systemd service.");
+    en.insert("updated_at", "Updated {time}");
+    en.insert(
+        "reset_done",
+        "{battery} thresholds reset to 0 / 100% (not saved)",
+    );
+    en.insert("reset_unsupported", "{battery}: no threshold file detected");
+    en.insert("notif_critical_title", "Critical: plug in now");
+    en.insert("notifications_critical", "Critical battery (< 5%)");
+    en.insert("notifications_events", "Notify:");
+    en.insert("hours_singular", "{n} hour");
+    en.insert("hours_plural", "{n} hours");
+    en.insert("diagnostic_report", "Diagnostic report");
+    en.insert(
+        "diagnostic_redact",
+        "Hide serial numbers and Bluetooth addresses",
+    );
+    en.insert("diagnostic_copy", "Copy");
+    en.insert("diagnostic_save", "Save…");
+    en.insert("diagnostic_copied", "Report copied to the clipboard");
+    en.insert("diagnostic_saved", "Report saved: {path}");
+    en.insert("diagnostic_save_failed", "Cannot save the report: {error}");
+    en.insert("notifications_power_source", "Charger plugged/unplugged");
+    en.insert("calibration_button", "Calibrate…");
+    en.insert(
+        "calibration_tooltip",
+        "Guided full cycle (discharge to 5%, then charge to 100%) to recalibrate the gauge",
+    );
+    en.insert("calibration_title", "Battery calibration");
+    en.insert("calibration_intro", "The battery gauge drifts over time. Calibration discharges the battery down to 5% (unplug the charger if the force-discharge mode isn't available), then charges it to 100%. Your thresholds are then restored automatically.");
+    en.insert("calibration_idle", "Starting…");
+    en.insert("calibration_discharging", "Discharging to 5%…");
+    en.insert("calibration_charging", "Charging to 100%…");
+    en.insert(
+        "calibration_done",
+        "Calibration complete, thresholds restored",
+    );
+    en.insert("calibration_not_running", "No calibration in progress");
+    en.insert(
+        "calibration_other_battery",
+        "Calibration in progress on {battery}",
+    );
+    en.insert("calibration_start", "Start");
+    en.insert("calibration_stop", "Stop and restore thresholds");
+    en.insert("calibration_failed", "Failed: {error}");
+    en.insert(
+        "grade_a_description",
+        "Excellent condition, very little wear",
+    );
+    en.insert("grade_b_description", "Good condition, normal wear");
+    en.insert("grade_c_description", "Noticeable wear, reduced runtime");
+    en.insert(
+        "grade_d_description",
+        "Heavily worn battery, replacement advised",
+    );
+    en.insert(
+        "health_grade_tooltip",
+        "Wear grade: A ≥ 90%, B 75–89%, C 60–74%, D < 60% of the original capacity",
+    );
+    en.insert("export_csv", "Export to CSV");
+    en.insert("export_csv_saved", "Exported to {path}");
+    en.insert("export_csv_failed", "CSV export failed: {error}");
+    en.insert("requested", "requested");
+    en.insert("actual", "actual");
     en.insert("theme_setting", "Interface Theme");
     en.insert("theme_light", "Light");
     en.insert("theme_dark", "Dark");
@@ -340,6 +928,8 @@ static TRANSLATIONS: std::sync::LazyLock<
     en.insert("language_setting", "Interface Language");
     en.insert("language_fr", "Français");
     en.insert("language_en", "English");
+    en.insert("language_pt", "Português");
+    en.insert("language_pl", "Polski");
     en.insert(
         "language_changed",
         "Language changed. Restart the application to apply.",
@@ -348,43 +938,1009 @@ static TRANSLATIONS: std::sync::LazyLock<
 
     map.insert("en", en);
 
+    // Brazilian Portuguese translations
+    let mut pt = HashMap::new();
+    pt.insert("app_title", "Gerenciador de Bateria");
+    pt.insert("info_tab", "📊 Informações");
+    pt.insert("settings_tab", "⚙️ Configurações");
+
+    // Info tab
+    pt.insert("power_source", "🔌 Fonte de Energia");
+    pt.insert("on_ac", "Na tomada");
+    pt.insert("on_battery", "Na bateria");
+    pt.insert("battery_status", "⚡ Estado da Bateria");
+    pt.insert("charging", "Carregando");
+    pt.insert("discharging", "Descarregando");
+    pt.insert("full", "Cheio");
+    pt.insert("not_charging", "Não está carregando");
+    pt.insert("unknown", "Desconhecido");
+    pt.insert("charge_level", "🔋 Nível de Carga");
+    pt.insert("battery_health", "💚 Saúde da Bateria");
+    pt.insert("electrical_params", "⚡ Parâmetros Elétricos");
+    pt.insert("voltage", "Tensão");
+    pt.insert("capacity", "Capacidade");
+    pt.insert("capacity_level", "Nível");
+    pt.insert("status", "Estado");
+    pt.insert("connection", "Conexão");
+    pt.insert("current", "Corrente");
+    pt.insert("power", "Potência");
+    pt.insert("system_info", "🖥️ Informações do Sistema");
+    pt.insert("manufacturer", "Fabricante");
+    pt.insert("model", "Modelo");
+    pt.insert("technology", "Tecnologia");
+    pt.insert("capacity_info", "📊 Informações de Capacidade");
+    pt.insert("current_cap", "Atual");
+    pt.insert("design_cap", "Projetada");
+    pt.insert("charge_thresholds", "🎯 Limites de Carga");
+    pt.insert("start_threshold", "Início");
+    pt.insert("stop_threshold", "Parada");
+    pt.insert("discharge_alarm", "⚠️ Alarme de Descarga");
+    pt.insert("systemd_service", "🔧 Serviço Systemd");
+    pt.insert("service_active", "Ativo");
+    pt.insert("service_inactive", "Inativo");
+
+    // Settings tab
+    pt.insert("vendor_info", "🏭 Informações do Sistema");
+    pt.insert("product_name", "Modelo");
+    pt.insert("start_support", "Limite de início");
+    pt.insert("stop_support", "Limite de parada");
+    pt.insert("charge_settings", "⚙️ Limites de Carga");
+    pt.insert("start_threshold_pct", "Limite de início (%)");
+    pt.insert("stop_threshold_pct", "Limite de parada (%)");
+    pt.insert("alarm_settings", "⚠️ Alarme de Descarga");
+    pt.insert("alarm_threshold", "Limite do alarme (%)");
+    pt.insert("service_settings", "🔧 Serviço Systemd");
+    pt.insert(
+        "enable_service",
+        "Ativar a restauração automática na inicialização",
+    );
+    pt.insert("charge_100", "Carregar até 100%");
+    pt.insert(
+        "settings_applied",
+        "✓ Configurações aplicadas (reinicialização necessária): {thresholds}, alarme: {alarm}, serviço: {service}",
+    );
+    pt.insert("alarm", "Alarme");
+    pt.insert("service", "Serviço");
+    pt.insert("enabled", "ativado");
+    pt.insert("disabled", "desativado");
+    pt.insert("error", "Erro");
+    pt.insert("exec_error", "Erro de execução");
+    pt.insert("auth_canceled", "Autenticação cancelada");
+    pt.insert("no_battery", "Nenhuma bateria detectada neste sistema");
+    pt.insert("error_battery_init", "Erro ao criar BatteryInfo");
+    pt.insert("tab_info", "Informações");
+    pt.insert("tab_settings", "Configurações");
+    pt.insert("tab_ui", "Interface");
+    pt.insert("tab_peripherals", "Periféricos");
+    pt.insert("card_thresholds", "Limites");
+    pt.insert("card_charge", "Carga");
+    pt.insert("card_health", "Saúde");
+    pt.insert("card_power", "Energia");
+    pt.insert("card_status", "Estado");
+    pt.insert("card_battery", "Bateria");
+    pt.insert("card_electrical", "Elétrica");
+    pt.insert("card_capacity", "Capacidade");
+    pt.insert("card_service", "Serviço");
+    pt.insert("card_peripherals", "Periférico");
+    pt.insert("card_info", "Informações");
+    pt.insert("card_battery_status", "Estado da Bateria");
+    pt.insert("card_system_info", "Informações do Sistema");
+    pt.insert("card_threshold_settings", "Limites de Carga");
+    pt.insert("card_service_manager", "Serviço do Battery Manager");
+    pt.insert("threshold_start", "Início da carga");
+    pt.insert("threshold_stop", "Parada da carga");
+    pt.insert("threshold_start_pct", "Limite de início (%)");
+    pt.insert("threshold_stop_pct", "Limite de parada (%)");
+    pt.insert("connected", "✓ Conectado");
+    pt.insert("disconnected", "✗ Desconectado");
+    pt.insert("device_type", "Tipo");
+    pt.insert("device_scope", "Escopo");
+    pt.insert("serial_number", "Número de série");
+    pt.insert("wear", "Desgaste");
+    pt.insert("cycles", "Ciclos");
+    pt.insert("adapter", "Adaptador");
+    pt.insert("name", "Nome");
+    pt.insert("type", "Tipo");
+    pt.insert("current_capacity", "Atual");
+    pt.insert("full_capacity", "Cheia");
+    pt.insert("design_capacity", "Projetada");
+    pt.insert("enable_systemd_service", "Ativar o serviço systemd");
+    pt.insert(
+        "note_enabled",
+        "<b>Ativado:</b> aplica os limites imediatamente e de forma persistente",
+    );
+    pt.insert(
+        "note_disabled",
+        "<b>Desativado:</b> aplica os limites imediatamente, mas as alterações serão perdidas após reiniciar",
+    );
+    pt.insert(
+        "note_apply_required",
+        "<b>Importante:</b> as configurações só são aplicadas após clicar no botão <i>Aplicar</i>.",
+    );
+    pt.insert(
+        "warning_not_persistent",
+        "⚠️ Sem o serviço, estas configurações serão perdidas após reiniciar.",
+    );
+    pt.insert("apply_all_settings", "Aplicar todas as configurações");
+    pt.insert(
+        "error_start_greater_stop",
+        "Erro: o limite de início deve ser menor que o limite de parada",
+    );
+    pt.insert("success_applied", "Configurações aplicadas com sucesso");
+    pt.insert(
+        "settings_mismatch",
+        "Configurações aplicadas, mas o hardware informa valores diferentes",
+    );
+    pt.insert(
+        "error_no_pkexec",
+        "pkexec não encontrado, instale o polkit (policykit-1)",
+    );
+    pt.insert(
+        "error_no_helper",
+        "Auxiliar battery-manager-apply não encontrado, reinstale o aplicativo",
+    );
+    pt.insert("select_battery", "Selecionar bateria");
+    pt.insert("preset_lifespan", "Longevidade");
+    pt.insert("preset_balanced", "Equilibrado");
+    pt.insert("preset_travel", "Viagem");
+    pt.insert("aggregate_capacity", "Capacidade combinada");
+    pt.insert("card_profiles", "Perfis");
+    pt.insert("profile_none", "— Nenhum perfil —");
+    pt.insert("profile_name", "Nome do perfil");
+    pt.insert("profile_save", "Salvar");
+    pt.insert("profile_delete", "Excluir");
+    pt.insert("profile_saved", "Perfil salvo");
+    pt.insert("profile_name_required", "Digite um nome de perfil");
+    pt.insert(
+        "charge_100_tooltip",
+        "Eleva o limite de parada para 100% na próxima carga, sem salvá-lo",
+    );
+    pt.insert(
+        "charge_100_applied",
+        "Limite de parada elevado para 100% nesta carga",
+    );
+    pt.insert(
+        "charge_100_temporary",
+        "Temporário: o limite salvo volta ao reiniciar ou ao reiniciar o serviço",
+    );
+    pt.insert("notifications_setting", "Notificações");
+    pt.insert("notifications_enable", "Notificações na área de trabalho");
+    pt.insert(
+        "notifications_tooltip",
+        "Notificar quando a bateria cair abaixo do nível de alerta ou atingir o limite de parada da carga",
+    );
+    pt.insert("notif_low_title", "Bateria fraca");
+    pt.insert("notif_charged_title", "Carga concluída");
+    pt.insert(
+        "notif_charged_body",
+        "Bateria carregada a {stop}% — limite atingido",
+    );
+    pt.insert("notif_ac_plugged_title", "Carregador conectado");
+    pt.insert("notif_ac_unplugged_title", "Carregador desconectado");
+    pt.insert("notif_applied_title", "Limites aplicados");
+    pt.insert("notif_error_title", "Battery Manager: erro");
+    pt.insert("notifications_low_threshold", "Alerta de bateria fraca (%)");
+    pt.insert("history_graph", "Histórico de carga");
+    pt.insert("tray_open", "Abrir");
+    pt.insert("tray_apply_thresholds", "Aplicar limites…");
+    pt.insert("tray_quit", "Sair");
+    pt.insert("card_behavior", "Comportamento");
+    pt.insert("minimize_to_tray", "Minimizar para a bandeja ao fechar");
+    pt.insert(
+        "minimize_to_tray_tooltip",
+        "Requer uma bandeja do sistema compatível com StatusNotifierItem",
+    );
+    pt.insert("wear_over", "Desgaste em");
+    pt.insert("days", "dias");
+    pt.insert("wear_trend_pending", "medindo");
+    pt.insert("manufacture_date", "Fabricação");
+    pt.insert("months", "meses");
+    pt.insert("keyboard_shortcuts", "Atalhos de Teclado");
+    pt.insert("shortcuts_general", "Geral");
+    pt.insert("shortcuts_navigation", "Navegação");
+    pt.insert("shortcut_refresh", "Atualizar os dados da bateria");
+    pt.insert("shortcut_apply", "Aplicar as configurações");
+    pt.insert("shortcut_quit", "Sair");
+    pt.insert("shortcut_preferences", "Preferências da interface");
+    pt.insert("shortcut_tab", "Aba");
+    pt.insert("title_format_charging", "{app} – {percent}% ⚡ Carregando");
+    pt.insert(
+        "title_format_discharging",
+        "{app} – {percent}% 🔋 Descarregando",
+    );
+    pt.insert("title_format_full", "{app} – {percent}% ✓ Cheio");
+    pt.insert("battery_care", "Modo de conservação");
+    pt.insert(
+        "battery_care_tooltip",
+        "Limita a carga para preservar a bateria (arquivo liga/desliga do fabricante)",
+    );
+    pt.insert(
+        "auto_refresh_interval",
+        "Intervalo de atualização automática (s)",
+    );
+    pt.insert(
+        "auto_refresh_tooltip",
+        "Tempo entre duas leituras da bateria (conexão à tomada e mudanças de estado aparecem imediatamente)",
+    );
+    pt.insert("accent_color", "Cor de destaque");
+    pt.insert(
+        "accent_color_tooltip",
+        "Cor #RRGGBB dos interruptores e mensagens de sucesso (pressione Enter para aplicar)",
+    );
+    pt.insert("accent_reset", "Padrão");
+    pt.insert("accent_invalid", "Cor inválida: use o formato #RRGGBB");
+    pt.insert("health_trend", "Tendência da saúde (histórico recente)");
+    pt.insert(
+        "error_stop_out_of_range",
+        "Erro: o limite de parada não pode passar de 100%",
+    );
+    pt.insert(
+        "error_start_out_of_range",
+        "Erro: o limite de início deve estar entre 0 e 100%",
+    );
+    pt.insert("confirm_apply_title", "Aplicar estas configurações?");
+    pt.insert(
+        "confirm_apply_body",
+        "{changes}\n\nEstes valores são gravados com direitos de administrador. Continuar?",
+    );
+    pt.insert("confirm_apply_button", "Aplicar");
+    pt.insert("cancel", "Cancelar");
+    pt.insert("category_mouse", "Mouses");
+    pt.insert("category_keyboard", "Teclados");
+    pt.insert("category_headset", "Headsets e fones");
+    pt.insert("category_gamepad", "Controles");
+    pt.insert("category_other", "Outros dispositivos");
+    pt.insert(
+        "capacity_trend",
+        "Carga recente (verde: carregando, laranja: descarregando)",
+    );
+    pt.insert(
+        "threshold_not_applied",
+        "Limites aplicados, mas o kernel informa {actual}% — o hardware pode não suportar este valor",
+    );
+    pt.insert("charge_rate", "Taxa");
+    pt.insert("hp_bioscfg", "Driver BIOS HP (hp-bioscfg)");
+    pt.insert(
+        "hp_bioscfg_missing",
+        "não carregado, apenas os arquivos ACPI são usados",
+    );
+    pt.insert("time_to_empty", "Tempo restante");
+    pt.insert(
+        "error_threshold_gap",
+        "O limite de início deve ficar pelo menos {gap}% abaixo do limite de parada neste hardware",
+    );
+    pt.insert("learn_more", "Saiba mais");
+    pt.insert(
+        "recommended_defaults",
+        "Valores recomendados para o seu hardware",
+    );
+    pt.insert("alarm_reset", "Redefinir");
+    pt.insert(
+        "alarm_reset_tooltip",
+        "Apaga o alarme de descarga (0) para voltar ao padrão do driver",
+    );
+    pt.insert("alarm_reset_done", "Alarme redefinido (padrão do driver)");
+    pt.insert("card_ups", "Nobreak");
+    pt.insert("ups_outage", "Queda de energia, na bateria");
+    pt.insert("capacity_loss_month", "perda de {loss}% por mês");
+    pt.insert("capacity_stable", "capacidade estável");
+    pt.insert(
+        "capacity_trend_pending",
+        "tendência após 2 semanas de registros",
+    );
+    pt.insert(
+        "capacity_trend_tooltip",
+        "Capacidade total registrada diariamente (exportada para health-history.csv), em % da capacidade de projeto",
+    );
+    pt.insert("service_not_installed", "Não instalado");
+    pt.insert(
+        "service_missing_note",
+        "O serviço battery-manager.service não está instalado: os limites não serão restaurados na inicialização.",
+    );
+    pt.insert("install_service", "Instalar o serviço");
+    pt.insert(
+        "install_service_tooltip",
+        "Instala o battery-manager.service e o ativa com os limites atuais",
+    );
+    pt.insert("service_installed", "Serviço instalado e ativado");
+    pt.insert("cycle_count_singular", "{n} ciclo");
+    pt.insert("cycle_count_plural", "{n} ciclos");
+    pt.insert("charge_behaviour", "Modo de carga");
+    pt.insert("charge_behaviour_tooltip", "Modo do kernel (charge_behaviour): pausar a carga ou forçar a descarga, por exemplo para calibrar a bateria");
+    pt.insert("behaviour_auto", "Auto");
+    pt.insert("behaviour_inhibit_charge", "Pausar carga");
+    pt.insert("behaviour_force_discharge", "Forçar descarga");
+    pt.insert("charge_behaviour_applied", "Modo de carga: {mode}");
+    pt.insert("reapply_on_resume", "Reaplicar os limites após a suspensão");
+    pt.insert("reapply_on_resume_tooltip", "Alguns firmwares esquecem os limites durante a suspensão: ao retomar, os limites salvos são gravados de novo se mudaram (pede a senha de administrador)");
+    pt.insert(
+        "resume_never",
+        "Nenhuma retomada desde a abertura do aplicativo",
+    );
+    pt.insert("resume_last", "Última retomada ({time}): {result}");
+    pt.insert("resume_kept", "limites mantidos pelo firmware");
+    pt.insert("resume_reapplied", "limites reaplicados");
+    pt.insert("resume_failed", "falha ao reaplicar ({error})");
+    pt.insert("applying", "Aplicando as configurações…");
+    pt.insert("change_line", "• {setting}: {old} → {new}");
+    pt.insert("no_changes", "Nenhuma alteração");
+    pt.insert("health_verdict_good", "boa");
+    pt.insert("health_verdict_degraded", "degradada");
+    pt.insert("health_verdict_critical", "crítica");
+    pt.insert("kernel_too_old", "O driver de limites deste fabricante requer o kernel {required} ou mais recente (atual: {running}): os arquivos de limites podem estar ausentes.");
+    pt.insert("udev_rule", "Regra udev");
+    pt.insert("persist_udev_rule", "Regra udev (sem serviço systemd)");
+    pt.insert("persist_udev_rule_tooltip", "Instala /etc/udev/rules.d/99-battery-manager.rules, que grava novamente o limite de parada sempre que a bateria aparece (inclusive na inicialização). Substitui o serviço systemd.");
+    pt.insert("updated_at", "Atualizado às {time}");
+    pt.insert(
+        "reset_done",
+        "Limites de {battery} redefinidos para 0 / 100% (não salvos)",
+    );
+    pt.insert(
+        "reset_unsupported",
+        "{battery}: nenhum arquivo de limite detectado",
+    );
+    pt.insert("notif_critical_title", "Crítico: ligue o carregador agora");
+    pt.insert("notifications_critical", "Bateria crítica (< 5%)");
+    pt.insert("notifications_events", "Notificar:");
+    pt.insert("hours_singular", "{n} hora");
+    pt.insert("hours_plural", "{n} horas");
+    pt.insert("diagnostic_report", "Relatório de diagnóstico");
+    pt.insert(
+        "diagnostic_redact",
+        "Ocultar números de série e endereços Bluetooth",
+    );
+    pt.insert("diagnostic_copy", "Copiar");
+    pt.insert("diagnostic_save", "Guardar…");
+    pt.insert(
+        "diagnostic_copied",
+        "Relatório copiado para a área de transferência",
+    );
+    pt.insert("diagnostic_saved", "Relatório guardado: {path}");
+    pt.insert(
+        "diagnostic_save_failed",
+        "Não foi possível guardar o relatório: {error}",
+    );
+    pt.insert("notifications_power_source", "Conexão do carregador");
+    pt.insert("calibration_button", "Calibrar…");
+    pt.insert("calibration_tooltip", "Ciclo completo guiado (descarga até 5%, depois carga até 100%) para recalibrar o indicador");
+    pt.insert("calibration_title", "Calibração da bateria");
+    pt.insert("calibration_intro", "O indicador da bateria desvia com o tempo. A calibração descarrega a bateria até 5% (desconecte o carregador se o modo force-discharge não estiver disponível) e depois carrega até 100%. Seus limites são restaurados automaticamente em seguida.");
+    pt.insert("calibration_idle", "Iniciando…");
+    pt.insert("calibration_discharging", "Descarregando até 5%…");
+    pt.insert("calibration_charging", "Carregando até 100%…");
+    pt.insert(
+        "calibration_done",
+        "Calibração concluída, limites restaurados",
+    );
+    pt.insert("calibration_not_running", "Nenhuma calibração em andamento");
+    pt.insert(
+        "calibration_other_battery",
+        "Calibração em andamento em {battery}",
+    );
+    pt.insert("calibration_start", "Iniciar");
+    pt.insert("calibration_stop", "Parar e restaurar os limites");
+    pt.insert("calibration_failed", "Falha: {error}");
+    pt.insert(
+        "grade_a_description",
+        "Excelente estado, pouquíssimo desgaste",
+    );
+    pt.insert("grade_b_description", "Bom estado, desgaste normal");
+    pt.insert(
+        "grade_c_description",
+        "Desgaste acentuado, autonomia reduzida",
+    );
+    pt.insert(
+        "grade_d_description",
+        "Bateria muito desgastada, substituição recomendada",
+    );
+    pt.insert(
+        "health_grade_tooltip",
+        "Nota de desgaste: A ≥ 90%, B 75–89%, C 60–74%, D < 60% da capacidade original",
+    );
+    pt.insert("export_csv", "Exportar para CSV");
+    pt.insert("export_csv_saved", "Exportado para {path}");
+    pt.insert("export_csv_failed", "Falha na exportação CSV: {error}");
+    pt.insert("requested", "solicitado");
+    pt.insert("actual", "real");
+    pt.insert("theme_setting", "Tema da Interface");
+    pt.insert("theme_light", "Claro");
+    pt.insert("theme_dark", "Escuro");
+    pt.insert("theme_applied", "Tema aplicado imediatamente");
+    pt.insert("not_detected", "Não detectado");
+    pt.insert("time_until_full", "até carregar");
+    pt.insert("time_remaining", "restante");
+    pt.insert("tab_ui", "Interface");
+
+    // Documentation
+    pt.insert("documentation", "Documentação");
+    pt.insert("help", "Ajuda");
+    pt.insert("open_readme", "Abrir o README");
+    pt.insert("open_references", "Abrir as referências");
+    pt.insert(
+        "docs_not_found",
+        "Documentação não encontrada (não instalada?)",
+    );
+    pt.insert("docs_open_failed", "Não foi possível abrir a documentação");
+
+    // About / Help
+    pt.insert("about", "Sobre");
+    pt.insert("open_about", "Abrir Sobre");
+    pt.insert(
+        "about_text",
+        "Gerenciador de limites de carga da bateria (GTK4) com restauração via systemd.",
+    );
+    pt.insert("error_execution", "Erro de execução");
+    pt.insert("language_setting", "Idioma da Interface");
+    pt.insert("language_fr", "Français");
+    pt.insert("language_en", "English");
+    pt.insert("language_pt", "Português");
+    pt.insert("language_pl", "Polski");
+    pt.insert(
+        "language_changed",
+        "Idioma alterado. Reinicie o aplicativo para aplicar.",
+    );
+    pt.insert("restart_required", "Reinício automático em 1 segundo...");
+
+    map.insert("pt", pt);
+
+    // Polish translations
+    let mut pl = HashMap::new();
+    pl.insert("app_title", "Menedżer baterii");
+    pl.insert("info_tab", "📊 Informacje");
+    pl.insert("settings_tab", "⚙️ Ustawienia");
+    pl.insert("power_source", "🔌 Źródło zasilania");
+    pl.insert("on_ac", "Zasilanie sieciowe");
+    pl.insert("on_battery", "Na baterii");
+    pl.insert("battery_status", "⚡ Stan baterii");
+    pl.insert("charging", "Ładowanie");
+    pl.insert("discharging", "Rozładowywanie");
+    pl.insert("full", "Pełna");
+    pl.insert("not_charging", "Nie ładuje");
+    pl.insert("unknown", "Nieznany");
+    pl.insert("charge_level", "🔋 Poziom naładowania");
+    pl.insert("battery_health", "💚 Zdrowie baterii");
+    pl.insert("electrical_params", "⚡ Parametry elektryczne");
+    pl.insert("voltage", "Napięcie");
+    pl.insert("capacity", "Pojemność");
+    pl.insert("capacity_level", "Poziom");
+    pl.insert("status", "Stan");
+    pl.insert("connection", "Połączenie");
+    pl.insert("current", "Prąd");
+    pl.insert("power", "Moc");
+    pl.insert("system_info", "🖥️ Informacje o systemie");
+    pl.insert("manufacturer", "Producent");
+    pl.insert("model", "Model");
+    pl.insert("technology", "Technologia");
+    pl.insert("capacity_info", "📊 Informacje o pojemności");
+    pl.insert("current_cap", "Obecna");
+    pl.insert("design_cap", "Fabryczna");
+    pl.insert("charge_thresholds", "🎯 Progi ładowania");
+    pl.insert("start_threshold", "Start");
+    pl.insert("stop_threshold", "Stop");
+    pl.insert("discharge_alarm", "⚠️ Alarm rozładowania");
+    pl.insert("systemd_service", "🔧 Usługa systemd");
+    pl.insert("service_active", "Aktywna");
+    pl.insert("service_inactive", "Nieaktywna");
+    pl.insert("vendor_info", "🏭 Informacje o systemie");
+    pl.insert("product_name", "Model");
+    pl.insert("start_support", "Próg rozpoczęcia");
+    pl.insert("stop_support", "Próg zakończenia");
+    pl.insert("charge_settings", "⚙️ Progi ładowania");
+    pl.insert("start_threshold_pct", "Próg rozpoczęcia (%)");
+    pl.insert("stop_threshold_pct", "Próg zakończenia (%)");
+    pl.insert("alarm_settings", "⚠️ Alarm rozładowania");
+    pl.insert("alarm_threshold", "Próg alarmu (%)");
+    pl.insert("service_settings", "🔧 Usługa systemd");
+    pl.insert(
+        "enable_service",
+        "Włącz automatyczne przywracanie przy starcie",
+    );
+    pl.insert("charge_100", "Naładuj do 100%");
+    pl.insert("settings_applied", "✓ Ustawienia zastosowane (wymagany restart): {thresholds}, alarm: {alarm}, usługa: {service}");
+    pl.insert("alarm", "Alarm");
+    pl.insert("service", "Usługa");
+    pl.insert("enabled", "włączona");
+    pl.insert("disabled", "wyłączona");
+    pl.insert("error", "Błąd");
+    pl.insert("exec_error", "Błąd wykonania");
+    pl.insert("auth_canceled", "Uwierzytelnianie anulowane");
+    pl.insert("no_battery", "Nie wykryto baterii w tym systemie");
+    pl.insert("error_battery_init", "Błąd tworzenia BatteryInfo");
+    pl.insert("tab_info", "Informacje");
+    pl.insert("tab_settings", "Ustawienia");
+    pl.insert("tab_ui", "Interfejs");
+    pl.insert("tab_peripherals", "Urządzenia peryferyjne");
+    pl.insert("card_thresholds", "Progi");
+    pl.insert("card_charge", "Naładowanie");
+    pl.insert("card_health", "Zdrowie");
+    pl.insert("card_power", "Zasilanie");
+    pl.insert("card_status", "Stan");
+    pl.insert("card_battery", "Bateria");
+    pl.insert("card_electrical", "Parametry elektryczne");
+    pl.insert("card_capacity", "Pojemność");
+    pl.insert("card_service", "Usługa");
+    pl.insert("card_peripherals", "Urządzenie peryferyjne");
+    pl.insert("card_info", "Informacje");
+    pl.insert("card_battery_status", "Stan baterii");
+    pl.insert("card_system_info", "Informacje o systemie");
+    pl.insert("card_threshold_settings", "Progi ładowania");
+    pl.insert("card_service_manager", "Usługa Battery Manager");
+    pl.insert("threshold_start", "Początek ładowania");
+    pl.insert("threshold_stop", "Koniec ładowania");
+    pl.insert("threshold_start_pct", "Próg rozpoczęcia (%)");
+    pl.insert("threshold_stop_pct", "Próg zakończenia (%)");
+    pl.insert("connected", "✓ Połączono");
+    pl.insert("disconnected", "✗ Rozłączono");
+    pl.insert("device_type", "Typ");
+    pl.insert("device_scope", "Zakres");
+    pl.insert("serial_number", "Numer seryjny");
+    pl.insert("wear", "Zużycie");
+    pl.insert("cycles", "Cykle");
+    pl.insert("adapter", "Zasilacz");
+    pl.insert("name", "Nazwa");
+    pl.insert("type", "Typ");
+    pl.insert("current_capacity", "Obecna");
+    pl.insert("full_capacity", "Pełna");
+    pl.insert("design_capacity", "Fabryczna");
+    pl.insert("enable_systemd_service", "Włącz usługę systemd");
+    pl.insert(
+        "note_enabled",
+        "<b>Włączona:</b> progi są stosowane natychmiast i trwale",
+    );
+    pl.insert("note_disabled", "<b>Wyłączona:</b> progi są stosowane natychmiast, ale zmiany zostaną utracone po restarcie");
+    pl.insert(
+        "note_apply_required",
+        "<b>Ważne:</b> ustawienia są stosowane dopiero po kliknięciu przycisku <i>Zastosuj</i>.",
+    );
+    pl.insert(
+        "warning_not_persistent",
+        "⚠️ Bez usługi te ustawienia zostaną utracone po restarcie.",
+    );
+    pl.insert("apply_all_settings", "Zastosuj wszystkie ustawienia");
+    pl.insert(
+        "error_start_greater_stop",
+        "Błąd: próg rozpoczęcia musi być niższy niż próg zakończenia",
+    );
+    pl.insert("success_applied", "Ustawienia zostały zastosowane");
+    pl.insert(
+        "settings_mismatch",
+        "Ustawienia zastosowane, ale sprzęt zgłasza inne wartości",
+    );
+    pl.insert(
+        "error_no_pkexec",
+        "Nie znaleziono pkexec, zainstaluj polkit (policykit-1)",
+    );
+    pl.insert(
+        "error_no_helper",
+        "Nie znaleziono programu pomocniczego battery-manager-apply, zainstaluj ponownie aplikację",
+    );
+    pl.insert("select_battery", "Wybierz baterię");
+    pl.insert("preset_lifespan", "Żywotność");
+    pl.insert("preset_balanced", "Zrównoważony");
+    pl.insert("preset_travel", "Podróż");
+    pl.insert("aggregate_capacity", "Łączna pojemność");
+    pl.insert("card_profiles", "Profile");
+    pl.insert("profile_none", "— Brak profilu —");
+    pl.insert("profile_name", "Nazwa profilu");
+    pl.insert("profile_save", "Zapisz");
+    pl.insert("profile_delete", "Usuń");
+    pl.insert("profile_saved", "Profil zapisany");
+    pl.insert("profile_name_required", "Podaj nazwę profilu");
+    pl.insert(
+        "charge_100_tooltip",
+        "Podnosi próg zakończenia do 100% na następne ładowanie, bez zapisywania go",
+    );
+    pl.insert(
+        "charge_100_applied",
+        "Próg zakończenia podniesiony do 100% dla tego ładowania",
+    );
+    pl.insert(
+        "charge_100_temporary",
+        "Tymczasowo: zapisany próg wraca po restarcie systemu lub usługi",
+    );
+    pl.insert("notifications_setting", "Powiadomienia");
+    pl.insert("notifications_enable", "Powiadomienia na pulpicie");
+    pl.insert("notifications_tooltip", "Powiadamiaj, gdy bateria spadnie poniżej poziomu alarmowego lub osiągnie próg zakończenia ładowania");
+    pl.insert("notif_low_title", "Niski poziom baterii");
+    pl.insert("notif_charged_title", "Ładowanie zakończone");
+    pl.insert(
+        "notif_charged_body",
+        "Bateria naładowana do {stop}% — osiągnięto próg",
+    );
+    pl.insert("notif_ac_plugged_title", "Ładowarka podłączona");
+    pl.insert("notif_ac_unplugged_title", "Ładowarka odłączona");
+    pl.insert("notif_applied_title", "Progi zastosowane");
+    pl.insert("notif_error_title", "Battery Manager: błąd");
+    pl.insert(
+        "notifications_low_threshold",
+        "Alarm niskiego poziomu baterii (%)",
+    );
+    pl.insert("history_graph", "Historia naładowania");
+    pl.insert("tray_open", "Otwórz");
+    pl.insert("tray_apply_thresholds", "Zastosuj progi…");
+    pl.insert("tray_quit", "Zakończ");
+    pl.insert("card_behavior", "Zachowanie");
+    pl.insert(
+        "minimize_to_tray",
+        "Minimalizuj do zasobnika przy zamykaniu",
+    );
+    pl.insert(
+        "minimize_to_tray_tooltip",
+        "Wymaga zasobnika systemowego obsługującego StatusNotifierItem",
+    );
+    pl.insert("wear_over", "Zużycie w ciągu");
+    pl.insert("days", "dni");
+    pl.insert("wear_trend_pending", "pomiar w toku");
+    pl.insert("manufacture_date", "Data produkcji");
+    pl.insert("months", "mies.");
+    pl.insert("keyboard_shortcuts", "Skróty klawiszowe");
+    pl.insert("shortcuts_general", "Ogólne");
+    pl.insert("shortcuts_navigation", "Nawigacja");
+    pl.insert("shortcut_refresh", "Odśwież dane baterii");
+    pl.insert("shortcut_apply", "Zastosuj ustawienia");
+    pl.insert("shortcut_quit", "Zakończ");
+    pl.insert("shortcut_preferences", "Preferencje interfejsu");
+    pl.insert("shortcut_tab", "Karta");
+    pl.insert("title_format_charging", "{app} – {percent}% ⚡ Ładowanie");
+    pl.insert(
+        "title_format_discharging",
+        "{app} – {percent}% 🔋 Rozładowywanie",
+    );
+    pl.insert("title_format_full", "{app} – {percent}% ✓ Pełna");
+    pl.insert("battery_care", "Tryb oszczędzania");
+    pl.insert(
+        "battery_care_tooltip",
+        "Ogranicza ładowanie, aby chronić baterię (plik wł./wył. producenta)",
+    );
+    pl.insert("auto_refresh_interval", "Interwał odświeżania (s)");
+    pl.insert("auto_refresh_tooltip", "Czas między dwoma odczytami baterii (podłączenie zasilacza i zmiany stanu są widoczne natychmiast)");
+    pl.insert("accent_color", "Kolor akcentu");
+    pl.insert(
+        "accent_color_tooltip",
+        "Kolor #RRGGBB przełączników i komunikatów o powodzeniu (naciśnij Enter, aby zastosować)",
+    );
+    pl.insert("accent_reset", "Domyślny");
+    pl.insert(
+        "accent_invalid",
+        "Nieprawidłowy kolor: użyj formatu #RRGGBB",
+    );
+    pl.insert("health_trend", "Trend zdrowia (ostatnia historia)");
+    pl.insert(
+        "error_stop_out_of_range",
+        "Błąd: próg zakończenia nie może przekraczać 100%",
+    );
+    pl.insert(
+        "error_start_out_of_range",
+        "Błąd: próg rozpoczęcia musi mieścić się między 0 a 100%",
+    );
+    pl.insert("confirm_apply_title", "Zastosować te ustawienia?");
+    pl.insert(
+        "confirm_apply_body",
+        "{changes}\n\nTe wartości zostaną zapisane z uprawnieniami administratora. Kontynuować?",
+    );
+    pl.insert("confirm_apply_button", "Zastosuj");
+    pl.insert("cancel", "Anuluj");
+    pl.insert("category_mouse", "Myszy");
+    pl.insert("category_keyboard", "Klawiatury");
+    pl.insert("category_headset", "Słuchawki");
+    pl.insert("category_gamepad", "Gamepady");
+    pl.insert("category_other", "Inne urządzenia");
+    pl.insert(
+        "capacity_trend",
+        "Ostatnie ładowanie (zielony: ładowanie, pomarańczowy: rozładowywanie)",
+    );
+    pl.insert(
+        "threshold_not_applied",
+        "Progi zastosowane, ale jądro zgłasza {actual}% — sprzęt może nie obsługiwać tej wartości",
+    );
+    pl.insert("charge_rate", "Tempo");
+    pl.insert("hp_bioscfg", "Sterownik BIOS HP (hp-bioscfg)");
+    pl.insert(
+        "hp_bioscfg_missing",
+        "niezaładowany, używane są tylko pliki ACPI",
+    );
+    pl.insert("time_to_empty", "Pozostały czas");
+    pl.insert(
+        "error_threshold_gap",
+        "Próg rozpoczęcia musi być co najmniej {gap}% poniżej progu zakończenia na tym sprzęcie",
+    );
+    pl.insert("learn_more", "Dowiedz się więcej");
+    pl.insert(
+        "recommended_defaults",
+        "Zalecane wartości dla Twojego sprzętu",
+    );
+    pl.insert("alarm_reset", "Resetuj");
+    pl.insert(
+        "alarm_reset_tooltip",
+        "Czyści alarm rozładowania (0), przywracając domyślną wartość sterownika",
+    );
+    pl.insert(
+        "alarm_reset_done",
+        "Alarm zresetowany (domyślna wartość sterownika)",
+    );
+    pl.insert("card_ups", "UPS");
+    pl.insert("ups_outage", "Awaria zasilania, praca na baterii");
+    pl.insert("capacity_loss_month", "{loss}% utraty miesięcznie");
+    pl.insert("capacity_stable", "pojemność stabilna");
+    pl.insert("capacity_trend_pending", "trend po 2 tygodniach pomiarów");
+    pl.insert(
+        "capacity_trend_tooltip",
+        "Pełna pojemność zapisywana codziennie (eksportowana do health-history.csv), w % pojemności fabrycznej",
+    );
+    pl.insert("service_not_installed", "Nie zainstalowano");
+    pl.insert("service_missing_note", "Usługa battery-manager.service nie jest zainstalowana: progi nie zostaną przywrócone przy starcie.");
+    pl.insert("install_service", "Zainstaluj usługę");
+    pl.insert(
+        "install_service_tooltip",
+        "Instaluje battery-manager.service i włącza ją z bieżącymi progami",
+    );
+    pl.insert("service_installed", "Usługa zainstalowana i włączona");
+    pl.insert("cycle_count_singular", "{n} cykl");
+    pl.insert("cycle_count_few", "{n} cykle");
+    pl.insert("cycle_count_plural", "{n} cykli");
+    pl.insert("charge_behaviour", "Tryb ładowania");
+    pl.insert("charge_behaviour_tooltip", "Tryb jądra (charge_behaviour): wstrzymanie ładowania lub wymuszenie rozładowania, np. do kalibracji baterii");
+    pl.insert("behaviour_auto", "Auto");
+    pl.insert("behaviour_inhibit_charge", "Wstrzymaj ładowanie");
+    pl.insert("behaviour_force_discharge", "Wymuś rozładowanie");
+    pl.insert("charge_behaviour_applied", "Tryb ładowania: {mode}");
+    pl.insert("reapply_on_resume", "Przywracaj progi po uśpieniu");
+    pl.insert("reapply_on_resume_tooltip", "Niektóre oprogramowania układowe zapominają progi podczas uśpienia: po wybudzeniu zapisane progi są zapisywane ponownie, jeśli się zmieniły (wymaga hasła administratora)");
+    pl.insert("resume_never", "Brak wybudzenia od uruchomienia aplikacji");
+    pl.insert("resume_last", "Ostatnie wybudzenie ({time}): {result}");
+    pl.insert(
+        "resume_kept",
+        "progi zachowane przez oprogramowanie układowe",
+    );
+    pl.insert("resume_reapplied", "progi przywrócone");
+    pl.insert("resume_failed", "nie udało się przywrócić progów ({error})");
+    pl.insert("applying", "Stosowanie ustawień…");
+    pl.insert("change_line", "• {setting}: {old} → {new}");
+    pl.insert("no_changes", "Brak zmian");
+    pl.insert("health_verdict_good", "dobre");
+    pl.insert("health_verdict_degraded", "obniżone");
+    pl.insert("health_verdict_critical", "krytyczne");
+    pl.insert("kernel_too_old", "Sterownik progów tego producenta wymaga jądra {required} lub nowszego (bieżące: {running}), więc pliki progów mogą być niedostępne.");
+    pl.insert("udev_rule", "Reguła udev");
+    pl.insert("persist_udev_rule", "Reguła udev (bez usługi systemd)");
+    pl.insert("persist_udev_rule_tooltip", "Instaluje /etc/udev/rules.d/99-battery-manager.rules, która ponownie zapisuje próg zakończenia przy każdym pojawieniu się baterii (także przy starcie). Zastępuje usługę systemd.");
+    pl.insert("updated_at", "Zaktualizowano {time}");
+    pl.insert(
+        "reset_done",
+        "Progi {battery} przywrócone do 0 / 100% (nie zapisano)",
+    );
+    pl.insert("reset_unsupported", "{battery}: nie wykryto pliku progu");
+    pl.insert(
+        "notif_critical_title",
+        "Krytyczny poziom: podłącz ładowarkę",
+    );
+    pl.insert("notifications_critical", "Krytyczny poziom baterii (< 5%)");
+    pl.insert("notifications_events", "Powiadamiaj:");
+    pl.insert("hours_singular", "{n} godzina");
+    pl.insert("hours_few", "{n} godziny");
+    pl.insert("hours_plural", "{n} godzin");
+    pl.insert("diagnostic_report", "Raport diagnostyczny");
+    pl.insert(
+        "diagnostic_redact",
+        "Ukryj numery seryjne i adresy Bluetooth",
+    );
+    pl.insert("diagnostic_copy", "Kopiuj");
+    pl.insert("diagnostic_save", "Zapisz…");
+    pl.insert("diagnostic_copied", "Raport skopiowano do schowka");
+    pl.insert("diagnostic_saved", "Raport zapisano: {path}");
+    pl.insert(
+        "diagnostic_save_failed",
+        "Nie można zapisać raportu: {error}",
+    );
+    pl.insert("notifications_power_source", "Podłączenie ładowarki");
+    pl.insert("calibration_button", "Kalibruj…");
+    pl.insert("calibration_tooltip", "Prowadzony pełny cykl (rozładowanie do 5%, potem ładowanie do 100%) do ponownej kalibracji wskaźnika");
+    pl.insert("calibration_title", "Kalibracja baterii");
+    pl.insert("calibration_intro", "Wskaźnik baterii z czasem traci dokładność. Kalibracja rozładowuje baterię do 5% (odłącz ładowarkę, jeśli tryb force-discharge nie jest dostępny), a następnie ładuje ją do 100%. Twoje progi zostaną potem automatycznie przywrócone.");
+    pl.insert("calibration_idle", "Uruchamianie…");
+    pl.insert("calibration_discharging", "Rozładowywanie do 5%…");
+    pl.insert("calibration_charging", "Ładowanie do 100%…");
+    pl.insert(
+        "calibration_done",
+        "Kalibracja zakończona, progi przywrócone",
+    );
+    pl.insert("calibration_not_running", "Brak kalibracji w toku");
+    pl.insert(
+        "calibration_other_battery",
+        "Kalibracja w toku na {battery}",
+    );
+    pl.insert("calibration_start", "Rozpocznij");
+    pl.insert("calibration_stop", "Zatrzymaj i przywróć progi");
+    pl.insert("calibration_failed", "Błąd: {error}");
+    pl.insert("grade_a_description", "Doskonały stan, bardzo małe zużycie");
+    pl.insert("grade_b_description", "Dobry stan, normalne zużycie");
+    pl.insert("grade_c_description", "Wyraźne zużycie, krótszy czas pracy");
+    pl.insert(
+        "grade_d_description",
+        "Bardzo zużyta bateria, zalecana wymiana",
+    );
+    pl.insert(
+        "health_grade_tooltip",
+        "Ocena zużycia: A ≥ 90%, B 75–89%, C 60–74%, D < 60% pierwotnej pojemności",
+    );
+    pl.insert("export_csv", "Eksportuj do CSV");
+    pl.insert("export_csv_saved", "Wyeksportowano do {path}");
+    pl.insert("export_csv_failed", "Eksport CSV nie powiódł się: {error}");
+    pl.insert("requested", "żądane");
+    pl.insert("actual", "rzeczywiste");
+    pl.insert("theme_setting", "Motyw interfejsu");
+    pl.insert("theme_light", "Jasny");
+    pl.insert("theme_dark", "Ciemny");
+    pl.insert("theme_applied", "Motyw zastosowany natychmiast");
+    pl.insert("not_detected", "Nie wykryto");
+    pl.insert("time_until_full", "do pełnego naładowania");
+    pl.insert("time_remaining", "pozostało");
+    pl.insert("documentation", "Dokumentacja");
+    pl.insert("help", "Pomoc");
+    pl.insert("open_readme", "Otwórz README");
+    pl.insert("open_references", "Otwórz odnośniki");
+    pl.insert(
+        "docs_not_found",
+        "Nie znaleziono dokumentacji (nie zainstalowano?)",
+    );
+    pl.insert("docs_open_failed", "Nie można otworzyć dokumentacji");
+    pl.insert("about", "O programie");
+    pl.insert("open_about", "Otwórz „O programie”");
+    pl.insert(
+        "about_text",
+        "Menedżer progów ładowania baterii (GTK4) z przywracaniem przez systemd.",
+    );
+    pl.insert("error_execution", "Błąd wykonania");
+    pl.insert("language_setting", "Język interfejsu");
+    pl.insert("language_fr", "Français");
+    pl.insert("language_en", "English");
+    pl.insert("language_pt", "Português");
+    pl.insert("language_pl", "Polski");
+    pl.insert(
+        "language_changed",
+        "Język zmieniony. Uruchom ponownie aplikację, aby zastosować.",
+    );
+    pl.insert("restart_required", "Automatyczny restart za 1 sekundę...");
+
+    map.insert("pl", pl);
+
     map
 });
 
-/// Set the current language
+/// Maps a language code to one of [`LANGUAGES`]
 ///
 /// # Arguments
-/// * `lang` - Language code ("en" or "fr")
+/// * `lang` - Language code ("fr", "en", "pt", "`pt_BR`", "pl" or "`pl_PL`")
 ///
-/// # Panics
-/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
-pub fn set_language(lang: &str) {
-    let normalized = if lang == "en" { "en" } else { "fr" };
+/// # Returns
+/// The supported code, or `None` for an unknown language
+pub fn normalize_language(lang: &str) -> Option<&'static str> {
+    match lang {
+        "fr" => Some("fr"),
+        "en" => Some("en"),
+        "pt" | "pt_BR" => Some("pt"),
+        "pl" | "pl_PL" => Some("pl"),
+        _ => None,
+    }
+}
+
+/// Decomposes a locale into its lookup chain
+///
+/// The encoding (`.UTF-8`) and modifier (`@euro`) are dropped, then the
+/// locale is followed by its bare language and by English:
+/// `de_AT.UTF-8` gives `["de_AT", "de", "en"]`.
+///
+/// # Arguments
+/// * `locale` - POSIX locale (e.g. the value of `LANG`)
+pub fn locale_chain(locale: &str) -> Vec<String> {
+    let locale = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('-', "_");
+    let language = locale.split('_').next().unwrap_or_default();
+
+    let mut chain: Vec<String> = Vec::new();
+    for code in [locale.as_str(), language, FALLBACK_LANG] {
+        if !code.is_empty() && !chain.iter().any(|c| c == code) {
+            chain.push(code.to_string());
+        }
+    }
+    chain
+}
 
+fn set_chain(chain: Vec<String>) {
     if crate::core::debug::is_debug_enabled() {
         crate::core::debug::debug_log_args(std::format_args!(
-            "🌐 [I18N] set_language -> {normalized}"
+            "🌐 [I18N] Language chain -> {}",
+            chain.join(" → ")
         ));
     }
 
     *CURRENT_LANG
         .write()
-        .expect("Language RwLock poisoned - this is a critical bug") = normalized.to_string();
+        .expect("Language RwLock poisoned - this is a critical bug") = chain;
 }
 
-/// Get the current language
+/// Set the current language
+///
+/// # Arguments
+/// * `lang` - Language code (see [`normalize_language`]); unknown codes fall back to French
 ///
 /// # Panics
 /// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
-pub fn get_language() -> String {
+pub fn set_language(lang: &str) {
+    let normalized = normalize_language(lang).unwrap_or("fr");
+    set_chain(locale_chain(normalized));
+}
+
+/// Set the current language from a system locale, with fallbacks
+///
+/// Unlike [`set_language`], unknown languages are kept in the chain so that
+/// runtime translation files can provide them (see [`locale_chain`]).
+///
+/// # Arguments
+/// * `locale` - POSIX locale (e.g. "`de_AT.UTF-8`")
+///
+/// # Panics
+/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn set_locale(locale: &str) {
+    set_chain(locale_chain(locale));
+}
+
+/// Returns the current lookup chain, most specific first
+///
+/// # Panics
+/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn language_chain() -> Vec<String> {
     CURRENT_LANG
         .read()
         .expect("Language RwLock poisoned - this is a critical bug")
         .clone()
 }
 
+/// Get the current language
+///
+/// This is the first built-in language of the chain (one of [`LANGUAGES`]).
+///
+/// # Panics
+/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn get_language() -> String {
+    language_chain()
+        .iter()
+        .find_map(|code| normalize_language(code))
+        .unwrap_or(FALLBACK_LANG)
+        .to_string()
+}
+
+/// Lists the English keys missing from the other built-in languages
+///
+/// # Returns
+/// `(lang, key)` pairs, sorted (empty when every translation is complete)
+pub fn check_completeness() -> Vec<(String, String)> {
+    let Some(en) = TRANSLATIONS.get("en") else {
+        return Vec::new();
+    };
+    let mut missing: Vec<(String, String)> = TRANSLATIONS
+        .iter()
+        .filter(|(lang, _)| **lang != "en")
+        .flat_map(|(lang, map)| {
+            en.keys()
+                .filter(|key| !map.contains_key(*key))
+                .map(|key| ((*lang).to_string(), (*key).to_string()))
+        })
+        .collect();
+    missing.sort();
+    missing
+}
+
 /// Get a translated string
 ///
+/// Each language of the chain is tried in turn; within a language, runtime
+/// overrides loaded with [`load_extra_translations`] take precedence over the
+/// built-in dictionary.
+///
 /// # Arguments
 /// * `key` - Translation key
 ///
@@ -394,23 +1950,215 @@ pub fn get_language() -> String {
 /// # Panics
 /// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
 pub fn t(key: &str) -> String {
-    let lang = CURRENT_LANG
+    let chain = language_chain();
+    let overrides = OVERRIDES
         .read()
-        .expect("Language RwLock poisoned - this is a critical bug")
-        .clone();
-    TRANSLATIONS
-        .get(lang.as_str())
-        .and_then(|lang_map| lang_map.get(key))
-        .map_or_else(|| key.to_string(), std::string::ToString::to_string)
+        .expect("Translation overrides RwLock poisoned - this is a critical bug");
+
+    for lang in &chain {
+        if let Some(value) = overrides.get(lang).and_then(|lang_map| lang_map.get(key)) {
+            return value.clone();
+        }
+        if let Some(value) = TRANSLATIONS
+            .get(lang.as_str())
+            .and_then(|lang_map| lang_map.get(key))
+        {
+            return (*value).to_string();
+        }
+    }
+    key.to_string()
+}
+
+/// Get a translated string with `{name}` placeholders filled in
+///
+/// # Arguments
+/// * `key` - Translation key
+/// * `replacements` - `(name, value)` pairs, `name` without braces
+///
+/// # Returns
+/// Translated string with every `{name}` replaced; unknown placeholders are kept
+///
+/// # Panics
+/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn t_fmt(key: &str, replacements: &[(&str, &str)]) -> String {
+    fill_placeholders(&t(key), replacements)
+}
+
+/// Plural category of a count (CLDR names)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralForm {
+    /// Singular: "1 cycle" (also 0 in French and Portuguese)
+    One,
+    /// Polish 2–4, except 12–14: "2 cykle"
+    Few,
+    /// Everything else: "5 cycles", "5 cykli"
+    Many,
+}
+
+/// Returns the plural category of `n` in a language
+///
+/// # Arguments
+/// * `lang` - Language code (one of [`LANGUAGES`])
+/// * `n` - Count
+pub const fn plural_form(lang: &str, n: u64) -> PluralForm {
+    match lang.as_bytes() {
+        b"pl" => {
+            if n == 1 {
+                PluralForm::One
+            } else if matches!(n % 10, 2..=4) && !matches!(n % 100, 12..=14) {
+                PluralForm::Few
+            } else {
+                PluralForm::Many
+            }
+        }
+        b"fr" | b"pt" if n <= 1 => PluralForm::One,
+        b"fr" | b"pt" => PluralForm::Many,
+        _ if n == 1 => PluralForm::One,
+        _ => PluralForm::Many,
+    }
+}
+
+/// Get a translated count from a singular/plural pair of keys
+///
+/// Polish "few" counts (2–4) use `<stem>_few` when `key_plural` is
+/// `<stem>_plural` and that key exists, and `key_plural` otherwise.
+///
+/// # Arguments
+/// * `key_singular` - Key used for the "one" form (1 in English, 0 and 1 in French)
+/// * `key_plural` - Key used otherwise
+/// * `n` - Count, filling `{n}`
+///
+/// # Panics
+/// Panics if the language `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn tn(key_singular: &str, key_plural: &str, n: u64) -> String {
+    let template = match plural_form(&get_language(), n) {
+        PluralForm::One => t(key_singular),
+        PluralForm::Few => key_plural
+            .strip_suffix("_plural")
+            .map(|stem| format!("{stem}_few"))
+            .map(|few_key| (t(&few_key), few_key))
+            .filter(|(template, few_key)| template != few_key)
+            .map_or_else(|| t(key_plural), |(template, _)| template),
+        PluralForm::Many => t(key_plural),
+    };
+    fill_placeholders(&template, &[("n", &n.to_string())])
+}
+
+/// Formats a duration for the time left and time to full
+///
+/// # Arguments
+/// * `minutes` - Duration in minutes
+///
+/// # Returns
+/// "25 min" under an hour, "2 hours" for whole hours, "1 hour 5 min" otherwise
+pub fn duration_text(minutes: u32) -> String {
+    let (hours, mins) = (minutes / 60, minutes % 60);
+    if hours == 0 {
+        return format!("{mins} min");
+    }
+    let hours = tn("hours_singular", "hours_plural", u64::from(hours));
+    if mins == 0 {
+        hours
+    } else {
+        format!("{hours} {mins} min")
+    }
+}
+
+fn fill_placeholders(template: &str, replacements: &[(&str, &str)]) -> String {
+    replacements
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
 }
 
+/// Loads translation overrides from a JSON file
+///
+/// The file maps language codes to key/value objects, for example:
+///
+/// ```json
+/// {
+///   "fr": { "app_title": "Gestionnaire de batterie" },
+///   "en": { "charging": "Charging…" }
+/// }
+/// ```
+///
+/// Only string values are accepted. Entries are merged into the runtime
+/// override map: later files replace keys defined by earlier ones, and keys
+/// not present in the file keep their built-in translation.
+///
+/// # Arguments
+/// * `path` - Path to the JSON file
+///
+/// # Errors
+/// Returns `I18nError::IoError` if the file cannot be read, or
+/// `I18nError::ParseError` if it is not valid JSON of the expected shape
+///
+/// # Panics
+/// Panics if the overrides `RwLock` is poisoned (indicates a serious bug in the application)
+pub fn load_extra_translations(path: &Path) -> Result<(), I18nError> {
+    let content = std::fs::read_to_string(path)?;
+    let parsed = parse_translation_json(&content)?;
+
+    let mut count = 0usize;
+    let mut overrides = OVERRIDES
+        .write()
+        .expect("Translation overrides RwLock poisoned - this is a critical bug");
+    for (lang, entries) in parsed {
+        count += entries.len();
+        overrides.entry(lang).or_default().extend(entries);
+    }
+
+    if crate::core::debug::is_debug_enabled() {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🌐 [I18N] Loaded {count} translation override(s) from {}",
+            path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Parsed translation file: language code with its (key, value) pairs
+pub(crate) type ParsedTranslations = Vec<(String, Vec<(String, String)>)>;
+
+/// Parses `{"lang": {"key": "value", ...}, ...}` into nested maps
+pub(crate) fn parse_translation_json(input: &str) -> Result<ParsedTranslations, I18nError> {
+    let document = json::parse(input)?;
+    let languages = document
+        .as_object()
+        .ok_or_else(|| I18nError::ParseError("expected an object of languages".to_string()))?;
+
+    languages
+        .iter()
+        .map(|(lang, entries)| {
+            let entries = entries
+                .as_object()
+                .ok_or_else(|| I18nError::ParseError(format!("\"{lang}\" is not an object")))?;
+            let entries = entries
+                .iter()
+                .map(|(key, value)| {
+                    let value = value.as_str().ok_or_else(|| {
+                        I18nError::ParseError(format!("\"{lang}.{key}\" is not a string"))
+                    })?;
+                    Ok((key.clone(), value.to_string()))
+                })
+                .collect::<Result<Vec<_>, I18nError>>()?;
+            Ok((lang.clone(), entries))
+        })
+        .collect()
+}
+
+/// Serializes the tests that change the current language, in any module
+#[cfg(test)]
+pub static TEST_LANG_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
 
     // Mutex pour synchroniser les tests qui modifient CURRENT_LANG
-    static TEST_MUTEX: Mutex<()> = Mutex::new(());
+    use super::TEST_LANG_LOCK as TEST_MUTEX;
 
     #[test]
     fn test_1_default_language() {
@@ -449,6 +2197,135 @@ mod tests {
         assert_eq!(t("charging"), "Charging");
     }
 
+    #[test]
+    fn test_translation_pt() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_language("pt_BR");
+        assert_eq!(get_language(), "pt");
+        assert_eq!(t("app_title"), "Gerenciador de Bateria");
+        assert_eq!(t("charging"), "Carregando");
+        set_language("pt");
+        assert_eq!(get_language(), "pt");
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_translation_pl() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_language("pl_PL");
+        assert_eq!(get_language(), "pl");
+        assert_eq!(t("charging"), "Ładowanie");
+        assert_eq!(t("discharging"), "Rozładowywanie");
+        assert_eq!(t("full"), "Pełna");
+        assert_eq!(t("battery_health"), "💚 Zdrowie baterii");
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_locale_chain() {
+        assert_eq!(locale_chain("de_AT"), ["de_AT", "de", "en"]);
+        assert_eq!(locale_chain("zh_TW"), ["zh_TW", "zh", "en"]);
+        assert_eq!(locale_chain("unknown"), ["unknown", "en"]);
+        assert_eq!(locale_chain("de_AT.UTF-8"), ["de_AT", "de", "en"]);
+        assert_eq!(locale_chain("ca_ES@valencia"), ["ca_ES", "ca", "en"]);
+        assert_eq!(locale_chain("en_GB"), ["en_GB", "en"]);
+        assert_eq!(locale_chain("fr"), ["fr", "en"]);
+    }
+
+    #[test]
+    fn test_set_locale_fallback() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_locale("de_AT.UTF-8");
+        assert_eq!(language_chain(), ["de_AT", "de", "en"]);
+        // No German dictionary: English is used
+        assert_eq!(get_language(), "en");
+        assert_eq!(t("charging"), "Charging");
+
+        set_locale("pt_BR.UTF-8");
+        assert_eq!(get_language(), "pt");
+        assert_eq!(t("charging"), "Carregando");
+        set_language("fr");
+        assert_eq!(language_chain(), ["fr", "en"]);
+    }
+
+    #[test]
+    fn test_plural_form() {
+        use PluralForm::{Few, Many, One};
+        let pl: Vec<PluralForm> = [0, 1, 2, 4, 5, 12, 14, 21, 22, 25, 112, 122]
+            .into_iter()
+            .map(|n| plural_form("pl", n))
+            .collect();
+        assert_eq!(
+            pl,
+            [Many, One, Few, Few, Many, Many, Many, Many, Few, Many, Many, Few]
+        );
+        assert_eq!(plural_form("en", 0), Many);
+        assert_eq!(plural_form("en", 1), One);
+        assert_eq!(plural_form("fr", 0), One);
+        assert_eq!(plural_form("fr", 2), Many);
+    }
+
+    #[test]
+    fn test_tn_cycles() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let cycles = |n| tn("cycle_count_singular", "cycle_count_plural", n);
+        set_language("pl");
+        assert_eq!(cycles(1), "1 cykl");
+        assert_eq!(cycles(3), "3 cykle");
+        assert_eq!(cycles(5), "5 cykli");
+        assert_eq!(cycles(12), "12 cykli");
+        set_language("en");
+        assert_eq!(cycles(0), "0 cycles");
+        assert_eq!(cycles(1), "1 cycle");
+        assert_eq!(cycles(2), "2 cycles");
+        set_language("fr");
+        assert_eq!(cycles(0), "0 cycle");
+        assert_eq!(cycles(1), "1 cycle");
+        assert_eq!(cycles(2), "2 cycles");
+    }
+
+    #[test]
+    fn test_tn() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_language("en");
+        assert_eq!(tn("hours_singular", "hours_plural", 0), "0 hours");
+        assert_eq!(tn("hours_singular", "hours_plural", 1), "1 hour");
+        assert_eq!(tn("hours_singular", "hours_plural", 2), "2 hours");
+        set_language("fr");
+        // French: 0 is singular too
+        assert_eq!(tn("hours_singular", "hours_plural", 0), "0 heure");
+        assert_eq!(tn("hours_singular", "hours_plural", 1), "1 heure");
+        assert_eq!(tn("hours_singular", "hours_plural", 2), "2 heures");
+    }
+
+    #[test]
+    fn test_duration_text() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_language("en");
+        assert_eq!(duration_text(25), "25 min");
+        assert_eq!(duration_text(60), "1 hour");
+        assert_eq!(duration_text(180), "3 hours");
+        assert_eq!(duration_text(65), "1 hour 5 min");
+        assert_eq!(duration_text(125), "2 hours 5 min");
+        set_language("fr");
+        assert_eq!(duration_text(65), "1 heure 5 min");
+        set_language("pl");
+        assert_eq!(duration_text(60), "1 godzina");
+        assert_eq!(duration_text(190), "3 godziny 10 min");
+        assert_eq!(duration_text(300), "5 godzin");
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_all_languages_complete() {
+        let missing = check_completeness();
+        assert!(missing.is_empty(), "missing translations: {missing:?}");
+        // Every selectable language has a built-in map
+        for lang in LANGUAGES {
+            assert!(TRANSLATIONS.contains_key(lang));
+        }
+    }
+
     #[test]
     fn test_5_missing_key() {
         let _lock = TEST_MUTEX.lock().unwrap();
@@ -456,4 +2333,106 @@ mod tests {
         set_language("en");
         assert_eq!(t("non_existent_key"), "non_existent_key");
     }
+
+    #[test]
+    fn test_t_fmt() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        set_language("en");
+        assert_eq!(
+            t_fmt("error_threshold_gap", &[("gap", "5")]),
+            "Start threshold must be at least 5% below the stop threshold on this hardware"
+        );
+
+        assert_eq!(
+            t_fmt(
+                "settings_applied",
+                &[
+                    ("thresholds", "60%-80%"),
+                    ("alarm", "5.0%"),
+                    ("service", "enabled")
+                ]
+            ),
+            "✓ Settings applied (reboot required): 60%-80%, alarm: 5.0%, service: enabled"
+        );
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(
+            fill_placeholders(
+                "Start: {start}, stop: {stop}, again {start}",
+                &[("start", "60%"), ("stop", "80%")]
+            ),
+            "Start: 60%, stop: 80%, again 60%"
+        );
+        // Missing values leave the placeholder visible
+        assert_eq!(
+            fill_placeholders("{service}", &[("start", "60%")]),
+            "{service}"
+        );
+    }
+
+    #[test]
+    fn test_parse_translation_json() {
+        let parsed = parse_translation_json(
+            r#"{
+                "fr": { "a": "Batterie \"pleine\"", "b": "\u00e9t\u00e9\n" },
+                "en": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].0, "fr");
+        assert_eq!(
+            parsed[0].1[0],
+            ("a".to_string(), "Batterie \"pleine\"".to_string())
+        );
+        assert_eq!(parsed[0].1[1], ("b".to_string(), "été\n".to_string()));
+        assert!(parsed[1].1.is_empty());
+    }
+
+    #[test]
+    fn test_parse_translation_json_surrogate_pair() {
+        let parsed = parse_translation_json(r#"{"en": {"icon": "\ud83d\udd0b"}}"#).unwrap();
+        assert_eq!(parsed[0].1[0].1, "🔋");
+    }
+
+    #[test]
+    fn test_parse_translation_json_rejects_invalid() {
+        assert!(parse_translation_json("").is_err());
+        assert!(parse_translation_json(r#"{"fr": {"a": 1}}"#).is_err());
+        assert!(parse_translation_json(r#"{"fr": "flat"}"#).is_err());
+        assert!(parse_translation_json(r#"{"fr": {"a": "b"}"#).is_err());
+        assert!(parse_translation_json(r#"{"fr": {"a": "b"}} extra"#).is_err());
+    }
+
+    #[test]
+    fn test_load_extra_translations_overrides_t() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let path =
+            std::env::temp_dir().join(format!("battery-manager-i18n-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"en": {"test_override_key": "Overridden"}, "fr": {"test_override_key": "Remplacé"}}"#,
+        )
+        .unwrap();
+
+        load_extra_translations(&path).unwrap();
+
+        set_language("en");
+        assert_eq!(t("test_override_key"), "Overridden");
+        set_language("fr");
+        assert_eq!(t("test_override_key"), "Remplacé");
+        // Built-in keys are untouched
+        assert_eq!(t("charging"), "En charge");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_extra_translations_missing_file() {
+        let result = load_extra_translations(Path::new("/nonexistent/translations.json"));
+        assert!(matches!(result, Err(I18nError::IoError(_))));
+    }
 }
@@ -0,0 +1,380 @@
+//! Minimal JSON support for data files
+//!
+//! Parses a full JSON document into a `JsonValue` tree and escapes strings
+//! for hand-written output. Only what the application's own files need;
+//! not a general-purpose serializer.
+
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// JSON parse error with a short description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError(pub String);
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JSON error: {}", self.0)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Parsed JSON value (object members keep their file order)
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the member `key` of an object
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as a non-negative integer, if it is one
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Number(n) if *n >= 0.0 && n.fract() == 0.0 && *n <= 2f64.powi(53) => {
+                Some(*n as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number as a float
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the string value
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the array items
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the object members
+    pub fn as_object(&self) -> Option<&[(String, Self)]> {
+        match self {
+            Self::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+/// Escapes a string for use inside a JSON string literal
+pub fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parses a complete JSON document
+///
+/// # Arguments
+///
+/// * `input` - JSON text
+///
+/// # Errors
+///
+/// Returns `JsonError` on invalid syntax or trailing characters
+pub fn parse(input: &str) -> Result<JsonValue, JsonError> {
+    let mut parser = Parser {
+        chars: input.chars().peekable(),
+        depth: 0,
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err(JsonError("trailing characters".to_string()));
+    }
+    Ok(value)
+}
+
+/// Nesting limit, so a corrupt file cannot overflow the stack
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(JsonError(format!("expected '{expected}', found '{c}'"))),
+            None => Err(JsonError(format!(
+                "expected '{expected}', found end of input"
+            ))),
+        }
+    }
+
+    fn expect_word(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in word.chars() {
+            if self.chars.next() != Some(expected) {
+                return Err(JsonError(format!("invalid literal, expected '{word}'")));
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.nested(Self::parse_object),
+            Some('[') => self.nested(Self::parse_array),
+            Some('"') => self.parse_string().map(JsonValue::String),
+            Some('t') => self.expect_word("true", JsonValue::Bool(true)),
+            Some('f') => self.expect_word("false", JsonValue::Bool(false)),
+            Some('n') => self.expect_word("null", JsonValue::Null),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(JsonError(format!("unexpected character '{c}'"))),
+            None => Err(JsonError("unexpected end of input".to_string())),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<JsonValue, JsonError>,
+    ) -> Result<JsonValue, JsonError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(JsonError("nesting too deep".to_string()));
+        }
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Ok(JsonValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            members.push((key, self.parse_value()?));
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => {}
+                Some('}') => return Ok(JsonValue::Object(members)),
+                Some(c) => return Err(JsonError(format!("expected ',' or '}}', found '{c}'"))),
+                None => return Err(JsonError("unterminated object".to_string())),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Ok(JsonValue::Array(items));
+        }
+
+        loop {
+            items.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => {}
+                Some(']') => return Ok(JsonValue::Array(items)),
+                Some(c) => return Err(JsonError(format!("expected ',' or ']', found '{c}'"))),
+                None => return Err(JsonError("unterminated array".to_string())),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| JsonError("invalid \\u escape".to_string()))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(out),
+                Some('\\') => {
+                    let escaped = match self.chars.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = self.parse_hex4()?;
+                            if (0xD800..0xDC00).contains(&code) {
+                                // Surrogate pair
+                                if self.chars.next() != Some('\\') || self.chars.next() != Some('u')
+                                {
+                                    return Err(JsonError("unpaired surrogate".to_string()));
+                                }
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(JsonError("unpaired surrogate".to_string()));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            char::from_u32(code)
+                                .ok_or_else(|| JsonError("invalid \\u escape".to_string()))?
+                        }
+                        _ => return Err(JsonError("invalid escape sequence".to_string())),
+                    };
+                    out.push(escaped);
+                }
+                Some(c) if u32::from(c) < 0x20 => {
+                    return Err(JsonError("control character in string".to_string()))
+                }
+                Some(c) => out.push(c),
+                None => return Err(JsonError("unterminated string".to_string())),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonError> {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+                text.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        // Rust accepts forms JSON forbids (e.g. "1." or leading '+'), reject them first
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        let leading_zero =
+            digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit());
+        let valid = digits.starts_with(|c: char| c.is_ascii_digit())
+            && !leading_zero
+            && !digits.contains(".e")
+            && !digits.contains(".E")
+            && !digits.ends_with('.');
+        match text.parse::<f64>() {
+            Ok(n) if valid && n.is_finite() => Ok(JsonValue::Number(n)),
+            _ => Err(JsonError(format!("invalid number '{text}'"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_document() {
+        let value = parse(
+            r#"{"BAT0": [{"date": "2026-01-02", "full": 4500000, "health": 90.5}], "ok": true, "x": null}"#,
+        )
+        .unwrap();
+
+        let entry = &value.get("BAT0").unwrap().as_array().unwrap()[0];
+        assert_eq!(entry.get("date").unwrap().as_str(), Some("2026-01-02"));
+        assert_eq!(entry.get("full").unwrap().as_u64(), Some(4_500_000));
+        assert_eq!(entry.get("health").unwrap().as_f64(), Some(90.5));
+        assert_eq!(value.get("ok"), Some(&JsonValue::Bool(true)));
+        assert_eq!(value.get("x"), Some(&JsonValue::Null));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_strings_and_numbers() {
+        assert_eq!(
+            parse(r#""a\"b\\c\n\u00e9\ud83d\udd0b""#).unwrap(),
+            JsonValue::String("a\"b\\c\né🔋".to_string())
+        );
+        assert_eq!(parse("-1.5e2").unwrap(), JsonValue::Number(-150.0));
+        assert_eq!(parse(" [ ] ").unwrap(), JsonValue::Array(vec![]));
+        assert_eq!(JsonValue::Number(1.5).as_u64(), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "01",
+            "1.",
+            "+1",
+            "tru",
+            "\"\\x\"",
+            "{} {}",
+        ] {
+            assert!(parse(input).is_err(), "accepted {input:?}");
+        }
+        assert!(parse(&"[".repeat(100)).is_err());
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let original = "quote \" backslash \\ newline \n tab \t bell \u{7}";
+        let json = format!("\"{}\"", escape(original));
+        assert_eq!(parse(&json).unwrap().as_str(), Some(original));
+    }
+}
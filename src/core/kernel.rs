@@ -0,0 +1,108 @@
+//! Running kernel version
+//!
+//! Vendor threshold drivers landed in different kernel releases; comparing
+//! the running version with the vendor's requirement explains missing
+//! threshold files.
+
+use std::sync::OnceLock;
+
+/// Kernel release string (e.g. "6.8.0-45-generic")
+const OSRELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+
+/// Kernel `major.minor` version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KernelVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl KernelVersion {
+    /// Parses a kernel release string ("6.12.1-arch1-1", "5.15.0-105-generic")
+    ///
+    /// # Returns
+    ///
+    /// `None` without a numeric `major.minor` prefix
+    pub fn parse(release: &str) -> Option<Self> {
+        let mut numbers = release.trim().split(['.', '-', '+']);
+        let major = numbers.next()?.parse().ok()?;
+        let minor = numbers.next()?.parse().ok()?;
+        Some(Self { major, minor })
+    }
+
+    /// Whether this version is `major.minor` or newer
+    pub fn at_least(self, major: u32, minor: u32) -> bool {
+        self >= Self { major, minor }
+    }
+}
+
+impl std::fmt::Display for KernelVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Returns the running kernel version (read once per session)
+///
+/// Falls back to `uname -r` when `/proc` is not mounted.
+pub fn running_version() -> Option<KernelVersion> {
+    static VERSION: OnceLock<Option<KernelVersion>> = OnceLock::new();
+    *VERSION.get_or_init(|| {
+        let release = std::fs::read_to_string(OSRELEASE_PATH).ok().or_else(|| {
+            std::process::Command::new("uname")
+                .arg("-r")
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        });
+        let version = release.as_deref().and_then(KernelVersion::parse);
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🐧 [KERNEL] Running kernel: {version:?}"
+        ));
+        version
+    })
+}
+
+/// Whether the running kernel is `major.minor` or newer
+///
+/// An unknown version counts as recent enough, so no warning is shown on a
+/// guess.
+pub fn kernel_at_least(major: u32, minor: u32) -> bool {
+    running_version().is_none_or(|version| version.at_least(major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() {
+        assert_eq!(
+            KernelVersion::parse("6.8.0-45-generic\n"),
+            Some(KernelVersion { major: 6, minor: 8 })
+        );
+        assert_eq!(
+            KernelVersion::parse("6.12-rc3"),
+            Some(KernelVersion {
+                major: 6,
+                minor: 12
+            })
+        );
+        assert_eq!(KernelVersion::parse("6"), None);
+        assert_eq!(KernelVersion::parse("linux"), None);
+    }
+
+    #[test]
+    fn test_at_least() {
+        let version = KernelVersion { major: 6, minor: 8 };
+        assert!(version.at_least(5, 9));
+        assert!(version.at_least(6, 8));
+        assert!(!version.at_least(6, 12));
+        assert!(!version.at_least(7, 0));
+        // Minor versions compare as numbers, not strings
+        assert!(KernelVersion {
+            major: 6,
+            minor: 12
+        }
+        .at_least(6, 9));
+    }
+}
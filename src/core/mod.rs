@@ -1,19 +1,45 @@
 //! Core business logic for battery management
 //!
-//! Provides battery information reading, vendor detection, threshold
-//! management, power supply monitoring, peripheral device detection,
-//! internationalization, and debug logging.
+//! - Battery data: `battery`, `power_supply`, `upower`, `peripheral`, `estimator`
+//! - Thresholds: `vendor_detection`, `threshold_apply`, `writer`, `presets`, `profiles`
+//! - Background work: `monitor`, `resume`, `daemon`, `calibration`, `alerts`, `systemd`
+//! - History and health: `history`, `health`, `health_tracker`
+//! - Command line output: `export`, `csv`, `health_check`, `diagnostics`, `watch`
+//! - Support: `config`, `i18n`, `json`, `kernel`, `debug`, `service`, `traits`
 
+pub mod alerts;
 pub mod battery;
+pub mod calibration;
+pub mod config;
+pub mod csv;
+pub mod daemon;
 pub mod debug;
+pub mod diagnostics;
+pub mod estimator;
+pub mod export;
+pub mod health;
+pub mod health_check;
+pub mod health_tracker;
+pub mod history;
 pub mod i18n;
+pub mod json;
+pub mod kernel;
+pub mod monitor;
 pub mod peripheral;
 pub mod power_supply;
-#[cfg(test)]
+pub mod presets;
+pub mod profiles;
+pub mod resume;
+pub mod service;
+pub mod systemd;
+pub mod threshold_apply;
 pub mod traits;
+pub mod upower;
 pub mod vendor_detection;
+pub mod watch;
+pub mod writer;
 
 pub use battery::BatteryInfo;
-pub use peripheral::PeripheralBattery;
+pub use peripheral::{CapacityTracker, DeviceCategory, PeripheralBattery, TimeToEmpty};
 pub use power_supply::PowerSupplyInfo;
 pub use vendor_detection::VendorInfo;
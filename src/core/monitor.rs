@@ -0,0 +1,186 @@
+//! Power supply change monitor
+//!
+//! Listens for kernel `power_supply` uevents (AC plugged or unplugged,
+//! battery status changes) on a netlink socket, so the UI can refresh right
+//! away instead of waiting for the next timer tick. The socket is watched
+//! on the GTK main loop, so no thread is needed and dropping the watch
+//! closes it.
+
+use std::cell::{Cell, RefCell};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Kernel uevent multicast group (udev rebroadcasts on group 2)
+const KERNEL_UEVENT_GROUP: u32 = 1;
+
+/// Delay before refreshing, so a burst of uevents (AC + battery) triggers a single refresh
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Netlink socket receiving kernel uevents
+pub struct UeventMonitor {
+    fd: OwnedFd,
+}
+
+impl UeventMonitor {
+    /// Opens a non-blocking uevent socket
+    ///
+    /// # Errors
+    ///
+    /// Returns the OS error if the socket cannot be created or bound
+    pub fn open() -> io::Result<Self> {
+        let raw = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+                libc::NETLINK_KOBJECT_UEVENT,
+            )
+        };
+        if raw < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Owned from here on, so early returns close it
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::sa_family_t::try_from(libc::AF_NETLINK).unwrap_or_default();
+        address.nl_groups = KERNEL_UEVENT_GROUP;
+        let length =
+            libc::socklen_t::try_from(std::mem::size_of::<libc::sockaddr_nl>()).unwrap_or_default();
+        let bound = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                (&raw const address).cast::<libc::sockaddr>(),
+                length,
+            )
+        };
+        if bound < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { fd })
+    }
+
+    /// Reads every pending uevent
+    ///
+    /// # Returns
+    ///
+    /// `true` if at least one of them concerns the `power_supply` subsystem
+    fn drain(&self) -> bool {
+        let mut buffer = [0u8; 8192];
+        let mut power_supply = false;
+        loop {
+            let read = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr().cast::<libc::c_void>(),
+                    buffer.len(),
+                    0,
+                )
+            };
+            let Ok(read) = usize::try_from(read) else {
+                // EAGAIN: nothing left to read
+                return power_supply;
+            };
+            if read == 0 {
+                return power_supply;
+            }
+            power_supply |= is_power_supply_event(&buffer[..read]);
+        }
+    }
+}
+
+/// Checks whether a raw uevent message concerns the `power_supply` subsystem
+///
+/// Kernel messages are `action@devpath` followed by NUL-separated
+/// `KEY=value` pairs.
+fn is_power_supply_event(message: &[u8]) -> bool {
+    message
+        .split(|byte| *byte == 0)
+        .any(|field| field == b"SUBSYSTEM=power_supply")
+}
+
+/// Running uevent watch
+#[derive(Clone)]
+pub struct WatchHandle {
+    source: Rc<Cell<Option<glib::SourceId>>>,
+}
+
+impl WatchHandle {
+    /// Stops the watch and closes the socket (no-op once stopped)
+    pub fn stop(&self) {
+        if let Some(id) = self.source.take() {
+            crate::core::debug::debug_log("🔌 [MONITOR] Uevent watch stopped");
+            id.remove();
+        }
+    }
+}
+
+/// Calls `on_change` shortly after each burst of power supply uevents
+///
+/// The watch also stops by itself once `on_change` returns
+/// `ControlFlow::Break`.
+///
+/// # Arguments
+///
+/// * `monitor` - Opened uevent socket (owned by the watch)
+/// * `on_change` - Refresh callback
+///
+/// # Returns
+///
+/// Handle used to stop the watch (e.g. when the window is destroyed)
+pub fn watch<F>(monitor: UeventMonitor, on_change: F) -> WatchHandle
+where
+    F: FnMut() -> glib::ControlFlow + 'static,
+{
+    let on_change = Rc::new(RefCell::new(on_change));
+    let pending = Rc::new(Cell::new(false));
+    let handle = WatchHandle {
+        source: Rc::new(Cell::new(None)),
+    };
+    let fd = monitor.fd.as_raw_fd();
+
+    let debounce_handle = handle.clone();
+    // Dropping this closure (when the source is removed) closes the socket
+    let id = glib::unix_fd_add_local(fd, glib::IOCondition::IN, move |_, _| {
+        if !monitor.drain() || pending.get() {
+            return glib::ControlFlow::Continue;
+        }
+
+        crate::core::debug::debug_log("🔌 [MONITOR] power_supply uevent, refreshing");
+        pending.set(true);
+        let on_change = on_change.clone();
+        let pending = pending.clone();
+        let handle = debounce_handle.clone();
+        glib::timeout_add_local_once(DEBOUNCE, move || {
+            pending.set(false);
+            if (on_change.borrow_mut())() == glib::ControlFlow::Break {
+                handle.stop();
+            }
+        });
+        glib::ControlFlow::Continue
+    });
+    handle.source.set(Some(id));
+    handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_power_supply_event() {
+        let ac = b"change@/devices/LNXSYSTM:00/ACPI0003:00/power_supply/AC\0ACTION=change\0DEVPATH=/devices/LNXSYSTM:00/ACPI0003:00/power_supply/AC\0SUBSYSTEM=power_supply\0POWER_SUPPLY_ONLINE=0\0SEQNUM=4242\0";
+        assert!(is_power_supply_event(ac));
+
+        let usb = b"add@/devices/pci0000:00/usb1/1-1\0ACTION=add\0SUBSYSTEM=usb\0SEQNUM=4243\0";
+        assert!(!is_power_supply_event(usb));
+
+        // The subsystem must match a whole field
+        assert!(!is_power_supply_event(
+            b"change@/x\0SUBSYSTEM=power_supply_ext\0"
+        ));
+        assert!(!is_power_supply_event(b""));
+    }
+}
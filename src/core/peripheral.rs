@@ -1,10 +1,285 @@
 //! Peripheral battery detection module
 //!
 //! Detects and monitors wireless peripheral devices (mouse, keyboard, etc.)
-//! with battery capability via HID++ protocol or similar interfaces.
+//! with battery capability via HID++ protocol or similar interfaces, and
+//! Bluetooth devices (headsets, gamepads…) reporting their battery to
+//! `BlueZ` (`org.bluez.Battery1`). `CapacityTracker` keeps recent levels per
+//! device to derive a charge or drain rate.
+//!
+//! `BlueZ` objects are read once, then kept up to date from its signals, so
+//! a refresh costs no bus round trip.
 
-use std::collections::BTreeMap;
+use glib::Variant;
+use gtk4::gio;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fs;
+use std::rc::{Rc, Weak};
+use std::sync::Mutex;
+
+const BLUEZ_NAME: &str = "org.bluez";
+const BLUEZ_DEVICE_INTERFACE: &str = "org.bluez.Device1";
+const BLUEZ_BATTERY_INTERFACE: &str = "org.bluez.Battery1";
+
+/// D-Bus call timeout in milliseconds
+const BLUEZ_TIMEOUT_MS: i32 = 2000;
+
+/// Period the charge rate is computed over
+const RATE_WINDOW_SECS: u64 = 3600;
+
+/// Samples closer than this to the previous one are dropped
+const MIN_SAMPLE_SPACING_SECS: u64 = 60;
+
+/// Span the samples must cover before a rate is reported
+const MIN_RATE_SPAN_SECS: u64 = 600;
+
+/// Level change between two samples treated as a reconnect jump
+const RECONNECT_JUMP_PERCENT: u8 = 20;
+
+/// Time constant of the smoothed drain rate
+const DRAIN_SMOOTHING_SECS: f64 = 1800.0;
+
+/// Drain observation needed before a time to empty is given
+const MIN_DRAIN_OBSERVATION_SECS: u64 = 900;
+
+/// Level rise treated as a charge event (smaller rises are reading noise)
+const CHARGE_EVENT_PERCENT: u8 = 2;
+
+/// Drain rate (percent per hour) below which a device counts as idle
+const IDLE_DRAIN_PERCENT_PER_HOUR: f64 = 0.1;
+
+/// Bluetooth devices seen with a battery, by address, so they stay listed
+/// (as disconnected) while out of range
+static BLUEZ_SEEN: Mutex<BTreeMap<String, PeripheralBattery>> = Mutex::new(BTreeMap::new());
+
+/// Kind of peripheral, in the order shown in the Peripherals tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeviceCategory {
+    Mouse,
+    Keyboard,
+    Headset,
+    Gamepad,
+    Other,
+}
+
+impl DeviceCategory {
+    /// Emoji shown on cards and section headers
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Mouse => "🖱️",
+            Self::Keyboard => "⌨️",
+            Self::Headset => "🎧",
+            Self::Gamepad => "🎮",
+            Self::Other => "🔋",
+        }
+    }
+
+    /// Translation key of the section header
+    pub const fn label_key(self) -> &'static str {
+        match self {
+            Self::Mouse => "category_mouse",
+            Self::Keyboard => "category_keyboard",
+            Self::Headset => "category_headset",
+            Self::Gamepad => "category_gamepad",
+            Self::Other => "category_other",
+        }
+    }
+}
+
+/// Recent charge levels of each peripheral, keyed by stable id
+///
+/// Levels are kept in memory for `RATE_WINDOW_SECS`. A long gap or a sudden
+/// jump (devices often report a stale or zero level right after
+/// reconnecting) restarts the history of that device. A smoothed drain rate
+/// is kept alongside, restarted whenever the device charges.
+#[derive(Debug, Clone, Default)]
+pub struct CapacityTracker {
+    samples: HashMap<String, VecDeque<(u64, u8)>>,
+    drains: HashMap<String, DrainEstimate>,
+}
+
+/// Exponentially smoothed drain rate of one device
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DrainEstimate {
+    /// Start of the current discharge observation (Unix seconds)
+    since: u64,
+    /// Smoothed drain, percent per hour (positive while draining)
+    rate: f64,
+    /// Total smoothing weight applied so far, to correct the start-up bias
+    weight: f64,
+}
+
+impl DrainEstimate {
+    const fn starting(now: u64) -> Self {
+        Self {
+            since: now,
+            rate: 0.0,
+            weight: 0.0,
+        }
+    }
+
+    /// Blends in the drain observed over `elapsed_secs`
+    #[allow(clippy::cast_precision_loss)]
+    fn update(&mut self, dropped_percent: f64, elapsed_secs: u64) {
+        let elapsed = elapsed_secs as f64;
+        let observed = dropped_percent / (elapsed / 3600.0);
+        let alpha = 1.0 - (-elapsed / DRAIN_SMOOTHING_SECS).exp();
+        self.rate += alpha * (observed - self.rate);
+        self.weight += alpha * (1.0 - self.weight);
+    }
+
+    /// Smoothed drain with the start-up bias removed
+    fn drain_per_hour(&self) -> Option<f64> {
+        (self.weight > 0.0).then(|| self.rate / self.weight)
+    }
+}
+
+/// Estimated time until a peripheral is empty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeToEmpty {
+    /// Not enough discharge observed yet (or charging)
+    Unknown,
+    /// Level not dropping
+    Idle,
+    /// Hours left at the smoothed drain rate
+    Hours(f32),
+}
+
+impl CapacityTracker {
+    /// Creates an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the level of a device
+    ///
+    /// # Arguments
+    ///
+    /// * `stable_id` - Device stable id
+    /// * `now` - Unix timestamp in seconds
+    /// * `capacity_percent` - Level reported now
+    pub fn record(&mut self, stable_id: &str, now: u64, capacity_percent: u8) {
+        let samples = self.samples.entry(stable_id.to_string()).or_default();
+
+        let mut previous = samples.back().copied();
+        if let Some((last_time, last_capacity)) = previous {
+            let restart = now < last_time
+                || now - last_time > RATE_WINDOW_SECS
+                || last_capacity.abs_diff(capacity_percent) >= RECONNECT_JUMP_PERCENT;
+            if restart {
+                samples.clear();
+                previous = None;
+            } else if now - last_time < MIN_SAMPLE_SPACING_SECS {
+                return;
+            }
+        }
+
+        samples.push_back((now, capacity_percent));
+        while samples
+            .front()
+            .is_some_and(|(time, _)| now - time > RATE_WINDOW_SECS)
+        {
+            samples.pop_front();
+        }
+
+        let drain = self
+            .drains
+            .entry(stable_id.to_string())
+            .or_insert(DrainEstimate::starting(now));
+        match previous {
+            Some((last_time, last_capacity))
+                if capacity_percent < last_capacity.saturating_add(CHARGE_EVENT_PERCENT) =>
+            {
+                drain.update(
+                    f64::from(last_capacity) - f64::from(capacity_percent),
+                    now - last_time,
+                );
+            }
+            _ => *drain = DrainEstimate::starting(now),
+        }
+    }
+
+    /// Charge rate of a device, in percent per hour
+    ///
+    /// Uses a least-squares fit so single noisy readings barely move it.
+    ///
+    /// # Arguments
+    ///
+    /// * `stable_id` - Device stable id
+    ///
+    /// # Returns
+    ///
+    /// * `Some(rate)` - Positive while charging, negative while draining
+    /// * `None` - Samples cover less than `MIN_RATE_SPAN_SECS`
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn rate_per_hour(&self, stable_id: &str) -> Option<f32> {
+        let samples = self.samples.get(stable_id)?;
+        let (first_time, _) = *samples.front()?;
+        let (last_time, _) = *samples.back()?;
+        if last_time - first_time < MIN_RATE_SPAN_SECS {
+            return None;
+        }
+
+        let count = samples.len() as f64;
+        let mean_time = samples
+            .iter()
+            .map(|(time, _)| (time - first_time) as f64)
+            .sum::<f64>()
+            / count;
+        let mean_capacity = samples
+            .iter()
+            .map(|(_, capacity)| f64::from(*capacity))
+            .sum::<f64>()
+            / count;
+        let (covariance, variance) =
+            samples
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (time, capacity)| {
+                    let dt = (time - first_time) as f64 - mean_time;
+                    let dc = f64::from(*capacity) - mean_capacity;
+                    (covariance + dt * dc, variance + dt * dt)
+                });
+
+        Some((covariance / variance * 3600.0) as f32)
+    }
+
+    /// Estimated time until a device is empty, from its smoothed drain rate
+    ///
+    /// # Arguments
+    ///
+    /// * `stable_id` - Device stable id
+    ///
+    /// # Returns
+    ///
+    /// `TimeToEmpty::Unknown` until `MIN_DRAIN_OBSERVATION_SECS` of discharge
+    /// were observed, `TimeToEmpty::Idle` when the level doesn't drop
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn time_to_empty(&self, stable_id: &str) -> TimeToEmpty {
+        let (Some(drain), Some(&(last_time, capacity))) = (
+            self.drains.get(stable_id),
+            self.samples.get(stable_id).and_then(VecDeque::back),
+        ) else {
+            return TimeToEmpty::Unknown;
+        };
+        if last_time.saturating_sub(drain.since) < MIN_DRAIN_OBSERVATION_SECS {
+            return TimeToEmpty::Unknown;
+        }
+
+        match drain.drain_per_hour() {
+            Some(rate) if rate >= IDLE_DRAIN_PERCENT_PER_HOUR => {
+                TimeToEmpty::Hours((f64::from(capacity) / rate) as f32)
+            }
+            Some(_) => TimeToEmpty::Idle,
+            None => TimeToEmpty::Unknown,
+        }
+    }
+
+    /// Drops the history of a device
+    pub fn forget(&mut self, stable_id: &str) {
+        self.samples.remove(stable_id);
+        self.drains.remove(stable_id);
+    }
+}
 
 /// Peripheral device battery information (read-only)
 #[derive(Debug, Clone)]
@@ -19,6 +294,8 @@ pub struct PeripheralBattery {
     pub online: bool,
     pub device_type: String,
     pub scope: String,
+    /// Connection state when the source reports one (`BlueZ`), guessed otherwise
+    pub connected: Option<bool>,
 }
 
 impl PeripheralBattery {
@@ -94,12 +371,38 @@ impl PeripheralBattery {
             online,
             device_type,
             scope,
+            connected: None,
         }
     }
 
-    /// Scans `/sys/class/power_supply/` for peripheral batteries
+    /// Keeps the best entry per stable id (the same device can show up
+    /// under several names or sources)
+    fn keep_best(best_by_id: &mut BTreeMap<String, Self>, device: Self) {
+        let id = device.stable_id();
+
+        match best_by_id.get(&id) {
+            None => {
+                best_by_id.insert(id, device);
+            }
+            Some(existing) => {
+                let existing_score = existing.dedupe_score();
+                let new_score = device.dedupe_score();
+
+                if new_score > existing_score
+                    || (new_score == existing_score && device.name < existing.name)
+                {
+                    best_by_id.insert(id, device);
+                }
+            }
+        }
+    }
+
+    /// Scans `/sys/class/power_supply/` and `BlueZ` for peripheral batteries
     ///
-    /// Detects devices matching patterns: `hidpp_battery_*`, `hid-*-battery`, etc.
+    /// Detects sysfs devices matching patterns: `hidpp_battery_*`, `hid-*-battery`,
+    /// `sony_controller_battery_*`, `ps-controller-battery-*`, or any battery
+    /// with device scope
+    /// and Bluetooth devices exposing `org.bluez.Battery1`.
     ///
     /// # Returns
     ///
@@ -111,39 +414,27 @@ impl PeripheralBattery {
         if let Ok(entries) = fs::read_dir("/sys/class/power_supply") {
             for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
+                let base_path = format!("/sys/class/power_supply/{name}");
+                let device_type = Self::read_sysfs_string(&base_path, "type").unwrap_or_default();
+                let scope = Self::read_sysfs_string(&base_path, "scope").unwrap_or_default();
 
-                // Filtrer les périphériques (souris, clavier via HID++)
-                if name.starts_with("hidpp_battery_")
-                    || name.starts_with("hid-")
-                    || name.contains("mouse")
-                    || name.contains("keyboard")
-                {
+                // Filtrer les périphériques (souris, clavier via HID++, manettes…)
+                if is_peripheral_entry(&name, &device_type, &scope) {
                     matched_entries += 1;
-                    let device = Self::new(&name);
-                    let id = device.stable_id();
-
-                    match best_by_id.get(&id) {
-                        None => {
-                            best_by_id.insert(id, device);
-                        }
-                        Some(existing) => {
-                            let existing_score = existing.dedupe_score();
-                            let new_score = device.dedupe_score();
-
-                            if new_score > existing_score
-                                || (new_score == existing_score && device.name < existing.name)
-                            {
-                                best_by_id.insert(id, device);
-                            }
-                        }
-                    }
+                    Self::keep_best(&mut best_by_id, Self::new(&name));
                 }
             }
         }
 
+        let bluetooth = detect_bluez();
+        let bluetooth_entries = bluetooth.len();
+        for device in bluetooth {
+            Self::keep_best(&mut best_by_id, device);
+        }
+
         if crate::core::debug::is_debug_enabled() {
             crate::core::debug::debug_log_args(std::format_args!(
-                "🖱️ [PERIPHERALS] matched_entries={matched_entries} unique_devices={} (after dedupe)",
+                "🖱️ [PERIPHERALS] matched_entries={matched_entries} bluetooth={bluetooth_entries} unique_devices={} (after dedupe)",
                 best_by_id.len()
             ));
         }
@@ -188,11 +479,13 @@ impl PeripheralBattery {
     /// Returns a stable identifier for matching the same device across refreshes.
     ///
     /// Some devices can change their `/sys/class/power_supply/*` name depending on mode
-    /// (wired vs wireless, receiver reconnect, etc). Prefer serial number when available.
+    /// (wired vs wireless, receiver reconnect, etc). Prefer serial number when available,
+    /// in lower case: `BlueZ` and sysfs report Bluetooth addresses in lower case, `UPower`
+    /// in upper case.
     pub fn stable_id(&self) -> String {
         let serial = self.serial_number.as_deref().unwrap_or("").trim();
         if !serial.is_empty() && serial != "Unknown" {
-            return format!("serial:{serial}");
+            return format!("serial:{}", serial.to_ascii_lowercase());
         }
 
         let manufacturer = self.manufacturer.trim();
@@ -209,6 +502,10 @@ impl PeripheralBattery {
     /// `online` is not always reliable across device modes; when charging via cable,
     /// some devices may flip `online` while still being present and reporting a status.
     pub fn is_connected(&self) -> bool {
+        if let Some(connected) = self.connected {
+            return connected;
+        }
+
         if self.online {
             return true;
         }
@@ -238,22 +535,39 @@ impl PeripheralBattery {
         )
     }
 
+    /// Returns the device category, guessed from name, model and type
+    ///
+    /// The first match wins, in this order: mouse, keyboard, headset
+    /// (headphones, earbuds), gamepad (controller, gaming input).
+    pub fn category(&self) -> DeviceCategory {
+        let name_lower = self.name.to_lowercase();
+        let model_lower = self.model_name.to_lowercase();
+        let type_lower = self.device_type.to_lowercase();
+        let mentions = |word: &str| {
+            name_lower.contains(word) || model_lower.contains(word) || type_lower.contains(word)
+        };
+
+        if mentions("mouse") {
+            DeviceCategory::Mouse
+        } else if mentions("keyboard") {
+            DeviceCategory::Keyboard
+        } else if mentions("headset") || mentions("headphone") || mentions("earbud") {
+            DeviceCategory::Headset
+        } else if mentions("gamepad") || mentions("controller") || mentions("gaming") {
+            DeviceCategory::Gamepad
+        } else {
+            DeviceCategory::Other
+        }
+    }
+
     /// Returns device icon emoji based on name/type
     ///
     /// # Returns
     ///
-    /// "🖱️" for mouse, "⌨️" for keyboard, "🔋" for generic
+    /// "🖱️" for mouse, "⌨️" for keyboard, "🎧" for headset, "🎮" for gamepad,
+    /// "🔋" for generic
     pub fn get_device_icon(&self) -> &'static str {
-        let name_lower = self.name.to_lowercase();
-        if name_lower.contains("mouse") || self.model_name.to_lowercase().contains("mouse") {
-            "🖱️"
-        } else if name_lower.contains("keyboard")
-            || self.model_name.to_lowercase().contains("keyboard")
-        {
-            "⌨️"
-        } else {
-            "🔋"
-        }
+        self.category().icon()
     }
 
     /// Returns CSS class for status color
@@ -274,10 +588,494 @@ impl PeripheralBattery {
     }
 }
 
+/// Checks whether a `/sys/class/power_supply` entry is a peripheral battery
+///
+/// # Arguments
+///
+/// * `name` - Entry name (e.g., "`hidpp_battery_1`", "`ps-controller-battery-aa:bb:cc:dd:ee:ff`")
+/// * `device_type` - Content of its `type` file
+/// * `scope` - Content of its `scope` file (empty if missing)
+fn is_peripheral_entry(name: &str, device_type: &str, scope: &str) -> bool {
+    name.starts_with("hidpp_battery_")
+        || name.starts_with("hid-")
+        || name.starts_with("sony_controller")
+        || name.starts_with("ps-controller")
+        || name.contains("mouse")
+        || name.contains("keyboard")
+        // Laptop batteries have no scope or `System`
+        || (device_type == "Battery" && scope == "Device")
+}
+
+/// Bluetooth device as reported by `BlueZ`
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BluezDevice {
+    address: String,
+    alias: String,
+    /// Freedesktop icon name (`audio-headset`, `input-gaming`…)
+    icon: String,
+    connected: bool,
+    /// `None` when the device exposes no `Battery1` interface
+    percentage: Option<u8>,
+}
+
+impl BluezDevice {
+    /// Builds a device from the interfaces of a `BlueZ` object
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - `org.bluez.Device1` properties
+    /// * `battery` - `org.bluez.Battery1` properties, if present
+    fn from_properties(
+        device: &HashMap<String, Variant>,
+        battery: Option<&HashMap<String, Variant>>,
+    ) -> Self {
+        let string = |key: &str| {
+            device
+                .get(key)
+                .and_then(|v| v.str().map(str::to_string))
+                .unwrap_or_default()
+        };
+
+        Self {
+            address: string("Address"),
+            alias: string("Alias"),
+            icon: string("Icon"),
+            connected: device
+                .get("Connected")
+                .and_then(Variant::get::<bool>)
+                .unwrap_or_default(),
+            percentage: battery
+                .and_then(|b| b.get("Percentage"))
+                .and_then(Variant::get::<u8>),
+        }
+    }
+
+    /// Device type shown in the card, from the `BlueZ` icon name
+    fn device_type(&self) -> &'static str {
+        match self.icon.as_str() {
+            "audio-headset" => "Headset",
+            "audio-headphones" => "Headphones",
+            "audio-card" => "Speakers",
+            "input-gaming" => "Gamepad",
+            "input-mouse" => "Mouse",
+            "input-keyboard" => "Keyboard",
+            "input-tablet" => "Tablet",
+            "phone" => "Phone",
+            _ => "Bluetooth",
+        }
+    }
+
+    /// Maps the device to a peripheral entry (requires a battery level)
+    fn to_peripheral(&self) -> Option<PeripheralBattery> {
+        let percentage = self.percentage?;
+        let address = self.address.to_lowercase();
+        Some(PeripheralBattery {
+            name: format!("bluez_{}", address.replace(':', "_")),
+            manufacturer: "Bluetooth".to_string(),
+            model_name: if self.alias.trim().is_empty() {
+                self.address.clone()
+            } else {
+                self.alias.trim().to_string()
+            },
+            // Battery1 only reports a level
+            status: "Unknown".to_string(),
+            capacity_percent: percentage.min(100),
+            voltage_now: None,
+            // Same format as the `uniq` of Bluetooth HID devices in sysfs
+            serial_number: Some(address),
+            online: self.connected,
+            device_type: self.device_type().to_string(),
+            scope: "Device".to_string(),
+            connected: Some(self.connected),
+        })
+    }
+}
+
+/// Interfaces and properties of a `BlueZ` object
+type BluezInterfaces = HashMap<String, HashMap<String, Variant>>;
+
+/// `BlueZ` objects kept up to date from the bus signals
+///
+/// Filled once from `GetManagedObjects`, then updated by `InterfacesAdded`,
+/// `InterfacesRemoved` and `PropertiesChanged`. Signals are dispatched by
+/// the main loop; the one-shot command line modes only use the first
+/// snapshot.
+struct BluezCache {
+    objects: RefCell<BTreeMap<String, BluezInterfaces>>,
+    /// Kept alive with the cache
+    subscriptions: RefCell<Vec<gio::SignalSubscription>>,
+}
+
+thread_local! {
+    /// Cache of the peripheral scan
+    static BLUEZ_CACHE: RefCell<Option<Rc<BluezCache>>> = const { RefCell::new(None) };
+}
+
+impl BluezCache {
+    /// Returns the cache of this thread, connecting on first use
+    ///
+    /// # Returns
+    ///
+    /// `None` if the system bus is unavailable
+    fn shared() -> Option<Rc<Self>> {
+        if let Some(cache) = BLUEZ_CACHE.with(|cache| cache.borrow().clone()) {
+            return Some(cache);
+        }
+        let connection = match gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>) {
+            Ok(connection) => connection,
+            Err(e) => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [BLUEZ] System bus unavailable: {e}"
+                ));
+                return None;
+            }
+        };
+
+        let cache = Rc::new(Self {
+            objects: RefCell::new(managed_objects(&connection)),
+            subscriptions: RefCell::new(Vec::new()),
+        });
+        cache.subscribe(&connection);
+        BLUEZ_CACHE.with(|shared| *shared.borrow_mut() = Some(Rc::clone(&cache)));
+        Some(cache)
+    }
+
+    /// Follows pairing, unpairing and property changes
+    fn subscribe(self: &Rc<Self>, connection: &gio::DBusConnection) {
+        let subscribe =
+            |member: &str,
+             interface: &str,
+             apply: fn(&mut BTreeMap<String, BluezInterfaces>, &str, &Variant)| {
+                let weak: Weak<Self> = Rc::downgrade(self);
+                connection.subscribe_to_signal(
+                    Some(BLUEZ_NAME),
+                    Some(interface),
+                    Some(member),
+                    None,
+                    None,
+                    gio::DBusSignalFlags::NONE,
+                    move |signal| {
+                        if let Some(cache) = weak.upgrade() {
+                            apply(
+                                &mut cache.objects.borrow_mut(),
+                                signal.object_path,
+                                signal.parameters,
+                            );
+                        }
+                    },
+                )
+            };
+
+        let subscriptions = [
+            subscribe(
+                "InterfacesAdded",
+                "org.freedesktop.DBus.ObjectManager",
+                interfaces_added,
+            ),
+            subscribe(
+                "InterfacesRemoved",
+                "org.freedesktop.DBus.ObjectManager",
+                interfaces_removed,
+            ),
+            subscribe(
+                "PropertiesChanged",
+                "org.freedesktop.DBus.Properties",
+                properties_changed,
+            ),
+        ];
+        self.subscriptions.borrow_mut().extend(subscriptions);
+    }
+}
+
+/// `InterfacesAdded(o, a{sa{sv}})`
+fn interfaces_added(
+    objects: &mut BTreeMap<String, BluezInterfaces>,
+    _: &str,
+    parameters: &Variant,
+) {
+    let (Some(path), Some(interfaces)) = (
+        parameters.child_value(0).str().map(str::to_string),
+        parameters.child_value(1).get::<BluezInterfaces>(),
+    ) else {
+        return;
+    };
+    objects.entry(path).or_default().extend(interfaces);
+}
+
+/// `InterfacesRemoved(o, as)`
+fn interfaces_removed(
+    objects: &mut BTreeMap<String, BluezInterfaces>,
+    _: &str,
+    parameters: &Variant,
+) {
+    let (Some(path), Some(removed)) = (
+        parameters.child_value(0).str().map(str::to_string),
+        parameters.child_value(1).get::<Vec<String>>(),
+    ) else {
+        return;
+    };
+    if let Some(interfaces) = objects.get_mut(&path) {
+        for interface in &removed {
+            interfaces.remove(interface);
+        }
+        if interfaces.is_empty() {
+            objects.remove(&path);
+        }
+    }
+}
+
+/// `PropertiesChanged(s, a{sv}, as)` on a known interface of an object
+fn properties_changed(
+    objects: &mut BTreeMap<String, BluezInterfaces>,
+    path: &str,
+    parameters: &Variant,
+) {
+    let interface = parameters.child_value(0);
+    let Some(properties) = interface
+        .str()
+        .and_then(|interface| objects.get_mut(path)?.get_mut(interface))
+    else {
+        return;
+    };
+    let changed: HashMap<String, Variant> = parameters.child_value(1).get().unwrap_or_default();
+    let invalidated: Vec<String> = parameters.child_value(2).get().unwrap_or_default();
+    properties.extend(changed);
+    for name in invalidated {
+        properties.remove(&name);
+    }
+}
+
+/// Reads every `BlueZ` object, once when the cache is created
+///
+/// # Returns
+///
+/// Interfaces by object path (empty without Bluetooth)
+fn managed_objects(connection: &gio::DBusConnection) -> BTreeMap<String, BluezInterfaces> {
+    let reply = match connection.call_sync(
+        Some(BLUEZ_NAME),
+        "/",
+        "org.freedesktop.DBus.ObjectManager",
+        "GetManagedObjects",
+        None,
+        None,
+        gio::DBusCallFlags::NONE,
+        BLUEZ_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+    ) {
+        Ok(reply) => reply,
+        Err(e) => {
+            // Usual case without Bluetooth: org.bluez is not running
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [BLUEZ] GetManagedObjects failed: {e}"
+            ));
+            return BTreeMap::new();
+        }
+    };
+
+    // a{oa{sa{sv}}}: object path -> interface -> properties
+    reply
+        .child_value(0)
+        .get::<HashMap<glib::variant::ObjectPath, BluezInterfaces>>()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(path, interfaces)| (path.as_str().to_string(), interfaces))
+        .collect()
+}
+
+/// Lists the devices known to `BlueZ`
+fn bluez_devices() -> Vec<BluezDevice> {
+    let Some(cache) = BluezCache::shared() else {
+        return Vec::new();
+    };
+    let objects = cache.objects.borrow();
+    objects
+        .values()
+        .filter_map(|interfaces| {
+            let device = interfaces.get(BLUEZ_DEVICE_INTERFACE)?;
+            Some(BluezDevice::from_properties(
+                device,
+                interfaces.get(BLUEZ_BATTERY_INTERFACE),
+            ))
+        })
+        .collect()
+}
+
+/// Merges the current `BlueZ` scan with the devices seen earlier
+///
+/// Devices that reported a battery stay listed as disconnected, with their
+/// last level, while they are still paired (known to `BlueZ`).
+///
+/// # Arguments
+///
+/// * `devices` - Current `BlueZ` scan
+/// * `seen` - Devices seen with a battery, by address (updated)
+fn merge_bluez(
+    devices: &[BluezDevice],
+    seen: &mut BTreeMap<String, PeripheralBattery>,
+) -> Vec<PeripheralBattery> {
+    let mut peripherals = Vec::new();
+
+    for device in devices {
+        let address = device.address.to_lowercase();
+        let current = if device.connected {
+            device.to_peripheral()
+        } else {
+            None
+        };
+        match current {
+            Some(peripheral) => {
+                seen.insert(address, peripheral.clone());
+                peripherals.push(peripheral);
+            }
+            None => {
+                if let Some(last) = seen.get(&address) {
+                    peripherals.push(PeripheralBattery {
+                        online: false,
+                        connected: Some(false),
+                        ..last.clone()
+                    });
+                }
+            }
+        }
+    }
+
+    // Forget unpaired devices
+    seen.retain(|address, _| devices.iter().any(|d| d.address.to_lowercase() == *address));
+
+    peripherals
+}
+
+/// Detects Bluetooth devices reporting a battery level to `BlueZ`
+fn detect_bluez() -> Vec<PeripheralBattery> {
+    let devices = bluez_devices();
+    let mut seen = BLUEZ_SEEN
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    merge_bluez(&devices, &mut seen)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Records one level per `step` seconds, starting at `start`
+    fn record_series(tracker: &mut CapacityTracker, start: u64, step: u64, levels: &[u8]) {
+        for (time, level) in (start..)
+            .step_by(usize::try_from(step).unwrap())
+            .zip(levels)
+        {
+            tracker.record("mouse", time, *level);
+        }
+    }
+
+    #[test]
+    fn test_capacity_rate_noisy_data() {
+        // Draining 6%/h, read every 5 minutes with ±1% noise
+        let mut tracker = CapacityTracker::new();
+        let levels = [81, 79, 80, 78, 79, 77, 78, 76, 77, 75, 76, 74, 75];
+        record_series(&mut tracker, 1000, 300, &levels);
+
+        let rate = tracker.rate_per_hour("mouse").unwrap();
+        assert!((rate + 6.0).abs() < 1.0, "rate = {rate}");
+        assert_eq!(tracker.rate_per_hour("keyboard"), None);
+    }
+
+    #[test]
+    fn test_capacity_rate_sample_spacing() {
+        let mut tracker = CapacityTracker::new();
+        tracker.record("mouse", 1000, 50);
+        // Too close to the previous sample: dropped
+        tracker.record("mouse", 1030, 40);
+        assert_eq!(tracker.samples["mouse"].len(), 1);
+
+        // Enough spacing, but not enough span for a rate yet
+        tracker.record("mouse", 1300, 49);
+        assert_eq!(tracker.rate_per_hour("mouse"), None);
+
+        tracker.record("mouse", 1000 + MIN_RATE_SPAN_SECS, 48);
+        let rate = tracker.rate_per_hour("mouse").unwrap();
+        assert!((rate + 12.0).abs() < 1.0, "rate = {rate}");
+    }
+
+    #[test]
+    fn test_capacity_rate_reconnect_jump() {
+        let mut tracker = CapacityTracker::new();
+        record_series(&mut tracker, 1000, 300, &[60, 59, 58, 57]);
+        assert!(tracker.rate_per_hour("mouse").unwrap() < 0.0);
+
+        // Stale zero level right after reconnecting: history restarts
+        tracker.record("mouse", 2200, 0);
+        assert_eq!(tracker.rate_per_hour("mouse"), None);
+        tracker.record("mouse", 2500, 56);
+        assert_eq!(tracker.samples["mouse"].len(), 1);
+
+        // Out of range for longer than the window: history restarts too
+        tracker.record("mouse", 2500 + RATE_WINDOW_SECS + 1, 55);
+        assert_eq!(tracker.samples["mouse"].len(), 1);
+
+        tracker.forget("mouse");
+        assert!(!tracker.samples.contains_key("mouse"));
+        assert!(!tracker.drains.contains_key("mouse"));
+    }
+
+    #[test]
+    fn test_time_to_empty_smoothing() {
+        // 1% every 5 minutes: 12%/h
+        let mut tracker = CapacityTracker::new();
+        let levels: Vec<u8> = (48..=60).rev().collect();
+        record_series(&mut tracker, 1000, 300, &levels);
+        let TimeToEmpty::Hours(hours) = tracker.time_to_empty("mouse") else {
+            panic!("no estimate");
+        };
+        assert!((hours - 4.0).abs() < 0.1, "hours = {hours}");
+
+        // A single 3% drop (36%/h) is damped
+        tracker.record("mouse", 1000 + 13 * 300, 45);
+        let TimeToEmpty::Hours(hours) = tracker.time_to_empty("mouse") else {
+            panic!("no estimate");
+        };
+        let drain = 45.0 / hours;
+        assert!(drain > 12.0 && drain < 20.0, "drain = {drain}");
+    }
+
+    #[test]
+    fn test_time_to_empty_waits_for_data() {
+        let mut tracker = CapacityTracker::new();
+        record_series(&mut tracker, 1000, 300, &[60, 59, 58]);
+        assert_eq!(tracker.time_to_empty("mouse"), TimeToEmpty::Unknown);
+        assert_eq!(tracker.time_to_empty("keyboard"), TimeToEmpty::Unknown);
+    }
+
+    #[test]
+    fn test_time_to_empty_resets_on_charge() {
+        let mut tracker = CapacityTracker::new();
+        record_series(&mut tracker, 1000, 300, &[60, 59, 58, 57, 56, 55, 54]);
+        assert!(matches!(
+            tracker.time_to_empty("mouse"),
+            TimeToEmpty::Hours(_)
+        ));
+
+        // Plugged in: the estimate restarts from scratch
+        tracker.record("mouse", 3100, 60);
+        assert_eq!(tracker.time_to_empty("mouse"), TimeToEmpty::Unknown);
+        record_series(&mut tracker, 3400, 300, &[60, 59]);
+        assert_eq!(tracker.time_to_empty("mouse"), TimeToEmpty::Unknown);
+
+        // One-point jitter upwards is not a charge event
+        record_series(&mut tracker, 4000, 300, &[60, 59, 58]);
+        assert!(matches!(
+            tracker.time_to_empty("mouse"),
+            TimeToEmpty::Hours(_)
+        ));
+    }
+
+    #[test]
+    fn test_time_to_empty_idle_device() {
+        let mut tracker = CapacityTracker::new();
+        record_series(&mut tracker, 1000, 300, &[70; 8]);
+        assert_eq!(tracker.time_to_empty("mouse"), TimeToEmpty::Idle);
+    }
+
     #[test]
     fn test_peripheral_detection() {
         // Test de détection (peut être vide si aucun périphérique)
@@ -299,6 +1097,7 @@ mod tests {
             online: true,
             device_type: "Battery".to_string(),
             scope: "Device".to_string(),
+            connected: None,
         };
 
         // Vérifier que la classe CSS est correcte (85% = success)
@@ -318,8 +1117,209 @@ mod tests {
             online: true,
             device_type: "Battery".to_string(),
             scope: "Device".to_string(),
+            connected: None,
         };
 
         assert_eq!(mouse.get_device_icon(), "🖱️");
     }
+
+    #[test]
+    fn test_device_category() {
+        let device = |name: &str, model: &str, device_type: &str| PeripheralBattery {
+            name: name.to_string(),
+            manufacturer: "Unknown".to_string(),
+            model_name: model.to_string(),
+            status: "Discharging".to_string(),
+            capacity_percent: 50,
+            voltage_now: None,
+            serial_number: None,
+            online: true,
+            device_type: device_type.to_string(),
+            scope: "Device".to_string(),
+            connected: None,
+        };
+
+        // Model name heuristics on generic sysfs entries
+        let category = |model: &str| device("hidpp_battery_3", model, "Battery").category();
+        assert_eq!(
+            category("G Pro Wireless Gaming Mouse"),
+            DeviceCategory::Mouse
+        );
+        assert_eq!(
+            category("MX Keys Wireless Keyboard"),
+            DeviceCategory::Keyboard
+        );
+        assert_eq!(category("G733 Gaming Headset"), DeviceCategory::Headset);
+        assert_eq!(category("Galaxy Buds2 Earbuds"), DeviceCategory::Headset);
+        assert_eq!(category("Wireless Controller"), DeviceCategory::Gamepad);
+        assert_eq!(category("Unifying Device"), DeviceCategory::Other);
+
+        // Entry name and BlueZ/UPower type
+        assert_eq!(
+            device("ps-controller-battery-a4:ae", "Unknown", "Battery").category(),
+            DeviceCategory::Gamepad
+        );
+        assert_eq!(
+            device("bluez_ac_80", "WH-1000XM4", "Headphones").category(),
+            DeviceCategory::Headset
+        );
+        assert_eq!(
+            device("/org/upower/js", "Pad", "Gaming input").category(),
+            DeviceCategory::Gamepad
+        );
+
+        // Display order
+        assert!(DeviceCategory::Mouse < DeviceCategory::Keyboard);
+        assert!(DeviceCategory::Gamepad < DeviceCategory::Other);
+    }
+
+    fn headset(connected: bool, percentage: Option<u8>) -> BluezDevice {
+        BluezDevice {
+            address: "AC:80:0A:12:34:56".to_string(),
+            alias: "WH-1000XM4".to_string(),
+            icon: "audio-headset".to_string(),
+            connected,
+            percentage,
+        }
+    }
+
+    #[test]
+    fn test_bluez_device_mapping() {
+        let peripheral = headset(true, Some(70)).to_peripheral().unwrap();
+        assert_eq!(peripheral.device_type, "Headset");
+        assert_eq!(peripheral.model_name, "WH-1000XM4");
+        assert_eq!(peripheral.capacity_percent, 70);
+        assert_eq!(peripheral.stable_id(), "serial:ac:80:0a:12:34:56");
+        assert_eq!(peripheral.get_device_icon(), "🎧");
+        assert!(peripheral.is_connected());
+
+        let gamepad = BluezDevice {
+            icon: "input-gaming".to_string(),
+            alias: "Xbox Wireless Controller".to_string(),
+            ..headset(true, Some(40))
+        };
+        assert_eq!(gamepad.to_peripheral().unwrap().get_device_icon(), "🎮");
+
+        // No Battery1 interface: nothing to show
+        assert!(headset(true, None).to_peripheral().is_none());
+    }
+
+    #[test]
+    fn test_bluez_and_upower_twin_share_one_card() {
+        let bluez = headset(true, Some(70)).to_peripheral().unwrap();
+        let upower = crate::core::upower::UPowerDevice {
+            native_path: "/org/bluez/hci0/dev_AC_80_0A_12_34_56".to_string(),
+            kind: 17,
+            serial: "AC:80:0A:12:34:56".to_string(),
+            percentage: 70.0,
+            is_present: true,
+            ..Default::default()
+        }
+        .to_peripheral();
+        assert_eq!(bluez.stable_id(), upower.stable_id());
+
+        let mut best_by_id = BTreeMap::new();
+        PeripheralBattery::keep_best(&mut best_by_id, bluez);
+        PeripheralBattery::keep_best(&mut best_by_id, upower);
+        assert_eq!(best_by_id.len(), 1);
+    }
+
+    #[test]
+    fn test_bluez_signals_update_cache() {
+        use glib::prelude::ToVariant;
+
+        let path = "/org/bluez/hci0/dev_AC_80_0A_12_34_56";
+        let properties = |entries: &[(&str, Variant)]| -> HashMap<String, Variant> {
+            entries
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), value.clone()))
+                .collect()
+        };
+        let mut objects = BTreeMap::new();
+
+        let added: BluezInterfaces = HashMap::from([
+            (
+                BLUEZ_DEVICE_INTERFACE.to_string(),
+                properties(&[
+                    ("Address", "AC:80:0A:12:34:56".to_variant()),
+                    ("Connected", true.to_variant()),
+                ]),
+            ),
+            (
+                BLUEZ_BATTERY_INTERFACE.to_string(),
+                properties(&[("Percentage", 70u8.to_variant())]),
+            ),
+        ]);
+        let object_path = glib::variant::ObjectPath::try_from(path.to_string()).unwrap();
+        interfaces_added(
+            &mut objects,
+            "/",
+            &(object_path.clone(), added).to_variant(),
+        );
+
+        let changed = (
+            BLUEZ_BATTERY_INTERFACE,
+            properties(&[("Percentage", 65u8.to_variant())]),
+            Vec::<String>::new(),
+        );
+        properties_changed(&mut objects, path, &changed.to_variant());
+        let interfaces = &objects[path];
+        let device = BluezDevice::from_properties(
+            &interfaces[BLUEZ_DEVICE_INTERFACE],
+            interfaces.get(BLUEZ_BATTERY_INTERFACE),
+        );
+        assert_eq!(device.percentage, Some(65));
+        assert!(device.connected);
+
+        let removed = (object_path, vec![BLUEZ_BATTERY_INTERFACE.to_string()]);
+        interfaces_removed(&mut objects, "/", &removed.to_variant());
+        assert!(!objects[path].contains_key(BLUEZ_BATTERY_INTERFACE));
+    }
+
+    #[test]
+    fn test_bluez_out_of_range_stays_disconnected() {
+        let mut seen = BTreeMap::new();
+
+        let listed = merge_bluez(&[headset(true, Some(70))], &mut seen);
+        assert_eq!(listed.len(), 1);
+
+        // Out of range: still paired, Battery1 gone
+        let listed = merge_bluez(&[headset(false, None)], &mut seen);
+        assert_eq!(listed.len(), 1);
+        assert!(!listed[0].is_connected());
+        assert_eq!(listed[0].capacity_percent, 70);
+
+        // Unpaired: forgotten
+        assert!(merge_bluez(&[], &mut seen).is_empty());
+        assert!(merge_bluez(&[headset(false, None)], &mut seen).is_empty());
+    }
+
+    #[test]
+    fn test_peripheral_entry_names() {
+        for name in [
+            "hidpp_battery_0",
+            "hid-e9:97:e3:8b-battery",
+            "sony_controller_battery_a4:ae:12:34:56:78",
+            "ps-controller-battery-a4:ae:12:34:56:78",
+            "wacom_mouse_battery",
+        ] {
+            assert!(is_peripheral_entry(name, "Battery", ""), "{name}");
+        }
+
+        // Unknown naming, but the kernel says it powers a device
+        assert!(is_peripheral_entry(
+            "nintendo_switch_controller_battery_98:b6",
+            "Battery",
+            "Device"
+        ));
+
+        for (name, device_type, scope) in [
+            ("BAT0", "Battery", ""),
+            ("BAT1", "Battery", "System"),
+            ("AC", "Mains", ""),
+            ("ucsi-source-psy-USBC000:001", "USB", "Device"),
+        ] {
+            assert!(!is_peripheral_entry(name, device_type, scope), "{name}");
+        }
+    }
 }
@@ -1,46 +1,147 @@
 //! Power supply detection module
 //!
-//! Provides AC power (mains) detection and status information.
+//! Provides AC power (mains) detection and status information, plus the
+//! uninterruptible power supply (UPS) of desktops when one is connected.
 
 use std::fs;
+use std::path::Path;
 
 use crate::core::i18n::t;
 
+/// Uninterruptible power supply (USB UPS reported with type "UPS")
+///
+/// Unlike a laptop battery, the UPS battery only runs during a mains outage:
+/// `online` tells whether the UPS itself is still fed by the mains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpsInfo {
+    /// Power supply name (e.g., "ups0")
+    pub name: String,
+    /// Charge of the UPS battery, when reported
+    pub capacity_percent: Option<u8>,
+    /// Raw status ("Charging", "Discharging", "Full"...)
+    pub status: String,
+    /// Whether the UPS is fed by the mains
+    pub online: bool,
+}
+
+impl UpsInfo {
+    /// Reads a UPS power supply directory
+    ///
+    /// Some UPS drivers don't expose `online`: a discharging UPS is then
+    /// considered on battery.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Power supply directory (e.g., `/sys/class/power_supply/ups0`)
+    /// * `name` - Power supply name
+    fn read(dir: &Path, name: &str) -> Self {
+        let read = |file: &str| {
+            fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        let status = read("status").unwrap_or_else(|| "Unknown".to_string());
+        let online = read("online").map_or(status != "Discharging", |online| online == "1");
+
+        Self {
+            name: name.to_string(),
+            capacity_percent: read("capacity").and_then(|c| c.parse().ok()),
+            status,
+            online,
+        }
+    }
+
+    /// Returns markup string for the UPS power source
+    ///
+    /// # Returns
+    ///
+    /// Pango markup string ("On AC Power" or "On Battery")
+    pub fn get_source_markup(&self) -> String {
+        if self.online {
+            format!("<span size='large' weight='bold'>🔌 {}</span>", t("on_ac"))
+        } else {
+            format!(
+                "<span size='large' weight='bold'>⚠️ {}</span>",
+                t("ups_outage")
+            )
+        }
+    }
+
+    /// Returns CSS class for the UPS power source
+    ///
+    /// # Returns
+    ///
+    /// "color-success" on mains, "color-danger" during an outage
+    pub const fn get_source_css_class(&self) -> &'static str {
+        if self.online {
+            "color-success"
+        } else {
+            "color-danger"
+        }
+    }
+}
+
 /// AC power supply information
 #[derive(Debug, Clone)]
 pub struct PowerSupplyInfo {
     pub ac_online: bool,
     pub ac_name: String,
+    /// UPS connected to the machine, if any
+    pub ups: Option<UpsInfo>,
 }
 
 impl PowerSupplyInfo {
     /// Creates a new instance by detecting AC power status
     ///
-    /// Scans `/sys/class/power_supply/` for "Mains" type devices
+    /// Scans `/sys/class/power_supply/` for "Mains" and "UPS" type devices
     ///
     /// # Returns
     ///
-    /// `PowerSupplyInfo` with AC status and device name
+    /// `PowerSupplyInfo` with AC status, device name and UPS
     pub fn new() -> Self {
+        Self::from_root(Path::new("/sys/class/power_supply"))
+    }
+
+    /// Detects the power supplies under a power supply class directory
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Power supply class directory (injected for tests)
+    ///
+    /// # Returns
+    ///
+    /// `PowerSupplyInfo` with the first Mains and the first UPS found
+    pub fn from_root(root: &Path) -> Self {
         let mut ac_online = false;
         let mut ac_name = t("not_detected");
         let mut found_mains = false;
+        let mut ups = None;
 
-        if let Ok(entries) = fs::read_dir("/sys/class/power_supply") {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                let type_path = format!("/sys/class/power_supply/{name}/type");
-                if let Ok(psu_type) = fs::read_to_string(&type_path) {
-                    if psu_type.trim() == "Mains" {
-                        ac_name.clone_from(&name);
-                        found_mains = true;
-                        let online_path = format!("/sys/class/power_supply/{name}/online");
-                        if let Ok(online) = fs::read_to_string(&online_path) {
-                            ac_online = online.trim() == "1";
-                        }
-                        break;
+        let mut names: Vec<String> = fs::read_dir(root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+
+        for name in names {
+            let dir = root.join(&name);
+            let Ok(psu_type) = fs::read_to_string(dir.join("type")) else {
+                continue;
+            };
+            match psu_type.trim() {
+                "Mains" if !found_mains => {
+                    ac_name.clone_from(&name);
+                    found_mains = true;
+                    if let Ok(online) = fs::read_to_string(dir.join("online")) {
+                        ac_online = online.trim() == "1";
                     }
                 }
+                "UPS" if ups.is_none() => ups = Some(UpsInfo::read(&dir, &name)),
+                _ => {}
             }
         }
 
@@ -52,9 +153,22 @@ impl PowerSupplyInfo {
             } else {
                 crate::core::debug::debug_log("🔌 [POWER] No 'Mains' power supply found");
             }
+            if let Some(ups) = &ups {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "🔌 [POWER] UPS={} online={} status={} capacity={:?}",
+                    ups.name,
+                    ups.online,
+                    ups.status,
+                    ups.capacity_percent
+                ));
+            }
         }
 
-        Self { ac_online, ac_name }
+        Self {
+            ac_online,
+            ac_name,
+            ups,
+        }
     }
 
     /// Returns markup string for power source display
@@ -95,3 +209,141 @@ impl Default for PowerSupplyInfo {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Temporary power supply class directory, removed on drop
+    struct TempRoot(PathBuf);
+
+    impl TempRoot {
+        fn new(tag: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "battery-manager-power-{tag}-{}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn supply(&self, name: &str, files: &[(&str, &str)]) {
+            let dir = self.0.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            for (file, content) in files {
+                fs::write(dir.join(file), format!("{content}\n")).unwrap();
+            }
+        }
+    }
+
+    impl Drop for TempRoot {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_ups_detected() {
+        let root = TempRoot::new("ups");
+        root.supply("BAT0", &[("type", "Battery"), ("capacity", "80")]);
+        root.supply(
+            "ups0",
+            &[
+                ("type", "UPS"),
+                ("capacity", "97"),
+                ("status", "Discharging"),
+                ("online", "0"),
+            ],
+        );
+
+        let info = PowerSupplyInfo::from_root(&root.0);
+        assert_eq!(
+            info.ups,
+            Some(UpsInfo {
+                name: "ups0".to_string(),
+                capacity_percent: Some(97),
+                status: "Discharging".to_string(),
+                online: false,
+            })
+        );
+        assert!(!info.ac_online);
+        assert_eq!(info.ups.unwrap().get_source_css_class(), "color-danger");
+    }
+
+    #[test]
+    fn test_mains_and_ups() {
+        let root = TempRoot::new("mains-ups");
+        root.supply("AC", &[("type", "Mains"), ("online", "1")]);
+        // No "online" file: the status decides
+        root.supply("ups0", &[("type", "UPS"), ("status", "Charging")]);
+
+        let info = PowerSupplyInfo::from_root(&root.0);
+        assert!(info.ac_online);
+        assert_eq!(info.ac_name, "AC");
+        let ups = info.ups.unwrap();
+        assert!(ups.online);
+        assert_eq!(ups.capacity_percent, None);
+        assert_eq!(ups.get_source_css_class(), "color-success");
+    }
+
+    fn power_supply(ac_online: bool) -> PowerSupplyInfo {
+        PowerSupplyInfo {
+            ac_online,
+            ac_name: "AC".to_string(),
+            ups: None,
+        }
+    }
+
+    #[test]
+    fn test_power_source_markup() {
+        use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        set_language("fr");
+        assert_eq!(
+            power_supply(true).get_power_source_markup(),
+            "<span size='xx-large' weight='bold'>🔌 Sur secteur</span>"
+        );
+        assert_eq!(
+            power_supply(false).get_power_source_markup(),
+            "<span size='xx-large' weight='bold'>🔋 Sur batterie</span>"
+        );
+
+        set_language("en");
+        assert_eq!(
+            power_supply(true).get_power_source_markup(),
+            "<span size='xx-large' weight='bold'>🔌 On AC Power</span>"
+        );
+        assert_eq!(
+            power_supply(false).get_power_source_markup(),
+            "<span size='xx-large' weight='bold'>🔋 On Battery</span>"
+        );
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_power_source_css_class() {
+        // Colors come from the theme CSS, never from the markup
+        assert_eq!(
+            power_supply(true).get_power_source_css_class(),
+            "color-success"
+        );
+        assert_eq!(
+            power_supply(false).get_power_source_css_class(),
+            "color-warning"
+        );
+    }
+
+    #[test]
+    fn test_no_ups() {
+        let root = TempRoot::new("no-ups");
+        root.supply("AC", &[("type", "Mains"), ("online", "0")]);
+        root.supply("BAT0", &[("type", "Battery")]);
+
+        let info = PowerSupplyInfo::from_root(&root.0);
+        assert_eq!(info.ups, None);
+        assert!(!info.ac_online);
+    }
+}
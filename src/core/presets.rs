@@ -0,0 +1,92 @@
+//! Charge threshold presets
+//!
+//! Named start/stop pairs offered as one-click shortcuts in the settings
+//! tab. Selecting a preset only fills the spin buttons; applying still
+//! goes through the privileged helper.
+
+use crate::core::i18n::t;
+
+/// A named start/stop threshold pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThresholdPreset {
+    /// i18n key of the preset name
+    pub name_key: &'static str,
+    pub start: u8,
+    pub stop: u8,
+}
+
+/// Built-in presets, from most battery-friendly to full capacity
+pub const PRESETS: [ThresholdPreset; 3] = [
+    ThresholdPreset {
+        name_key: "preset_lifespan",
+        start: 40,
+        stop: 60,
+    },
+    ThresholdPreset {
+        name_key: "preset_balanced",
+        start: 60,
+        stop: 80,
+    },
+    ThresholdPreset {
+        name_key: "preset_travel",
+        start: 95,
+        stop: 100,
+    },
+];
+
+impl ThresholdPreset {
+    /// Returns the translated button label (e.g., "Longévité 40–60")
+    ///
+    /// # Arguments
+    ///
+    /// * `start_supported` - Whether the battery exposes a start threshold
+    ///
+    /// # Returns
+    ///
+    /// Preset name followed by its range, or only the stop value
+    pub fn label(&self, start_supported: bool) -> String {
+        if start_supported {
+            format!("{} {}–{}", t(self.name_key), self.start, self.stop)
+        } else {
+            format!("{} {}%", t(self.name_key), self.stop)
+        }
+    }
+
+    /// Returns the values to fill in
+    ///
+    /// # Arguments
+    ///
+    /// * `start_supported` - Whether the battery exposes a start threshold
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (start, stop); start is `None` when unsupported
+    pub const fn values(&self, start_supported: bool) -> (Option<u8>, u8) {
+        if start_supported {
+            (Some(self.start), self.stop)
+        } else {
+            (None, self.stop)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid_ranges() {
+        for preset in PRESETS {
+            assert!(preset.start < preset.stop, "{preset:?}");
+            assert!(preset.stop <= 100, "{preset:?}");
+            assert!(preset.name_key.starts_with("preset_"));
+        }
+    }
+
+    #[test]
+    fn test_preset_values_respect_start_support() {
+        let balanced = PRESETS[1];
+        assert_eq!(balanced.values(true), (Some(60), 80));
+        assert_eq!(balanced.values(false), (None, 80));
+    }
+}
@@ -0,0 +1,364 @@
+//! Named user profiles for threshold configurations
+//!
+//! Profiles bundle start/stop thresholds, alarm and service settings under
+//! a user-chosen name, persisted to `~/.config/battery-manager/profiles.toml`:
+//!
+//! ```toml
+//! [[profile]]
+//! name = "Bureau"
+//! start = 60
+//! stop = 80
+//! alarm = 10.0
+//! service = true
+//! ```
+//!
+//! Only this small TOML subset is read and written.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur when loading or saving profiles
+#[derive(Debug)]
+pub enum ProfileError {
+    /// I/O error when reading or writing the profiles file
+    IoError(std::io::Error),
+    /// Invalid file content (line number, description)
+    ParseError(usize, String),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "I/O Error: {e}"),
+            Self::ParseError(line, msg) => write!(f, "Parse error at line {line}: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<std::io::Error> for ProfileError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+/// A named set of threshold, alarm and service settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Start threshold, `None` when the battery doesn't support it
+    pub start: Option<u8>,
+    pub stop: u8,
+    /// Discharge alarm in percent
+    pub alarm_percent: f32,
+    pub enable_service: bool,
+}
+
+/// Returns the default profiles file path
+///
+/// # Returns
+///
+/// `~/.config/battery-manager/profiles.toml`, or `None` without a config dir
+pub fn profiles_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("battery-manager").join("profiles.toml"))
+}
+
+/// Loads profiles, starting with an empty list if the file is missing or corrupt
+///
+/// # Arguments
+///
+/// * `path` - Profiles file path
+///
+/// # Returns
+///
+/// Profiles in file order
+pub fn load_profiles(path: &Path) -> Vec<Profile> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "📂 [PROFILES] No profiles loaded from {}: {e}",
+                path.display()
+            ));
+            return Vec::new();
+        }
+    };
+
+    match parse_profiles(&text) {
+        Ok(profiles) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "📂 [PROFILES] Loaded {} profile(s) from {}",
+                profiles.len(),
+                path.display()
+            ));
+            profiles
+        }
+        Err(e) => {
+            crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [PROFILES] Ignoring corrupt {}: {e}",
+                path.display()
+            ));
+            Vec::new()
+        }
+    }
+}
+
+/// Writes profiles to disk, creating the parent directory if needed
+///
+/// # Arguments
+///
+/// * `path` - Profiles file path
+/// * `profiles` - Profiles to persist
+///
+/// # Errors
+///
+/// Returns `ProfileError::IoError` if the file cannot be written
+pub fn save_profiles(path: &Path, profiles: &[Profile]) -> Result<(), ProfileError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serialize_profiles(profiles))?;
+    crate::core::debug::debug_log_args(std::format_args!(
+        "💾 [PROFILES] Saved {} profile(s) to {}",
+        profiles.len(),
+        path.display()
+    ));
+    Ok(())
+}
+
+/// Inserts a profile, replacing any existing profile with the same name
+///
+/// # Arguments
+///
+/// * `profiles` - Profile list to update
+/// * `profile` - Profile to insert
+pub fn upsert_profile(profiles: &mut Vec<Profile>, profile: Profile) {
+    if let Some(existing) = profiles.iter_mut().find(|p| p.name == profile.name) {
+        *existing = profile;
+    } else {
+        profiles.push(profile);
+    }
+}
+
+/// Escapes a string for a TOML basic string
+fn escape_toml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Serializes profiles to TOML (`[[profile]]` array of tables)
+fn serialize_profiles(profiles: &[Profile]) -> String {
+    let mut out = String::from("# Battery Manager profiles\n");
+    for profile in profiles {
+        let _ = writeln!(out, "\n[[profile]]");
+        let _ = writeln!(out, "name = \"{}\"", escape_toml(&profile.name));
+        if let Some(start) = profile.start {
+            let _ = writeln!(out, "start = {start}");
+        }
+        let _ = writeln!(out, "stop = {}", profile.stop);
+        let _ = writeln!(out, "alarm = {:.1}", profile.alarm_percent);
+        let _ = writeln!(out, "service = {}", profile.enable_service);
+    }
+    out
+}
+
+/// Parses a TOML basic string (`"..."`) value
+fn parse_toml_string(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                '"' => out.push('"'),
+                '\\' => out.push('\\'),
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+/// Profile being parsed (fields become mandatory at the end of the table)
+#[derive(Default)]
+struct PartialProfile {
+    name: Option<String>,
+    start: Option<u8>,
+    stop: Option<u8>,
+    alarm_percent: Option<f32>,
+    enable_service: Option<bool>,
+}
+
+impl PartialProfile {
+    fn finish(self, line: usize) -> Result<Profile, ProfileError> {
+        let missing = |field: &str| ProfileError::ParseError(line, format!("missing '{field}'"));
+        Ok(Profile {
+            name: self.name.ok_or_else(|| missing("name"))?,
+            start: self.start,
+            stop: self.stop.ok_or_else(|| missing("stop"))?,
+            alarm_percent: self.alarm_percent.unwrap_or(10.0),
+            enable_service: self.enable_service.unwrap_or(false),
+        })
+    }
+}
+
+/// Parses the profiles TOML subset
+fn parse_profiles(text: &str) -> Result<Vec<Profile>, ProfileError> {
+    let mut profiles = Vec::new();
+    let mut current: Option<(usize, PartialProfile)> = None;
+
+    for (idx, raw_line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line == "[[profile]]" {
+            if let Some((start_line, partial)) = current.take() {
+                profiles.push(partial.finish(start_line)?);
+            }
+            current = Some((line_no, PartialProfile::default()));
+            continue;
+        }
+
+        let err = |msg: &str| ProfileError::ParseError(line_no, msg.to_string());
+        let Some((_, partial)) = current.as_mut() else {
+            return Err(err("key outside of a [[profile]] table"));
+        };
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected key = value"))?;
+        let value = value.trim();
+
+        match key.trim() {
+            "name" => {
+                partial.name = Some(parse_toml_string(value).ok_or_else(|| err("invalid name"))?);
+            }
+            "start" => {
+                partial.start = Some(
+                    value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|v| *v <= 100)
+                        .ok_or_else(|| err("invalid start"))?,
+                );
+            }
+            "stop" => {
+                partial.stop = Some(
+                    value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|v| (1..=100).contains(v))
+                        .ok_or_else(|| err("invalid stop"))?,
+                );
+            }
+            "alarm" => {
+                partial.alarm_percent = Some(
+                    value
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|v| (0.0..=100.0).contains(v))
+                        .ok_or_else(|| err("invalid alarm"))?,
+                );
+            }
+            "service" => {
+                partial.enable_service =
+                    Some(value.parse::<bool>().map_err(|_| err("invalid service"))?);
+            }
+            other => return Err(err(&format!("unknown key '{other}'"))),
+        }
+    }
+
+    if let Some((start_line, partial)) = current {
+        profiles.push(partial.finish(start_line)?);
+    }
+
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_profiles() -> Vec<Profile> {
+        vec![
+            Profile {
+                name: "Bureau".to_string(),
+                start: Some(40),
+                stop: 60,
+                alarm_percent: 10.0,
+                enable_service: true,
+            },
+            Profile {
+                name: "Déplacement \"long\" \\ test".to_string(),
+                start: None,
+                stop: 100,
+                alarm_percent: 7.5,
+                enable_service: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_profiles_round_trip() {
+        let profiles = sample_profiles();
+        let text = serialize_profiles(&profiles);
+        assert_eq!(parse_profiles(&text).unwrap(), profiles);
+        assert_eq!(parse_profiles(&serialize_profiles(&[])).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_profiles_file_round_trip_and_corrupt_file() {
+        let dir =
+            std::env::temp_dir().join(format!("battery-manager-profiles-{}", std::process::id()));
+        let path = dir.join("profiles.toml");
+        let _ = fs::remove_dir_all(&dir);
+
+        // Missing file starts empty
+        assert!(load_profiles(&path).is_empty());
+
+        save_profiles(&path, &sample_profiles()).unwrap();
+        assert_eq!(load_profiles(&path), sample_profiles());
+
+        // Corrupt file starts empty instead of failing
+        fs::write(&path, "[[profile]]\nname = \"x\"\nstop = banana\n").unwrap();
+        assert!(load_profiles(&path).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_profiles_errors() {
+        assert!(parse_profiles("name = \"orphan\"").is_err());
+        assert!(parse_profiles("[[profile]]\nname = \"x\"").is_err());
+        assert!(parse_profiles("[[profile]]\nname = \"x\"\nstop = 101").is_err());
+        assert!(parse_profiles("[[profile]]\nname = x\nstop = 80").is_err());
+    }
+
+    #[test]
+    fn test_upsert_profile_replaces_by_name() {
+        let mut profiles = sample_profiles();
+        let mut updated = profiles[0].clone();
+        updated.stop = 80;
+
+        upsert_profile(&mut profiles, updated);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].stop, 80);
+    }
+}
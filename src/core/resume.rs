@@ -0,0 +1,214 @@
+//! Threshold re-apply after resume from suspend
+//!
+//! Many firmwares forget the charge thresholds while suspended, and the boot
+//! service only runs once. When enabled (`reapply_on_resume` in
+//! `config.toml`), the app listens for logind's `PrepareForSleep(false)` on
+//! the system bus and writes the saved thresholds of
+//! `/etc/battery-manager/<BAT>.conf` again, only when the kernel no longer
+//! reports them (so a resume normally asks for nothing).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use gtk4::gio;
+
+use crate::core::BatteryInfo;
+
+/// Well-known name of logind
+const LOGIND_NAME: &str = "org.freedesktop.login1";
+
+/// Object path of the logind manager
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+
+/// Manager interface emitting `PrepareForSleep(b)`
+const LOGIND_MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+
+/// Thresholds persisted by the last apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavedThresholds {
+    /// `START_THRESHOLD`, `None` when unsupported
+    pub start: Option<u8>,
+    /// `STOP_THRESHOLD`
+    pub stop: u8,
+}
+
+impl SavedThresholds {
+    /// Parses a `<BAT>.conf` file (`KEY=value` lines, as written by the helper)
+    ///
+    /// # Returns
+    ///
+    /// `None` without a valid `STOP_THRESHOLD`
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut start = None;
+        let mut stop = None;
+        for line in text.lines() {
+            match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("START_THRESHOLD", value)) => start = value.parse().ok(),
+                Some(("STOP_THRESHOLD", value)) => stop = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(Self { start, stop: stop? })
+    }
+
+    /// Reads the saved thresholds of a battery
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - Configuration directory (`/etc/battery-manager` outside tests)
+    /// * `battery_name` - Battery name (e.g., "BAT0")
+    pub fn load(config_dir: &Path, battery_name: &str) -> Option<Self> {
+        let text = std::fs::read_to_string(config_dir.join(format!("{battery_name}.conf"))).ok()?;
+        Self::parse(&text)
+    }
+
+    /// Whether the kernel still reports these thresholds
+    ///
+    /// A start threshold the kernel doesn't expose is not compared.
+    pub fn matches(self, info: &BatteryInfo) -> bool {
+        info.charge_stop_threshold == Some(self.stop)
+            && (self.start.is_none()
+                || info.charge_start_threshold.is_none()
+                || info.charge_start_threshold == self.start)
+    }
+}
+
+/// Result of a re-apply after resume
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReapplyOutcome {
+    /// The kernel kept the thresholds, nothing was written
+    AlreadySet,
+    /// The saved thresholds were written again
+    Applied,
+    /// Writing failed (message for the user)
+    Failed(String),
+}
+
+/// Last re-apply of a battery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReapplyRecord {
+    /// Local time of the re-apply ("HH:MM")
+    pub time: String,
+    pub outcome: ReapplyOutcome,
+}
+
+/// Last re-apply per battery, since the app started
+static LAST_REAPPLY: Mutex<Option<HashMap<String, ReapplyRecord>>> = Mutex::new(None);
+
+/// Stores the outcome of a re-apply, stamped with the current local time
+pub fn record(battery_name: &str, outcome: ReapplyOutcome) {
+    let time = glib::DateTime::now_local()
+        .and_then(|now| now.format("%H:%M"))
+        .map(|time| time.to_string())
+        .unwrap_or_default();
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🌅 [RESUME] {battery_name}: {outcome:?} at {time}"
+    ));
+    if let Ok(mut last) = LAST_REAPPLY.lock() {
+        last.get_or_insert_with(HashMap::new)
+            .insert(battery_name.to_string(), ReapplyRecord { time, outcome });
+    }
+}
+
+/// Returns the last re-apply of a battery since the app started
+pub fn last_reapply(battery_name: &str) -> Option<ReapplyRecord> {
+    LAST_REAPPLY
+        .lock()
+        .ok()
+        .and_then(|last| last.as_ref()?.get(battery_name).cloned())
+}
+
+/// Calls `on_resume` each time the system wakes up from suspend
+///
+/// # Returns
+///
+/// The subscription (keep it alive), or `None` without a system bus
+pub fn subscribe(on_resume: impl Fn() + 'static) -> Option<gio::SignalSubscription> {
+    let connection = match gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>) {
+        Ok(connection) => connection,
+        Err(e) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [RESUME] System bus unreachable, no re-apply after resume: {e}"
+            ));
+            return None;
+        }
+    };
+
+    Some(connection.subscribe_to_signal(
+        Some(LOGIND_NAME),
+        Some(LOGIND_MANAGER_INTERFACE),
+        Some("PrepareForSleep"),
+        Some(LOGIND_PATH),
+        None,
+        gio::DBusSignalFlags::NONE,
+        move |signal| {
+            // true before suspending, false after waking up
+            if signal.parameters.child_value(0).get::<bool>() == Some(false) {
+                on_resume();
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_saved_thresholds() {
+        assert_eq!(
+            SavedThresholds::parse("START_THRESHOLD=60\nSTOP_THRESHOLD=80\n"),
+            Some(SavedThresholds {
+                start: Some(60),
+                stop: 80
+            })
+        );
+        assert_eq!(
+            SavedThresholds::parse("STOP_THRESHOLD=90\n"),
+            Some(SavedThresholds {
+                start: None,
+                stop: 90
+            })
+        );
+        assert_eq!(SavedThresholds::parse("START_THRESHOLD=60\n"), None);
+        assert_eq!(SavedThresholds::parse("STOP_THRESHOLD=high\n"), None);
+    }
+
+    #[test]
+    fn test_saved_thresholds_match() {
+        let saved = SavedThresholds {
+            start: Some(60),
+            stop: 80,
+        };
+        let mut info = BatteryInfo {
+            manufacturer: String::new(),
+            model_name: String::new(),
+            technology: String::new(),
+            capacity_level: String::new(),
+            charge_now: 0,
+            charge_full: 0,
+            charge_full_design: 0,
+            current_now: 0,
+            voltage_now: 0,
+            cycle_count: 0,
+            charge_stop_threshold: None,
+            ..BatteryInfo::sample()
+        };
+
+        info.charge_start_threshold = Some(60);
+        info.charge_stop_threshold = Some(80);
+        assert!(saved.matches(&info));
+
+        // Firmware reset after suspend
+        info.charge_stop_threshold = Some(100);
+        assert!(!saved.matches(&info));
+
+        // Start threshold not exposed by the kernel: only stop is compared
+        info.charge_start_threshold = None;
+        info.charge_stop_threshold = Some(80);
+        assert!(saved.matches(&info));
+        info.charge_start_threshold = Some(0);
+        assert!(!saved.matches(&info));
+    }
+}
@@ -0,0 +1,210 @@
+//! Systemd restore service status
+//!
+//! The unit is queried over D-Bus (see [`crate::core::systemd`]), falling
+//! back to `systemctl` when the system bus is unreachable. Either can stall
+//! for a noticeable time, so the UI never queries on the main thread: the
+//! result is cached for [`CACHE_TTL`] and refreshed in a worker thread
+//! (`gio::spawn_blocking`).
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gtk4::gio;
+
+use crate::core::i18n::t;
+use crate::core::systemd::{self, UnitState};
+use crate::core::threshold_apply::UNIT_INSTALL_PATH;
+
+/// Generates the restore unit (shared with the privileged helper, which writes it)
+pub use crate::core::threshold_apply::unit_file;
+
+/// Unit restoring the thresholds at boot
+pub const SERVICE_UNIT: &str = "battery-manager.service";
+
+/// How long a unit state query result is reused
+pub const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Last unit state query result
+#[derive(Debug, Clone, Copy)]
+struct ServiceCache {
+    state: Option<UnitState>,
+    checked_at: Option<Instant>,
+}
+
+impl ServiceCache {
+    /// Cached state, if still younger than `ttl`
+    fn fresh(&self, now: Instant, ttl: Duration) -> Option<UnitState> {
+        match (self.state, self.checked_at) {
+            (Some(state), Some(at)) if now.saturating_duration_since(at) < ttl => Some(state),
+            _ => None,
+        }
+    }
+}
+
+static CACHE: Mutex<ServiceCache> = Mutex::new(ServiceCache {
+    state: None,
+    checked_at: None,
+});
+
+/// Checks whether the unit file is installed (used without D-Bus)
+pub fn unit_file_exists() -> bool {
+    [
+        UNIT_INSTALL_PATH,
+        "/usr/lib/systemd/system/battery-manager.service",
+        "/lib/systemd/system/battery-manager.service",
+    ]
+    .into_iter()
+    .any(|p| Path::new(p).is_file())
+}
+
+/// Queries the unit state (blocking, keep it off the GTK main thread)
+///
+/// Uses systemd's D-Bus API; `systemctl is-active` and the unit file
+/// locations are only used when the system bus is unreachable.
+///
+/// # Returns
+///
+/// State of the restore service
+pub fn query_unit_state() -> UnitState {
+    match systemd::system_bus() {
+        Ok(connection) => systemd::unit_state(&connection, SERVICE_UNIT).unwrap_or_else(|e| {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [SERVICE] systemd D-Bus query failed: {e}"
+            ));
+            UnitState::Inactive
+        }),
+        Err(e) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [SERVICE] System bus unreachable, using systemctl: {e}"
+            ));
+            let active = std::process::Command::new("systemctl")
+                .args(["is-active", SERVICE_UNIT])
+                .output()
+                .ok()
+                .is_some_and(|output| output.status.success());
+            if active {
+                UnitState::Active
+            } else if unit_file_exists() {
+                UnitState::Inactive
+            } else {
+                UnitState::NotInstalled
+            }
+        }
+    }
+}
+
+/// Last known service state, without checking
+///
+/// # Returns
+///
+/// `None` until a first check has completed
+pub fn cached_unit_state() -> Option<UnitState> {
+    CACHE.lock().ok().and_then(|cache| cache.state)
+}
+
+/// Returns the service state, checking it in a worker thread when the cache is stale
+///
+/// # Returns
+///
+/// State of the restore service
+pub async fn unit_state() -> UnitState {
+    let cached = CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.fresh(Instant::now(), CACHE_TTL));
+    if let Some(state) = cached {
+        return state;
+    }
+
+    let state = gio::spawn_blocking(query_unit_state)
+        .await
+        .unwrap_or(UnitState::Inactive);
+    crate::core::debug::debug_log_args(std::format_args!("🔧 [SERVICE] {SERVICE_UNIT}: {state:?}"));
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.state = Some(state);
+        cache.checked_at = Some(Instant::now());
+    }
+    state
+}
+
+/// Forces the next [`unit_state`] call to check again
+///
+/// Called after the service was enabled or disabled.
+pub fn invalidate() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.checked_at = None;
+    }
+}
+
+/// Returns formatted systemd service status with markup
+///
+/// # Arguments
+///
+/// * `state` - Service state, `None` while the first check is running
+///
+/// # Returns
+///
+/// Pango markup string ("Active", "Inactive", "Not installed" or "Unknown")
+pub fn status_markup(state: Option<UnitState>) -> String {
+    let key = match state {
+        Some(UnitState::Active) => "service_active",
+        Some(UnitState::Inactive) => "service_inactive",
+        Some(UnitState::NotInstalled) => "service_not_installed",
+        None => "unknown",
+    };
+    format!("<span size='xx-large' weight='bold'>{}</span>", t(key))
+}
+
+/// Returns CSS class for service status (active=success, inactive=danger)
+///
+/// # Arguments
+///
+/// * `state` - Service state, `None` while the first check is running
+pub const fn status_css_class(state: Option<UnitState>) -> &'static str {
+    match state {
+        Some(UnitState::Active) => "color-success",
+        Some(UnitState::Inactive) => "color-danger",
+        Some(UnitState::NotInstalled) | None => "dim-label",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_ttl() {
+        let now = Instant::now();
+        let cache = ServiceCache {
+            state: Some(UnitState::Active),
+            checked_at: Some(now),
+        };
+        assert_eq!(cache.fresh(now, CACHE_TTL), Some(UnitState::Active));
+        assert_eq!(cache.fresh(now + CACHE_TTL, CACHE_TTL), None);
+
+        let never_checked = ServiceCache {
+            state: None,
+            checked_at: None,
+        };
+        assert_eq!(never_checked.fresh(now, CACHE_TTL), None);
+
+        // Invalidated: the value is kept for display but is stale
+        let invalidated = ServiceCache {
+            state: Some(UnitState::Inactive),
+            checked_at: None,
+        };
+        assert_eq!(invalidated.fresh(now, CACHE_TTL), None);
+    }
+
+    #[test]
+    fn test_status_markup() {
+        assert!(status_markup(Some(UnitState::Active)).contains(&t("service_active")));
+        assert!(status_markup(Some(UnitState::Inactive)).contains(&t("service_inactive")));
+        assert!(status_markup(Some(UnitState::NotInstalled)).contains(&t("service_not_installed")));
+        assert!(status_markup(None).contains(&t("unknown")));
+        assert_eq!(status_css_class(Some(UnitState::Active)), "color-success");
+        assert_eq!(status_css_class(Some(UnitState::Inactive)), "color-danger");
+        assert_eq!(status_css_class(Some(UnitState::NotInstalled)), "dim-label");
+    }
+}
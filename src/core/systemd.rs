@@ -0,0 +1,214 @@
+//! Minimal systemd client over D-Bus (`org.freedesktop.systemd1`)
+//!
+//! Spawning `systemctl` fails inside Flatpak and containers even when the
+//! host service is running, while the system bus is usually reachable.
+//! Callers fall back to `systemctl` only when the bus itself is unreachable.
+//!
+//! Shared with the privileged helper (`src/bin/battery-manager-apply.rs`),
+//! so it must not depend on the rest of `crate::core`.
+
+use glib::variant::ToVariant;
+use glib::Variant;
+use gtk4::gio;
+
+/// Well-known name of the systemd manager
+const SYSTEMD_NAME: &str = "org.freedesktop.systemd1";
+
+/// Object path of the systemd manager
+const SYSTEMD_PATH: &str = "/org/freedesktop/systemd1";
+
+/// Manager interface (unit lookup and management)
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+
+/// Unit interface (`LoadState`, `ActiveState`)
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+
+/// D-Bus call timeout (enabling a unit reloads systemd, which can take a while)
+const CALL_TIMEOUT_MS: i32 = 25_000;
+
+/// State of a systemd unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitState {
+    /// No unit file: the service was never installed
+    NotInstalled,
+    /// Installed but not running
+    Inactive,
+    /// Running (or starting/reloading)
+    Active,
+}
+
+impl UnitState {
+    /// Maps systemd's `LoadState` and `ActiveState` properties
+    ///
+    /// # Arguments
+    ///
+    /// * `load_state` - "loaded", "not-found", "masked"...
+    /// * `active_state` - "active", "inactive", "failed"...
+    pub fn from_properties(load_state: &str, active_state: &str) -> Self {
+        if load_state == "not-found" {
+            Self::NotInstalled
+        } else if matches!(active_state, "active" | "activating" | "reloading") {
+            Self::Active
+        } else {
+            Self::Inactive
+        }
+    }
+}
+
+/// Connects to the system bus
+///
+/// # Errors
+///
+/// Returns the GIO error when the bus is unreachable (callers fall back to `systemctl`)
+pub fn system_bus() -> Result<gio::DBusConnection, glib::Error> {
+    gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>)
+}
+
+/// Calls a method of the systemd manager
+fn call_manager(
+    connection: &gio::DBusConnection,
+    method: &str,
+    args: &Variant,
+) -> Result<Variant, glib::Error> {
+    connection.call_sync(
+        Some(SYSTEMD_NAME),
+        SYSTEMD_PATH,
+        MANAGER_INTERFACE,
+        method,
+        Some(args),
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+    )
+}
+
+/// Reads a string property of a unit object
+fn unit_property(
+    connection: &gio::DBusConnection,
+    unit_path: &str,
+    property: &str,
+) -> Result<String, glib::Error> {
+    let reply = connection.call_sync(
+        Some(SYSTEMD_NAME),
+        unit_path,
+        "org.freedesktop.DBus.Properties",
+        "Get",
+        Some(&(UNIT_INTERFACE, property).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+    )?;
+    Ok(reply
+        .child_value(0)
+        .as_variant()
+        .and_then(|value| value.str().map(str::to_string))
+        .unwrap_or_default())
+}
+
+/// Returns the object path of a unit
+///
+/// `GetUnit` only knows loaded units; an inactive service may be unloaded,
+/// so `LoadUnit` is tried next (it reports "not-found" for missing units).
+fn unit_path(connection: &gio::DBusConnection, unit: &str) -> Result<String, glib::Error> {
+    let args = (unit,).to_variant();
+    let reply = call_manager(connection, "GetUnit", &args)
+        .or_else(|_| call_manager(connection, "LoadUnit", &args))?;
+    Ok(reply.child_value(0).str().unwrap_or_default().to_string())
+}
+
+/// Reads the state of a unit
+///
+/// # Errors
+///
+/// Returns the D-Bus error if systemd cannot be queried
+pub fn unit_state(connection: &gio::DBusConnection, unit: &str) -> Result<UnitState, glib::Error> {
+    let path = unit_path(connection, unit)?;
+    let load_state = unit_property(connection, &path, "LoadState")?;
+    let active_state = unit_property(connection, &path, "ActiveState")?;
+    Ok(UnitState::from_properties(&load_state, &active_state))
+}
+
+/// Enables a unit at boot and starts it now
+///
+/// # Errors
+///
+/// Returns the first D-Bus error (requires root or a polkit authorization)
+#[allow(dead_code)] // Only called by the privileged helper
+pub fn enable_and_start(connection: &gio::DBusConnection, unit: &str) -> Result<(), glib::Error> {
+    // EnableUnitFiles(files, runtime, force)
+    call_manager(
+        connection,
+        "EnableUnitFiles",
+        &(vec![unit], false, true).to_variant(),
+    )?;
+    call_manager(connection, "Reload", &().to_variant())?;
+    call_manager(connection, "StartUnit", &(unit, "replace").to_variant())?;
+    Ok(())
+}
+
+/// Disables a unit at boot and stops it now
+///
+/// # Errors
+///
+/// Returns the first D-Bus error (requires root or a polkit authorization)
+#[allow(dead_code)] // Only called by the privileged helper
+pub fn disable_and_stop(connection: &gio::DBusConnection, unit: &str) -> Result<(), glib::Error> {
+    // DisableUnitFiles(files, runtime)
+    call_manager(
+        connection,
+        "DisableUnitFiles",
+        &(vec![unit], false).to_variant(),
+    )?;
+    call_manager(connection, "Reload", &().to_variant())?;
+    call_manager(connection, "StopUnit", &(unit, "replace").to_variant())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_state_from_properties() {
+        assert_eq!(
+            UnitState::from_properties("not-found", "inactive"),
+            UnitState::NotInstalled
+        );
+        assert_eq!(
+            UnitState::from_properties("loaded", "inactive"),
+            UnitState::Inactive
+        );
+        assert_eq!(
+            UnitState::from_properties("loaded", "failed"),
+            UnitState::Inactive
+        );
+        assert_eq!(
+            UnitState::from_properties("loaded", "active"),
+            UnitState::Active
+        );
+        assert_eq!(
+            UnitState::from_properties("loaded", "activating"),
+            UnitState::Active
+        );
+    }
+
+    #[test]
+    fn test_method_signatures() {
+        assert_eq!(
+            (vec!["battery-manager.service"], false, true)
+                .to_variant()
+                .type_()
+                .as_str(),
+            "(asbb)"
+        );
+        assert_eq!(
+            ("battery-manager.service", "replace")
+                .to_variant()
+                .type_()
+                .as_str(),
+            "(ss)"
+        );
+    }
+}
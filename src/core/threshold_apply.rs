@@ -0,0 +1,1338 @@
+//! Typed apply plan for privileged threshold writes
+//!
+//! Describes which sysfs/config files must be written and which systemd
+//! action must run, then serializes that plan to the argument list of the
+//! `battery-manager-apply` helper, which performs it as root via pkexec.
+//!
+//! This module is shared with the helper binary (`src/bin/`), so it must not
+//! depend on the rest of `crate::core`.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Name of the privileged helper binary
+pub const HELPER_NAME: &str = "battery-manager-apply";
+
+/// Installed location of the helper
+pub const HELPER_INSTALL_PATH: &str = "/usr/bin/battery-manager-apply";
+
+/// Directory holding the persisted per-battery configuration
+pub const CONFIG_DIR: &str = "/etc/battery-manager";
+
+/// Where the helper installs the restore unit
+pub const UNIT_INSTALL_PATH: &str = "/etc/systemd/system/battery-manager.service";
+
+/// Where the helper installs the udev rule (alternative to the restore unit)
+pub const UDEV_RULE_INSTALL_PATH: &str = "/etc/udev/rules.d/99-battery-manager.rules";
+
+/// Vendor threshold files outside the `charge_*threshold` family
+const VENDOR_THRESHOLD_FILES: [&str; 4] = [
+    // Samsung, LG
+    "battery_care_limit",
+    "battery_care_limiter",
+    // HP BIOS settings driver
+    "battery_optimized_charge_level_min",
+    "battery_optimized_charge_level_max",
+];
+
+/// Whether a sysfs file name is one the app writes
+///
+/// Matches the `charge_*threshold` family (and Huawei's combined
+/// `charge_control_thresholds`), `charge_behaviour` and the vendor care
+/// limits. The helper refuses any other file.
+pub fn is_threshold_control_file(name: &str) -> bool {
+    (name.starts_with("charge_") && (name.ends_with("threshold") || name.ends_with("thresholds")))
+        || name == "charge_behaviour"
+        || VENDOR_THRESHOLD_FILES.contains(&name)
+}
+
+/// Stop threshold attributes the udev rule sets on the battery device
+const UDEV_STOP_ATTRIBUTES: [&str; 3] = [
+    "charge_control_end_threshold",
+    "charge_stop_threshold",
+    "charge_end_threshold",
+];
+
+/// File name of the Huawei combined "start stop" threshold file
+const HUAWEI_COMBINED_FILE: &str = "charge_control_thresholds";
+
+/// Systemd action to perform on `battery-manager.service`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    /// `systemctl enable` + `systemctl start`
+    Enable,
+    /// `systemctl disable` + `systemctl stop`
+    Disable,
+    /// Leave the service untouched
+    Keep,
+}
+
+impl ServiceAction {
+    /// Argument value used on the helper command line
+    pub const fn as_arg(self) -> &'static str {
+        match self {
+            Self::Enable => "enable",
+            Self::Disable => "disable",
+            Self::Keep => "keep",
+        }
+    }
+}
+
+/// Restore unit to generate and install before the service action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitInstall {
+    /// Battery restored at boot (e.g., "BAT0")
+    pub battery_name: String,
+    /// Start threshold at install time, `None` when unsupported
+    pub start: Option<u8>,
+    /// Stop threshold at install time
+    pub stop: u8,
+    /// Detected start threshold files, restored at boot
+    pub start_paths: Vec<String>,
+    /// Detected stop threshold files, restored at boot
+    pub stop_paths: Vec<String>,
+}
+
+/// Change to the udev rule at [`UDEV_RULE_INSTALL_PATH`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UdevRuleAction {
+    /// Write the rule (the helper generates its content)
+    Install { battery_name: String, stop: u8 },
+    /// Delete the rule
+    Remove,
+}
+
+/// Ordered list of file writes and the service action to perform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyPlan {
+    pub writes: Vec<(PathBuf, String)>,
+    /// Unit written to [`UNIT_INSTALL_PATH`], the helper generates its content
+    pub install_unit: Option<UnitInstall>,
+    pub udev_rule: Option<UdevRuleAction>,
+    pub service_action: ServiceAction,
+}
+
+/// Settings requested by the user
+#[derive(Debug, Clone)]
+pub struct ApplyRequest<'a> {
+    /// Battery name (e.g., "BAT0")
+    pub battery_name: &'a str,
+    /// Start threshold, `None` when unsupported
+    pub start: Option<u8>,
+    /// Stop threshold
+    pub stop: u8,
+    /// Raw alarm value (same unit as `charge_full`/`energy_full`)
+    pub alarm_value: u64,
+    /// Whether the restore service should be enabled
+    pub enable_service: bool,
+    /// Persist the stop threshold with a udev rule instead of the service
+    pub udev_rule: bool,
+}
+
+/// System state the plan depends on (injected so tests don't need root)
+#[derive(Debug, Clone)]
+pub struct ApplyEnvironment<'a> {
+    /// Power supply class directory (`/sys/class/power_supply` outside tests)
+    pub power_supply_root: &'a Path,
+    /// Configuration directory (`/etc/battery-manager` outside tests)
+    pub config_dir: &'a Path,
+    /// Existing start threshold files for the battery
+    pub start_paths: &'a [String],
+    /// Existing stop threshold files for the battery
+    pub stop_paths: &'a [String],
+    /// Whether `battery-manager.service` is installed
+    pub service_unit_exists: bool,
+    /// Whether the udev rule is installed
+    pub udev_rule_exists: bool,
+}
+
+/// Reason a pair of charge thresholds is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdError {
+    /// Stop threshold above 100%
+    StopOutOfRange,
+    /// Start threshold above 100%
+    StartOutOfRange,
+    /// Start threshold not below the stop threshold
+    StartNotBelowStop,
+    /// Start threshold closer to the stop threshold than the vendor allows
+    GapTooSmall { min_gap: u8 },
+}
+
+impl ThresholdError {
+    /// Translation key of the message (`Display` is implemented in
+    /// `core::writer`, since the helper binary has no translations)
+    pub const fn message_key(self) -> &'static str {
+        match self {
+            Self::StopOutOfRange => "error_stop_out_of_range",
+            Self::StartOutOfRange => "error_start_out_of_range",
+            Self::StartNotBelowStop => "error_start_greater_stop",
+            Self::GapTooSmall { .. } => "error_threshold_gap",
+        }
+    }
+}
+
+/// Checks a pair of charge thresholds before building a plan
+///
+/// # Arguments
+///
+/// * `start` - Start threshold, `None` when unsupported
+/// * `stop` - Stop threshold
+///
+/// # Errors
+///
+/// Returns the first problem found: stop range, start range, then order
+pub fn validate_thresholds(start: Option<u8>, stop: u8) -> Result<(), ThresholdError> {
+    if stop > 100 {
+        return Err(ThresholdError::StopOutOfRange);
+    }
+    if let Some(start) = start {
+        if start > 100 {
+            return Err(ThresholdError::StartOutOfRange);
+        }
+        if start >= stop {
+            return Err(ThresholdError::StartNotBelowStop);
+        }
+    }
+    Ok(())
+}
+
+/// Checks thresholds read from the settings spinners against a vendor gap
+///
+/// The spinners already clamp to 0–100; the raw values are checked again
+/// before anything is written.
+///
+/// # Arguments
+///
+/// * `start` - Start spinner value, `None` when unsupported
+/// * `stop` - Stop spinner value
+/// * `min_gap` - Minimum distance between start and stop required by the vendor
+///
+/// # Errors
+///
+/// Same order as [`validate_thresholds`], then `GapTooSmall`
+pub fn validate_threshold_values(
+    start: Option<f64>,
+    stop: f64,
+    min_gap: u8,
+) -> Result<(), ThresholdError> {
+    let percent = |value: f64, error: ThresholdError| {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let percent = value.round() as u8;
+        if (0.0..=100.0).contains(&value) {
+            Ok(percent)
+        } else {
+            Err(error)
+        }
+    };
+    let stop = percent(stop, ThresholdError::StopOutOfRange)?;
+    let start = start
+        .map(|start| percent(start, ThresholdError::StartOutOfRange))
+        .transpose()?;
+
+    validate_thresholds(start, stop)?;
+    match start {
+        Some(start) if stop - start < min_gap => Err(ThresholdError::GapTooSmall { min_gap }),
+        _ => Ok(()),
+    }
+}
+
+/// Returns true if the path is the Huawei combined threshold file
+fn is_combined_file(path: &str) -> bool {
+    Path::new(path)
+        .file_name()
+        .is_some_and(|name| name == HUAWEI_COMBINED_FILE)
+}
+
+/// Content of the per-battery config file read back at boot
+fn config_content(start: Option<u8>, stop: u8) -> String {
+    match start {
+        Some(start) => format!("START_THRESHOLD={start}\nSTOP_THRESHOLD={stop}\n"),
+        None => format!("STOP_THRESHOLD={stop}\n"),
+    }
+}
+
+/// Whether a threshold path can be written into the restore unit as is
+///
+/// Only plain sysfs names: no quote, space, `$` or `%` (a systemd specifier).
+pub fn is_unit_safe_path(path: &str) -> bool {
+    path.starts_with('/')
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '.' | ':'))
+}
+
+/// Shell command writing `$variable` to `path` when it is writable
+fn unit_write_command(path: &str, variable: &str) -> String {
+    format!("if [ -w \"{path}\" ]; then echo \"$${variable}\" > \"{path}\"; fi;")
+}
+
+/// Shell commands restoring the thresholds of the unit
+///
+/// Writes the same files as [`build_plan`]. Without detected files, the
+/// standard `power_supply` attributes of the battery are tried instead.
+fn unit_restore_commands(unit: &UnitInstall) -> String {
+    let safe = |paths: &[String]| -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| is_unit_safe_path(path))
+            .cloned()
+            .collect()
+    };
+    let (start_paths, stop_paths) = (safe(&unit.start_paths), safe(&unit.stop_paths));
+    if start_paths.is_empty() && stop_paths.is_empty() {
+        return format!(
+            "d=/sys/class/power_supply/{}; \
+             if [ -n \"$$START_THRESHOLD\" ]; then \
+             for f in charge_control_start_threshold charge_start_threshold; do \
+             [ -w \"$$d/$$f\" ] && echo \"$$START_THRESHOLD\" > \"$$d/$$f\" && break; done; fi; \
+             for f in charge_control_end_threshold charge_stop_threshold charge_end_threshold; do \
+             [ -w \"$$d/$$f\" ] && echo \"$$STOP_THRESHOLD\" > \"$$d/$$f\" && break; done",
+            unit.battery_name
+        );
+    }
+
+    let mut commands = Vec::new();
+    // Huawei exposes a single file taking "start stop"
+    if let Some(combined) = start_paths
+        .iter()
+        .chain(&stop_paths)
+        .find(|p| is_combined_file(p))
+    {
+        commands.push(format!(
+            "if [ -w \"{combined}\" ]; then \
+             echo \"$${{START_THRESHOLD:-0}} $$STOP_THRESHOLD\" > \"{combined}\"; fi;"
+        ));
+    }
+    let start_writes: Vec<String> = start_paths
+        .iter()
+        .filter(|p| !is_combined_file(p))
+        .map(|path| unit_write_command(path, "START_THRESHOLD"))
+        .collect();
+    if !start_writes.is_empty() {
+        commands.push(format!(
+            "if [ -n \"$$START_THRESHOLD\" ]; then {} fi;",
+            start_writes.join(" ")
+        ));
+    }
+    commands.extend(
+        stop_paths
+            .iter()
+            .filter(|p| !is_combined_file(p))
+            .map(|path| unit_write_command(path, "STOP_THRESHOLD")),
+    );
+    commands.join(" ")
+}
+
+/// Generates the restore unit for a battery
+///
+/// The unit does not embed the thresholds: it reads `<CONFIG_DIR>/<battery>.conf`
+/// at every boot, so later applies only need to rewrite the config file. The
+/// install-time values are kept in a comment. `$` is doubled for systemd.
+///
+/// # Arguments
+///
+/// * `unit` - Battery, install-time thresholds and detected threshold files
+///
+/// # Returns
+///
+/// Unit file content for `battery-manager.service`
+pub fn unit_file(unit: &UnitInstall) -> String {
+    let battery_name = &unit.battery_name;
+    let stop = unit.stop;
+    let thresholds = match unit.start {
+        Some(start) => format!("start {start}%, stop {stop}%"),
+        None => format!("stop {stop}%"),
+    };
+    let config = format!("{CONFIG_DIR}/{battery_name}.conf");
+    let script = format!(". {config}; {}", unit_restore_commands(unit));
+    format!(
+        "# Generated by Battery Manager for {battery_name} ({thresholds} at install time)
+# Thresholds are read from {config} at every boot.
+[Unit]
+Description=Battery Manager - Restore charge thresholds ({battery_name})
+After=multi-user.target
+ConditionPathExists={config}
+
+[Service]
+Type=oneshot
+ExecStart=/bin/sh -c '{script}'
+RemainAfterExit=yes
+
+[Install]
+WantedBy=multi-user.target
+"
+    )
+}
+
+/// Generates the udev rule writing the stop threshold when the battery appears
+///
+/// One line per stop attribute name used by the kernel drivers; `TEST`
+/// skips the names the battery doesn't expose. Only the stop threshold is
+/// restored (it is the one protecting the battery).
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `stop` - Stop threshold (validated, 0–100)
+///
+/// # Returns
+///
+/// Rule file content for `99-battery-manager.rules`
+pub fn udev_rule(battery_name: &str, stop: u8) -> String {
+    let mut rule = format!(
+        "# Generated by Battery Manager for {battery_name} (stop {stop}%)\n\
+         # Writes the stop threshold each time the battery device is added.\n"
+    );
+    for attribute in UDEV_STOP_ATTRIBUTES {
+        let _ = writeln!(
+            rule,
+            "ACTION==\"add\", SUBSYSTEM==\"power_supply\", KERNEL==\"{battery_name}\", \
+             TEST==\"{attribute}\", ATTR{{{attribute}}}=\"{stop}\""
+        );
+    }
+    rule
+}
+
+/// Whether the udev rule is installed
+pub fn udev_rule_installed() -> bool {
+    Path::new(UDEV_RULE_INSTALL_PATH).is_file()
+}
+
+/// Lists the threshold file writes for a start/stop pair
+fn threshold_writes(
+    start: Option<u8>,
+    stop: u8,
+    env: &ApplyEnvironment<'_>,
+) -> Vec<(PathBuf, String)> {
+    let mut writes: Vec<(PathBuf, String)> = Vec::new();
+
+    // Huawei exposes a single file taking "start stop"
+    if let Some(combined) = env
+        .start_paths
+        .iter()
+        .chain(env.stop_paths)
+        .find(|p| is_combined_file(p))
+    {
+        let start = start.unwrap_or(0);
+        writes.push((PathBuf::from(combined), format!("{start} {stop}")));
+    }
+
+    if let Some(start) = start {
+        for path in env.start_paths.iter().filter(|p| !is_combined_file(p)) {
+            writes.push((PathBuf::from(path), start.to_string()));
+        }
+    }
+
+    for path in env.stop_paths.iter().filter(|p| !is_combined_file(p)) {
+        writes.push((PathBuf::from(path), stop.to_string()));
+    }
+
+    writes
+}
+
+/// Builds the apply plan for a request
+///
+/// # Arguments
+///
+/// * `request` - Settings requested by the user
+/// * `env` - Detected files and service state
+///
+/// # Returns
+///
+/// `ApplyPlan` with threshold, alarm and config writes plus the service action
+pub fn build_plan(request: &ApplyRequest<'_>, env: &ApplyEnvironment<'_>) -> ApplyPlan {
+    let stop = request.stop;
+    let mut writes = threshold_writes(request.start, stop, env);
+
+    let alarm_path = env
+        .power_supply_root
+        .join(request.battery_name)
+        .join("alarm");
+    if alarm_path.is_file() {
+        writes.push((alarm_path, request.alarm_value.to_string()));
+    }
+
+    // Persist config (START_THRESHOLD only if supported)
+    writes.push((
+        env.config_dir
+            .join(format!("{}.conf", request.battery_name)),
+        config_content(request.start, stop),
+    ));
+
+    // The udev rule replaces the service: never both
+    let service_action = if request.enable_service && !request.udev_rule {
+        ServiceAction::Enable
+    } else if env.service_unit_exists {
+        ServiceAction::Disable
+    } else {
+        ServiceAction::Keep
+    };
+
+    let udev_rule = if request.udev_rule {
+        Some(UdevRuleAction::Install {
+            battery_name: request.battery_name.to_string(),
+            stop,
+        })
+    } else {
+        env.udev_rule_exists.then_some(UdevRuleAction::Remove)
+    };
+
+    ApplyPlan {
+        writes,
+        install_unit: None,
+        udev_rule,
+        service_action,
+    }
+}
+
+/// Builds a plan installing and enabling the restore unit
+///
+/// Writes the config file the unit reads, installs the unit, then enables
+/// and starts it (which applies the thresholds right away).
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `start` - Start threshold, `None` when unsupported
+/// * `stop` - Stop threshold
+/// * `env` - Detected files (the unit restores them) and config directory
+///
+/// # Returns
+///
+/// `ApplyPlan` with the config write, the unit install and `ServiceAction::Enable`
+pub fn build_install_plan(
+    battery_name: &str,
+    start: Option<u8>,
+    stop: u8,
+    env: &ApplyEnvironment<'_>,
+) -> ApplyPlan {
+    ApplyPlan {
+        writes: vec![(
+            env.config_dir.join(format!("{battery_name}.conf")),
+            config_content(start, stop),
+        )],
+        install_unit: Some(UnitInstall {
+            battery_name: battery_name.to_string(),
+            start,
+            stop,
+            start_paths: env.start_paths.to_vec(),
+            stop_paths: env.stop_paths.to_vec(),
+        }),
+        udev_rule: None,
+        service_action: ServiceAction::Enable,
+    }
+}
+
+/// Builds a plan writing saved thresholds again (after a resume from suspend)
+///
+/// Only the threshold files are written: the config file already holds
+/// these values and the service is left as it is.
+///
+/// # Arguments
+///
+/// * `start` - Saved start threshold, `None` when unsupported
+/// * `stop` - Saved stop threshold
+/// * `env` - Detected files and service state
+///
+/// # Returns
+///
+/// `ApplyPlan` with threshold writes only and no service action
+pub fn build_reapply_plan(start: Option<u8>, stop: u8, env: &ApplyEnvironment<'_>) -> ApplyPlan {
+    ApplyPlan {
+        writes: threshold_writes(start, stop, env),
+        install_unit: None,
+        udev_rule: None,
+        service_action: ServiceAction::Keep,
+    }
+}
+
+/// Builds a one-off plan raising the stop threshold to 100%
+///
+/// Nothing is persisted: the config file is left untouched, so the saved
+/// thresholds come back at the next reboot or service restart.
+///
+/// # Arguments
+///
+/// * `current_start` - Current start threshold (kept for combined files)
+/// * `env` - Detected files and service state
+///
+/// # Returns
+///
+/// `ApplyPlan` with only stop threshold writes and no service action
+pub fn build_full_charge_plan(current_start: Option<u8>, env: &ApplyEnvironment<'_>) -> ApplyPlan {
+    let mut writes: Vec<(PathBuf, String)> = Vec::new();
+
+    if let Some(combined) = env
+        .start_paths
+        .iter()
+        .chain(env.stop_paths)
+        .find(|p| is_combined_file(p))
+    {
+        let start = current_start.unwrap_or(0);
+        writes.push((PathBuf::from(combined), format!("{start} 100")));
+    }
+
+    for path in env.stop_paths.iter().filter(|p| !is_combined_file(p)) {
+        writes.push((PathBuf::from(path), "100".to_string()));
+    }
+
+    ApplyPlan {
+        writes,
+        install_unit: None,
+        udev_rule: None,
+        service_action: ServiceAction::Keep,
+    }
+}
+
+/// Builds a plan clearing the discharge alarm (back to the driver default)
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `power_supply_root` - Power supply class directory
+///
+/// # Returns
+///
+/// * `Some(ApplyPlan)` - Single write of 0 to the alarm file
+/// * `None` - The battery has no alarm file
+pub fn build_alarm_reset_plan(battery_name: &str, power_supply_root: &Path) -> Option<ApplyPlan> {
+    let alarm_path = power_supply_root.join(battery_name).join("alarm");
+    alarm_path.is_file().then(|| ApplyPlan {
+        writes: vec![(alarm_path, "0".to_string())],
+        install_unit: None,
+        udev_rule: None,
+        service_action: ServiceAction::Keep,
+    })
+}
+
+/// Builds a plan switching the `charge_behaviour` mode
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `mode` - Kernel mode name ("auto", "inhibit-charge", "force-discharge")
+/// * `power_supply_root` - Power supply class directory
+///
+/// # Returns
+///
+/// * `Some(ApplyPlan)` - Single write of the mode, nothing persisted
+/// * `None` - The battery has no `charge_behaviour` file
+pub fn build_charge_behaviour_plan(
+    battery_name: &str,
+    mode: &str,
+    power_supply_root: &Path,
+) -> Option<ApplyPlan> {
+    let path = power_supply_root
+        .join(battery_name)
+        .join("charge_behaviour");
+    path.is_file().then(|| ApplyPlan {
+        writes: vec![(path, mode.to_string())],
+        install_unit: None,
+        udev_rule: None,
+        service_action: ServiceAction::Keep,
+    })
+}
+
+impl ApplyPlan {
+    /// Serializes the plan to helper arguments
+    ///
+    /// Format: `--write <path> <value>`... `[--install-unit <battery> <start|-> <stop>`
+    /// `[--unit-start-path <path>]... [--unit-stop-path <path>]...]`
+    /// `[--udev-rule <battery> <stop> | --remove-udev-rule]` `--service <enable|disable|keep>`
+    ///
+    /// # Returns
+    ///
+    /// Argument list (without the program name)
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::with_capacity(self.writes.len() * 3 + 2);
+        for (path, value) in &self.writes {
+            args.push("--write".to_string());
+            args.push(path.to_string_lossy().to_string());
+            args.push(value.clone());
+        }
+        if let Some(unit) = &self.install_unit {
+            args.push("--install-unit".to_string());
+            args.push(unit.battery_name.clone());
+            args.push(
+                unit.start
+                    .map_or_else(|| "-".to_string(), |s| s.to_string()),
+            );
+            args.push(unit.stop.to_string());
+            for path in &unit.start_paths {
+                args.push("--unit-start-path".to_string());
+                args.push(path.clone());
+            }
+            for path in &unit.stop_paths {
+                args.push("--unit-stop-path".to_string());
+                args.push(path.clone());
+            }
+        }
+        match &self.udev_rule {
+            Some(UdevRuleAction::Install { battery_name, stop }) => {
+                args.push("--udev-rule".to_string());
+                args.push(battery_name.clone());
+                args.push(stop.to_string());
+            }
+            Some(UdevRuleAction::Remove) => args.push("--remove-udev-rule".to_string()),
+            None => {}
+        }
+        args.push("--service".to_string());
+        args.push(self.service_action.as_arg().to_string());
+        args
+    }
+}
+
+/// Locates the helper binary
+///
+/// Prefers the installed path. Only debug builds fall back to a helper next
+/// to the running executable (`cargo run`): a release build must not hand
+/// pkexec a binary from a directory its user may be able to write to.
+///
+/// # Returns
+///
+/// * `Some(PathBuf)` - Absolute path to the helper
+/// * `None` - Helper not found
+pub fn locate_helper() -> Option<PathBuf> {
+    let installed = PathBuf::from(HELPER_INSTALL_PATH);
+    if installed.is_file() {
+        return Some(installed);
+    }
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(HELPER_NAME)))
+        .filter(|p| p.is_file())
+}
+
+/// Outcome of a finished `pkexec` run of the helper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    /// The helper applied the plan
+    Success,
+    /// Authentication dialog dismissed (126) or authorization denied (127)
+    Cancelled,
+    /// Unexpected failure, with the output shown to the user
+    Failed(String),
+}
+
+/// Maps the exit status of `pkexec battery-manager-apply` to an outcome
+///
+/// pkexec exits with 126 when the authentication dialog is dismissed and 127
+/// when authorization is denied: neither is an error worth showing raw
+/// output for. Any other non-zero code comes from the helper itself.
+///
+/// # Arguments
+///
+/// * `code` - Exit code (`None` if killed by a signal)
+/// * `stderr` - Output of the run, shown only for unexpected failures
+pub fn classify_pkexec_result(code: Option<i32>, stderr: &str) -> ApplyOutcome {
+    match code {
+        Some(0) => ApplyOutcome::Success,
+        Some(126 | 127) => ApplyOutcome::Cancelled,
+        _ if !stderr.trim().is_empty() => ApplyOutcome::Failed(stderr.trim().to_string()),
+        _ => ApplyOutcome::Failed(format!("pkexec returned non-zero status: {code:?}")),
+    }
+}
+
+/// Lists the files to read the stop threshold back from after an apply
+///
+/// Keeps the vendor stop files of this battery (and platform-wide files such
+/// as the Huawei combined file), then falls back to the standard
+/// `charge_control_end_threshold` file.
+///
+/// # Arguments
+///
+/// * `power_supply_root` - Usually `/sys/class/power_supply`
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `stop_paths` - Detected vendor stop threshold files
+///
+/// # Returns
+///
+/// Candidate files, most specific first
+pub fn stop_read_back_paths(
+    power_supply_root: &Path,
+    battery_name: &str,
+    stop_paths: &[String],
+) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = stop_paths
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| {
+            !path.starts_with(power_supply_root)
+                || path
+                    .parent()
+                    .and_then(Path::file_name)
+                    .is_some_and(|dir| dir == battery_name)
+        })
+        .collect();
+    paths.push(
+        power_supply_root
+            .join(battery_name)
+            .join("charge_control_end_threshold"),
+    );
+    paths
+}
+
+/// Compares a stop threshold read back from sysfs with the written value
+///
+/// Combined "start stop" files report the stop value last.
+///
+/// # Arguments
+///
+/// * `expected` - Stop threshold that was written
+/// * `raw` - File content read back
+///
+/// # Returns
+///
+/// * `Some(actual)` - The kernel reports another value
+/// * `None` - Value kept, or content not a percentage
+pub fn stop_read_back_mismatch(expected: u8, raw: &str) -> Option<u8> {
+    let actual: u8 = raw.split_whitespace().last()?.parse().ok()?;
+    (actual != expected).then_some(actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_classify_pkexec_result() {
+        assert_eq!(classify_pkexec_result(Some(0), ""), ApplyOutcome::Success);
+        assert_eq!(
+            classify_pkexec_result(Some(126), "Error executing command as another user"),
+            ApplyOutcome::Cancelled
+        );
+        assert_eq!(
+            classify_pkexec_result(Some(127), "Not authorized"),
+            ApplyOutcome::Cancelled
+        );
+        assert_eq!(
+            classify_pkexec_result(Some(1), "write refused\n"),
+            ApplyOutcome::Failed("write refused".to_string())
+        );
+        assert_eq!(
+            classify_pkexec_result(None, "  "),
+            ApplyOutcome::Failed("pkexec returned non-zero status: None".to_string())
+        );
+    }
+
+    struct TempRoot(PathBuf);
+
+    impl TempRoot {
+        fn new(tag: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "battery-manager-apply-{tag}-{}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("BAT0")).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempRoot {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn request(start: Option<u8>, enable_service: bool) -> ApplyRequest<'static> {
+        ApplyRequest {
+            battery_name: "BAT0",
+            start,
+            stop: 80,
+            alarm_value: 250_000,
+            enable_service,
+            udev_rule: false,
+        }
+    }
+
+    #[test]
+    fn test_udev_rule_content() {
+        let rule = udev_rule("BAT1", 75);
+        assert!(rule.starts_with("# Generated by Battery Manager for BAT1 (stop 75%)\n"));
+        let lines: Vec<&str> = rule.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "ACTION==\"add\", SUBSYSTEM==\"power_supply\", KERNEL==\"BAT1\", \
+             TEST==\"charge_control_end_threshold\", ATTR{charge_control_end_threshold}=\"75\""
+        );
+        assert!(lines[1].ends_with("ATTR{charge_stop_threshold}=\"75\""));
+        assert!(lines[2].ends_with("ATTR{charge_end_threshold}=\"75\""));
+    }
+
+    #[test]
+    fn test_plan_udev_rule_replaces_service() {
+        let root = TempRoot::new("udev");
+        let mut env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &[],
+            stop_paths: &[],
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+
+        // Rule requested: installed, and the service is turned off
+        let mut with_rule = request(None, true);
+        with_rule.udev_rule = true;
+        let plan = build_plan(&with_rule, &env);
+        assert_eq!(
+            plan.udev_rule,
+            Some(UdevRuleAction::Install {
+                battery_name: "BAT0".to_string(),
+                stop: 80
+            })
+        );
+        assert_eq!(plan.service_action, ServiceAction::Disable);
+        assert!(plan
+            .to_args()
+            .windows(3)
+            .any(|w| w == ["--udev-rule", "BAT0", "80"]));
+
+        // Back to the service: an installed rule is removed
+        env.udev_rule_exists = true;
+        let plan = build_plan(&request(None, true), &env);
+        assert_eq!(plan.udev_rule, Some(UdevRuleAction::Remove));
+        assert_eq!(plan.service_action, ServiceAction::Enable);
+
+        env.udev_rule_exists = false;
+        assert_eq!(build_plan(&request(None, true), &env).udev_rule, None);
+    }
+
+    #[test]
+    fn test_plan_start_unsupported() {
+        let root = TempRoot::new("nostart");
+        let stop_paths = vec!["/sys/class/power_supply/BAT0/charge_control_end_threshold".into()];
+        let env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &[],
+            stop_paths: &stop_paths,
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+
+        let plan = build_plan(&request(None, true), &env);
+        assert_eq!(
+            plan.writes[0],
+            (PathBuf::from(&stop_paths[0]), "80".to_string())
+        );
+        let (config_path, config) = plan.writes.last().unwrap();
+        assert_eq!(config_path, Path::new("/etc/battery-manager/BAT0.conf"));
+        assert_eq!(config, "STOP_THRESHOLD=80\n");
+        assert_eq!(plan.service_action, ServiceAction::Enable);
+    }
+
+    #[test]
+    fn test_plan_alarm_file_missing_or_present() {
+        let root = TempRoot::new("alarm");
+        let env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &[],
+            stop_paths: &[],
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+        let alarm_path = root.0.join("BAT0").join("alarm");
+
+        let plan = build_plan(&request(Some(60), true), &env);
+        assert!(plan.writes.iter().all(|(p, _)| p != &alarm_path));
+
+        fs::write(&alarm_path, "0\n").unwrap();
+        let plan = build_plan(&request(Some(60), true), &env);
+        assert!(plan.writes.contains(&(alarm_path, "250000".to_string())));
+    }
+
+    #[test]
+    fn test_plan_huawei_combined_write() {
+        let root = TempRoot::new("huawei");
+        let combined = vec!["/sys/devices/platform/huawei-wmi/charge_control_thresholds".into()];
+        let env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &combined,
+            stop_paths: &combined,
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+
+        let plan = build_plan(&request(Some(40), true), &env);
+        let combined_writes: Vec<_> = plan
+            .writes
+            .iter()
+            .filter(|(p, _)| p == Path::new(&combined[0]))
+            .collect();
+        assert_eq!(combined_writes.len(), 1);
+        assert_eq!(combined_writes[0].1, "40 80");
+    }
+
+    #[test]
+    fn test_plan_service_disable_without_unit() {
+        let root = TempRoot::new("service");
+        let mut env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &[],
+            stop_paths: &[],
+            service_unit_exists: false,
+            udev_rule_exists: false,
+        };
+
+        let plan = build_plan(&request(None, false), &env);
+        assert_eq!(plan.service_action, ServiceAction::Keep);
+
+        env.service_unit_exists = true;
+        let plan = build_plan(&request(None, false), &env);
+        assert_eq!(plan.service_action, ServiceAction::Disable);
+    }
+
+    #[test]
+    fn test_full_charge_plan_does_not_persist() {
+        let root = TempRoot::new("full");
+        fs::write(root.0.join("BAT0").join("alarm"), "0\n").unwrap();
+        let start_paths =
+            vec!["/sys/class/power_supply/BAT0/charge_control_start_threshold".into()];
+        let stop_paths = vec!["/sys/class/power_supply/BAT0/charge_control_end_threshold".into()];
+        let env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &start_paths,
+            stop_paths: &stop_paths,
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+
+        let plan = build_full_charge_plan(Some(60), &env);
+        assert_eq!(
+            plan.writes,
+            vec![(PathBuf::from(&stop_paths[0]), "100".to_string())]
+        );
+        assert_eq!(plan.service_action, ServiceAction::Keep);
+        assert!(plan
+            .writes
+            .iter()
+            .all(|(path, _)| path.extension().is_none_or(|ext| ext != "conf")));
+        assert!(plan
+            .to_args()
+            .iter()
+            .all(|arg| !arg.starts_with("/etc/battery-manager")));
+    }
+
+    #[test]
+    fn test_alarm_reset_plan() {
+        let root = TempRoot::new("alarm-reset");
+        assert_eq!(build_alarm_reset_plan("BAT0", &root.0), None);
+
+        let alarm_path = root.0.join("BAT0").join("alarm");
+        fs::write(&alarm_path, "520000\n").unwrap();
+        let plan = build_alarm_reset_plan("BAT0", &root.0).unwrap();
+        assert_eq!(plan.writes, vec![(alarm_path, "0".to_string())]);
+        assert_eq!(plan.service_action, ServiceAction::Keep);
+    }
+
+    #[test]
+    fn test_charge_behaviour_plan() {
+        let root = TempRoot::new("charge-behaviour");
+        assert_eq!(
+            build_charge_behaviour_plan("BAT0", "inhibit-charge", &root.0),
+            None
+        );
+
+        let path = root.0.join("BAT0").join("charge_behaviour");
+        fs::write(&path, "[auto] inhibit-charge force-discharge\n").unwrap();
+        let plan = build_charge_behaviour_plan("BAT0", "force-discharge", &root.0).unwrap();
+        assert_eq!(plan.writes, vec![(path, "force-discharge".to_string())]);
+        assert_eq!(plan.service_action, ServiceAction::Keep);
+    }
+
+    #[test]
+    fn test_reapply_plan_writes_thresholds_only() {
+        let root = TempRoot::new("reapply");
+        fs::write(root.0.join("BAT0").join("alarm"), "0\n").unwrap();
+        let start_paths =
+            vec!["/sys/class/power_supply/BAT0/charge_control_start_threshold".into()];
+        let stop_paths = vec!["/sys/class/power_supply/BAT0/charge_control_end_threshold".into()];
+        let env = ApplyEnvironment {
+            power_supply_root: &root.0,
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths: &start_paths,
+            stop_paths: &stop_paths,
+            service_unit_exists: true,
+            udev_rule_exists: false,
+        };
+
+        // No alarm nor config write, the service is left alone
+        let plan = build_reapply_plan(Some(60), 80, &env);
+        assert_eq!(
+            plan.writes,
+            vec![
+                (PathBuf::from(&start_paths[0]), "60".to_string()),
+                (PathBuf::from(&stop_paths[0]), "80".to_string()),
+            ]
+        );
+        assert_eq!(plan.service_action, ServiceAction::Keep);
+    }
+
+    #[test]
+    fn test_plan_to_args() {
+        let plan = ApplyPlan {
+            writes: vec![(
+                PathBuf::from("/sys/class/power_supply/BAT0/charge_control_end_threshold"),
+                "80".to_string(),
+            )],
+            install_unit: None,
+            udev_rule: None,
+            service_action: ServiceAction::Keep,
+        };
+
+        assert_eq!(
+            plan.to_args(),
+            vec![
+                "--write",
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold",
+                "80",
+                "--service",
+                "keep"
+            ]
+        );
+    }
+
+    /// Environment with the given threshold files and no service installed
+    fn install_env<'a>(
+        start_paths: &'a [String],
+        stop_paths: &'a [String],
+    ) -> ApplyEnvironment<'a> {
+        ApplyEnvironment {
+            power_supply_root: Path::new("/sys/class/power_supply"),
+            config_dir: Path::new("/etc/battery-manager"),
+            start_paths,
+            stop_paths,
+            service_unit_exists: false,
+            udev_rule_exists: false,
+        }
+    }
+
+    /// Unit installed for a battery without detected threshold files
+    fn unit(battery_name: &str, start: Option<u8>, stop: u8) -> UnitInstall {
+        UnitInstall {
+            battery_name: battery_name.to_string(),
+            start,
+            stop,
+            start_paths: Vec::new(),
+            stop_paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_install_plan() {
+        let plan = build_install_plan("BAT0", None, 80, &install_env(&[], &[]));
+        assert_eq!(
+            plan.to_args(),
+            vec![
+                "--write",
+                "/etc/battery-manager/BAT0.conf",
+                "STOP_THRESHOLD=80\n",
+                "--install-unit",
+                "BAT0",
+                "-",
+                "80",
+                "--service",
+                "enable"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unit_file() {
+        let expected = r#"# Generated by Battery Manager for BAT0 (start 60%, stop 80% at install time)
+# Thresholds are read from /etc/battery-manager/BAT0.conf at every boot.
+[Unit]
+Description=Battery Manager - Restore charge thresholds (BAT0)
+After=multi-user.target
+ConditionPathExists=/etc/battery-manager/BAT0.conf
+
+[Service]
+Type=oneshot
+ExecStart=/bin/sh -c '. /etc/battery-manager/BAT0.conf; d=/sys/class/power_supply/BAT0; if [ -n "$$START_THRESHOLD" ]; then for f in charge_control_start_threshold charge_start_threshold; do [ -w "$$d/$$f" ] && echo "$$START_THRESHOLD" > "$$d/$$f" && break; done; fi; for f in charge_control_end_threshold charge_stop_threshold charge_end_threshold; do [ -w "$$d/$$f" ] && echo "$$STOP_THRESHOLD" > "$$d/$$f" && break; done'
+RemainAfterExit=yes
+
+[Install]
+WantedBy=multi-user.target
+"#;
+        assert_eq!(unit_file(&unit("BAT0", Some(60), 80)), expected);
+
+        let stop_only = unit_file(&unit("CMB0", None, 90));
+        assert_eq!(
+            stop_only.lines().next(),
+            Some("# Generated by Battery Manager for CMB0 (stop 90% at install time)")
+        );
+        assert!(stop_only.contains(". /etc/battery-manager/CMB0.conf;"));
+        assert!(stop_only.contains("d=/sys/class/power_supply/CMB0;"));
+    }
+
+    #[test]
+    fn test_unit_file_restores_detected_vendor_files() {
+        let stop_paths = ["/sys/devices/platform/samsung/battery_care_limit".to_string()];
+        let plan = build_install_plan("BAT1", None, 80, &install_env(&[], &stop_paths));
+        let unit = plan.install_unit.unwrap();
+        let expected = r#"# Generated by Battery Manager for BAT1 (stop 80% at install time)
+# Thresholds are read from /etc/battery-manager/BAT1.conf at every boot.
+[Unit]
+Description=Battery Manager - Restore charge thresholds (BAT1)
+After=multi-user.target
+ConditionPathExists=/etc/battery-manager/BAT1.conf
+
+[Service]
+Type=oneshot
+ExecStart=/bin/sh -c '. /etc/battery-manager/BAT1.conf; if [ -w "/sys/devices/platform/samsung/battery_care_limit" ]; then echo "$$STOP_THRESHOLD" > "/sys/devices/platform/samsung/battery_care_limit"; fi;'
+RemainAfterExit=yes
+
+[Install]
+WantedBy=multi-user.target
+"#;
+        assert_eq!(unit_file(&unit), expected);
+
+        // Huawei combined file plus a separate start file
+        let combined = "/sys/devices/platform/huawei-wmi/charge_control_thresholds".to_string();
+        let unit = UnitInstall {
+            start_paths: vec![
+                combined.clone(),
+                "/sys/class/power_supply/BAT0/charge_control_start_threshold".to_string(),
+            ],
+            stop_paths: vec![combined],
+            ..self::unit("BAT0", Some(40), 80)
+        };
+        assert!(unit_file(&unit).contains(
+            "ExecStart=/bin/sh -c '. /etc/battery-manager/BAT0.conf; \
+             if [ -w \"/sys/devices/platform/huawei-wmi/charge_control_thresholds\" ]; then \
+             echo \"$${START_THRESHOLD:-0} $$STOP_THRESHOLD\" > \"/sys/devices/platform/huawei-wmi/charge_control_thresholds\"; fi; \
+             if [ -n \"$$START_THRESHOLD\" ]; then \
+             if [ -w \"/sys/class/power_supply/BAT0/charge_control_start_threshold\" ]; then \
+             echo \"$$START_THRESHOLD\" > \"/sys/class/power_supply/BAT0/charge_control_start_threshold\"; fi; fi;'"
+        ));
+
+        // Paths that would need quoting are never written into the unit
+        let unsafe_unit = UnitInstall {
+            stop_paths: vec!["/sys/x/charge_control_end_threshold\"; reboot".to_string()],
+            ..self::unit("BAT0", None, 80)
+        };
+        assert!(!unit_file(&unsafe_unit).contains("reboot"));
+    }
+
+    #[test]
+    fn test_stop_read_back_mismatch() {
+        assert_eq!(stop_read_back_mismatch(80, "80\n"), None);
+        assert_eq!(stop_read_back_mismatch(80, "100\n"), Some(100));
+        // Huawei combined file: "start stop"
+        assert_eq!(stop_read_back_mismatch(80, "40 80\n"), None);
+        assert_eq!(stop_read_back_mismatch(80, "40 90"), Some(90));
+        // Nothing to compare
+        assert_eq!(stop_read_back_mismatch(80, ""), None);
+        assert_eq!(stop_read_back_mismatch(80, "on"), None);
+    }
+
+    #[test]
+    fn test_stop_read_back_paths() {
+        let root = Path::new("/sys/class/power_supply");
+        let stop_paths = vec![
+            "/sys/class/power_supply/BAT0/charge_control_end_threshold".to_string(),
+            "/sys/class/power_supply/BAT1/charge_control_end_threshold".to_string(),
+            "/sys/devices/platform/huawei-wmi/charge_control_thresholds".to_string(),
+        ];
+
+        assert_eq!(
+            stop_read_back_paths(root, "BAT1", &stop_paths),
+            vec![
+                PathBuf::from("/sys/class/power_supply/BAT1/charge_control_end_threshold"),
+                PathBuf::from("/sys/devices/platform/huawei-wmi/charge_control_thresholds"),
+                PathBuf::from("/sys/class/power_supply/BAT1/charge_control_end_threshold"),
+            ]
+        );
+        assert_eq!(
+            stop_read_back_paths(root, "BATT", &[]),
+            vec![PathBuf::from(
+                "/sys/class/power_supply/BATT/charge_control_end_threshold"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_thresholds() {
+        assert_eq!(validate_thresholds(Some(40), 80), Ok(()));
+        assert_eq!(validate_thresholds(None, 100), Ok(()));
+        assert_eq!(validate_thresholds(Some(0), 1), Ok(()));
+
+        assert_eq!(
+            validate_thresholds(None, 101),
+            Err(ThresholdError::StopOutOfRange)
+        );
+        // Stop is checked first
+        assert_eq!(
+            validate_thresholds(Some(150), 150),
+            Err(ThresholdError::StopOutOfRange)
+        );
+        assert_eq!(
+            validate_thresholds(Some(101), 100),
+            Err(ThresholdError::StartOutOfRange)
+        );
+        assert_eq!(
+            validate_thresholds(Some(80), 80),
+            Err(ThresholdError::StartNotBelowStop)
+        );
+        assert_eq!(
+            validate_thresholds(Some(85), 80),
+            Err(ThresholdError::StartNotBelowStop)
+        );
+        assert_eq!(
+            ThresholdError::StartNotBelowStop.message_key(),
+            "error_start_greater_stop"
+        );
+    }
+
+    #[test]
+    fn test_validate_threshold_values_boundaries() {
+        assert_eq!(validate_threshold_values(Some(0.0), 100.0, 1), Ok(()));
+        assert_eq!(validate_threshold_values(None, 0.0, 1), Ok(()));
+
+        assert_eq!(
+            validate_threshold_values(None, 100.5, 1),
+            Err(ThresholdError::StopOutOfRange)
+        );
+        assert_eq!(
+            validate_threshold_values(Some(40.0), -1.0, 1),
+            Err(ThresholdError::StopOutOfRange)
+        );
+        assert_eq!(
+            validate_threshold_values(Some(-0.5), 80.0, 1),
+            Err(ThresholdError::StartOutOfRange)
+        );
+        assert_eq!(
+            validate_threshold_values(Some(f64::NAN), 80.0, 1),
+            Err(ThresholdError::StartOutOfRange)
+        );
+        assert_eq!(
+            validate_threshold_values(Some(80.0), 80.0, 5),
+            Err(ThresholdError::StartNotBelowStop)
+        );
+    }
+
+    #[test]
+    fn test_validate_threshold_values_gap() {
+        // Exactly the minimum gap is allowed
+        assert_eq!(validate_threshold_values(Some(75.0), 80.0, 5), Ok(()));
+        assert_eq!(
+            validate_threshold_values(Some(76.0), 80.0, 5),
+            Err(ThresholdError::GapTooSmall { min_gap: 5 })
+        );
+        assert_eq!(validate_threshold_values(Some(79.0), 80.0, 1), Ok(()));
+        // No start threshold: no gap to respect
+        assert_eq!(validate_threshold_values(None, 80.0, 5), Ok(()));
+    }
+}
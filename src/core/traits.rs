@@ -1,9 +1,19 @@
 //! Trait abstractions for dependency injection and testing
 //!
 //! Provides `BatteryService` and `ThresholdWriter` traits to abstract
-//! battery operations, enabling mock implementations for unit tests.
+//! battery operations, enabling mock implementations for unit tests
+//! (`MockBatteryService`), the
+//! `BatteryHistoryService` used to record samples over time and the
+//! `NotificationService` used to send desktop notifications.
 
-use super::battery::{BatteryError, BatteryInfo};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::alerts::BatteryEvent;
+use super::battery::{with_retry, BatteryError, READ_ATTEMPTS, READ_RETRY_DELAY};
+use super::battery::{AggregateInfo, BatteryInfo};
+use super::history::unix_now;
 
 /// Battery information service trait
 ///
@@ -20,8 +30,36 @@ pub trait BatteryService {
     /// Returns `BatteryError` if name is invalid or read fails
     fn get_info(&self, name: &str) -> Result<BatteryInfo, BatteryError>;
 
+    /// Like `get_info`, retrying transient I/O errors (see `with_retry`)
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once `READ_ATTEMPTS` reads failed
+    fn get_info_with_retry(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        with_retry(READ_ATTEMPTS, READ_RETRY_DELAY, || self.get_info(name))
+    }
+
     /// Lists all available batteries on the system
     fn list_batteries(&self) -> Vec<String>;
+
+    /// Combines several batteries into one figure
+    ///
+    /// Unreadable batteries are left out.
+    fn aggregate(&self, batteries: &[String]) -> AggregateInfo {
+        aggregate_infos(self, batteries)
+    }
+}
+
+/// Reads each battery and combines them with `BatteryInfo::aggregate`
+pub fn aggregate_infos<S: BatteryService + ?Sized>(
+    service: &S,
+    batteries: &[String],
+) -> AggregateInfo {
+    let infos: Vec<BatteryInfo> = batteries
+        .iter()
+        .filter_map(|name| service.get_info(name).ok())
+        .collect();
+    BatteryInfo::aggregate(&infos)
 }
 
 /// Real battery service implementation
@@ -32,11 +70,66 @@ impl BatteryService for SystemBatteryService {
         BatteryInfo::new(name)
     }
 
+    fn get_info_with_retry(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        BatteryInfo::new_with_retry(name)
+    }
+
     fn list_batteries(&self) -> Vec<String> {
         BatteryInfo::get_battery_list()
     }
 }
 
+#[cfg(test)]
+/// Battery service serving fixed values instead of sysfs, for tests
+pub struct MockBatteryService {
+    batteries: Vec<String>,
+    infos: Vec<BatteryInfo>,
+}
+
+#[cfg(test)]
+impl MockBatteryService {
+    /// Lists batteries whose information cannot be read
+    pub const fn new(batteries: Vec<String>) -> Self {
+        Self {
+            batteries,
+            infos: Vec::new(),
+        }
+    }
+
+    /// Serves these batteries, listed in order
+    pub fn with_infos(infos: Vec<BatteryInfo>) -> Self {
+        Self {
+            batteries: infos.iter().map(|info| info.name.clone()).collect(),
+            infos,
+        }
+    }
+}
+
+#[cfg(test)]
+impl BatteryService for MockBatteryService {
+    fn get_info(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        if !name.starts_with("BAT") {
+            return Err(BatteryError::InvalidBatteryName(name.to_string()));
+        }
+        // Batteries listed without information behave like unreadable files
+        self.infos
+            .iter()
+            .find(|info| info.name == name)
+            .cloned()
+            .ok_or_else(|| {
+                BatteryError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Mock service",
+                ))
+            })
+    }
+
+    fn list_batteries(&self) -> Vec<String> {
+        self.batteries.clone()
+    }
+}
+
+#[cfg(test)]
 /// Charge threshold writer service trait
 ///
 /// Abstracts threshold writing for testing purposes
@@ -58,11 +151,13 @@ pub trait ThresholdWriter {
     fn supports_start_threshold(&self) -> bool;
 }
 
+#[cfg(test)]
 /// System threshold writer implementation
 pub struct SystemThresholdWriter {
     supports_start: bool,
 }
 
+#[cfg(test)]
 impl SystemThresholdWriter {
     /// Creates a new system threshold writer
     pub const fn new(supports_start: bool) -> Self {
@@ -70,23 +165,12 @@ impl SystemThresholdWriter {
     }
 }
 
+#[cfg(test)]
 impl ThresholdWriter for SystemThresholdWriter {
     fn apply_thresholds(&self, _battery: &str, start: Option<u8>, stop: u8) -> Result<(), String> {
-        // Validation
-        if stop > 100 {
-            return Err("Seuil d'arrêt invalide (> 100)".to_string());
-        }
-
-        if let Some(s) = start {
-            if s > 100 {
-                return Err("Seuil de démarrage invalide (> 100)".to_string());
-            }
-            if s >= stop {
-                return Err(
-                    "Le seuil de démarrage doit être inférieur au seuil d'arrêt".to_string()
-                );
-            }
-        }
+        // Same validation as the settings tab
+        crate::core::threshold_apply::validate_thresholds(start, stop)
+            .map_err(|e| e.to_string())?;
 
         // Note: Actual writing is done by pkexec in settings_tab.rs
         // This trait is mainly for tests and abstraction
@@ -98,37 +182,391 @@ impl ThresholdWriter for SystemThresholdWriter {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// One recorded battery sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySample {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub capacity_percent: u8,
+    pub health_percent: f32,
+    /// Raw sysfs status ("Charging", "Discharging", ...)
+    pub status: String,
+    /// Power draw in watts, `None` for samples recorded before it was stored
+    pub power_watts: Option<f32>,
+}
+
+impl HistorySample {
+    /// Builds a sample from freshly read battery information
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Battery information
+    /// * `timestamp` - Unix timestamp in seconds
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_info(info: &BatteryInfo, timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            capacity_percent: info.capacity_percent,
+            health_percent: info.health_percent,
+            status: info.status.clone(),
+            power_watts: Some(info.power_watts() as f32),
+        }
+    }
+}
+
+/// Battery history storage trait
+///
+/// Abstracts where samples are kept so the UI can be tested without disk access
+pub trait BatteryHistoryService {
+    /// Records the current state of a battery
+    ///
+    /// # Arguments
+    ///
+    /// * `battery` - Battery name (e.g., "BAT0")
+    /// * `info` - Freshly read battery information
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the sample cannot be stored
+    fn record_sample(&self, battery: &str, info: &BatteryInfo) -> std::io::Result<()>;
+
+    /// Returns the most recent samples of a battery, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `battery` - Battery name
+    /// * `last_n` - Maximum number of samples
+    fn get_samples(&self, battery: &str, last_n: usize) -> Vec<HistorySample>;
 
-    /// Mock du service de batterie pour les tests
-    struct MockBatteryService {
-        batteries: Vec<String>,
+    /// Lists every battery that appears in the history (sorted, unique)
+    #[allow(dead_code)] // Read side is consumed by the history views
+    fn get_battery_list_from_history(&self) -> Vec<String>;
+}
+
+/// History stored as CSV lines: `timestamp,battery,capacity,health,status,power`
+///
+/// Lines written before the power column was added are still read.
+pub struct FlatFileBatteryHistory {
+    path: PathBuf,
+}
+
+impl FlatFileBatteryHistory {
+    /// Creates a history backed by the given CSV file
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
     }
 
-    impl MockBatteryService {
-        fn new(batteries: Vec<String>) -> Self {
-            Self { batteries }
+    /// Returns the default history file path
+    ///
+    /// # Returns
+    ///
+    /// `~/.local/share/battery-manager/history.csv`, or `None` without a data dir
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|d| d.join("battery-manager").join("history.csv"))
+    }
+
+    /// Parses one CSV line into (battery, sample); malformed lines are skipped
+    fn parse_line(line: &str) -> Option<(&str, HistorySample)> {
+        let mut fields = line.split(',');
+        let timestamp = fields.next()?.parse().ok()?;
+        let battery = fields.next()?;
+        let capacity_percent = fields.next()?.parse().ok()?;
+        let health_percent = fields.next()?.parse().ok()?;
+        let status = fields.next()?.to_string();
+        let power_watts = match fields.next() {
+            Some(power) => Some(power.parse().ok()?),
+            None => None,
+        };
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some((
+            battery,
+            HistorySample {
+                timestamp,
+                capacity_percent,
+                health_percent,
+                status,
+                power_watts,
+            },
+        ))
+    }
+
+    fn read_lines(&self) -> Vec<String> {
+        fs::read_to_string(&self.path)
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl BatteryHistoryService for FlatFileBatteryHistory {
+    fn record_sample(&self, battery: &str, info: &BatteryInfo) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
         }
+
+        let sample = HistorySample::from_info(info, unix_now());
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{},{battery},{},{:.1},{},{:.2}",
+            sample.timestamp,
+            sample.capacity_percent,
+            sample.health_percent,
+            sample.status.replace([',', '\n'], " "),
+            sample.power_watts.unwrap_or_default()
+        )
     }
 
-    impl BatteryService for MockBatteryService {
-        fn get_info(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
-            if !name.starts_with("BAT") {
-                return Err(BatteryError::InvalidBatteryName(name.to_string()));
+    fn get_samples(&self, battery: &str, last_n: usize) -> Vec<HistorySample> {
+        let mut samples: Vec<HistorySample> = self
+            .read_lines()
+            .iter()
+            .filter_map(|line| Self::parse_line(line))
+            .filter(|(name, _)| *name == battery)
+            .map(|(_, sample)| sample)
+            .collect();
+
+        let skip = samples.len().saturating_sub(last_n);
+        samples.drain(..skip);
+        samples
+    }
+
+    fn get_battery_list_from_history(&self) -> Vec<String> {
+        let mut batteries: Vec<String> = self
+            .read_lines()
+            .iter()
+            .filter_map(|line| Self::parse_line(line).map(|(name, _)| name.to_string()))
+            .collect();
+        batteries.sort();
+        batteries.dedup();
+        batteries
+    }
+}
+
+/// Desktop notification service trait
+///
+/// Abstracts the notification backend (GIO or libnotify) so alerts can be
+/// tested without a notification daemon
+pub trait NotificationService {
+    /// Warns that the battery dropped below the low battery threshold
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Current capacity in percent
+    /// * `time_remaining` - Estimated minutes left, if known
+    fn notify_low_battery(&self, capacity: u8, time_remaining: Option<u32>);
+
+    /// Asks to plug in now, the battery being below the critical level
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Current capacity in percent
+    /// * `time_remaining` - Estimated minutes left, if known
+    fn notify_critical_battery(&self, capacity: u8, time_remaining: Option<u32>);
+
+    /// Reports that charging stopped at the stop threshold
+    ///
+    /// # Arguments
+    ///
+    /// * `stop` - Charge stop threshold in percent
+    fn notify_charge_complete(&self, stop: u8);
+
+    /// Reports that the charger was plugged in or unplugged
+    ///
+    /// # Arguments
+    ///
+    /// * `ac_online` - Whether the charger is now plugged in
+    /// * `capacity` - Current capacity in percent
+    fn notify_power_source(&self, ac_online: bool, capacity: u8);
+
+    /// Confirms that new thresholds were applied
+    ///
+    /// # Arguments
+    ///
+    /// * `stop` - Applied charge stop threshold in percent
+    fn notify_threshold_applied(&self, stop: u8);
+
+    /// Reports an error worth surfacing outside the window
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - Error message
+    fn notify_error(&self, msg: &str);
+
+    /// Sends the notification matching an alert event
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event detected by the alarm monitor
+    fn notify_event(&self, event: &BatteryEvent) {
+        match event {
+            BatteryEvent::LowBattery {
+                capacity,
+                time_remaining_minutes,
+            } => self.notify_low_battery(*capacity, *time_remaining_minutes),
+            BatteryEvent::CriticalBattery {
+                capacity,
+                time_remaining_minutes,
+            } => self.notify_critical_battery(*capacity, *time_remaining_minutes),
+            BatteryEvent::ChargeComplete { stop_threshold, .. } => {
+                self.notify_charge_complete(*stop_threshold);
             }
-            // Returns IoError as mock service cannot read real files
-            Err(BatteryError::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Mock service",
-            )))
+            BatteryEvent::PowerSourceChanged {
+                ac_online,
+                capacity,
+            } => self.notify_power_source(*ac_online, *capacity),
         }
+    }
+}
 
-        fn list_batteries(&self) -> Vec<String> {
-            self.batteries.clone()
+#[cfg(feature = "notifications")]
+/// Notification service backed by libnotify (`notify-rust`)
+pub struct LibnotifyService;
+
+#[cfg(feature = "notifications")]
+impl LibnotifyService {
+    /// Shows a notification, logging failures instead of propagating them
+    fn show(summary: &str, body: &str, urgency: notify_rust::Urgency) {
+        let result = notify_rust::Notification::new()
+            .appname("Battery Manager")
+            .summary(summary)
+            .body(body)
+            .icon("battery")
+            .urgency(urgency)
+            .show();
+
+        if let Err(e) = result {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [NOTIFY] Failed to show notification: {e}"
+            ));
         }
     }
+}
+
+#[cfg(feature = "notifications")]
+impl NotificationService for LibnotifyService {
+    fn notify_low_battery(&self, capacity: u8, time_remaining: Option<u32>) {
+        Self::show(
+            &crate::core::i18n::t("notif_low_title"),
+            &super::alerts::discharge_body(capacity, time_remaining),
+            notify_rust::Urgency::Normal,
+        );
+    }
+
+    fn notify_critical_battery(&self, capacity: u8, time_remaining: Option<u32>) {
+        Self::show(
+            &crate::core::i18n::t("notif_critical_title"),
+            &super::alerts::discharge_body(capacity, time_remaining),
+            notify_rust::Urgency::Critical,
+        );
+    }
+
+    fn notify_charge_complete(&self, stop: u8) {
+        Self::show(
+            &crate::core::i18n::t("notif_charged_title"),
+            &crate::core::i18n::t_fmt("notif_charged_body", &[("stop", &stop.to_string())]),
+            notify_rust::Urgency::Normal,
+        );
+    }
+
+    fn notify_power_source(&self, ac_online: bool, capacity: u8) {
+        Self::show(
+            &crate::core::i18n::t(if ac_online {
+                "notif_ac_plugged_title"
+            } else {
+                "notif_ac_unplugged_title"
+            }),
+            &format!("{capacity}%"),
+            notify_rust::Urgency::Low,
+        );
+    }
+
+    fn notify_threshold_applied(&self, stop: u8) {
+        Self::show(
+            &crate::core::i18n::t("notif_applied_title"),
+            &format!("{} {stop}%", crate::core::i18n::t("threshold_stop")),
+            notify_rust::Urgency::Low,
+        );
+    }
+
+    fn notify_error(&self, msg: &str) {
+        Self::show(
+            &crate::core::i18n::t("notif_error_title"),
+            msg,
+            notify_rust::Urgency::Normal,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn history_info(capacity: u8, status: &str) -> BatteryInfo {
+        BatteryInfo {
+            status: status.to_string(),
+            capacity_percent: capacity,
+            charge_now: 0,
+            current_now: 0,
+            voltage_now: 0,
+            cycle_count: 0,
+            health_percent: 98.5,
+            wear_percent: 1.5,
+            charge_stop_threshold: None,
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_flat_file_history() {
+        let dir =
+            std::env::temp_dir().join(format!("battery-manager-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = FlatFileBatteryHistory::new(&dir.join("history.csv"));
+
+        assert!(history.get_samples("BAT0", 10).is_empty());
+
+        for capacity in [90, 80, 70] {
+            history
+                .record_sample("BAT0", &history_info(capacity, "Discharging"))
+                .unwrap();
+        }
+        history
+            .record_sample("BAT1", &history_info(50, "Not charging"))
+            .unwrap();
+
+        let samples = history.get_samples("BAT0", 2);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].capacity_percent, 80);
+        assert_eq!(samples[1].capacity_percent, 70);
+        assert!((samples[1].health_percent - 98.5).abs() < f32::EPSILON);
+        assert_eq!(samples[1].status, "Discharging");
+
+        assert_eq!(samples[1].power_watts, Some(0.0));
+
+        assert_eq!(history.get_samples("BAT1", 10)[0].status, "Not charging");
+        assert_eq!(
+            history.get_battery_list_from_history(),
+            vec!["BAT0", "BAT1"]
+        );
+
+        // Lines recorded before the power column still parse
+        let (battery, sample) =
+            FlatFileBatteryHistory::parse_line("100,BAT0,55,97.0,Charging").unwrap();
+        assert_eq!(battery, "BAT0");
+        assert_eq!(sample.power_watts, None);
+        assert!(FlatFileBatteryHistory::parse_line("100,BAT0,55,97.0,Charging,x").is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 
     #[test]
     fn test_mock_battery_service() {
@@ -160,12 +598,14 @@ mod tests {
 
     #[test]
     fn test_threshold_writer_validation() {
+        let _lock = crate::core::i18n::TEST_LANG_LOCK.lock().unwrap();
+        crate::core::i18n::set_language("fr");
         let writer = SystemThresholdWriter::new(true);
 
         // Test seuil stop > 100
         let result = writer.apply_thresholds("BAT0", None, 150);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("invalide"));
+        assert!(result.unwrap_err().contains("dépasser 100"));
 
         // Test seuil start > 100
         let result = writer.apply_thresholds("BAT0", Some(150), 80);
@@ -179,6 +619,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_threshold_writer_error_variants() {
+        use crate::core::threshold_apply::ThresholdError;
+
+        let _lock = crate::core::i18n::TEST_LANG_LOCK.lock().unwrap();
+        let writer = SystemThresholdWriter::new(true);
+        for (start, stop, expected) in [
+            (None, 150, ThresholdError::StopOutOfRange),
+            (Some(150), 100, ThresholdError::StartOutOfRange),
+            (Some(80), 80, ThresholdError::StartNotBelowStop),
+        ] {
+            assert_eq!(
+                writer.apply_thresholds("BAT0", start, stop),
+                Err(expected.to_string())
+            );
+        }
+    }
+
     #[test]
     fn test_threshold_writer_valid() {
         let writer = SystemThresholdWriter::new(true);
@@ -199,4 +657,77 @@ mod tests {
         let writer_without_start = SystemThresholdWriter::new(false);
         assert!(!writer_without_start.supports_start_threshold());
     }
+
+    /// Mock du service de notifications qui enregistre les appels
+    #[derive(Default)]
+    struct MockNotificationService {
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl NotificationService for MockNotificationService {
+        fn notify_low_battery(&self, capacity: u8, time_remaining: Option<u32>) {
+            self.calls
+                .borrow_mut()
+                .push(format!("low:{capacity}:{time_remaining:?}"));
+        }
+
+        fn notify_critical_battery(&self, capacity: u8, _time_remaining: Option<u32>) {
+            self.calls.borrow_mut().push(format!("critical:{capacity}"));
+        }
+
+        fn notify_charge_complete(&self, stop: u8) {
+            self.calls.borrow_mut().push(format!("complete:{stop}"));
+        }
+
+        fn notify_power_source(&self, ac_online: bool, capacity: u8) {
+            self.calls
+                .borrow_mut()
+                .push(format!("ac:{ac_online}:{capacity}"));
+        }
+
+        fn notify_threshold_applied(&self, stop: u8) {
+            self.calls.borrow_mut().push(format!("applied:{stop}"));
+        }
+
+        fn notify_error(&self, msg: &str) {
+            self.calls.borrow_mut().push(format!("error:{msg}"));
+        }
+    }
+
+    #[test]
+    fn test_notify_event_dispatch() {
+        let mock = MockNotificationService::default();
+        let service: &dyn NotificationService = &mock;
+
+        service.notify_event(&BatteryEvent::LowBattery {
+            capacity: 9,
+            time_remaining_minutes: Some(40),
+        });
+        service.notify_event(&BatteryEvent::CriticalBattery {
+            capacity: 4,
+            time_remaining_minutes: None,
+        });
+        service.notify_event(&BatteryEvent::ChargeComplete {
+            capacity: 79,
+            stop_threshold: 80,
+        });
+        service.notify_event(&BatteryEvent::PowerSourceChanged {
+            ac_online: false,
+            capacity: 79,
+        });
+        service.notify_threshold_applied(80);
+        service.notify_error("boom");
+
+        assert_eq!(
+            *mock.calls.borrow(),
+            vec![
+                "low:9:Some(40)",
+                "critical:4",
+                "complete:80",
+                "ac:false:79",
+                "applied:80",
+                "error:boom"
+            ]
+        );
+    }
 }
@@ -0,0 +1,1025 @@
+//! `UPower` battery backend
+//!
+//! Reads batteries and peripherals from `org.freedesktop.UPower` on the
+//! system bus. Used when sysfs reports no battery or leaves critical fields
+//! empty (some ARM laptops), or when forced with `--backend=upower`; with a
+//! working sysfs, it still answers the reads that fail at runtime (sandbox,
+//! driver hiccup). The combined figure of several batteries comes from
+//! `UPower`'s display device.
+//!
+//! Devices are read once, then kept up to date from `UPower`'s signals, so a
+//! refresh costs no bus round trip.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use glib::Variant;
+
+use crate::core::battery::{AggregateInfo, BatteryError};
+use crate::core::i18n::t;
+use crate::core::traits::{aggregate_infos, BatteryService, SystemBatteryService};
+use crate::core::{BatteryInfo, PeripheralBattery};
+
+const UPOWER_NAME: &str = "org.freedesktop.UPower";
+const UPOWER_PATH: &str = "/org/freedesktop/UPower";
+const DEVICE_INTERFACE: &str = "org.freedesktop.UPower.Device";
+
+/// Composite device summing every laptop battery
+const DISPLAY_DEVICE_PATH: &str = "/org/freedesktop/UPower/devices/DisplayDevice";
+
+/// D-Bus call timeout in milliseconds
+const CALL_TIMEOUT_MS: i32 = 2000;
+
+/// `UPower` device types (`Type` property)
+const KIND_LINE_POWER: u32 = 1;
+const KIND_BATTERY: u32 = 2;
+
+/// Battery data source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `/sys/class/power_supply`
+    Sysfs,
+    /// `org.freedesktop.UPower` over the system bus
+    UPower,
+}
+
+impl Backend {
+    /// Parses a `--backend=` value
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - "sysfs" or "upower"
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Backend)` - Known backend
+    /// * `None` - Unknown value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "sysfs" => Some(Self::Sysfs),
+            "upower" => Some(Self::UPower),
+            _ => None,
+        }
+    }
+}
+
+/// Forced backend (0 = automatic, 1 = sysfs, 2 = `UPower`)
+static BACKEND_OVERRIDE: AtomicU8 = AtomicU8::new(0);
+
+/// Force a backend instead of the automatic choice (`--backend=`)
+pub fn set_backend(backend: Backend) {
+    let value = match backend {
+        Backend::Sysfs => 1,
+        Backend::UPower => 2,
+    };
+    BACKEND_OVERRIDE.store(value, Ordering::Relaxed);
+}
+
+/// Returns the forced backend, if any
+pub fn forced_backend() -> Option<Backend> {
+    match BACKEND_OVERRIDE.load(Ordering::Relaxed) {
+        1 => Some(Backend::Sysfs),
+        2 => Some(Backend::UPower),
+        _ => None,
+    }
+}
+
+/// Properties of a `UPower` device
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UPowerDevice {
+    pub native_path: String,
+    pub kind: u32,
+    pub power_supply: bool,
+    pub is_present: bool,
+    pub vendor: String,
+    pub model: String,
+    pub serial: String,
+    pub technology: u32,
+    pub state: u32,
+    pub percentage: f64,
+    /// Energies in Wh, rate in W, voltage in V
+    pub energy: f64,
+    pub energy_full: f64,
+    pub energy_full_design: f64,
+    pub energy_rate: f64,
+    pub voltage: f64,
+    /// -1 when unknown
+    pub charge_cycles: i32,
+    /// Health in percent (0 when unknown)
+    pub capacity: f64,
+    /// Seconds (0 when unknown)
+    pub time_to_empty: i64,
+    pub time_to_full: i64,
+    pub charge_threshold_supported: bool,
+    pub charge_start_threshold: u32,
+    pub charge_end_threshold: u32,
+}
+
+/// Converts a physical quantity to sysfs micro-units (µWh, µV, µA)
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn micro(value: f64) -> u64 {
+    if value.is_finite() && value > 0.0 {
+        (value * 1_000_000.0).round() as u64
+    } else {
+        0
+    }
+}
+
+impl UPowerDevice {
+    /// Reads a device from its `GetAll` property dictionary (`a{sv}`)
+    fn from_properties(properties: &HashMap<String, Variant>) -> Self {
+        let string = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Variant::get::<String>)
+                .unwrap_or_default()
+        };
+        let double = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Variant::get::<f64>)
+                .unwrap_or_default()
+        };
+        let uint = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Variant::get::<u32>)
+                .unwrap_or_default()
+        };
+        let int64 = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Variant::get::<i64>)
+                .unwrap_or_default()
+        };
+        let boolean = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Variant::get::<bool>)
+                .unwrap_or_default()
+        };
+
+        Self {
+            native_path: string("NativePath"),
+            kind: uint("Type"),
+            power_supply: boolean("PowerSupply"),
+            is_present: boolean("IsPresent"),
+            vendor: string("Vendor"),
+            model: string("Model"),
+            serial: string("Serial"),
+            technology: uint("Technology"),
+            state: uint("State"),
+            percentage: double("Percentage"),
+            energy: double("Energy"),
+            energy_full: double("EnergyFull"),
+            energy_full_design: double("EnergyFullDesign"),
+            energy_rate: double("EnergyRate"),
+            voltage: double("Voltage"),
+            charge_cycles: properties
+                .get("ChargeCycles")
+                .and_then(Variant::get::<i32>)
+                .unwrap_or(-1),
+            capacity: double("Capacity"),
+            time_to_empty: int64("TimeToEmpty"),
+            time_to_full: int64("TimeToFull"),
+            charge_threshold_supported: boolean("ChargeThresholdSupported"),
+            charge_start_threshold: uint("ChargeStartThreshold"),
+            charge_end_threshold: uint("ChargeEndThreshold"),
+        }
+    }
+
+    /// Whether this is a laptop battery (powers the system)
+    pub const fn is_laptop_battery(&self) -> bool {
+        self.kind == KIND_BATTERY && self.power_supply
+    }
+
+    /// Whether this is a peripheral with a battery (mouse, keyboard, headset…)
+    pub const fn is_peripheral(&self) -> bool {
+        !self.power_supply && self.kind != KIND_LINE_POWER && self.kind != 0
+    }
+
+    /// Returns the sysfs-style status for the `UPower` state
+    fn status(&self) -> String {
+        match self.state {
+            1 => "Charging".to_string(),
+            2 | 3 => "Discharging".to_string(),
+            4 => "Full".to_string(),
+            5 | 6 => "Not charging".to_string(),
+            _ => t("unknown"),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn capacity_percent(&self) -> u8 {
+        self.percentage.clamp(0.0, 100.0).round() as u8
+    }
+
+    /// Maps the device to the battery information shown by the UI
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn to_battery_info(&self) -> BatteryInfo {
+        let or_unknown = |value: &str| {
+            if value.trim().is_empty() {
+                t("unknown")
+            } else {
+                value.trim().to_string()
+            }
+        };
+        let technology = match self.technology {
+            1 => "Li-ion".to_string(),
+            2 => "Li-poly".to_string(),
+            3 => "LiFe".to_string(),
+            4 => "Pb".to_string(),
+            5 => "NiCd".to_string(),
+            6 => "NiMH".to_string(),
+            _ => t("unknown"),
+        };
+
+        let charge_now = micro(self.energy);
+        let charge_full = micro(self.energy_full).max(1);
+        let charge_full_design = micro(self.energy_full_design).max(1);
+        let voltage_now = micro(self.voltage);
+        let current_now = if self.voltage > 0.0 {
+            micro(self.energy_rate / self.voltage)
+        } else {
+            0
+        };
+
+        let health_percent = if self.capacity > 0.0 {
+            self.capacity as f32
+        } else {
+            (charge_full as f32 / charge_full_design as f32) * 100.0
+        };
+
+        let status = self.status();
+        let seconds = match status.as_str() {
+            "Charging" => self.time_to_full,
+            "Discharging" => self.time_to_empty,
+            _ => 0,
+        };
+        let time_remaining_minutes = u32::try_from(seconds / 60).ok().filter(|m| *m > 0);
+
+        let threshold = |value: u32| {
+            self.charge_threshold_supported
+                .then(|| u8::try_from(value.min(100)).unwrap_or(100))
+        };
+
+        BatteryInfo {
+            name: self.native_path.clone(),
+            manufacturer: or_unknown(&self.vendor),
+            model_name: or_unknown(&self.model),
+            technology,
+            capacity_percent: self.capacity_percent(),
+            capacity_level: t("unknown"),
+            capacity_estimated: false,
+            status,
+            charge_now,
+            charge_full,
+            charge_full_design,
+            current_now,
+            voltage_now,
+            cycle_count: u32::try_from(self.charge_cycles).unwrap_or(0),
+            health_percent,
+            wear_percent: 100.0 - health_percent,
+            time_remaining_minutes,
+            charge_start_threshold: threshold(self.charge_start_threshold),
+            charge_stop_threshold: threshold(self.charge_end_threshold),
+            alarm: None,
+            manufacture_date: None,
+            charge_behaviour: None,
+        }
+    }
+
+    /// Maps the display device to the combined battery figure
+    ///
+    /// # Arguments
+    ///
+    /// * `battery_count` - Number of laptop batteries behind the composite
+    pub fn to_aggregate(&self, battery_count: usize) -> AggregateInfo {
+        AggregateInfo {
+            battery_count,
+            charge_now: micro(self.energy),
+            charge_full: micro(self.energy_full),
+            capacity_percent: self.percentage.clamp(0.0, 100.0),
+            power_watts: if self.energy_rate.is_finite() {
+                self.energy_rate.abs()
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Maps the device to a peripheral entry
+    pub fn to_peripheral(&self) -> PeripheralBattery {
+        let or_unknown = |value: &str| {
+            if value.trim().is_empty() {
+                "Unknown".to_string()
+            } else {
+                value.trim().to_string()
+            }
+        };
+        let device_type = match self.kind {
+            5 => "Mouse",
+            6 => "Keyboard",
+            8 => "Phone",
+            10 => "Tablet",
+            12 => "Gaming input",
+            13 => "Pen",
+            14 => "Touchpad",
+            17 => "Headset",
+            18 => "Speakers",
+            19 => "Headphones",
+            _ => "Device",
+        };
+        let status = match self.state {
+            1 | 2 | 4 => self.status(),
+            _ => "Unknown".to_string(),
+        };
+
+        PeripheralBattery {
+            name: if self.native_path.is_empty() {
+                format!("upower_{}", device_type.to_lowercase())
+            } else {
+                self.native_path.clone()
+            },
+            manufacturer: or_unknown(&self.vendor),
+            model_name: or_unknown(&self.model),
+            status,
+            capacity_percent: self.capacity_percent(),
+            voltage_now: (self.voltage > 0.0).then(|| micro(self.voltage)),
+            serial_number: (!self.serial.trim().is_empty()).then(|| self.serial.trim().to_string()),
+            online: self.is_present,
+            device_type: device_type.to_string(),
+            scope: "Device".to_string(),
+            connected: None,
+        }
+    }
+}
+
+/// Properties of every `UPower` device, by object path
+type DeviceProperties = BTreeMap<String, HashMap<String, Variant>>;
+
+/// `UPower` devices kept up to date from the bus signals
+///
+/// Filled once from `EnumerateDevices`, then updated by `DeviceAdded`,
+/// `DeviceRemoved` and `PropertiesChanged`. Signals are dispatched by the
+/// main loop; the one-shot command line modes only use the first snapshot.
+struct DeviceCache {
+    connection: gio::DBusConnection,
+    devices: RefCell<DeviceProperties>,
+    /// Kept alive with the cache
+    subscriptions: RefCell<Vec<gio::SignalSubscription>>,
+}
+
+thread_local! {
+    /// Cache shared by the battery service and the peripheral scan
+    static DEVICE_CACHE: RefCell<Option<Rc<DeviceCache>>> = const { RefCell::new(None) };
+}
+
+impl DeviceCache {
+    /// Returns the cache of this thread, connecting on first use
+    ///
+    /// # Returns
+    ///
+    /// `None` if the system bus is unavailable
+    fn shared() -> Option<Rc<Self>> {
+        if let Some(cache) = DEVICE_CACHE.with(|cache| cache.borrow().clone()) {
+            return Some(cache);
+        }
+        let connection = match gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>) {
+            Ok(connection) => connection,
+            Err(e) => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [UPOWER] System bus unavailable: {e}"
+                ));
+                return None;
+            }
+        };
+
+        let cache = Rc::new(Self {
+            devices: RefCell::new(initial_devices(&connection)),
+            connection,
+            subscriptions: RefCell::new(Vec::new()),
+        });
+        cache.subscribe();
+        DEVICE_CACHE.with(|shared| *shared.borrow_mut() = Some(Rc::clone(&cache)));
+        Some(cache)
+    }
+
+    /// Follows device hot-plug and property changes
+    fn subscribe(self: &Rc<Self>) {
+        let weak = Rc::downgrade(self);
+        let added = self.connection.subscribe_to_signal(
+            Some(UPOWER_NAME),
+            Some(UPOWER_NAME),
+            Some("DeviceAdded"),
+            Some(UPOWER_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |signal| {
+                if let Some(path) = signal.parameters.child_value(0).str() {
+                    fetch_device(&weak, path);
+                }
+            },
+        );
+
+        let weak = Rc::downgrade(self);
+        let removed = self.connection.subscribe_to_signal(
+            Some(UPOWER_NAME),
+            Some(UPOWER_NAME),
+            Some("DeviceRemoved"),
+            Some(UPOWER_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |signal| {
+                if let (Some(cache), Some(path)) =
+                    (weak.upgrade(), signal.parameters.child_value(0).str())
+                {
+                    crate::core::debug::debug_log_args(std::format_args!(
+                        "🔌 [UPOWER] Device removed: {path}"
+                    ));
+                    cache.devices.borrow_mut().remove(path);
+                }
+            },
+        );
+
+        let weak = Rc::downgrade(self);
+        let changed = self.connection.subscribe_to_signal(
+            Some(UPOWER_NAME),
+            Some("org.freedesktop.DBus.Properties"),
+            Some("PropertiesChanged"),
+            None,
+            Some(DEVICE_INTERFACE),
+            gio::DBusSignalFlags::NONE,
+            move |signal| {
+                if let Some(cache) = weak.upgrade() {
+                    apply_changes(
+                        &mut cache.devices.borrow_mut(),
+                        signal.object_path,
+                        signal.parameters,
+                    );
+                }
+            },
+        );
+
+        self.subscriptions
+            .borrow_mut()
+            .extend([added, removed, changed]);
+    }
+
+    /// Current properties of one device
+    fn device(&self, path: &str) -> Option<UPowerDevice> {
+        self.devices
+            .borrow()
+            .get(path)
+            .map(UPowerDevice::from_properties)
+    }
+}
+
+/// Reads the properties of one device object (blocking)
+fn get_all_sync(
+    connection: &gio::DBusConnection,
+    path: &str,
+) -> Result<HashMap<String, Variant>, glib::Error> {
+    let reply = connection.call_sync(
+        Some(UPOWER_NAME),
+        path,
+        "org.freedesktop.DBus.Properties",
+        "GetAll",
+        Some(&(DEVICE_INTERFACE,).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+    )?;
+    Ok(reply.child_value(0).get().unwrap_or_default())
+}
+
+/// Reads every device and the display device, once when the cache is created
+///
+/// # Returns
+///
+/// Properties by object path (empty if `UPower` is not running)
+fn initial_devices(connection: &gio::DBusConnection) -> DeviceProperties {
+    let paths = match connection.call_sync(
+        Some(UPOWER_NAME),
+        UPOWER_PATH,
+        UPOWER_NAME,
+        "EnumerateDevices",
+        None,
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+    ) {
+        Ok(reply) => reply.child_value(0),
+        Err(e) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [UPOWER] EnumerateDevices failed: {e}"
+            ));
+            return DeviceProperties::new();
+        }
+    };
+
+    paths
+        .iter()
+        .filter_map(|path| path.str().map(str::to_string))
+        .chain(std::iter::once(DISPLAY_DEVICE_PATH.to_string()))
+        .filter_map(|path| {
+            let properties = get_all_sync(connection, &path).ok()?;
+            Some((path, properties))
+        })
+        .collect()
+}
+
+/// Reads a device added after start-up without blocking the main loop
+fn fetch_device(cache: &Weak<DeviceCache>, path: &str) {
+    let Some(connection) = cache.upgrade().map(|cache| cache.connection.clone()) else {
+        return;
+    };
+    let cache = cache.clone();
+    let added_path = path.to_string();
+    connection.call(
+        Some(UPOWER_NAME),
+        path,
+        "org.freedesktop.DBus.Properties",
+        "GetAll",
+        Some(&(DEVICE_INTERFACE,).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        None::<&gio::Cancellable>,
+        move |reply| match (reply, cache.upgrade()) {
+            (Ok(reply), Some(cache)) => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "🔌 [UPOWER] Device added: {added_path}"
+                ));
+                let properties = reply.child_value(0).get().unwrap_or_default();
+                cache.devices.borrow_mut().insert(added_path, properties);
+            }
+            (Err(e), _) => crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [UPOWER] Cannot read added device {added_path}: {e}"
+            )),
+            (Ok(_), None) => {}
+        },
+    );
+}
+
+/// Merges a `PropertiesChanged(s, a{sv}, as)` signal into the cached device
+///
+/// Devices the cache doesn't know are ignored: their `DeviceAdded` brings
+/// every property.
+fn apply_changes(devices: &mut DeviceProperties, path: &str, parameters: &Variant) {
+    let Some(properties) = devices.get_mut(path) else {
+        return;
+    };
+    let changed: HashMap<String, Variant> = parameters.child_value(1).get().unwrap_or_default();
+    let invalidated: Vec<String> = parameters.child_value(2).get().unwrap_or_default();
+    properties.extend(changed);
+    for name in invalidated {
+        properties.remove(&name);
+    }
+}
+
+/// Battery service reading from `UPower`
+pub struct UPowerBatteryService {
+    cache: Rc<DeviceCache>,
+}
+
+impl UPowerBatteryService {
+    /// Connects to the system bus
+    ///
+    /// The device cache is shared by every service of the thread.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the system bus is unavailable
+    pub fn new() -> Option<Self> {
+        DeviceCache::shared().map(|cache| Self { cache })
+    }
+
+    /// Every device known to `UPower`
+    ///
+    /// # Returns
+    ///
+    /// Devices by object path (empty if `UPower` is not running)
+    pub fn devices(&self) -> Vec<UPowerDevice> {
+        self.cache
+            .devices
+            .borrow()
+            .iter()
+            .filter(|(path, _)| path.as_str() != DISPLAY_DEVICE_PATH)
+            .map(|(_, properties)| UPowerDevice::from_properties(properties))
+            .collect()
+    }
+
+    /// The display device, `UPower`'s composite of every laptop battery
+    ///
+    /// # Returns
+    ///
+    /// `None` when `UPower` is not running or the system has no battery
+    pub fn display_device(&self) -> Option<UPowerDevice> {
+        self.cache
+            .device(DISPLAY_DEVICE_PATH)
+            .filter(|device| device.kind == KIND_BATTERY && device.is_present)
+    }
+}
+
+impl BatteryService for UPowerBatteryService {
+    fn get_info(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        self.devices()
+            .into_iter()
+            .find(|d| d.is_laptop_battery() && d.native_path == name)
+            .map(|d| d.to_battery_info())
+            .ok_or_else(|| {
+                BatteryError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("UPower has no battery {name}"),
+                ))
+            })
+    }
+
+    fn list_batteries(&self) -> Vec<String> {
+        let mut batteries: Vec<String> = self
+            .devices()
+            .into_iter()
+            .filter(|d| d.is_laptop_battery() && !d.native_path.is_empty())
+            .map(|d| d.native_path)
+            .collect();
+        batteries.sort();
+        batteries
+    }
+
+    fn aggregate(&self, batteries: &[String]) -> AggregateInfo {
+        self.display_device().map_or_else(
+            || aggregate_infos(self, batteries),
+            |device| device.to_aggregate(batteries.len()),
+        )
+    }
+}
+
+/// Connects the `UPower` service
+type ConnectFn = fn() -> Option<Rc<dyn BatteryService>>;
+
+/// Battery service asking `UPower` when a sysfs read fails
+///
+/// `UPower` is only contacted on the first failure.
+pub struct FallbackBatteryService {
+    primary: Rc<dyn BatteryService>,
+    connect_fallback: ConnectFn,
+    fallback: OnceCell<Option<Rc<dyn BatteryService>>>,
+}
+
+impl FallbackBatteryService {
+    /// Wraps `primary`, connecting the fallback with `connect_fallback` when needed
+    pub fn new(primary: Rc<dyn BatteryService>, connect_fallback: ConnectFn) -> Self {
+        Self {
+            primary,
+            connect_fallback,
+            fallback: OnceCell::new(),
+        }
+    }
+
+    /// Answers a failed read from the fallback, else returns the original error
+    fn recover(&self, name: &str, error: BatteryError) -> Result<BatteryInfo, BatteryError> {
+        let fallback = self.fallback.get_or_init(self.connect_fallback);
+        match fallback.as_ref().map(|fallback| fallback.get_info(name)) {
+            Some(Ok(info)) => {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [BACKEND] {name}: sysfs read failed ({error}), using UPower"
+                ));
+                Ok(info)
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+impl BatteryService for FallbackBatteryService {
+    fn get_info(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        self.primary
+            .get_info(name)
+            .or_else(|error| self.recover(name, error))
+    }
+
+    fn get_info_with_retry(&self, name: &str) -> Result<BatteryInfo, BatteryError> {
+        self.primary
+            .get_info_with_retry(name)
+            .or_else(|error| self.recover(name, error))
+    }
+
+    fn list_batteries(&self) -> Vec<String> {
+        self.primary.list_batteries()
+    }
+}
+
+/// Connects to `UPower` as a battery service
+fn connect_upower() -> Option<Rc<dyn BatteryService>> {
+    UPowerBatteryService::new().map(|service| Rc::new(service) as Rc<dyn BatteryService>)
+}
+
+/// Chooses the battery backend
+///
+/// Uses sysfs unless it reports no battery or an incomplete first battery
+/// and `UPower` does better; `--backend=` forces one. Automatically chosen
+/// sysfs falls back to `UPower` for the reads that fail.
+///
+/// # Returns
+///
+/// Battery service for the UI
+pub fn select_battery_service() -> Rc<dyn BatteryService> {
+    let (service, reason): (Rc<dyn BatteryService>, &str) = match forced_backend() {
+        Some(Backend::Sysfs) => (Rc::new(SystemBatteryService), "forced sysfs"),
+        Some(Backend::UPower) => connect_upower().map_or_else(
+            || {
+                (
+                    Rc::new(SystemBatteryService) as Rc<dyn BatteryService>,
+                    "UPower forced but unavailable, using sysfs",
+                )
+            },
+            |service| (service, "forced UPower"),
+        ),
+        None => {
+            let sysfs = SystemBatteryService;
+            let sysfs_usable = sysfs
+                .list_batteries()
+                .first()
+                .and_then(|name| sysfs.get_info(name).ok())
+                .is_some_and(|info| !info.is_incomplete());
+
+            if sysfs_usable {
+                (
+                    Rc::new(FallbackBatteryService::new(Rc::new(sysfs), connect_upower)),
+                    "sysfs complete, UPower if a read fails",
+                )
+            } else {
+                match connect_upower().filter(|service| !service.list_batteries().is_empty()) {
+                    Some(service) => (service, "sysfs empty or incomplete, using UPower"),
+                    None => (Rc::new(sysfs), "sysfs incomplete and no UPower battery"),
+                }
+            }
+        }
+    };
+
+    crate::core::debug::debug_log_args(std::format_args!("🔌 [BACKEND] {reason}"));
+    service
+}
+
+/// Merges `UPower` peripherals into the sysfs and `BlueZ` scan, deduplicated by stable id
+///
+/// Sysfs and `BlueZ` entries win, since `UPower` usually re-exports the same devices.
+///
+/// # Arguments
+///
+/// * `sysfs` - Peripherals found in `/sys/class/power_supply` and `BlueZ`
+/// * `upower` - Peripherals reported by `UPower`
+///
+/// # Returns
+///
+/// Combined list, sorted by stable id
+pub fn merge_peripherals(
+    sysfs: Vec<PeripheralBattery>,
+    upower: Vec<PeripheralBattery>,
+) -> Vec<PeripheralBattery> {
+    let mut by_id: BTreeMap<String, PeripheralBattery> = BTreeMap::new();
+    for device in sysfs {
+        by_id.insert(device.stable_id(), device);
+    }
+    for device in upower {
+        by_id.entry(device.stable_id()).or_insert(device);
+    }
+    by_id.into_values().collect()
+}
+
+/// Detects peripherals from sysfs and, unless sysfs is forced, `UPower`
+///
+/// # Returns
+///
+/// Deduplicated peripheral list
+pub fn detect_peripherals() -> Vec<PeripheralBattery> {
+    let sysfs = PeripheralBattery::detect_all();
+    if forced_backend() == Some(Backend::Sysfs) {
+        return sysfs;
+    }
+
+    let upower: Vec<PeripheralBattery> = UPowerBatteryService::new()
+        .map(|service| service.devices())
+        .unwrap_or_default()
+        .iter()
+        .filter(|d| d.is_peripheral())
+        .map(UPowerDevice::to_peripheral)
+        .collect();
+    merge_peripherals(sysfs, upower)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::traits::MockBatteryService;
+
+    fn laptop_battery() -> UPowerDevice {
+        UPowerDevice {
+            native_path: "BAT0".to_string(),
+            kind: KIND_BATTERY,
+            power_supply: true,
+            is_present: true,
+            vendor: "ACME".to_string(),
+            model: "5B10".to_string(),
+            technology: 1,
+            state: 2,
+            percentage: 77.6,
+            energy: 38.8,
+            energy_full: 50.0,
+            energy_full_design: 55.0,
+            energy_rate: 6.0,
+            voltage: 12.0,
+            charge_cycles: 120,
+            time_to_empty: 23_280,
+            charge_threshold_supported: true,
+            charge_start_threshold: 40,
+            charge_end_threshold: 80,
+            ..UPowerDevice::default()
+        }
+    }
+
+    #[test]
+    fn test_display_device_aggregate() {
+        let mut display = laptop_battery();
+        display.native_path = String::new();
+        display.energy = 60.0;
+        display.energy_full = 80.0;
+        display.percentage = 75.0;
+        display.energy_rate = -9.5;
+
+        let aggregate = display.to_aggregate(2);
+        assert_eq!(aggregate.battery_count, 2);
+        assert_eq!(aggregate.charge_now, 60_000_000);
+        assert_eq!(aggregate.charge_full, 80_000_000);
+        assert!((aggregate.capacity_percent - 75.0).abs() < f64::EPSILON);
+        assert!((aggregate.power_watts - 9.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_properties_changed() {
+        let path = "/org/freedesktop/UPower/devices/battery_BAT0";
+        let mut devices = DeviceProperties::new();
+        devices.insert(
+            path.to_string(),
+            HashMap::from([
+                ("NativePath".to_string(), "BAT0".to_variant()),
+                ("Percentage".to_string(), 80.0.to_variant()),
+                ("Vendor".to_string(), "ACME".to_variant()),
+            ]),
+        );
+
+        let signal = (
+            DEVICE_INTERFACE,
+            HashMap::from([("Percentage".to_string(), 79.0.to_variant())]),
+            vec!["Vendor".to_string()],
+        )
+            .to_variant();
+        apply_changes(&mut devices, path, &signal);
+        // Unknown devices wait for their DeviceAdded
+        apply_changes(
+            &mut devices,
+            "/org/freedesktop/UPower/devices/mouse",
+            &signal,
+        );
+
+        assert_eq!(devices.len(), 1);
+        let device = UPowerDevice::from_properties(&devices[path]);
+        assert_eq!(device.native_path, "BAT0");
+        assert!((device.percentage - 79.0).abs() < f64::EPSILON);
+        assert_eq!(device.vendor, "");
+    }
+
+    #[test]
+    fn test_fallback_answers_failed_reads() {
+        let upower: ConnectFn = || {
+            Some(Rc::new(MockBatteryService::with_infos(vec![
+                laptop_battery().to_battery_info(),
+            ])))
+        };
+        let unreadable = || Rc::new(MockBatteryService::new(vec!["BAT0".to_string()]));
+
+        let service = FallbackBatteryService::new(unreadable(), upower);
+        assert_eq!(service.list_batteries(), vec!["BAT0".to_string()]);
+        assert_eq!(service.get_info("BAT0").unwrap().capacity_percent, 78);
+        // Unknown to UPower too: the sysfs error is kept
+        assert!(matches!(
+            service.get_info("BAT1"),
+            Err(BatteryError::IoError(_))
+        ));
+
+        let service = FallbackBatteryService::new(unreadable(), || None);
+        assert!(service.get_info("BAT0").is_err());
+    }
+
+    #[test]
+    fn test_backend_parse() {
+        assert_eq!(Backend::parse("sysfs"), Some(Backend::Sysfs));
+        assert_eq!(Backend::parse("UPower"), Some(Backend::UPower));
+        assert_eq!(Backend::parse("acpi"), None);
+    }
+
+    #[test]
+    fn test_battery_info_mapping() {
+        let info = laptop_battery().to_battery_info();
+
+        assert_eq!(info.name, "BAT0");
+        assert_eq!(info.status, "Discharging");
+        assert_eq!(info.capacity_percent, 78);
+        assert_eq!(info.charge_now, 38_800_000);
+        assert_eq!(info.charge_full_design, 55_000_000);
+        assert_eq!(info.current_now, 500_000);
+        assert!((info.power_watts() - 6.0).abs() < 1e-6);
+        assert!((info.health_percent - 90.909).abs() < 0.01);
+        assert_eq!(info.cycle_count, 120);
+        assert_eq!(info.time_remaining_minutes, Some(388));
+        assert_eq!(info.charge_start_threshold, Some(40));
+        assert_eq!(info.charge_stop_threshold, Some(80));
+        assert_eq!(info.technology, "Li-ion");
+        assert!(!info.is_incomplete());
+
+        let unsupported = UPowerDevice {
+            charge_threshold_supported: false,
+            charge_cycles: -1,
+            capacity: 88.0,
+            state: 4,
+            ..laptop_battery()
+        };
+        let info = unsupported.to_battery_info();
+        assert_eq!(info.status, "Full");
+        assert_eq!(info.charge_stop_threshold, None);
+        assert_eq!(info.cycle_count, 0);
+        assert_eq!(info.time_remaining_minutes, None);
+        assert!((info.health_percent - 88.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_device_kinds() {
+        assert!(laptop_battery().is_laptop_battery());
+        assert!(!laptop_battery().is_peripheral());
+
+        let mouse = UPowerDevice {
+            native_path: "hidpp_battery_0".to_string(),
+            kind: 5,
+            serial: "e9-97-e3-8b".to_string(),
+            percentage: 55.0,
+            state: 2,
+            ..UPowerDevice::default()
+        };
+        assert!(mouse.is_peripheral());
+        let peripheral = mouse.to_peripheral();
+        assert_eq!(peripheral.device_type, "Mouse");
+        assert_eq!(peripheral.stable_id(), "serial:e9-97-e3-8b");
+        assert_eq!(peripheral.voltage_now, None);
+
+        let line_power = UPowerDevice {
+            kind: KIND_LINE_POWER,
+            ..UPowerDevice::default()
+        };
+        assert!(!line_power.is_peripheral());
+    }
+
+    #[test]
+    fn test_merge_peripherals_dedups_by_serial() {
+        let device = |name: &str, serial: &str, capacity: f64| {
+            UPowerDevice {
+                native_path: name.to_string(),
+                kind: 6,
+                serial: serial.to_string(),
+                percentage: capacity,
+                ..UPowerDevice::default()
+            }
+            .to_peripheral()
+        };
+
+        let merged = merge_peripherals(
+            vec![device("hidpp_battery_1", "AA", 40.0)],
+            vec![
+                device("/org/upower/kbd", "AA", 41.0),
+                device("/org/upower/headset", "BB", 90.0),
+            ],
+        );
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].name, "hidpp_battery_1");
+        assert_eq!(merged[1].capacity_percent, 90);
+
+        // BlueZ address in lower case, UPower in upper case
+        let merged = merge_peripherals(
+            vec![device("bluez_ac_80", "ac:80:0a:12:34:56", 70.0)],
+            vec![device(
+                "/org/bluez/hci0/dev_AC_80",
+                "AC:80:0A:12:34:56",
+                70.0,
+            )],
+        );
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].name, "bluez_ac_80");
+    }
+}
@@ -1,9 +1,45 @@
 //! Vendor detection and battery threshold file discovery
 //!
 //! Identifies laptop manufacturer and locates charge threshold control files
-//! in `/sys/class/power_supply/`.
+//! in `/sys/class/power_supply/`. Users can declare extra threshold files in
+//! `~/.config/battery-manager/paths.conf`, named like the ones the helper
+//! accepts (`charge_*threshold`, the vendor care limits):
+//!
+//! ```text
+//! start_path=/sys/devices/platform/acme-laptop/charge_start_threshold
+//! stop_path=/sys/devices/platform/acme-laptop/charge_end_threshold
+//! ```
 
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Component, Path};
+use std::sync::{Mutex, OnceLock};
+
+use crate::core::threshold_apply;
+
+/// Root of the kernel power supply class in sysfs
+const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+/// Directory of the HP BIOS configuration WMI driver
+pub const HP_BIOSCFG_DIR: &str = "/sys/bus/wmi/drivers/hp-bioscfg";
+
+/// ACPI device some HP firmwares expose the thresholds on
+const HP_ACPI_DIR: &str = "/sys/bus/platform/devices/ACPI000D:00";
+
+/// TLP page on vendor battery care specifics, for vendors without a dedicated guide
+const TLP_VENDORS_URL: &str = "https://linrunner.de/tlp/settings/bc-vendors.html";
+
+/// User file declaring extra threshold paths, next to `config.toml`
+const PATHS_FILE: &str = "paths.conf";
+
+/// Prefixes user-declared threshold paths must live under
+const OVERRIDE_PREFIXES: [&str; 2] = ["/sys/class/power_supply/", "/sys/devices/"];
+
+/// Per-battery detection results, kept for the whole session
+///
+/// Entries are leaked on purpose: there are only ever a handful of batteries
+/// and hardware doesn't change at runtime.
+static DETECTION_CACHE: OnceLock<Mutex<HashMap<String, &'static VendorInfo>>> = OnceLock::new();
 
 /// Laptop vendor types with different battery control interfaces
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,9 +56,76 @@ pub enum VendorType {
     Msi,
     Toshiba,
     Macbook,
+    Hp, // EliteBook, ProBook, ZBook
     Generic,
 }
 
+/// Kind of control a vendor's stop threshold file expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdControl {
+    /// Numeric percentage (e.g. `charge_control_end_threshold`)
+    Percentage,
+    /// On/off "battery care" file (Samsung `battery_care_limit`, Sony `battery_care_limiter`)
+    CareToggle,
+}
+
+impl VendorType {
+    /// Returns the control the vendor's stop threshold file expects
+    pub const fn threshold_control(&self) -> ThresholdControl {
+        match self {
+            Self::Samsung | Self::Sony => ThresholdControl::CareToggle,
+            _ => ThresholdControl::Percentage,
+        }
+    }
+
+    /// Returns the minimum distance between start and stop thresholds
+    ///
+    /// Lenovo firmwares ignore a start threshold closer than 5% to the stop
+    /// threshold; elsewhere start only has to stay below stop.
+    pub const fn min_threshold_gap(&self) -> u8 {
+        match self {
+            Self::Lenovo => 5,
+            _ => 1,
+        }
+    }
+
+    /// Returns the value written to a battery care file
+    ///
+    /// Samsung takes 0/1; Sony's `sony-laptop` driver takes the limit
+    /// itself (80%) or 0 to disable it.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether conservation mode should be on
+    pub const fn care_value(&self, enabled: bool) -> u8 {
+        match (self, enabled) {
+            (_, false) => 0,
+            (Self::Sony, true) => 80,
+            (_, true) => 1,
+        }
+    }
+
+    /// Returns the first kernel release whose driver exposes the thresholds
+    ///
+    /// `ThinkPad` `thinkpad_acpi` uses the generic `charge_control_*` files
+    /// since 5.9, Dell `dell-laptop` since 6.12, ASUS `asus-wmi` since 5.4,
+    /// MSI `msi-ec` since 6.3 and HP `hp-bioscfg` since 6.6.
+    ///
+    /// # Returns
+    ///
+    /// `(major, minor)`, or `None` when no requirement is known
+    pub const fn min_kernel(&self) -> Option<(u32, u32)> {
+        match self {
+            Self::Lenovo => Some((5, 9)),
+            Self::Dell => Some((6, 12)),
+            Self::Asus => Some((5, 4)),
+            Self::Msi => Some((6, 3)),
+            Self::Hp => Some((6, 6)),
+            _ => None,
+        }
+    }
+}
+
 /// Vendor-specific battery information
 #[derive(Debug, Clone)]
 pub struct VendorInfo {
@@ -30,24 +133,33 @@ pub struct VendorInfo {
     pub product_name: String,
     pub supports_start_threshold: bool,
     pub supports_stop_threshold: bool,
+    /// Threshold files found for the probed battery
+    pub threshold_files: ThresholdFiles,
+    pub vendor_type: VendorType,
 }
 
 /// Battery charge threshold file paths
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ThresholdFiles {
     pub start_paths: Vec<String>,
     pub stop_paths: Vec<String>,
 }
 
 impl VendorInfo {
-    /// Automatically detects system vendor and threshold support
+    /// Detects system vendor and threshold support for a specific battery
+    ///
+    /// Reads DMI information and enumerates the threshold files actually
+    /// present under the battery's sysfs directory (plus vendor-specific
+    /// platform paths), so batteries named e.g. `BATC` are handled.
     ///
-    /// Reads DMI information and checks for threshold files existence
+    /// # Arguments
+    ///
+    /// * `battery_name` - Battery name (e.g., "BAT0", "BATC")
     ///
     /// # Returns
     ///
     /// `VendorInfo` with manufacturer, product name, and threshold support flags
-    pub fn detect() -> Self {
+    pub fn detect_for_battery(battery_name: &str) -> Self {
         let manufacturer = Self::read_dmi("sys_vendor")
             .unwrap_or_else(|| "Unknown".to_string())
             .to_lowercase();
@@ -55,7 +167,6 @@ impl VendorInfo {
         let product = Self::read_dmi("product_name").unwrap_or_else(|| "Unknown".to_string());
 
         let vendor_type = Self::identify_vendor(&manufacturer, &product);
-        let threshold_files = Self::get_threshold_files(&vendor_type);
 
         if crate::core::debug::is_debug_enabled() {
             crate::core::debug::debug_log_args(std::format_args!(
@@ -63,19 +174,15 @@ impl VendorInfo {
             ));
         }
 
-        // Vérifier le support réel des fichiers
-        let supports_start = threshold_files
-            .start_paths
-            .iter()
-            .any(|p| fs::metadata(p).is_ok());
-        let supports_stop = threshold_files
-            .stop_paths
-            .iter()
-            .any(|p| fs::metadata(p).is_ok());
+        let probed =
+            Self::probe_threshold_files(Path::new(POWER_SUPPLY_ROOT), battery_name, &vendor_type);
+        let threshold_files = Self::merge_overrides(Self::load_path_overrides(), probed);
+        let supports_start = !threshold_files.start_paths.is_empty();
+        let supports_stop = !threshold_files.stop_paths.is_empty();
 
         if crate::core::debug::is_debug_enabled() {
             crate::core::debug::debug_log_args(std::format_args!(
-                "🎯 [VENDOR] supports_start={supports_start} supports_stop={supports_stop}"
+                "🎯 [VENDOR] {battery_name}: supports_start={supports_start} supports_stop={supports_stop} files={threshold_files:?}"
             ));
         }
 
@@ -84,9 +191,114 @@ impl VendorInfo {
             product_name: product,
             supports_start_threshold: supports_start,
             supports_stop_threshold: supports_stop,
+            threshold_files,
+            vendor_type,
+        }
+    }
+
+    /// Returns the control to show for the stop threshold
+    ///
+    /// A care toggle is only used when the stop file really is a
+    /// `battery_care_*` file; user overrides pointing to a percentage file
+    /// keep the spinner.
+    pub fn threshold_control(&self) -> ThresholdControl {
+        let is_care_file = self.threshold_files.stop_paths.first().is_some_and(|p| {
+            Path::new(p)
+                .file_name()
+                .is_some_and(|f| f.to_string_lossy().starts_with("battery_care"))
+        });
+        if is_care_file {
+            self.vendor_type.threshold_control()
+        } else {
+            ThresholdControl::Percentage
+        }
+    }
+
+    /// Returns the thresholds suggested when none are configured yet
+    ///
+    /// The start value is ignored by batteries without a start threshold;
+    /// vendors using a care toggle only get the usual 80% limit.
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (start, stop) in percent
+    pub const fn get_recommended_thresholds(&self) -> (Option<u8>, u8) {
+        match self.vendor_type {
+            VendorType::Lenovo | VendorType::Tuxedo => (Some(40), 80),
+            VendorType::Asus | VendorType::Generic => (Some(60), 80),
+            VendorType::Dell => (Some(50), 90),
+            VendorType::Huawei => (Some(70), 90),
+            // Firmware "Balanced" mode
+            VendorType::System76 => (Some(86), 90),
+            VendorType::Msi => (Some(70), 80),
+            VendorType::Samsung
+            | VendorType::Sony
+            | VendorType::Lg
+            | VendorType::Toshiba
+            | VendorType::Macbook
+            | VendorType::Hp => (None, 80),
+        }
+    }
+
+    /// Returns a battery guide or kernel driver documentation for the vendor
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&str)` - URL of the guide
+    /// * `None` - No vendor-specific guide (HP, generic hardware)
+    pub const fn get_documentation_url(&self) -> Option<&'static str> {
+        match self.vendor_type {
+            VendorType::Asus => Some("https://asus-linux.org/"),
+            VendorType::Lenovo => Some(
+                "https://www.kernel.org/doc/html/latest/admin-guide/laptops/thinkpad-acpi.html",
+            ),
+            VendorType::Sony => {
+                Some("https://www.kernel.org/doc/html/latest/admin-guide/laptops/sony-laptop.html")
+            }
+            VendorType::Lg => {
+                Some("https://www.kernel.org/doc/html/latest/admin-guide/laptops/lg-laptop.html")
+            }
+            VendorType::System76 => {
+                Some("https://support.system76.com/articles/laptop-battery-thresholds/")
+            }
+            VendorType::Dell
+            | VendorType::Huawei
+            | VendorType::Tuxedo
+            | VendorType::Samsung
+            | VendorType::Msi
+            | VendorType::Toshiba
+            | VendorType::Macbook => Some(TLP_VENDORS_URL),
+            VendorType::Hp | VendorType::Generic => None,
         }
     }
 
+    /// Cached variant of [`VendorInfo::detect_for_battery`]
+    ///
+    /// Detection runs once per battery; later calls (e.g. settings tab
+    /// rebuilds) return the same instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `battery_name` - Battery name (e.g., "BAT0", "BATC")
+    ///
+    /// # Returns
+    ///
+    /// Shared `VendorInfo` for this battery
+    pub fn detect_cached(battery_name: &str) -> &'static Self {
+        let cache = DETECTION_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut cache = cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if let Some(info) = cache.get(battery_name) {
+            return info;
+        }
+
+        let info: &'static Self = Box::leak(Box::new(Self::detect_for_battery(battery_name)));
+        cache.insert(battery_name.to_string(), info);
+        info
+    }
+
     /// Reads DMI system information from `/sys/class/dmi/id/`
     ///
     /// # Arguments
@@ -169,6 +381,11 @@ impl VendorInfo {
             return VendorType::Toshiba;
         }
 
+        // HP (sys_vendor is "HP" or "Hewlett-Packard")
+        if manufacturer == "hp" || manufacturer.contains("hewlett") {
+            return VendorType::Hp;
+        }
+
         // Apple / Macbook
         if manufacturer.contains("apple") {
             return VendorType::Macbook;
@@ -249,6 +466,19 @@ impl VendorInfo {
                     "{base}/macsmc-battery/charge_control_end_threshold"
                 )],
             },
+            VendorType::Hp => ThresholdFiles {
+                start_paths: vec![
+                    format!("{HP_BIOSCFG_DIR}/battery_optimized_charge_level_min"),
+                    format!("{HP_ACPI_DIR}/charge_control_start_threshold"),
+                ],
+                stop_paths: vec![
+                    format!("{HP_BIOSCFG_DIR}/battery_optimized_charge_level_max"),
+                    format!("{HP_ACPI_DIR}/charge_control_end_threshold"),
+                ]
+                .into_iter()
+                .chain(bat_paths(&["BAT0", "BAT1"], "charge_control_end_threshold"))
+                .collect(),
+            },
             VendorType::Generic => ThresholdFiles {
                 start_paths: bat_paths(&["BAT0", "BAT1"], "charge_control_start_threshold")
                     .into_iter()
@@ -262,12 +492,242 @@ impl VendorInfo {
             },
         }
     }
+
+    /// Checks that a user-declared path is absolute, normalized, below sysfs
+    /// and names a threshold file the helper will write
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Declared threshold file path
+    ///
+    /// # Returns
+    ///
+    /// `true` if the path is a threshold file under `/sys/class/power_supply`
+    /// or `/sys/devices`
+    fn is_safe_override_path(path: &str) -> bool {
+        let path = Path::new(path);
+        path.is_absolute()
+            && path
+                .components()
+                .all(|c| matches!(c, Component::RootDir | Component::Normal(_)))
+            && OVERRIDE_PREFIXES
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(threshold_apply::is_threshold_control_file)
+    }
+
+    /// Parses `start_path=` / `stop_path=` lines of `paths.conf`
+    ///
+    /// Comments (`#`), unknown keys and unsafe paths are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - File content
+    ///
+    /// # Returns
+    ///
+    /// Declared start and stop paths, in file order
+    fn parse_path_overrides(text: &str) -> ThresholdFiles {
+        let mut files = ThresholdFiles::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, path)) = line.split_once('=') else {
+                continue;
+            };
+            let path = path.trim();
+            let target = match key.trim() {
+                "start_path" => &mut files.start_paths,
+                "stop_path" => &mut files.stop_paths,
+                _ => continue,
+            };
+
+            if Self::is_safe_override_path(path) {
+                target.push(path.to_string());
+            } else {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [VENDOR] Ignoring {PATHS_FILE} entry, not a sysfs threshold file: {path}"
+                ));
+            }
+        }
+
+        files
+    }
+
+    /// Reads the user-declared threshold paths that exist on this system
+    fn load_path_overrides() -> ThresholdFiles {
+        let Some(path) = dirs::config_dir().map(|d| d.join("battery-manager").join(PATHS_FILE))
+        else {
+            return ThresholdFiles::default();
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return ThresholdFiles::default();
+        };
+
+        let mut files = Self::parse_path_overrides(&text);
+        let exists = |p: &String| {
+            let exists = fs::metadata(p).is_ok();
+            if !exists {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [VENDOR] {PATHS_FILE} path not found: {p}"
+                ));
+            }
+            exists
+        };
+        files.start_paths.retain(exists);
+        files.stop_paths.retain(exists);
+        files
+    }
+
+    /// Puts user-declared paths first, then the detected ones not already listed
+    fn merge_overrides(overrides: ThresholdFiles, detected: ThresholdFiles) -> ThresholdFiles {
+        let merge = |mut first: Vec<String>, rest: Vec<String>| {
+            for path in rest {
+                if !first.contains(&path) {
+                    first.push(path);
+                }
+            }
+            first
+        };
+
+        ThresholdFiles {
+            start_paths: merge(overrides.start_paths, detected.start_paths),
+            stop_paths: merge(overrides.stop_paths, detected.stop_paths),
+        }
+    }
+
+    /// Returns true if a file name looks like a charge threshold control file
+    ///
+    /// Matches the `charge_*threshold` family (e.g. `charge_control_end_threshold`,
+    /// `charge_start_threshold`, `charge_end_threshold`).
+    fn is_threshold_file_name(name: &str) -> bool {
+        name.starts_with("charge_") && name.ends_with("threshold")
+    }
+
+    /// Enumerates existing threshold files for a battery
+    ///
+    /// Scans `{root}/{battery_name}` for any `charge_*threshold` file and for
+    /// the vendor-specific file names (e.g. Samsung's `battery_care_limit`),
+    /// then adds the vendor platform paths living outside the power supply
+    /// class (e.g. `huawei-wmi`). Only paths that exist are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Power supply class directory (`/sys/class/power_supply` outside tests)
+    /// * `battery_name` - Battery directory name (e.g., "BATC")
+    /// * `vendor` - Detected vendor type
+    ///
+    /// # Returns
+    ///
+    /// `ThresholdFiles` containing only existing start and stop paths
+    fn probe_threshold_files(
+        root: &Path,
+        battery_name: &str,
+        vendor: &VendorType,
+    ) -> ThresholdFiles {
+        let known = Self::get_threshold_files(vendor);
+        let is_platform_path = |p: &String| !p.starts_with(POWER_SUPPLY_ROOT);
+        let file_name = |p: &String| {
+            Path::new(p)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+        };
+
+        let vendor_start_names: Vec<String> = known
+            .start_paths
+            .iter()
+            .filter(|p| !is_platform_path(p))
+            .filter_map(file_name)
+            .collect();
+        let vendor_stop_names: Vec<String> = known
+            .stop_paths
+            .iter()
+            .filter(|p| !is_platform_path(p))
+            .filter_map(file_name)
+            .collect();
+
+        let mut files = ThresholdFiles::default();
+
+        if let Ok(entries) = fs::read_dir(root.join(battery_name)) {
+            let mut names: Vec<String> = entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+
+            for name in names {
+                let path = root
+                    .join(battery_name)
+                    .join(&name)
+                    .to_string_lossy()
+                    .to_string();
+                if vendor_start_names.contains(&name)
+                    || (Self::is_threshold_file_name(&name) && name.contains("start"))
+                {
+                    files.start_paths.push(path);
+                } else if vendor_stop_names.contains(&name) || Self::is_threshold_file_name(&name) {
+                    files.stop_paths.push(path);
+                }
+            }
+        }
+
+        // Vendor platform paths (outside the power supply class)
+        for path in known.start_paths.iter().filter(|p| is_platform_path(p)) {
+            if fs::metadata(path).is_ok() {
+                files.start_paths.push(path.clone());
+            }
+        }
+        for path in known.stop_paths.iter().filter(|p| is_platform_path(p)) {
+            if fs::metadata(path).is_ok() {
+                files.stop_paths.push(path.clone());
+            }
+        }
+
+        // HP: the BIOS settings driver is authoritative when loaded
+        if *vendor == VendorType::Hp && hp_bioscfg_present() {
+            Self::prefer_paths_under(&mut files, Path::new(HP_BIOSCFG_DIR));
+        }
+
+        files
+    }
+
+    /// Moves the paths below `dir` to the front, keeping the order otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - Detected threshold files
+    /// * `dir` - Directory whose files take precedence
+    fn prefer_paths_under(files: &mut ThresholdFiles, dir: &Path) {
+        for paths in [&mut files.start_paths, &mut files.stop_paths] {
+            paths.sort_by_key(|p| !Path::new(p).starts_with(dir));
+        }
+    }
+}
+
+/// Whether the HP BIOS configuration driver (`hp-bioscfg`) is loaded
+pub fn hp_bioscfg_present() -> bool {
+    Path::new(HP_BIOSCFG_DIR).is_dir()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_detect_cached_returns_same_instance() {
+        let first = VendorInfo::detect_cached("BAT_CACHE_TEST");
+        let second = VendorInfo::detect_cached("BAT_CACHE_TEST");
+        assert!(std::ptr::eq(first, second));
+
+        let other = VendorInfo::detect_cached("BAT_CACHE_OTHER");
+        assert!(!std::ptr::eq(first, other));
+    }
+
     #[test]
     fn test_identify_vendor_asus() {
         let vendor = VendorInfo::identify_vendor("asustek computer inc.", "TUF Gaming");
@@ -316,6 +776,15 @@ mod tests {
         assert_eq!(vendor, VendorType::Samsung);
     }
 
+    #[test]
+    fn test_identify_vendor_hp() {
+        let vendor = VendorInfo::identify_vendor("hp", "HP EliteBook 840 G8");
+        assert_eq!(vendor, VendorType::Hp);
+
+        let vendor2 = VendorInfo::identify_vendor("hewlett-packard", "HP ProBook 450 G5");
+        assert_eq!(vendor2, VendorType::Hp);
+    }
+
     #[test]
     fn test_identify_vendor_generic() {
         let vendor = VendorInfo::identify_vendor("unknown manufacturer", "unknown product");
@@ -336,6 +805,42 @@ mod tests {
         assert!(!files.stop_paths.is_empty()); // Lenovo: stop threshold
     }
 
+    #[test]
+    fn test_threshold_files_hp() {
+        let files = VendorInfo::get_threshold_files(&VendorType::Hp);
+        assert_eq!(
+            files.stop_paths,
+            vec![
+                "/sys/bus/wmi/drivers/hp-bioscfg/battery_optimized_charge_level_max",
+                "/sys/bus/platform/devices/ACPI000D:00/charge_control_end_threshold",
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold",
+                "/sys/class/power_supply/BAT1/charge_control_end_threshold",
+            ]
+        );
+        assert_eq!(files.start_paths.len(), 2);
+    }
+
+    #[test]
+    fn test_prefer_paths_under() {
+        let mut files = ThresholdFiles {
+            start_paths: vec![],
+            stop_paths: vec![
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold".to_string(),
+                "/sys/bus/platform/devices/ACPI000D:00/charge_control_end_threshold".to_string(),
+                "/sys/bus/wmi/drivers/hp-bioscfg/battery_optimized_charge_level_max".to_string(),
+            ],
+        };
+        VendorInfo::prefer_paths_under(&mut files, Path::new(HP_BIOSCFG_DIR));
+        assert_eq!(
+            files.stop_paths,
+            vec![
+                "/sys/bus/wmi/drivers/hp-bioscfg/battery_optimized_charge_level_max",
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold",
+                "/sys/bus/platform/devices/ACPI000D:00/charge_control_end_threshold",
+            ]
+        );
+    }
+
     #[test]
     fn test_threshold_files_generic() {
         let files = VendorInfo::get_threshold_files(&VendorType::Generic);
@@ -348,9 +853,283 @@ mod tests {
             .any(|p| p.contains("charge_control_end_threshold")));
     }
 
+    /// Creates a fake power supply class directory with the given battery files
+    fn fake_power_supply(test_name: &str, battery: &str, files: &[&str]) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "battery-manager-{test_name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(battery)).unwrap();
+        for file in files {
+            fs::write(root.join(battery).join(file), "80\n").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_probe_threshold_files_batc() {
+        let root = fake_power_supply(
+            "probe-batc",
+            "BATC",
+            &["charge_control_end_threshold", "capacity", "status"],
+        );
+
+        let files = VendorInfo::probe_threshold_files(&root, "BATC", &VendorType::Generic);
+        assert!(files.start_paths.is_empty());
+        assert_eq!(files.stop_paths.len(), 1);
+        assert!(files.stop_paths[0].ends_with("BATC/charge_control_end_threshold"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_probe_threshold_files_globs_unknown_names() {
+        let root = fake_power_supply(
+            "probe-glob",
+            "BATC",
+            &["charge_start_threshold", "charge_end_threshold"],
+        );
+
+        let files = VendorInfo::probe_threshold_files(&root, "BATC", &VendorType::Generic);
+        assert_eq!(files.start_paths.len(), 1);
+        assert!(files.start_paths[0].ends_with("charge_start_threshold"));
+        assert_eq!(files.stop_paths.len(), 1);
+        assert!(files.stop_paths[0].ends_with("charge_end_threshold"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_probe_threshold_files_vendor_names() {
+        let root = fake_power_supply("probe-samsung", "BATC", &["battery_care_limit"]);
+
+        let files = VendorInfo::probe_threshold_files(&root, "BATC", &VendorType::Samsung);
+        assert!(files.start_paths.is_empty());
+        assert_eq!(files.stop_paths.len(), 1);
+
+        // Not a threshold file for a vendor that doesn't use it
+        let files = VendorInfo::probe_threshold_files(&root, "BATC", &VendorType::Dell);
+        assert!(files.stop_paths.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_probe_threshold_files_missing_battery() {
+        let root = fake_power_supply("probe-missing", "BAT0", &[]);
+
+        let files = VendorInfo::probe_threshold_files(&root, "BATC", &VendorType::Generic);
+        assert!(files.start_paths.is_empty());
+        assert!(files.stop_paths.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_threshold_control_per_vendor() {
+        assert_eq!(
+            VendorType::Samsung.threshold_control(),
+            ThresholdControl::CareToggle
+        );
+        assert_eq!(
+            VendorType::Sony.threshold_control(),
+            ThresholdControl::CareToggle
+        );
+        assert_eq!(
+            VendorType::Lenovo.threshold_control(),
+            ThresholdControl::Percentage
+        );
+
+        let vendor_info = |vendor_type: VendorType, stop: &str| VendorInfo {
+            manufacturer: String::new(),
+            product_name: String::new(),
+            supports_start_threshold: false,
+            supports_stop_threshold: true,
+            threshold_files: ThresholdFiles {
+                start_paths: vec![],
+                stop_paths: vec![stop.to_string()],
+            },
+            vendor_type,
+        };
+        assert_eq!(
+            vendor_info(
+                VendorType::Sony,
+                "/sys/class/power_supply/BAT0/battery_care_limiter"
+            )
+            .threshold_control(),
+            ThresholdControl::CareToggle
+        );
+        // Override pointing to a percentage file keeps the spinner
+        assert_eq!(
+            vendor_info(
+                VendorType::Samsung,
+                "/sys/class/power_supply/BAT0/charge_control_end_threshold"
+            )
+            .threshold_control(),
+            ThresholdControl::Percentage
+        );
+    }
+
+    #[test]
+    fn test_min_threshold_gap_per_vendor() {
+        assert_eq!(VendorType::min_threshold_gap(&VendorType::Lenovo), 5);
+        assert_eq!(VendorType::min_threshold_gap(&VendorType::Asus), 1);
+        assert_eq!(VendorType::min_threshold_gap(&VendorType::Dell), 1);
+        assert_eq!(VendorType::min_threshold_gap(&VendorType::Generic), 1);
+    }
+
+    #[test]
+    fn test_documentation_url_per_vendor() {
+        let url = |vendor_type: VendorType| {
+            VendorInfo {
+                manufacturer: String::new(),
+                product_name: String::new(),
+                supports_start_threshold: false,
+                supports_stop_threshold: false,
+                threshold_files: ThresholdFiles::default(),
+                vendor_type,
+            }
+            .get_documentation_url()
+        };
+
+        assert_eq!(url(VendorType::Asus), Some("https://asus-linux.org/"));
+        assert!(url(VendorType::Lenovo).is_some_and(|u| u.ends_with("thinkpad-acpi.html")));
+        assert!(url(VendorType::Sony).is_some_and(|u| u.ends_with("sony-laptop.html")));
+        assert!(url(VendorType::Lg).is_some_and(|u| u.ends_with("lg-laptop.html")));
+        assert!(url(VendorType::System76).is_some_and(|u| u.contains("system76.com")));
+        for vendor_type in [
+            VendorType::Dell,
+            VendorType::Huawei,
+            VendorType::Tuxedo,
+            VendorType::Samsung,
+            VendorType::Msi,
+            VendorType::Toshiba,
+            VendorType::Macbook,
+        ] {
+            assert_eq!(url(vendor_type), Some(TLP_VENDORS_URL));
+        }
+        assert_eq!(url(VendorType::Hp), None);
+        assert_eq!(url(VendorType::Generic), None);
+    }
+
+    #[test]
+    fn test_recommended_thresholds_per_vendor() {
+        let recommended = |vendor_type: VendorType| {
+            VendorInfo {
+                manufacturer: String::new(),
+                product_name: String::new(),
+                supports_start_threshold: true,
+                supports_stop_threshold: true,
+                threshold_files: ThresholdFiles::default(),
+                vendor_type,
+            }
+            .get_recommended_thresholds()
+        };
+
+        assert_eq!(recommended(VendorType::Asus), (Some(60), 80));
+        assert_eq!(recommended(VendorType::Lenovo), (Some(40), 80));
+        assert_eq!(recommended(VendorType::Dell), (Some(50), 90));
+        assert_eq!(recommended(VendorType::Huawei), (Some(70), 90));
+        assert_eq!(recommended(VendorType::System76), (Some(86), 90));
+        assert_eq!(recommended(VendorType::Tuxedo), (Some(40), 80));
+        assert_eq!(recommended(VendorType::Msi), (Some(70), 80));
+        assert_eq!(recommended(VendorType::Generic), (Some(60), 80));
+        for vendor_type in [
+            VendorType::Samsung,
+            VendorType::Sony,
+            VendorType::Lg,
+            VendorType::Toshiba,
+            VendorType::Macbook,
+            VendorType::Hp,
+        ] {
+            assert_eq!(recommended(vendor_type), (None, 80));
+        }
+
+        // Every recommendation respects the vendor's minimum gap
+        for vendor_type in [VendorType::Lenovo, VendorType::Dell, VendorType::System76] {
+            let min_gap = vendor_type.min_threshold_gap();
+            let (start, stop) = recommended(vendor_type);
+            assert!(start.is_some_and(|start| stop - start >= min_gap));
+        }
+    }
+
+    #[test]
+    fn test_min_kernel_per_vendor() {
+        assert_eq!(VendorType::Dell.min_kernel(), Some((6, 12)));
+        assert_eq!(VendorType::Lenovo.min_kernel(), Some((5, 9)));
+        assert_eq!(VendorType::Generic.min_kernel(), None);
+    }
+
+    #[test]
+    fn test_care_value_per_vendor() {
+        assert_eq!(VendorType::Samsung.care_value(true), 1);
+        assert_eq!(VendorType::Samsung.care_value(false), 0);
+        assert_eq!(VendorType::Sony.care_value(true), 80);
+        assert_eq!(VendorType::Sony.care_value(false), 0);
+    }
+
+    #[test]
+    fn test_parse_path_overrides() {
+        let files = VendorInfo::parse_path_overrides(
+            "# custom laptop\n\
+             start_path=/sys/devices/platform/acme/charge_start_threshold\n\
+             stop_path = /sys/class/power_supply/BAT0/charge_end_threshold\n\
+             unknown=/sys/devices/x\n\
+             garbage\n",
+        );
+        assert_eq!(
+            files.start_paths,
+            vec!["/sys/devices/platform/acme/charge_start_threshold"]
+        );
+        assert_eq!(
+            files.stop_paths,
+            vec!["/sys/class/power_supply/BAT0/charge_end_threshold"]
+        );
+    }
+
+    #[test]
+    fn test_path_overrides_reject_unsafe_paths() {
+        assert!(!VendorInfo::is_safe_override_path("/etc/passwd"));
+        assert!(!VendorInfo::is_safe_override_path(
+            "/sys/class/power_supply/../../../etc/passwd"
+        ));
+        assert!(!VendorInfo::is_safe_override_path("sys/devices/x"));
+        assert!(!VendorInfo::is_safe_override_path("/sys/devicesX/x"));
+        // The helper would refuse to write it
+        assert!(!VendorInfo::is_safe_override_path(
+            "/sys/devices/platform/acme/unbind"
+        ));
+        assert!(VendorInfo::is_safe_override_path(
+            "/sys/devices/platform/acme/charge_stop_threshold"
+        ));
+
+        let files = VendorInfo::parse_path_overrides("stop_path=/etc/passwd\n");
+        assert!(files.stop_paths.is_empty());
+    }
+
+    #[test]
+    fn test_merge_overrides_first_without_duplicates() {
+        let overrides = ThresholdFiles {
+            start_paths: vec![],
+            stop_paths: vec!["/sys/devices/a".into(), "/sys/devices/b".into()],
+        };
+        let detected = ThresholdFiles {
+            start_paths: vec!["/sys/devices/s".into()],
+            stop_paths: vec!["/sys/devices/b".into(), "/sys/devices/c".into()],
+        };
+
+        let merged = VendorInfo::merge_overrides(overrides, detected);
+        assert_eq!(merged.start_paths, vec!["/sys/devices/s"]);
+        assert_eq!(
+            merged.stop_paths,
+            vec!["/sys/devices/a", "/sys/devices/b", "/sys/devices/c"]
+        );
+    }
+
     #[test]
     fn test_vendor_detection_returns_valid_info() {
-        let info = VendorInfo::detect();
+        let info = VendorInfo::detect_for_battery("BAT0");
         // Verify detection always returns something
         assert!(!info.manufacturer.is_empty());
         assert!(!info.product_name.is_empty());
@@ -0,0 +1,219 @@
+//! Terminal watch mode
+//!
+//! Prints a live one-line battery summary to stdout at the configured
+//! auto-refresh interval, without starting GTK. Intended for headless/SSH
+//! use (`--watch`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::core::debug::{colorize_line, should_colorize_stdout, LogColor};
+use crate::core::estimator::Estimator;
+use crate::core::i18n::t;
+use crate::core::{BatteryInfo, PowerSupplyInfo};
+
+/// Granularity of the sleep loop, so Ctrl-C is handled promptly
+const SLEEP_STEP: Duration = Duration::from_millis(100);
+
+/// Set by the SIGINT/SIGTERM handler to stop the watch loop
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_stop_signal(_signal: libc::c_int) {
+    STOP_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Makes SIGINT and SIGTERM stop the terminal loops instead of killing the process
+pub fn install_stop_handlers() {
+    // SAFETY: the handler only stores into an atomic, which is async-signal-safe.
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            handle_stop_signal as *const () as libc::sighandler_t,
+        );
+        libc::signal(
+            libc::SIGTERM,
+            handle_stop_signal as *const () as libc::sighandler_t,
+        );
+    }
+}
+
+/// Whether SIGINT or SIGTERM was received
+pub fn stop_requested() -> bool {
+    STOP_REQUESTED.load(Ordering::Relaxed)
+}
+
+/// Sleeps for `duration`, returning early once a stop is requested
+pub fn sleep_unless_stopped(duration: Duration) {
+    let mut waited = Duration::ZERO;
+    while waited < duration && !stop_requested() {
+        std::thread::sleep(SLEEP_STEP);
+        waited += SLEEP_STEP;
+    }
+}
+
+/// Picks the line color from battery state
+fn line_color(info: &BatteryInfo) -> LogColor {
+    match info.status.as_str() {
+        "Charging" | "Full" => LogColor::Success,
+        "Discharging" if info.capacity_percent < 20 => LogColor::Error,
+        "Discharging" => LogColor::Warning,
+        _ => LogColor::None,
+    }
+}
+
+/// Formats a single summary line for the watch mode
+///
+/// # Arguments
+///
+/// * `info` - Battery information
+/// * `power_supply` - AC power supply information
+/// * `colorize` - Wrap the line in ANSI color codes
+///
+/// # Returns
+///
+/// One-line summary: name, capacity, status, power source, power draw and time remaining
+pub fn format_watch_line(
+    info: &BatteryInfo,
+    power_supply: &PowerSupplyInfo,
+    colorize: bool,
+) -> String {
+    let source = if power_supply.ac_online {
+        format!("🔌 {}", t("on_ac"))
+    } else {
+        format!("🔋 {}", t("on_battery"))
+    };
+
+    let mut line = format!(
+        "{} {:>3}% | {} | {} | {:.2} W",
+        info.name,
+        info.capacity_text(),
+        info.status_text(),
+        source,
+        info.power_watts()
+    );
+
+    if let Some(time_text) = info.time_remaining_formatted() {
+        line.push_str(" | ");
+        line.push_str(&time_text);
+    }
+
+    if colorize {
+        colorize_line(line_color(info), &line)
+    } else {
+        line
+    }
+}
+
+/// Runs the watch loop until interrupted (Ctrl-C / SIGTERM)
+///
+/// # Arguments
+///
+/// * `interval` - Time between two printed lines (the auto-refresh interval)
+///
+/// # Returns
+///
+/// Process exit code (0 on clean interruption, 1 when no battery is found)
+pub fn run(interval: Duration) -> i32 {
+    let Some(battery_name) = BatteryInfo::get_battery_list().into_iter().next() else {
+        eprintln!("{}", t("no_battery"));
+        return 1;
+    };
+
+    install_stop_handlers();
+
+    crate::core::debug::debug_log_args(std::format_args!(
+        "👀 [WATCH] Watching {battery_name} every {}s",
+        interval.as_secs()
+    ));
+
+    let colorize = should_colorize_stdout();
+    let mut estimator = Estimator::default();
+
+    while !stop_requested() {
+        match BatteryInfo::new(&battery_name) {
+            Ok(mut info) => {
+                estimator.smooth_info(&mut info);
+                let power_supply = PowerSupplyInfo::new();
+                println!("{}", format_watch_line(&info, &power_supply, colorize));
+            }
+            Err(e) => {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [WATCH] Error during refresh: {e}"
+                ));
+            }
+        }
+
+        sleep_unless_stopped(interval);
+    }
+
+    crate::core::debug::debug_log("👋 [WATCH] Interrupted, exiting");
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+
+    fn sample_info(status: &str, capacity: u8) -> BatteryInfo {
+        BatteryInfo {
+            status: status.to_string(),
+            capacity_percent: capacity,
+            charge_now: 4_000_000,
+            current_now: 1_000_000,
+            cycle_count: 50,
+            time_remaining_minutes: Some(65),
+            ..BatteryInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_format_watch_line_plain() {
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        set_language("en");
+        let info = sample_info("Discharging", 78);
+        let power_supply = PowerSupplyInfo {
+            ac_online: false,
+            ac_name: "AC".to_string(),
+            ups: None,
+        };
+
+        let line = format_watch_line(&info, &power_supply, false);
+        assert!(line.starts_with("BAT0  78%"));
+        assert!(line.contains("12.00 W"));
+        assert!(line.contains("1 hour 5 min"));
+        assert!(!line.contains('\n'));
+        assert!(!line.contains('\u{001b}'));
+        set_language("fr");
+    }
+
+    #[test]
+    fn test_format_watch_line_colorized() {
+        let power_supply = PowerSupplyInfo {
+            ac_online: true,
+            ac_name: "AC".to_string(),
+            ups: None,
+        };
+
+        let charging = format_watch_line(&sample_info("Charging", 50), &power_supply, true);
+        assert!(charging.starts_with("\u{001b}[32m"));
+
+        let low = format_watch_line(&sample_info("Discharging", 10), &power_supply, true);
+        assert!(low.starts_with("\u{001b}[31m"));
+    }
+
+    #[test]
+    fn test_format_watch_line_without_time() {
+        let mut info = sample_info("Full", 100);
+        info.time_remaining_minutes = None;
+        let power_supply = PowerSupplyInfo {
+            ac_online: true,
+            ac_name: "AC".to_string(),
+            ups: None,
+        };
+
+        let line = format_watch_line(&info, &power_supply, false);
+        assert!(!line.contains('⏱'));
+        assert!(line.ends_with(" W"));
+    }
+}
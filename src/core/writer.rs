@@ -0,0 +1,401 @@
+//! Privileged threshold writes
+//!
+//! Runs apply plans through the `battery-manager-apply` helper with `pkexec`,
+//! for the settings tab as well as the command line (`--reset-thresholds`).
+//! Processes already running as root (the `--daemon` mode started by a system
+//! service) run the helper directly: there is no polkit agent to ask.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::core::i18n::{t, t_fmt};
+use crate::core::resume::{ReapplyOutcome, SavedThresholds};
+use crate::core::threshold_apply::{
+    self, build_reapply_plan, ApplyEnvironment, ApplyOutcome, ApplyPlan, ThresholdError,
+};
+use crate::core::vendor_detection::ThresholdControl;
+use crate::core::{BatteryInfo, VendorInfo};
+
+/// Truncates a string for logging purposes
+fn truncate_for_log(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let mut out = s.chars().take(max_chars).collect::<String>();
+    out.push('…');
+    out
+}
+
+/// Result of executing settings via pkexec
+pub enum ApplyResult {
+    /// Settings applied successfully
+    Success,
+    /// pkexec execution failed
+    Failed(String),
+    /// Authentication dialog dismissed or denied
+    Cancelled,
+    /// pkexec not installed
+    NoPkexec,
+    /// `battery-manager-apply` helper not installed
+    NoHelper,
+}
+
+impl From<ApplyOutcome> for ApplyResult {
+    fn from(outcome: ApplyOutcome) -> Self {
+        match outcome {
+            ApplyOutcome::Success => Self::Success,
+            ApplyOutcome::Cancelled => Self::Cancelled,
+            ApplyOutcome::Failed(detail) => Self::Failed(detail),
+        }
+    }
+}
+
+/// Translated message, in the current language
+impl std::fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GapTooSmall { min_gap } => {
+                f.write_str(&t_fmt(self.message_key(), &[("gap", &min_gap.to_string())]))
+            }
+            _ => f.write_str(&t(self.message_key())),
+        }
+    }
+}
+
+impl std::error::Error for ThresholdError {}
+
+/// Maps an error launching pkexec to an apply result
+fn failure_from_spawn_error(err: &std::io::Error) -> ApplyResult {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        ApplyResult::NoPkexec
+    } else {
+        ApplyResult::Failed(format!("{}: {err}", t("error_execution")))
+    }
+}
+
+/// Output of a failed helper run, logged and trimmed for the user
+fn failure_output(result: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let code = result.status.code();
+    let stderr_preview = truncate_for_log(stderr.trim(), 400);
+    let stdout_preview = truncate_for_log(stdout.trim(), 400);
+
+    crate::core::debug::debug_log_args(std::format_args!(
+        "❌ [WRITER] Helper execution failed: code={code:?} stdout='{stdout_preview}' stderr='{stderr_preview}'"
+    ));
+    // The helper reports some errors on stdout
+    if stderr_preview.is_empty() {
+        stdout_preview
+    } else {
+        stderr_preview
+    }
+}
+
+/// Whether the process runs with root privileges
+pub fn running_as_root() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Runs the apply plan through the privileged helper directly
+///
+/// Only works as root; used by the daemon, which has no polkit agent to
+/// answer pkexec.
+pub fn execute_as_root(plan: &ApplyPlan) -> ApplyResult {
+    let Some(helper) = threshold_apply::locate_helper() else {
+        crate::core::debug::debug_log("❌ [WRITER] battery-manager-apply helper not found");
+        return ApplyResult::NoHelper;
+    };
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🔐 [WRITER] Running {} as root with {} writes",
+        helper.display(),
+        plan.writes.len()
+    ));
+
+    match Command::new(&helper).args(plan.to_args()).output() {
+        Ok(result) if result.status.success() => {
+            crate::core::debug::debug_log("✅ [WRITER] Helper execution succeeded");
+            ApplyResult::Success
+        }
+        Ok(result) => ApplyResult::Failed(failure_output(&result)),
+        Err(err) => ApplyResult::Failed(format!("{}: {err}", t("error_execution"))),
+    }
+}
+
+/// Runs the apply plan through the privileged helper via pkexec
+pub fn execute_with_pkexec(plan: &ApplyPlan) -> ApplyResult {
+    let pkexec_check = Command::new("which").arg("pkexec").output();
+
+    match pkexec_check {
+        Ok(result) if result.status.success() => {
+            let Some(helper) = threshold_apply::locate_helper() else {
+                crate::core::debug::debug_log("❌ [WRITER] battery-manager-apply helper not found");
+                return ApplyResult::NoHelper;
+            };
+
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔐 [WRITER] pkexec found, running {} with {} writes, service={:?}",
+                helper.display(),
+                plan.writes.len(),
+                plan.service_action
+            ));
+            let output = Command::new("pkexec")
+                .arg(&helper)
+                .args(plan.to_args())
+                .output();
+
+            match output {
+                Ok(result) if result.status.success() => {
+                    crate::core::debug::debug_log("✅ [WRITER] pkexec execution succeeded");
+                    ApplyResult::Success
+                }
+                Ok(result) => threshold_apply::classify_pkexec_result(
+                    result.status.code(),
+                    &failure_output(&result),
+                )
+                .into(),
+                Err(err) => {
+                    crate::core::debug::debug_log_args(std::format_args!(
+                        "❌ [WRITER] Execution error: {err}"
+                    ));
+                    failure_from_spawn_error(&err)
+                }
+            }
+        }
+        _ => {
+            crate::core::debug::debug_log(
+                "❌ [WRITER] pkexec not found (which pkexec failed or returned non-zero)",
+            );
+            ApplyResult::NoPkexec
+        }
+    }
+}
+
+/// User-facing text of a failed apply result (`None` on success)
+pub fn failure_text(result: &ApplyResult) -> Option<String> {
+    match result {
+        ApplyResult::Success => None,
+        ApplyResult::Cancelled => Some(t("auth_canceled")),
+        ApplyResult::Failed(error_msg) => Some(error_msg.clone()),
+        ApplyResult::NoHelper => Some(t("error_no_helper")),
+        ApplyResult::NoPkexec => Some(t("error_no_pkexec")),
+    }
+}
+
+/// Writes the saved thresholds again if the firmware dropped them
+///
+/// Used after resume from suspend and by the daemon.
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `execute` - Runs the plan ([`execute_with_pkexec`] or [`execute_as_root`])
+///
+/// # Returns
+///
+/// `None` when nothing was ever saved for this battery
+pub fn reapply_saved_thresholds(
+    battery_name: &str,
+    execute: impl FnOnce(&ApplyPlan) -> ApplyResult,
+) -> Option<ReapplyOutcome> {
+    let saved = SavedThresholds::load(Path::new(threshold_apply::CONFIG_DIR), battery_name)?;
+    if BatteryInfo::new(battery_name).is_ok_and(|info| saved.matches(&info)) {
+        return Some(ReapplyOutcome::AlreadySet);
+    }
+
+    let vendor_info = VendorInfo::detect_cached(battery_name);
+    let plan = build_reapply_plan(
+        saved.start,
+        saved.stop,
+        &ApplyEnvironment {
+            power_supply_root: Path::new("/sys/class/power_supply"),
+            config_dir: Path::new(threshold_apply::CONFIG_DIR),
+            start_paths: &vendor_info.threshold_files.start_paths,
+            stop_paths: &vendor_info.threshold_files.stop_paths,
+            // A re-apply plan never touches the restore service
+            service_unit_exists: false,
+            udev_rule_exists: false,
+        },
+    );
+    let result = execute(&plan);
+    Some(failure_text(&result).map_or(ReapplyOutcome::Applied, ReapplyOutcome::Failed))
+}
+
+/// Builds the plan writing the firmware defaults (start 0%, stop 100%)
+///
+/// Care toggles are switched off instead. Nothing is persisted: the saved
+/// thresholds come back at the next boot if the service or udev rule is on.
+///
+/// # Arguments
+///
+/// * `vendor_info` - Detected vendor and threshold files
+/// * `env` - Detected files and service state
+pub fn build_reset_plan(vendor_info: &VendorInfo, env: &ApplyEnvironment<'_>) -> ApplyPlan {
+    let stop = match vendor_info.threshold_control() {
+        ThresholdControl::CareToggle => vendor_info.vendor_type.care_value(false),
+        ThresholdControl::Percentage => 100,
+    };
+    build_reapply_plan((!env.start_paths.is_empty()).then_some(0), stop, env)
+}
+
+/// Restores the default thresholds of a battery (`--reset-thresholds`)
+///
+/// # Arguments
+///
+/// * `battery` - Battery name (e.g., "BAT0")
+///
+/// # Errors
+///
+/// Returns the message to show when the battery has no threshold file or
+/// the privileged write fails
+pub fn reset_thresholds(battery: &str) -> Result<(), String> {
+    let vendor_info = VendorInfo::detect_cached(battery);
+    let files = &vendor_info.threshold_files;
+    if files.stop_paths.is_empty() {
+        return Err(t_fmt("reset_unsupported", &[("battery", battery)]));
+    }
+
+    let plan = build_reset_plan(
+        vendor_info,
+        &ApplyEnvironment {
+            power_supply_root: Path::new("/sys/class/power_supply"),
+            config_dir: Path::new(threshold_apply::CONFIG_DIR),
+            start_paths: &files.start_paths,
+            stop_paths: &files.stop_paths,
+            service_unit_exists: false,
+            udev_rule_exists: false,
+        },
+    );
+    crate::core::debug::debug_log_args(std::format_args!(
+        "↺ [WRITER] Resetting thresholds of {battery}: {:?}",
+        plan.writes
+    ));
+    failure_text(&execute_with_pkexec(&plan)).map_or(Ok(()), Err)
+}
+
+/// Runs `--reset-thresholds`
+///
+/// # Arguments
+///
+/// * `battery` - Battery name, the first detected battery when `None`
+///
+/// # Returns
+///
+/// Process exit code (0 on success)
+pub fn run_reset(battery: Option<&str>) -> i32 {
+    let Some(battery) = battery
+        .map(str::to_string)
+        .or_else(|| BatteryInfo::get_battery_list().into_iter().next())
+    else {
+        eprintln!("{}", t("no_battery"));
+        return 1;
+    };
+
+    match reset_thresholds(&battery) {
+        Ok(()) => {
+            println!("{}", t_fmt("reset_done", &[("battery", &battery)]));
+            0
+        }
+        Err(error) => {
+            println!("{}: {error}", t("error"));
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::vendor_detection::{ThresholdFiles, VendorType};
+
+    fn vendor_info(vendor_type: VendorType, start: &[&str], stop: &[&str]) -> VendorInfo {
+        let paths = |list: &[&str]| list.iter().map(|p| (*p).to_string()).collect::<Vec<_>>();
+        VendorInfo {
+            manufacturer: String::new(),
+            product_name: String::new(),
+            supports_start_threshold: !start.is_empty(),
+            supports_stop_threshold: !stop.is_empty(),
+            threshold_files: ThresholdFiles {
+                start_paths: paths(start),
+                stop_paths: paths(stop),
+            },
+            vendor_type,
+        }
+    }
+
+    fn reset_writes(info: &VendorInfo) -> Vec<(String, String)> {
+        let plan = build_reset_plan(
+            info,
+            &ApplyEnvironment {
+                power_supply_root: Path::new("/sys/class/power_supply"),
+                config_dir: Path::new("/etc/battery-manager"),
+                start_paths: &info.threshold_files.start_paths,
+                stop_paths: &info.threshold_files.stop_paths,
+                service_unit_exists: false,
+                udev_rule_exists: false,
+            },
+        );
+        assert!(plan.install_unit.is_none() && plan.udev_rule.is_none());
+        plan.writes
+            .into_iter()
+            .map(|(path, value)| (path.display().to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_reset_plan() {
+        let start = "/sys/class/power_supply/BAT0/charge_control_start_threshold";
+        let stop = "/sys/class/power_supply/BAT0/charge_control_end_threshold";
+        assert_eq!(
+            reset_writes(&vendor_info(VendorType::Lenovo, &[start], &[stop])),
+            [
+                (start.to_string(), "0".to_string()),
+                (stop.to_string(), "100".to_string())
+            ]
+        );
+        assert_eq!(
+            reset_writes(&vendor_info(VendorType::Asus, &[], &[stop])),
+            [(stop.to_string(), "100".to_string())]
+        );
+
+        // Care toggles are switched off
+        let care = "/sys/devices/platform/samsung/battery_care_limit";
+        assert_eq!(
+            reset_writes(&vendor_info(VendorType::Samsung, &[], &[care])),
+            [(care.to_string(), "0".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_failure_from_spawn_error() {
+        assert!(matches!(
+            failure_from_spawn_error(&std::io::Error::from(std::io::ErrorKind::NotFound)),
+            ApplyResult::NoPkexec
+        ));
+        assert!(matches!(
+            failure_from_spawn_error(&std::io::Error::from(std::io::ErrorKind::PermissionDenied)),
+            ApplyResult::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn test_threshold_error_display() {
+        let _lock = crate::core::i18n::TEST_LANG_LOCK.lock().unwrap();
+        crate::core::i18n::set_language("en");
+        assert_eq!(
+            ThresholdError::StopOutOfRange.to_string(),
+            "Error: stop threshold cannot exceed 100%"
+        );
+        assert_eq!(
+            ThresholdError::GapTooSmall { min_gap: 5 }.to_string(),
+            "Start threshold must be at least 5% below the stop threshold on this hardware"
+        );
+
+        crate::core::i18n::set_language("fr");
+        assert_eq!(
+            ThresholdError::StartNotBelowStop.to_string(),
+            "Erreur: le seuil de début doit être inférieur au seuil de fin"
+        );
+    }
+}
@@ -5,9 +5,29 @@
 //! - ui/ : User interface (GTK4, theme management)
 //!
 //! # Command-line arguments
+//! - `--backend=sysfs|upower` : Force the battery data source (default: sysfs, `UPower` as fallback)
+//! - `--battery=BAT0` : With `--json`, only print this battery; with `--reset-thresholds`, the battery to reset
+//! - `--check-health` : Print the health of the first battery and exit with 0 (≥80%), 1 (60-79%), 2 (<60%) or 3 (no battery)
+//! - `--daemon` : Run in the background without GTK, re-applying the saved thresholds when they drift
 //! - `--debug` : Enable debug mode with exhaustive tracing
+//! - `--interval=30` : With `--daemon`, seconds between two checks (5–3600, default 30)
+//! - `--json` : Print the status of every battery as JSON and exit (no GUI)
+//! - `--list-batteries` : Print each detected battery (status, capacity, health) and exit; a JSON array with `--json`
 //! - `--lang=en` : Set language to English (default: fr)
 //! - `--lang=fr` : Set language to French
+//! - `--lang=pt` : Set language to Brazilian Portuguese (`pt_BR` also accepted)
+//! - `--lang=pl` : Set language to Polish
+//! - `--log-file=PATH` : Also write ISO-8601 timestamped log lines to PATH (rotated above 1 MB)
+//! - `--log-format=json` : One JSON object per log line (`level`, `ts`, `msg`, `tag`)
+//! - `--log-timestamps` : Prefix log lines with `[HH:MM:SS.mmm]`
+//! - `--no-dbus` : Do not export battery data on the session D-Bus
+//! - `--reset-thresholds` : Write the default thresholds (0/100%) through `pkexec` and exit, e.g. to charge fully before a trip
+//! - `--tray` : Start hidden in the system tray (window opened from the tray menu)
+//! - `--version`, `-V` : Print the version, git commit, features and vendor support
+//! - `--watch` : Print live battery status to the terminal (no GUI)
+//!
+//! # Environment
+//! - `BATTERY_MANAGER_LOG_LEVEL=error|warn|info|debug` : Most verbose level shown with `--debug` (default: debug)
 
 mod core;
 mod ui;
@@ -18,54 +38,178 @@ use std::env;
 
 const APP_ID: &str = "com.battery.manager";
 
-fn main() {
-    // Load or detect language preference
-    let config_file = dirs::config_dir().map(|d| d.join("battery-manager").join("language.conf"));
-
-    let mut lang_loaded = false;
-    if let Some(ref config_path) = config_file {
-        if let Ok(saved_lang) = std::fs::read_to_string(config_path) {
-            let lang = saved_lang.trim();
-            if lang == "en" || lang == "fr" {
-                core::i18n::set_language(lang);
-                lang_loaded = true;
+/// Prints command-line usage
+fn print_help() {
+    println!("Battery Manager v{}", env!("CARGO_PKG_VERSION"));
+    println!("\nUsage: battery-manager [OPTIONS]");
+    println!("\nOptions:");
+    println!("  --backend=sysfs|upower");
+    println!("                     Force the battery data source (default: automatic)");
+    println!("  --battery=BAT0     With --json, only print this battery;");
+    println!("                     with --reset-thresholds, the battery to reset");
+    println!("  --check-health     Print the battery health and exit (no GUI)");
+    println!("                     Exit codes: 0 health >= 80%, 1 60-79%, 2 < 60%, 3 no battery");
+    println!("  --debug            Enable debug mode with exhaustive tracing");
+    println!("  --json             Print battery status as JSON and exit (no GUI)");
+    println!("  --lang=en          Set language to English");
+    println!("  --lang=fr          Set language to French (default)");
+    println!("  --lang=pt          Set language to Brazilian Portuguese");
+    println!("  --lang=pl          Set language to Polish");
+    println!("  --list-batteries   List detected batteries and exit (JSON array with --json)");
+    println!("  --log-file=PATH    Also write log lines to PATH (rotated above 1 MB)");
+    println!("  --log-format=json  One JSON object per log line (plain by default)");
+    println!("  --log-timestamps   Prefix log lines with [HH:MM:SS.mmm]");
+    println!("  --no-dbus          Do not export battery data on the session D-Bus");
+    println!("  --reset-thresholds Restore the 0/100% thresholds (asks for the password) and exit");
+    println!("  --tray             Start hidden in the system tray");
+    println!("  --watch            Print live battery status to the terminal (no GUI)");
+    println!("  --version, -V      Show version, build and vendor support");
+    println!("  --help, -h         Show this help message");
+    println!("\nDaemon:");
+    println!("  --daemon           Run in the background without a window, re-applying the");
+    println!("                     saved thresholds when the firmware drops them");
+    println!("                     (PID file: /run/battery-manager.pid as root,");
+    println!("                     else $XDG_RUNTIME_DIR/battery-manager.pid)");
+    println!("  --interval=30      Seconds between two checks (5-3600, default 30)");
+    println!("                     Stops cleanly on SIGTERM or SIGINT");
+    println!("\nEnvironment:");
+    println!("  BATTERY_MANAGER_LOG_LEVEL=error|warn|info|debug");
+    println!("                     Most verbose level shown with --debug (default: debug)");
+}
+
+/// Cargo features compiled in
+const ENABLED_FEATURES: &[&str] = &[
+    #[cfg(feature = "notifications")]
+    "notifications",
+];
+
+/// Formats the `--version` output
+///
+/// # Arguments
+///
+/// * `version` - Package version
+/// * `git_hash` - Commit the binary was built from ("unknown" outside a checkout)
+/// * `features` - Enabled Cargo features
+/// * `vendor` - Detected vendor support
+fn version_text(version: &str, git_hash: &str, features: &[&str], vendor: &str) -> String {
+    let features = if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(", ")
+    };
+    format!(
+        "Battery Manager {version} (git {git_hash})\nFeatures: {features}\nVendor support: {vendor}"
+    )
+}
+
+/// Describes the vendor support detected for the first battery
+fn vendor_support() -> String {
+    let Some(battery) = core::BatteryInfo::get_battery_list().into_iter().next() else {
+        return "no battery detected".to_string();
+    };
+    let vendor = core::VendorInfo::detect_for_battery(&battery);
+    let thresholds = match (
+        vendor.supports_start_threshold,
+        vendor.supports_stop_threshold,
+    ) {
+        (true, true) => "start and stop thresholds",
+        (false, true) => "stop threshold",
+        (true, false) => "start threshold",
+        (false, false) => "no thresholds",
+    };
+    format!("{:?} on {battery} ({thresholds})", vendor.vendor_type)
+}
+
+/// Loads translation overrides (packager-wide first, then per-user)
+fn load_translation_overrides() {
+    let override_files = [
+        Some(std::path::PathBuf::from(
+            "/usr/share/battery-manager/translations.json",
+        )),
+        dirs::config_dir().map(|d| d.join("battery-manager").join("translations.json")),
+    ];
+    for path in override_files.into_iter().flatten() {
+        if path.is_file() {
+            if let Err(e) = core::i18n::load_extra_translations(&path) {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [MAIN] Failed to load {}: {e}",
+                    path.display()
+                ));
             }
         }
     }
+}
 
-    // If no saved preference, detect system language
-    if !lang_loaded {
+#[allow(clippy::too_many_lines)]
+fn main() {
+    // Load saved settings (config.toml, or the older per-setting files)
+    let config = core::config::load_config();
+
+    // Saved language, or detect the system language
+    if config.language.is_empty() {
         if let Ok(sys_lang) = env::var("LANG").or_else(|_| env::var("LC_ALL")) {
-            let lang = if sys_lang.starts_with("en") {
-                "en"
-            } else {
-                "fr" // Default to French
-            };
-            core::i18n::set_language(lang);
+            // e.g. de_AT.UTF-8 -> de_AT, de, then English
+            core::i18n::set_locale(&sys_lang);
         }
+    } else {
+        core::i18n::set_language(&config.language);
     }
 
-    // Load saved theme preference
-    let theme_file = dirs::config_dir().map(|d| d.join("battery-manager").join("theme.conf"));
+    ui::theme::set_theme(&config.theme);
+    ui::tray::set_minimize_to_tray(config.minimize_to_tray);
 
-    if let Some(ref theme_path) = theme_file {
-        if let Ok(saved_theme) = std::fs::read_to_string(theme_path) {
-            let theme = saved_theme.trim();
-            if theme == "dark" || theme == "light" {
-                ui::theme::set_theme(theme);
-            }
-        }
-    }
+    // Load saved accent color (invalid values fall back to the defaults)
+    ui::theme::load_accent(&config.accent_color);
 
     // Parse command-line arguments and filter GTK arguments
     let args: Vec<String> = env::args().collect();
     let mut gtk_args = vec![args[0].clone()];
+    let mut watch_mode = false;
+    let mut json_mode = false;
+    let mut check_health_mode = false;
+    let mut list_mode = false;
+    let mut reset_mode = false;
+    let mut daemon_mode = false;
+    let mut daemon_interval = core::daemon::DEFAULT_DAEMON_INTERVAL_SECS;
+    let mut battery_filter: Option<String> = None;
 
     for arg in &args[1..] {
         match arg.as_str() {
+            arg if arg.starts_with("--backend=") => {
+                let value = arg.trim_start_matches("--backend=");
+                match core::upower::Backend::parse(value) {
+                    Some(backend) => core::upower::set_backend(backend),
+                    None => crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [MAIN] Unknown backend '{value}' (expected sysfs or upower)"
+                    )),
+                }
+            }
+            arg if arg.starts_with("--battery=") => {
+                battery_filter = Some(arg.trim_start_matches("--battery=").to_string());
+            }
+            "--check-health" => {
+                check_health_mode = true;
+            }
+            "--daemon" => {
+                daemon_mode = true;
+            }
+            arg if arg.starts_with("--interval=") => {
+                let value = arg.trim_start_matches("--interval=");
+                match core::daemon::parse_interval(value) {
+                    Some(secs) => daemon_interval = secs,
+                    None => crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [MAIN] Invalid interval '{value}' (expected 5 to 3600 seconds)"
+                    )),
+                }
+            }
             "--debug" => {
                 core::debug::enable_debug();
                 crate::core::debug::debug_log("🚀 [MAIN] Debug mode enabled");
+                for (lang, key) in core::i18n::check_completeness() {
+                    crate::core::debug::debug_log_args(std::format_args!(
+                        "⚠️ [I18N] Missing key '{key}' in language '{lang}'"
+                    ));
+                }
             }
             arg if arg.starts_with("--lang=") => {
                 let lang = &arg[7..];
@@ -76,14 +220,58 @@ fn main() {
                     ));
                 }
             }
+            "--list-batteries" => {
+                list_mode = true;
+            }
+            arg if arg.starts_with("--log-file=") => {
+                let path = arg.trim_start_matches("--log-file=");
+                if let Err(e) = core::debug::set_log_file(std::path::Path::new(path)) {
+                    crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [MAIN] Cannot open log file {path}: {e}"
+                    ));
+                }
+            }
+            arg if arg.starts_with("--log-format=") => {
+                let value = arg.trim_start_matches("--log-format=");
+                match core::debug::LogFormat::parse(value) {
+                    Some(format) => core::debug::set_log_format(format),
+                    None => crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [MAIN] Unknown log format '{value}' (expected plain or json)"
+                    )),
+                }
+            }
+            "--log-timestamps" => {
+                core::debug::enable_timestamps();
+            }
+            "--no-dbus" => {
+                ui::dbus::disable_dbus();
+            }
+            "--json" => {
+                json_mode = true;
+            }
+            "--reset-thresholds" => {
+                reset_mode = true;
+            }
+            "--tray" => {
+                ui::tray::enable_tray_mode();
+            }
+            "--watch" => {
+                watch_mode = true;
+            }
+            "--version" | "-V" => {
+                println!(
+                    "{}",
+                    version_text(
+                        env!("CARGO_PKG_VERSION"),
+                        env!("BATTERY_MANAGER_GIT_HASH"),
+                        ENABLED_FEATURES,
+                        &vendor_support()
+                    )
+                );
+                std::process::exit(0);
+            }
             "--help" | "-h" => {
-                println!("Battery Manager v{}", env!("CARGO_PKG_VERSION"));
-                println!("\nUsage: battery-manager [OPTIONS]");
-                println!("\nOptions:");
-                println!("  --debug        Enable debug mode with exhaustive tracing");
-                println!("  --lang=en      Set language to English");
-                println!("  --lang=fr      Set language to French (default)");
-                println!("  --help, -h     Show this help message");
+                print_help();
                 std::process::exit(0);
             }
             _ => {
@@ -93,6 +281,46 @@ fn main() {
         }
     }
 
+    load_translation_overrides();
+
+    if reset_mode {
+        crate::core::debug::debug_log("↺ [MAIN] Threshold reset requested, GTK not started");
+        std::process::exit(core::writer::run_reset(battery_filter.as_deref()));
+    }
+
+    if list_mode {
+        crate::core::debug::debug_log("📋 [MAIN] Battery list requested, GTK not started");
+        std::process::exit(core::export::run_list(json_mode));
+    }
+
+    if json_mode {
+        crate::core::debug::debug_log("📄 [MAIN] JSON status requested, GTK not started");
+        std::process::exit(core::export::run(battery_filter.as_deref()));
+    }
+
+    if check_health_mode {
+        crate::core::debug::debug_log("🩺 [MAIN] Health check requested, GTK not started");
+        let code = core::health_check::check_health(
+            core::health_check::HEALTH_WARN_PERCENT,
+            core::health_check::HEALTH_CRIT_PERCENT,
+        );
+        std::process::exit(code.code());
+    }
+
+    if daemon_mode {
+        crate::core::debug::debug_log("🛡️ [MAIN] Daemon mode requested, GTK not started");
+        std::process::exit(core::daemon::run(std::time::Duration::from_secs(
+            daemon_interval,
+        )));
+    }
+
+    if watch_mode {
+        crate::core::debug::debug_log("👀 [MAIN] Watch mode requested, GTK not started");
+        std::process::exit(core::watch::run(std::time::Duration::from_secs(
+            config.refresh_interval_secs,
+        )));
+    }
+
     crate::core::debug::debug_log("🚀 [MAIN] Starting Battery Manager application");
     crate::core::debug::debug_log_args(std::format_args!(
         "🌐 [MAIN] Current language: {}",
@@ -108,3 +336,20 @@ fn main() {
     crate::core::debug::debug_log("🖥️ [MAIN] Running GTK4 application");
     app.run_with_args(&gtk_args);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_text() {
+        assert_eq!(
+            version_text("1.0.0", "0123456789ab", &["notifications"], "Lenovo on BAT0 (start and stop thresholds)"),
+            "Battery Manager 1.0.0 (git 0123456789ab)\nFeatures: notifications\nVendor support: Lenovo on BAT0 (start and stop thresholds)"
+        );
+        assert_eq!(
+            version_text("1.0.0", "unknown", &[], "no battery detected"),
+            "Battery Manager 1.0.0 (git unknown)\nFeatures: none\nVendor support: no battery detected"
+        );
+    }
+}
@@ -1,28 +1,58 @@
 //! Main application window and UI initialization
 //!
 //! Builds the GTK4 application window with notebook tabs for battery
-//! information and settings. Manages auto-refresh timer.
+//! information and settings. Manages auto-refresh timer, slowed down on
+//! battery while the window is in the background and paused while it is
+//! minimised or hidden.
 
 use glib::timeout_add_local;
 use gtk4::prelude::*;
 use gtk4::{
-    gio, AboutDialog, Application, ApplicationWindow, Box, HeaderBar, Label, MenuButton, Notebook,
-    Orientation, Separator,
+    gdk, gio, AboutDialog, Application, ApplicationWindow, Box, Button, DropDown, HeaderBar, Label,
+    MenuButton, Notebook, Orientation, ScrolledWindow, Separator, Widget,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::time::Duration;
 
+use crate::core::alerts::{event_enabled, low_threshold, notifications_enabled, AlarmMonitor};
+use crate::core::config::{reapply_on_resume, timer_interval, WindowState};
+use crate::core::estimator::Estimator;
+use crate::core::health::{local_date, record_daily_snapshot, HealthHistory};
+use crate::core::health_tracker;
+use crate::core::history::{prune_file, unix_now, HistoryBuffer, BUFFER_CAPACITY, RETENTION};
 use crate::core::i18n::t;
-use crate::core::{BatteryInfo, PeripheralBattery, PowerSupplyInfo};
+use crate::core::kernel::{self, KernelVersion};
+use crate::core::monitor::{self, UeventMonitor};
+use crate::core::resume;
+use crate::core::traits::{
+    BatteryHistoryService, BatteryService, FlatFileBatteryHistory, NotificationService,
+};
+use crate::core::upower::{detect_peripherals, select_battery_service};
+use crate::core::writer::{execute_with_pkexec, reapply_saved_thresholds};
+use crate::core::{BatteryInfo, PeripheralBattery, PowerSupplyInfo, VendorInfo};
 use crate::debug_ui;
-use crate::ui::info_tab::build_info_tab;
+use crate::ui::components::UpdatableWidgets;
+use crate::ui::dbus::{dbus_enabled, BatteryDbusService};
+use crate::ui::diagnostics::show_diagnostics_window;
+use crate::ui::info_tab::{
+    build_info_tab, refresh_service_label, update_capacity_trend, update_info_widgets,
+    update_threshold_labels, update_timestamp, update_wear_trends,
+};
+use crate::ui::notifications::create_notification_service;
 use crate::ui::peripherals_tab::{
     build_peripherals_tab, update_peripherals_tab, UpdatablePeripheralsWidgets,
 };
-use crate::ui::settings_tab::build_settings_tab;
-use crate::ui::ui_tab::build_ui_tab;
+use crate::ui::settings_tab::{build_settings_tab, update_resume_label};
+use crate::ui::shortcuts::{
+    set_accels, show_shortcuts_window, ACTION_APPLY_SETTINGS, ACTION_QUIT as ACTION_APP_QUIT,
+    ACTION_REFRESH, ACTION_SHOW_PREFERENCES, ACTION_SHOW_SHORTCUTS, ACTION_SWITCH_TAB,
+};
+use crate::ui::tray::{
+    minimize_to_tray, tray_mode, TrayIcon, ACTION_APPLY_THRESHOLDS, ACTION_OPEN, ACTION_QUIT,
+};
+use crate::ui::ui_tab::{build_ui_tab, ACTION_REFRESH_INTERVAL};
 
 fn find_installed_doc(filename: &str) -> Option<PathBuf> {
     let candidates = [
@@ -55,8 +85,68 @@ fn open_doc(filename: &str) {
     }
 }
 
+/// Delay between a resume and the threshold check, in seconds
+const RESUME_DELAY_SECS: u32 = 2;
+
+/// Application actions registered by `ensure_help_menu`
+const HELP_MENU_ACTIONS: [&str; 6] = [
+    "about",
+    "diagnostics",
+    "open_readme",
+    "open_references",
+    ACTION_APP_QUIT,
+    ACTION_SHOW_SHORTCUTS,
+];
+
+/// Builds the hardware summary shown in the About dialog, for bug reports
+///
+/// Left untranslated, since it is meant to be pasted into an issue.
+///
+/// # Arguments
+///
+/// * `batteries` - Each battery with its detected vendor support
+/// * `kernel` - Running kernel version
+///
+/// # Returns
+///
+/// One fact per line (vendor, product, kernel, then the threshold files of
+/// each battery)
+fn system_information(
+    batteries: &[(String, &VendorInfo)],
+    kernel: Option<KernelVersion>,
+) -> String {
+    let mut lines = Vec::new();
+    if let Some((_, vendor)) = batteries.first() {
+        lines.push(format!(
+            "Vendor: {} ({:?})",
+            vendor.manufacturer, vendor.vendor_type
+        ));
+        lines.push(format!("Product: {}", vendor.product_name));
+    }
+    lines.push(format!(
+        "Kernel: {}",
+        kernel.map_or_else(|| "unknown".to_string(), |version| version.to_string())
+    ));
+    if batteries.is_empty() {
+        lines.push("No battery detected".to_string());
+    }
+    for (name, vendor) in batteries {
+        let files = &vendor.threshold_files;
+        let list = |paths: &[String]| {
+            if paths.is_empty() {
+                "none".to_string()
+            } else {
+                paths.join(", ")
+            }
+        };
+        lines.push(format!("{name} start: {}", list(&files.start_paths)));
+        lines.push(format!("{name} stop: {}", list(&files.stop_paths)));
+    }
+    lines.join("\n")
+}
+
 fn ensure_help_menu(app: &Application, window: &ApplicationWindow) {
-    if app.lookup_action("about").is_some() {
+    if app.lookup_action(HELP_MENU_ACTIONS[0]).is_some() {
         return;
     }
 
@@ -65,6 +155,13 @@ fn ensure_help_menu(app: &Application, window: &ApplicationWindow) {
         #[weak]
         window,
         move |_, _| {
+            let batteries: Vec<(String, &VendorInfo)> = BatteryInfo::get_battery_list()
+                .into_iter()
+                .map(|name| {
+                    let vendor = VendorInfo::detect_cached(&name);
+                    (name, vendor)
+                })
+                .collect();
             let about = AboutDialog::builder()
                 .transient_for(&window)
                 .modal(true)
@@ -74,12 +171,21 @@ fn ensure_help_menu(app: &Application, window: &ApplicationWindow) {
                 .license_type(gtk4::License::MitX11)
                 .website("https://github.com/weedmanu/Battery_Charge_Manager")
                 .website_label("GitHub")
+                .system_information(system_information(&batteries, kernel::running_version()))
                 .build();
             about.present();
         }
     ));
     app.add_action(&about_action);
 
+    let diagnostics_action = gio::SimpleAction::new("diagnostics", None);
+    diagnostics_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        move |_, _| show_diagnostics_window(&window)
+    ));
+    app.add_action(&diagnostics_action);
+
     let readme_action = gio::SimpleAction::new("open_readme", None);
     readme_action.connect_activate(move |_, _| {
         open_doc("README.html");
@@ -91,11 +197,457 @@ fn ensure_help_menu(app: &Application, window: &ApplicationWindow) {
         open_doc("REFERENCES.html");
     });
     app.add_action(&references_action);
+
+    let quit_action = gio::SimpleAction::new(ACTION_APP_QUIT, None);
+    quit_action.connect_activate(glib::clone!(
+        #[weak]
+        app,
+        move |_, _| app.quit()
+    ));
+    app.add_action(&quit_action);
+
+    let shortcuts_action = gio::SimpleAction::new(ACTION_SHOW_SHORTCUTS, None);
+    shortcuts_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        move |_, _| show_shortcuts_window(&window)
+    ));
+    app.add_action(&shortcuts_action);
+
+    set_accels(app);
+}
+
+/// Selects the Settings tab and moves the keyboard focus to its Apply button
+fn focus_apply_button(notebook: &Notebook, tabs: &Rc<RefCell<BatteryTabs>>) {
+    let tabs = tabs.borrow();
+    if let Some(page) = notebook.page_num(&tabs.settings_page) {
+        notebook.set_current_page(Some(page));
+    }
+    tabs.apply_button.grab_focus();
+}
+
+/// Creates the action running an immediate refresh (F5 / Ctrl+R)
+///
+/// # Arguments
+///
+/// * `refresh` - Refresh callback of the auto-update timer
+fn refresh_action(refresh: RefreshFn) -> gio::SimpleAction {
+    let action = gio::SimpleAction::new(ACTION_REFRESH, None);
+    action.connect_activate(move |_, _| {
+        debug_ui!("Manual refresh requested");
+        let _ = (refresh.borrow_mut())();
+    });
+    action
+}
+
+/// Registers the window actions triggered by keyboard shortcuts
+///
+/// # Arguments
+///
+/// * `window` - Main window
+/// * `notebook` - Main notebook
+/// * `tabs` - Battery-specific tabs (to run their apply action)
+/// * `preferences_page` - Interface tab, selected by Ctrl+,
+/// * `refresh` - Refresh callback of the auto-update timer
+fn register_shortcut_actions(
+    window: &ApplicationWindow,
+    notebook: &Notebook,
+    tabs: &Rc<RefCell<BatteryTabs>>,
+    preferences_page: &Widget,
+    refresh: RefreshFn,
+) {
+    window.add_action(&refresh_action(refresh));
+
+    let switch_tab_action = gio::SimpleAction::new(ACTION_SWITCH_TAB, Some(glib::VariantTy::INT32));
+    switch_tab_action.connect_activate(glib::clone!(
+        #[weak]
+        notebook,
+        move |_, parameter| {
+            let Some(page) = parameter.and_then(glib::Variant::get::<i32>) else {
+                return;
+            };
+            if u32::try_from(page).is_ok_and(|page| page < notebook.n_pages()) {
+                notebook.set_current_page(u32::try_from(page).ok());
+            }
+        }
+    ));
+    window.add_action(&switch_tab_action);
+
+    // The page moves when the peripherals tab appears, so look it up each time
+    let show_preferences_action = gio::SimpleAction::new(ACTION_SHOW_PREFERENCES, None);
+    show_preferences_action.connect_activate(glib::clone!(
+        #[weak]
+        notebook,
+        #[weak]
+        preferences_page,
+        move |_, _| {
+            if let Some(page) = notebook.page_num(&preferences_page) {
+                notebook.set_current_page(Some(page));
+            }
+        }
+    ));
+    window.add_action(&show_preferences_action);
+
+    // Same as clicking Apply (still asks for confirmation)
+    let apply_settings_action = gio::SimpleAction::new(ACTION_APPLY_SETTINGS, None);
+    apply_settings_action.connect_activate(glib::clone!(
+        #[weak]
+        notebook,
+        #[strong]
+        tabs,
+        move |_, _| {
+            focus_apply_button(&notebook, &tabs);
+            let apply_action = tabs.borrow().apply_action.clone();
+            if apply_action.is_enabled() {
+                debug_ui!("Apply shortcut used");
+                apply_action.activate(None);
+            }
+        }
+    ));
+    window.add_action(&apply_settings_action);
+}
+
+/// Refresh callback of the auto-update timer (`Break` once the window is closed)
+type RefreshFn = Rc<RefCell<dyn FnMut() -> glib::ControlFlow>>;
+
+/// Called with fresh battery information after each refresh
+type RefreshListener = Rc<dyn Fn(&BatteryInfo)>;
+
+/// Tabs tied to the selected battery, rebuilt when the selection changes
+struct BatteryTabs {
+    info_page: Widget,
+    settings_page: Widget,
+    apply_button: Button,
+    /// Runs the settings apply (button and Ctrl+S)
+    apply_action: gio::SimpleAction,
+    /// Last re-apply after resume, under the service switches
+    resume_label: Label,
+    widgets: UpdatableWidgets,
+}
+
+impl BatteryTabs {
+    /// Builds the Information and Settings tabs for a battery
+    fn build(
+        info: &BatteryInfo,
+        battery_name: &str,
+        notifier: &Rc<dyn NotificationService>,
+    ) -> Self {
+        debug_ui!("Building information tab for {battery_name}");
+        let power_supply = PowerSupplyInfo::new();
+        let health = HealthHistory::default_path()
+            .map(|path| HealthHistory::load(&path))
+            .unwrap_or_default();
+        let (info_content, widgets) = build_info_tab(
+            info,
+            &power_supply,
+            load_recent_history(battery_name),
+            &health,
+        );
+        update_capacity_trend(
+            &widgets,
+            health_tracker::compute_trend(health.snapshots(battery_name)),
+        );
+
+        debug_ui!("Building settings tab for {battery_name}");
+        let applied_widgets = widgets.clone();
+        let applied_notifier = notifier.clone();
+        let (settings_content, apply_button, apply_action, resume_label) = build_settings_tab(
+            info,
+            battery_name,
+            Rc::new(move |info: &BatteryInfo| {
+                update_threshold_labels(&applied_widgets, info);
+                refresh_service_label(&applied_widgets.service_label);
+                if notifications_enabled() {
+                    if let Some(stop) = info.charge_stop_threshold {
+                        applied_notifier.notify_threshold_applied(stop);
+                    }
+                }
+            }),
+        );
+
+        // Scrolls once the cards reflow to one column in a short window
+        let info_page = ScrolledWindow::new();
+        info_page.set_hscrollbar_policy(gtk4::PolicyType::Never);
+        info_page.set_propagate_natural_height(true);
+        info_page.set_child(Some(&info_content));
+
+        Self {
+            info_page: info_page.upcast(),
+            settings_page: settings_content.upcast(),
+            apply_button,
+            apply_action,
+            resume_label,
+            widgets,
+        }
+    }
+}
+
+/// Peripherals notebook page, present only while peripherals are detected
+struct PeripheralsPage {
+    notebook: glib::WeakRef<Notebook>,
+    widgets: Option<UpdatablePeripheralsWidgets>,
+}
+
+impl PeripheralsPage {
+    /// Notebook position of the page, right after the Information tab
+    const POSITION: u32 = 1;
+
+    fn new(notebook: &Notebook) -> Self {
+        Self {
+            notebook: notebook.downgrade(),
+            widgets: None,
+        }
+    }
+
+    /// Updates the page with the devices detected now
+    ///
+    /// The page is added when the first peripheral appears and removed once
+    /// the last card is gone.
+    fn sync(&mut self, peripherals: &[PeripheralBattery]) {
+        let Some(notebook) = self.notebook.upgrade() else {
+            return;
+        };
+
+        match self.widgets.as_mut() {
+            Some(widgets) => {
+                update_peripherals_tab(widgets, peripherals);
+                if widgets.devices.is_empty() {
+                    debug_ui!("Last peripheral gone, removing peripherals tab");
+                    if let Some(index) = notebook.page_num(&widgets.page) {
+                        notebook.remove_page(Some(index));
+                    }
+                    self.widgets = None;
+                }
+            }
+            None if !peripherals.is_empty() => {
+                debug_ui!("Building peripherals tab ({} device(s))", peripherals.len());
+                let widgets = build_peripherals_tab(peripherals);
+                let peripherals_tab_label =
+                    Label::new(Some(&format!("🖱️ {}", t("tab_peripherals"))));
+                notebook.insert_page(
+                    &widgets.page,
+                    Some(&peripherals_tab_label),
+                    Some(Self::POSITION),
+                );
+                self.widgets = Some(widgets);
+            }
+            None => {}
+        }
+    }
+}
+
+/// Registers the actions triggered from the tray menu
+///
+/// # Arguments
+///
+/// * `app` - GTK Application instance
+/// * `window` - Main window (shown again from the tray)
+/// * `notebook` - Main notebook
+/// * `tabs` - Battery-specific tabs (to select the settings page)
+fn register_tray_actions(
+    app: &Application,
+    window: &ApplicationWindow,
+    notebook: &Notebook,
+    tabs: &Rc<RefCell<BatteryTabs>>,
+) {
+    let open_action = gio::SimpleAction::new(ACTION_OPEN, None);
+    open_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        move |_, _| window.present()
+    ));
+    app.add_action(&open_action);
+
+    let apply_action = gio::SimpleAction::new(ACTION_APPLY_THRESHOLDS, None);
+    apply_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[weak]
+        notebook,
+        #[strong]
+        tabs,
+        move |_, _| {
+            focus_apply_button(&notebook, &tabs);
+            window.present();
+        }
+    ));
+    app.add_action(&apply_action);
+
+    let quit_action = gio::SimpleAction::new(ACTION_QUIT, None);
+    quit_action.connect_activate(glib::clone!(
+        #[weak]
+        app,
+        move |_, _| app.quit()
+    ));
+    app.add_action(&quit_action);
+}
+
+/// Seeds the history graph with the samples recorded on disk for a battery
+///
+/// # Arguments
+///
+/// * `battery_name` - Battery name
+///
+/// # Returns
+///
+/// Buffer with the samples of the last few hours (empty without a history file)
+fn load_recent_history(battery_name: &str) -> HistoryBuffer {
+    let mut buffer = HistoryBuffer::default();
+    let Some(path) = FlatFileBatteryHistory::default_path() else {
+        return buffer;
+    };
+
+    for sample in FlatFileBatteryHistory::new(&path).get_samples(battery_name, BUFFER_CAPACITY) {
+        buffer.push(sample);
+    }
+    debug_ui!(
+        "Loaded {} history sample(s) for {battery_name}",
+        buffer.len()
+    );
+    buffer
+}
+
+/// Maps the detected batteries to battery selector entries
+///
+/// # Arguments
+///
+/// * `batteries` - Detected battery names
+///
+/// # Returns
+///
+/// * `Some(Vec<String>)` - Dropdown entries, in detection order
+/// * `None` - Single battery, the selector is hidden
+fn battery_dropdown_items(batteries: &[String]) -> Option<Vec<String>> {
+    (batteries.len() > 1).then(|| batteries.iter().map(|b| format!("🔋 {b}")).collect())
+}
+
+/// Composes the main window title from the battery state
+///
+/// # Arguments
+///
+/// * `info` - Battery information
+///
+/// # Returns
+///
+/// Title such as "Battery Manager – 78% ⚡ Charging" (`{app}` and `{percent}`
+/// are replaced in the `title_format_*` translations)
+fn format_window_title(info: &BatteryInfo) -> String {
+    let template = match info.status.as_str() {
+        "Charging" => t("title_format_charging"),
+        "Discharging" => t("title_format_discharging"),
+        "Full" => t("title_format_full"),
+        "Not charging" if info.capacity_percent >= 100 => t("title_format_full"),
+        _ => format!("{{app}} – {{percent}}% {}", info.status_text()),
+    };
+
+    template
+        .replace("{app}", &t("app_title"))
+        .replace("{percent}", &info.capacity_text())
+}
+
+/// Refreshes the combined capacity label shown when several batteries exist
+fn update_aggregate_label(label: &Label, service: &dyn BatteryService, batteries: &[String]) {
+    let aggregate = service.aggregate(batteries);
+
+    label.set_markup(&format!(
+        "<span size='small'>{} ({} × 🔋): <b>{:.1}%</b> · {:.2} W</span>",
+        t("aggregate_capacity"),
+        aggregate.battery_count,
+        aggregate.capacity_percent,
+        aggregate.power_watts
+    ));
+}
+
+/// Swaps a notebook page in place, keeping its tab label and the current page
+fn replace_page(notebook: &Notebook, old_page: &Widget, new_page: &Widget) {
+    let Some(index) = notebook.page_num(old_page) else {
+        return;
+    };
+    let current = notebook.current_page();
+    let tab_label = notebook.tab_label(old_page);
+
+    notebook.remove_page(Some(index));
+    notebook.insert_page(new_page, tab_label.as_ref(), Some(index));
+    notebook.set_current_page(current);
+}
+
+/// Rebuilds the battery-specific tabs for a newly selected battery
+fn switch_battery(
+    notebook: &Notebook,
+    battery_name: &str,
+    battery_info: &Rc<RefCell<BatteryInfo>>,
+    current_battery: &Rc<RefCell<String>>,
+    tabs: &Rc<RefCell<BatteryTabs>>,
+    notifier: &Rc<dyn NotificationService>,
+    service: &dyn BatteryService,
+) {
+    debug_ui!("Battery selected: {battery_name}");
+    let info = match service.get_info(battery_name) {
+        Ok(info) => info,
+        Err(e) => {
+            crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [APP] {}: {e}",
+                t("error_battery_init")
+            ));
+            return;
+        }
+    };
+
+    let new_tabs = BatteryTabs::build(&info, battery_name, notifier);
+    {
+        let old_tabs = tabs.borrow();
+        replace_page(notebook, &old_tabs.info_page, &new_tabs.info_page);
+        replace_page(notebook, &old_tabs.settings_page, &new_tabs.settings_page);
+    }
+
+    *tabs.borrow_mut() = new_tabs;
+    *battery_info.borrow_mut() = info;
+    battery_name.clone_into(&mut current_battery.borrow_mut());
+    crate::ui::theme::apply_current_theme();
+}
+
+/// Rewrites the spreadsheet export of the health history
+fn export_health_csv(health: &HealthHistory) {
+    let Some(path) = health_tracker::default_path() else {
+        return;
+    };
+    if let Err(e) = health_tracker::export(&path, health) {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [APP] Failed to export {}: {e}",
+            path.display()
+        ));
+    }
+}
+
+/// Records today's health snapshot of every battery, then exports the history
+///
+/// # Arguments
+///
+/// * `service` - Battery data source
+/// * `batteries` - Battery names
+fn record_health_snapshots(service: &dyn BatteryService, batteries: &[String]) {
+    let Some(path) = HealthHistory::default_path() else {
+        return;
+    };
+    let today = local_date();
+    for info in batteries
+        .iter()
+        .filter_map(|name| service.get_info(name).ok())
+    {
+        if let Err(e) = record_daily_snapshot(&path, &info, &today) {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [APP] Failed to record health snapshot for {}: {e}",
+                info.name
+            ));
+        }
+    }
+    export_health_csv(&HealthHistory::load(&path));
 }
 
 /// Builds the main application UI window
 ///
-/// Creates a notebook with Information and Settings tabs. Shows
+/// Creates a notebook with Information and Settings tabs. Reads from
+/// sysfs, or from `UPower` when sysfs is empty or incomplete. Shows
 /// a fallback window if no battery is detected.
 ///
 /// # Arguments
@@ -103,8 +655,16 @@ fn ensure_help_menu(app: &Application, window: &ApplicationWindow) {
 /// * `app` - GTK Application instance
 #[allow(clippy::too_many_lines)]
 pub fn build_ui(app: &Application) {
+    // Launched again while running (e.g. hidden in the tray): show the window
+    if let Some(window) = app.windows().first() {
+        debug_ui!("Already running, presenting the existing window");
+        window.present();
+        return;
+    }
+
     crate::core::debug::debug_log("🚀 [APP] Starting UI build...");
-    let batteries = BatteryInfo::get_battery_list();
+    let service = select_battery_service();
+    let batteries = service.list_batteries();
     crate::core::debug::debug_log_args(std::format_args!(
         "🔋 [APP] Detected {} battery/batteries",
         batteries.len()
@@ -116,12 +676,13 @@ pub fn build_ui(app: &Application) {
         return;
     }
 
-    let current_battery = batteries[0].clone();
+    let current_battery = Rc::new(RefCell::new(batteries[0].clone()));
     crate::core::debug::debug_log_args(std::format_args!(
-        "🔋 [APP] Building UI for battery: {current_battery}"
+        "🔋 [APP] Building UI for battery: {}",
+        current_battery.borrow()
     ));
 
-    let battery_info = match BatteryInfo::new(&current_battery) {
+    let battery_info = match service.get_info(&current_battery.borrow()) {
         Ok(info) => Rc::new(RefCell::new(info)),
         Err(e) => {
             crate::core::debug::terminal_error_args(std::format_args!(
@@ -133,13 +694,20 @@ pub fn build_ui(app: &Application) {
         }
     };
 
+    // Health snapshots of every battery (before the tabs read them)
+    record_health_snapshots(service.as_ref(), &batteries);
+
+    // 800×400 until the user has a saved size
+    let window_state = WindowState::load();
     let window = ApplicationWindow::builder()
         .application(app)
         .title(t("app_title"))
-        .default_width(800)
-        .default_height(400)
-        .resizable(false)
+        .default_width(window_state.size.map_or(800, |(width, _)| width))
+        .default_height(window_state.size.map_or(400, |(_, height)| height))
         .build();
+    if window_state.maximized {
+        window.maximize();
+    }
 
     ensure_help_menu(app, &window);
 
@@ -152,6 +720,14 @@ pub fn build_ui(app: &Application) {
         Some(t("open_references").as_str()),
         Some("app.open_references"),
     );
+    help_menu.append(
+        Some(t("keyboard_shortcuts").as_str()),
+        Some("app.shortcuts"),
+    );
+    help_menu.append(
+        Some(t("diagnostic_report").as_str()),
+        Some("app.diagnostics"),
+    );
     help_menu.append(Some(t("about").as_str()), Some("app.about"));
     menu.append_section(Some(t("help").as_str()), &help_menu);
 
@@ -176,6 +752,13 @@ pub fn build_ui(app: &Application) {
         t("app_title")
     ));
     main_box.append(&header_label);
+
+    // Combined capacity (only when several batteries are present)
+    let aggregate = (batteries.len() > 1).then(|| {
+        let aggregate_label = Label::new(None);
+        main_box.append(&aggregate_label);
+        (aggregate_label, batteries.clone())
+    });
     main_box.append(&Separator::new(Orientation::Horizontal));
 
     // Notebook (onglets)
@@ -183,32 +766,19 @@ pub fn build_ui(app: &Application) {
     notebook.set_vexpand(true);
 
     // Onglet Informations
-    debug_ui!("Building information tab");
-    let info = battery_info.borrow();
-    let power_supply = PowerSupplyInfo::new();
-    let (info_content, updatable_widgets) = build_info_tab(&info, &power_supply);
-    drop(info);
+    let notifier = create_notification_service(app);
+    let tabs = Rc::new(RefCell::new(BatteryTabs::build(
+        &battery_info.borrow(),
+        &current_battery.borrow(),
+        &notifier,
+    )));
 
     let info_tab_label = Label::new(Some(&format!("📊 {}", t("tab_info"))));
-    notebook.append_page(&info_content, Some(&info_tab_label));
-
-    // Onglet Périphériques (si détectés)
-    debug_ui!("Checking for peripheral devices");
-    let peripherals = PeripheralBattery::detect_all();
-    let mut peripherals_widgets: Option<UpdatablePeripheralsWidgets> = None;
-    if !peripherals.is_empty() {
-        debug_ui!("Building peripherals tab ({} device(s))", peripherals.len());
-        let (peripherals_content, widgets) = build_peripherals_tab(&peripherals);
-        peripherals_widgets = Some(widgets);
-        let peripherals_tab_label = Label::new(Some(&format!("🖱️ {}", t("tab_peripherals"))));
-        notebook.append_page(&peripherals_content, Some(&peripherals_tab_label));
-    }
+    notebook.append_page(&tabs.borrow().info_page, Some(&info_tab_label));
 
     // Onglet Réglages
-    debug_ui!("Building settings tab");
-    let settings_content = build_settings_tab(&battery_info.borrow(), &current_battery);
     let settings_tab_label = Label::new(Some(&format!("⚙️ {}", t("tab_settings"))));
-    notebook.append_page(&settings_content, Some(&settings_tab_label));
+    notebook.append_page(&tabs.borrow().settings_page, Some(&settings_tab_label));
 
     // Onglet Interface
     debug_ui!("Building UI preferences tab");
@@ -216,7 +786,17 @@ pub fn build_ui(app: &Application) {
     let ui_tab_label = Label::new(Some(&format!("🎨 {}", t("tab_ui"))));
     notebook.append_page(&ui_content, Some(&ui_tab_label));
 
-    // Debug: log tab switches (useful with `--debug`)
+    // Onglet Périphériques (ajouté dès qu'un périphérique est détecté)
+    debug_ui!("Checking for peripheral devices");
+    let mut peripherals_page = PeripheralsPage::new(&notebook);
+    peripherals_page.sync(&detect_peripherals());
+
+    // Reopen on the last tab (the first one if it no longer exists)
+    let page = window_state.tab_within(notebook.n_pages());
+    debug_ui!("Restoring tab #{page}");
+    notebook.set_current_page(Some(page));
+
+    // Log tab switches (useful with `--debug`)
     notebook.connect_switch_page(|nb, page, page_num| {
         let tab_label = nb
             .tab_label(page)
@@ -225,21 +805,175 @@ pub fn build_ui(app: &Application) {
         debug_ui!("Switched tab -> #{page_num} ({tab_label})");
     });
 
+    let resume_batteries = batteries.clone();
+    let resume_battery = current_battery.clone();
+
+    // Battery selector (only when several batteries are present)
+    if let Some(items) = battery_dropdown_items(&batteries) {
+        let item_refs: Vec<&str> = items.iter().map(String::as_str).collect();
+        let battery_dropdown = DropDown::from_strings(&item_refs);
+        battery_dropdown.set_tooltip_text(Some(t("select_battery").as_str()));
+        battery_dropdown.connect_selected_notify(glib::clone!(
+            #[weak]
+            notebook,
+            #[strong]
+            battery_info,
+            #[strong]
+            current_battery,
+            #[strong]
+            tabs,
+            #[strong]
+            notifier,
+            #[strong]
+            service,
+            move |dropdown| {
+                let Some(name) = usize::try_from(dropdown.selected())
+                    .ok()
+                    .and_then(|i| batteries.get(i))
+                else {
+                    return;
+                };
+                if *name != *current_battery.borrow() {
+                    switch_battery(
+                        &notebook,
+                        name,
+                        &battery_info,
+                        &current_battery,
+                        &tabs,
+                        &notifier,
+                        service.as_ref(),
+                    );
+                }
+            }
+        ));
+        header_bar.pack_start(&battery_dropdown);
+    }
+
     main_box.append(&notebook);
     window.set_child(Some(&main_box));
 
     // Apply saved theme
     crate::ui::theme::apply_current_theme();
 
-    // Auto-update toutes les 5 secondes
-    setup_auto_update(
+    // Icône de la barre système
+    register_tray_actions(app, &window, &notebook, &tabs);
+    let tray_window = window.downgrade();
+    let tray = TrayIcon::new(app, move |registered| {
+        let Some(window) = tray_window.upgrade() else {
+            return;
+        };
+        if registered {
+            // The hidden window keeps refreshing for the icon now
+            let _ = WidgetExt::activate_action(
+                &window,
+                &format!("win.{ACTION_REFRESH_INTERVAL}"),
+                None,
+            );
+        } else if tray_mode() && !window.is_visible() {
+            crate::core::debug::debug_log(
+                "⚠️ [APP] --tray requested but no system tray available, showing the window",
+            );
+            window.present();
+        }
+    })
+    .map(Rc::new);
+
+    // Interface D-Bus de session
+    let dbus = if dbus_enabled() {
+        BatteryDbusService::new(app).map(Rc::new)
+    } else {
+        debug_ui!("D-Bus interface disabled (--no-dbus)");
+        None
+    };
+
+    // Run after every refresh, and once now with the initial information
+    let title_window = window.downgrade();
+    let mut on_refresh: Vec<RefreshListener> = vec![Rc::new(move |info: &BatteryInfo| {
+        if let Some(window) = title_window.upgrade() {
+            window.set_title(Some(&format_window_title(info)));
+        }
+    })];
+    if let Some(tray) = tray.clone() {
+        on_refresh.push(Rc::new(move |info: &BatteryInfo| tray.update(info)));
+    }
+    if let Some(ref dbus) = dbus {
+        let dbus = dbus.clone();
+        on_refresh.push(Rc::new(move |info: &BatteryInfo| dbus.update(info)));
+    }
+    if let Some((aggregate_label, batteries)) = aggregate {
+        let service = service.clone();
+        on_refresh.push(Rc::new(move |_: &BatteryInfo| {
+            update_aggregate_label(&aggregate_label, service.as_ref(), &batteries);
+        }));
+    }
+    for listener in &on_refresh {
+        listener(&battery_info.borrow());
+    }
+
+    let state_notebook = notebook.downgrade();
+    let close_tray = tray.clone();
+    window.connect_close_request(move |window| {
+        // default_size() keeps the unmaximized size; GTK 4 exposes no position
+        let state = WindowState {
+            size: Some(window.default_size()),
+            maximized: window.is_maximized(),
+            tab: state_notebook
+                .upgrade()
+                .and_then(|notebook| notebook.current_page())
+                .unwrap_or(0),
+        };
+        if let Err(e) = state.save() {
+            crate::core::debug::terminal_error_args(std::format_args!(
+                "❌ [APP] Cannot save window.conf: {e}"
+            ));
+        } else {
+            debug_ui!("Window state saved: {state:?}");
+        }
+
+        let has_tray = close_tray.as_ref().is_some_and(|tray| tray.is_registered());
+        if has_tray && (minimize_to_tray() || tray_mode()) {
+            debug_ui!("Window hidden to tray");
+            window.set_visible(false);
+            glib::Propagation::Stop
+        } else {
+            glib::Propagation::Proceed
+        }
+    });
+
+    // Auto-update : uevents power_supply + minuterie (30 s par défaut)
+    let refresh = setup_auto_update(
+        notifier,
+        on_refresh,
         battery_info.clone(),
         current_battery,
-        updatable_widgets,
-        peripherals_widgets,
+        tabs.clone(),
+        peripherals_page,
+        service,
     );
+    if let Some(dbus) = dbus {
+        let dbus_refresh = refresh.clone();
+        dbus.set_refresh_handler(Rc::new(move || {
+            let _ = (dbus_refresh.borrow_mut())();
+        }));
+    }
+    let event_driven = watch_power_supply(&window, &refresh);
+    watch_resume(&window, resume_batteries, resume_battery, &tabs, &refresh);
+    let has_tray = tray.is_some();
+    RefreshTimer::start(&window, refresh.clone(), event_driven, tray);
+    register_shortcut_actions(&window, &notebook, &tabs, ui_content.upcast_ref(), refresh);
 
-    window.present();
+    if !tray_mode() {
+        window.present();
+    } else if has_tray {
+        // The window stays attached to the app, which keeps running hidden;
+        // it is shown if the panel turns the icon down
+        debug_ui!("Started hidden in the tray (--tray)");
+    } else {
+        crate::core::debug::debug_log(
+            "⚠️ [APP] --tray requested but no system tray available, showing the window",
+        );
+        window.present();
+    }
 }
 
 /// Displays fallback window when no battery is detected
@@ -267,159 +1001,628 @@ fn build_no_battery_window(app: &Application) {
     window.present();
 }
 
-/// Sets up automatic widget refresh timer
+/// Builds the automatic widget refresh callback
 ///
-/// Refreshes battery information every 5 seconds.
+/// The callback follows the battery selector and returns `Break` once the
+/// window is closed.
 ///
 /// # Arguments
 ///
+/// * `notifier` - Desktop notification service
+/// * `on_refresh` - Called with the fresh battery information (window title, tray icon,
+///   D-Bus interface, combined capacity)
 /// * `battery_info` - Shared battery information
-/// * `current_battery` - Battery name to monitor
-/// * `widgets` - Updatable widget references
-#[allow(clippy::too_many_lines)]
+/// * `current_battery` - Name of the battery to monitor (changes with selection)
+/// * `tabs` - Battery-specific tabs holding the updatable widgets
+/// * `peripherals_page` - Peripherals tab, added and removed as devices come and go
+/// * `service` - Battery backend (sysfs or `UPower`)
+///
+/// # Returns
+///
+/// Refresh callback, run by the timer, power supply uevents, D-Bus and the refresh shortcut
 fn setup_auto_update(
+    notifier: Rc<dyn NotificationService>,
+    on_refresh: Vec<RefreshListener>,
     battery_info: Rc<RefCell<BatteryInfo>>,
-    current_battery: String,
-    widgets: crate::ui::components::UpdatableWidgets,
-    peripherals_widgets: Option<UpdatablePeripheralsWidgets>,
-) {
-    debug_ui!("Setting up 5-second auto-refresh timer");
-
-    timeout_add_local(
-        Duration::from_secs(5),
-        glib::clone!(
-            #[weak(rename_to = capacity_label)]
-            widgets.capacity_label,
-            #[weak(rename_to = health_label)]
-            widgets.health_label,
-            #[weak(rename_to = status_value)]
-            widgets.status_value,
-            #[weak(rename_to = voltage_value)]
-            widgets.voltage_value,
-            #[weak(rename_to = current_value)]
-            widgets.current_value,
-            #[weak(rename_to = power_value)]
-            widgets.power_value,
-            #[weak(rename_to = charge_now_value)]
-            widgets.charge_now_value,
-            #[weak(rename_to = power_source_value)]
-            widgets.power_source_value,
-            #[weak(rename_to = threshold_stop_label)]
-            widgets.threshold_stop_label,
-            #[weak(rename_to = service_label)]
-            widgets.service_label,
-            #[upgrade_or]
-            glib::ControlFlow::Break,
-            move || {
-                let threshold_start_opt = widgets.threshold_start_label.clone();
-                let alarm_opt = widgets.alarm_label.clone();
-
-                let info = match BatteryInfo::new(&current_battery) {
-                    Ok(info) => info,
-                    Err(e) => {
-                        crate::core::debug::terminal_error_args(std::format_args!(
-                            "❌ [UPDATE] Error during refresh: {e}"
-                        ));
-                        return glib::ControlFlow::Continue;
-                    }
-                };
-                let power_supply = PowerSupplyInfo::new();
-
-                if let Some(ref peripherals_widgets) = peripherals_widgets {
-                    update_peripherals_tab(peripherals_widgets);
-                }
+    current_battery: Rc<RefCell<String>>,
+    tabs: Rc<RefCell<BatteryTabs>>,
+    mut peripherals_page: PeripheralsPage,
+    service: Rc<dyn BatteryService>,
+) -> RefreshFn {
+    debug_ui!("Setting up auto-refresh");
 
-                // Update power supply
-                power_source_value.set_markup(&power_supply.get_power_source_markup());
-                power_source_value.remove_css_class("color-success");
-                power_source_value.remove_css_class("color-warning");
-                power_source_value.add_css_class(power_supply.get_power_source_css_class());
-
-                // Update status
-                status_value.set_markup(&info.get_status_markup());
-                // Remove old classes and add new one
-                status_value.remove_css_class("color-success");
-                status_value.remove_css_class("color-warning");
-                status_value.remove_css_class("color-primary");
-                let status_class = info.get_status_css_class();
-                status_value.add_css_class(status_class);
+    let history: Option<std::boxed::Box<dyn BatteryHistoryService>> =
+        FlatFileBatteryHistory::default_path().map(|path| {
+            if let Err(e) = prune_file(&path, RETENTION, unix_now()) {
                 crate::core::debug::debug_log_args(std::format_args!(
-                    "🔄 [UPDATE] Status class updated to: {status_class}"
+                    "⚠️ [UPDATE] Failed to prune history: {e}"
                 ));
+            }
+            std::boxed::Box::new(FlatFileBatteryHistory::new(&path))
+                as std::boxed::Box<dyn BatteryHistoryService>
+        });
+    let mut alarm_monitor = AlarmMonitor::default();
+    let mut estimator = Estimator::default();
+    // Set while refreshes keep failing, so the error is only notified once
+    let mut refresh_failing = false;
+    let health_path = HealthHistory::default_path();
+    // Last (battery, date) health snapshot, so the file is written at most once a day
+    let mut health_recorded: Option<(String, String)> = None;
 
-                // Update labels
-                capacity_label.set_markup(&format!(
-                    "<span size='xx-large' weight='bold'>{}</span><span size='large'>%</span>",
-                    info.capacity_percent
-                ));
-                // Note: capacity_label keeps color-primary class, no update needed
+    let refresh: RefreshFn = Rc::new(RefCell::new(move || {
+        // Window closed: the info tab is no longer part of a widget tree
+        if tabs.borrow().info_page.root().is_none() {
+            debug_ui!("Window closed, stopping auto-refresh timer");
+            return glib::ControlFlow::Break;
+        }
 
-                health_label.set_markup(&format!(
-                    "<span size='xx-large' weight='bold'>{:.1}</span><span size='large'>%</span>",
-                    info.health_percent
-                ));
-                // Remove old classes and add new one
-                health_label.remove_css_class("color-success");
-                health_label.remove_css_class("color-warning");
-                health_label.remove_css_class("color-danger");
-                let health_class = info.get_health_css_class();
-                health_label.add_css_class(health_class);
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "🔄 [UPDATE] Health class updated to: {health_class}"
+        let battery_name = current_battery.borrow().clone();
+        // Retried a few times: reads can fail for a moment after a resume
+        let mut info = match service.get_info_with_retry(&battery_name) {
+            Ok(info) => info,
+            Err(e) => {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [UPDATE] Error during refresh: {e}"
                 ));
+                if !refresh_failing && notifications_enabled() {
+                    notifier.notify_error(&format!("{battery_name}: {e}"));
+                }
+                refresh_failing = true;
+                return glib::ControlFlow::Continue;
+            }
+        };
+        refresh_failing = false;
+        crate::ui::calibration::on_refresh_sample(&info);
+        estimator.smooth_info(&mut info);
+        let power_supply = PowerSupplyInfo::new();
 
-                // Update electrical values
-                voltage_value.set_text(&format!("{}: {:.2} V", t("voltage"), info.voltage_v()));
-                current_value.set_text(&format!("{}: {} mA", t("current"), info.current_ma()));
-                power_value.set_text(&format!("{}: {:.2} W", t("power"), info.power_watts()));
-                charge_now_value.set_text(&format!(
-                    "{}: {} mAh",
-                    t("current_capacity"),
-                    info.charge_now_mah()
+        if let Some(ref history) = history {
+            if let Err(e) = history.record_sample(&battery_name, &info) {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [UPDATE] Failed to record history sample: {e}"
                 ));
+            }
+        }
 
-                // Update thresholds
-                if let Some(ref start_label) = threshold_start_opt {
-                    if let Some(start_val) = info.charge_start_threshold {
-                        start_label.set_markup(&format!(
-                            "<span size='x-large' weight='bold'>{start_val}%</span>"
-                        ));
-                        // Note: start_label keeps color-primary class
+        if let Some(ref path) = health_path {
+            let today = local_date();
+            if health_recorded.as_ref() != Some(&(battery_name.clone(), today.clone())) {
+                match record_daily_snapshot(path, &info, &today) {
+                    Ok(Some(health)) => {
+                        let tabs = tabs.borrow();
+                        update_wear_trends(&tabs.widgets, &health, &battery_name);
+                        update_capacity_trend(
+                            &tabs.widgets,
+                            health_tracker::compute_trend(health.snapshots(&battery_name)),
+                        );
+                        export_health_csv(&health);
                     }
+                    Ok(None) => {}
+                    Err(e) => crate::core::debug::debug_log_args(std::format_args!(
+                        "⚠️ [UPDATE] Failed to record health snapshot: {e}"
+                    )),
                 }
+                health_recorded = Some((battery_name.clone(), today));
+            }
+        }
 
-                threshold_stop_label.set_markup(&format!(
-                    "<span size='x-large' weight='bold'>{}</span>",
-                    info.charge_stop_threshold
-                        .map_or_else(|| "N/A".to_string(), |v| format!("{v}%"))
-                ));
-                // Note: threshold_stop_label garde sa classe color-success
-
-                // Update alarm
-                if let Some(ref alarm_label) = alarm_opt {
-                    if let Some(alarm_pct) = info.alarm_percent() {
-                        alarm_label.set_markup(&format!(
-                            "<span size='x-large' weight='bold'>{alarm_pct:.1}%</span>"
-                        ));
-                        // Note: alarm_label keeps color-danger class
-                    }
+        let events = [
+            alarm_monitor.update(&info, low_threshold()),
+            alarm_monitor.update_power_source(power_supply.ac_online, info.capacity_percent),
+        ];
+        for event in events.iter().flatten() {
+            if notifications_enabled() && event_enabled(event.kind()) {
+                notifier.notify_event(event);
+            }
+        }
+
+        peripherals_page.sync(&detect_peripherals());
+
+        update_info_widgets(&tabs.borrow().widgets, &info, &power_supply);
+        update_timestamp(&tabs.borrow().widgets.updated_label);
+        for listener in &on_refresh {
+            listener(&info);
+        }
+
+        *battery_info.borrow_mut() = info;
+
+        glib::ControlFlow::Continue
+    }));
+
+    refresh
+}
+
+/// Refreshes right away on power supply uevents (AC plugged, status change)
+///
+/// The watch is stopped when the window is destroyed.
+///
+/// # Returns
+///
+/// `true` if uevents are watched, `false` if the timer has to poll alone
+fn watch_power_supply(window: &ApplicationWindow, refresh: &RefreshFn) -> bool {
+    match UeventMonitor::open() {
+        Ok(uevents) => {
+            let uevent_refresh = refresh.clone();
+            let handle = monitor::watch(uevents, move || (uevent_refresh.borrow_mut())());
+            window.connect_destroy(move |_| handle.stop());
+            debug_ui!("Watching power_supply uevents");
+            true
+        }
+        Err(e) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [APP] Uevent monitor unavailable, polling only: {e}"
+            ));
+            false
+        }
+    }
+}
+
+/// Re-applies the saved thresholds after each resume from suspend
+///
+/// Does nothing unless `reapply_on_resume` is enabled. The subscription is
+/// dropped when the window is destroyed.
+fn watch_resume(
+    window: &ApplicationWindow,
+    batteries: Vec<String>,
+    current_battery: Rc<RefCell<String>>,
+    tabs: &Rc<RefCell<BatteryTabs>>,
+    refresh: &RefreshFn,
+) {
+    let tabs = tabs.clone();
+    let refresh = refresh.clone();
+    let subscription = resume::subscribe(move || {
+        if !reapply_on_resume() {
+            return;
+        }
+        debug_ui!("Resumed from suspend, checking the saved thresholds");
+        let batteries = batteries.clone();
+        let current_battery = current_battery.clone();
+        let tabs = tabs.clone();
+        let refresh = refresh.clone();
+        // Leave the firmware time to restore its own state first
+        glib::timeout_add_seconds_local_once(RESUME_DELAY_SECS, move || {
+            // pkexec waits for the password prompt: keep the main loop running
+            glib::spawn_future_local(async move {
+                let outcomes = gio::spawn_blocking(move || {
+                    batteries
+                        .into_iter()
+                        .filter_map(|battery| {
+                            let outcome = reapply_saved_thresholds(&battery, execute_with_pkexec)?;
+                            Some((battery, outcome))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await
+                .unwrap_or_default();
+                for (battery, outcome) in outcomes {
+                    resume::record(&battery, outcome);
                 }
+                let _ = (refresh.borrow_mut())();
+                update_resume_label(&tabs.borrow().resume_label, &current_battery.borrow());
+            });
+        });
+    });
+    if let Some(subscription) = subscription {
+        let subscription = RefCell::new(Some(subscription));
+        window.connect_destroy(move |_| drop(subscription.take()));
+        debug_ui!("Watching logind resume signals");
+    }
+}
 
-                // Update service status
-                service_label.set_markup(&info.service_status_markup());
-                // Remove old classes and add new one
-                service_label.remove_css_class("color-success");
-                service_label.remove_css_class("color-danger");
-                let service_class = info.service_status_css_class();
-                service_label.add_css_class(service_class);
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "🔄 [UPDATE] Service class updated to: {service_class}"
-                ));
+/// Factor applied to the refresh interval on battery while the window is in the background
+const BACKGROUND_SLOWDOWN: u64 = 4;
 
-                *battery_info.borrow_mut() = info;
+/// Interval used by the timer
+///
+/// On battery with the window unfocused (or hidden to the tray), nobody
+/// reads the values: the interval is stretched to save power.
+///
+/// # Arguments
+///
+/// * `configured` - Interval from the preferences (capped without uevents)
+/// * `on_battery` - AC adapter offline
+/// * `focused` - Main window active
+fn effective_interval(configured: u64, on_battery: bool, focused: bool) -> u64 {
+    if on_battery && !focused {
+        configured * BACKGROUND_SLOWDOWN
+    } else {
+        configured
+    }
+}
+
+/// Change the auto-refresh timer has to make
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerCommand {
+    /// Nothing changes
+    Keep,
+    /// (Re)start the timeout with this interval in seconds
+    Start(u64),
+    /// Refresh right away (values are stale), then start with this interval
+    RefreshAndStart(u64),
+    /// Stop the timeout
+    Pause,
+}
 
-                glib::ControlFlow::Continue
+/// Auto-refresh state machine
+///
+/// The timer runs while the window is shown, at the wanted interval, and is
+/// paused while the window is minimised or hidden (unless a tray icon still
+/// shows the values). Coming back triggers an immediate refresh.
+#[derive(Debug, Default)]
+struct RefreshController {
+    /// Interval of the running timeout, `None` while paused or not started
+    running: Option<u64>,
+    /// Paused because the window was hidden
+    paused: bool,
+}
+
+impl RefreshController {
+    /// Decides the timer change for the current window state
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden` - Window minimised or hidden, and no tray icon
+    /// * `secs` - Wanted interval
+    fn update(&mut self, hidden: bool, secs: u64) -> TimerCommand {
+        if hidden {
+            if self.paused {
+                return TimerCommand::Keep;
             }
-        ),
-    );
+            self.paused = true;
+            self.running = None;
+            return TimerCommand::Pause;
+        }
+
+        let resumed = std::mem::take(&mut self.paused);
+        if self.running == Some(secs) {
+            return TimerCommand::Keep;
+        }
+        self.running = Some(secs);
+        if resumed {
+            TimerCommand::RefreshAndStart(secs)
+        } else {
+            TimerCommand::Start(secs)
+        }
+    }
+}
+
+/// Auto-refresh timer, driven by a [`RefreshController`]
+struct RefreshTimer {
+    refresh: RefreshFn,
+    event_driven: bool,
+    /// Tray icon showing the values while the window is hidden
+    tray: Option<Rc<TrayIcon>>,
+    window: glib::WeakRef<ApplicationWindow>,
+    controller: RefCell<RefreshController>,
+    /// Running timeout
+    source: RefCell<Option<glib::SourceId>>,
+    /// Whether the background slowdown currently applies
+    slowed: Cell<bool>,
+}
+
+impl RefreshTimer {
+    /// Starts the timer and follows focus, visibility and interval changes
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Main window (focus state, `refresh-interval` action)
+    /// * `refresh` - Refresh callback of the auto-update timer
+    /// * `event_driven` - Whether power supply uevents trigger refreshes
+    /// * `tray` - Keep refreshing for the tray icon while the window is hidden
+    fn start(
+        window: &ApplicationWindow,
+        refresh: RefreshFn,
+        event_driven: bool,
+        tray: Option<Rc<TrayIcon>>,
+    ) {
+        let timer = Rc::new(Self {
+            refresh,
+            event_driven,
+            tray,
+            window: window.downgrade(),
+            controller: RefCell::new(RefreshController::default()),
+            source: RefCell::new(None),
+            slowed: Cell::new(false),
+        });
+        timer.rearm();
+
+        // The window keeps the timer alive through these handlers
+        let focus_timer = timer.clone();
+        window.connect_is_active_notify(move |_| focus_timer.rearm());
+        let visible_timer = timer.clone();
+        window.connect_visible_notify(move |_| visible_timer.rearm());
+        let minimized_timer = timer.clone();
+        window.connect_realize(move |window| {
+            let toplevel = window
+                .surface()
+                .and_then(|surface| surface.downcast::<gdk::Toplevel>().ok());
+            if let Some(toplevel) = toplevel {
+                let timer = minimized_timer.clone();
+                toplevel.connect_state_notify(move |_| timer.rearm());
+            }
+        });
+        let action = gio::SimpleAction::new(ACTION_REFRESH_INTERVAL, None);
+        action.connect_activate(move |_, _| timer.rearm());
+        window.add_action(&action);
+    }
+
+    /// Window state and interval wanted now, `None` once the window is gone
+    fn wanted(&self) -> Option<(bool, u64)> {
+        let window = self.window.upgrade()?;
+        let minimized = window
+            .surface()
+            .and_then(|surface| surface.downcast::<gdk::Toplevel>().ok())
+            .is_some_and(|toplevel| toplevel.state().contains(gdk::ToplevelState::MINIMIZED));
+        let has_tray = self.tray.as_ref().is_some_and(|tray| tray.is_registered());
+        let hidden = (minimized || !window.is_visible()) && !has_tray;
+        let focused = window.is_active();
+        let on_battery = !PowerSupplyInfo::new().ac_online;
+
+        let slowed = on_battery && !focused;
+        if slowed != self.slowed.replace(slowed) {
+            if slowed {
+                debug_ui!(
+                    "On battery in the background, refresh slowed down x{BACKGROUND_SLOWDOWN}"
+                );
+            } else {
+                debug_ui!("Window focused or on AC, normal refresh interval restored");
+            }
+        }
+        Some((
+            hidden,
+            effective_interval(timer_interval(self.event_driven), on_battery, focused),
+        ))
+    }
+
+    /// Applies the controller decision outside of a tick
+    fn rearm(self: &Rc<Self>) {
+        let Some((hidden, secs)) = self.wanted() else {
+            return;
+        };
+        let command = self.controller.borrow_mut().update(hidden, secs);
+        if command == TimerCommand::Keep {
+            return;
+        }
+        if let Some(source) = self.source.borrow_mut().take() {
+            source.remove();
+        }
+        match command {
+            TimerCommand::Start(secs) => {
+                debug_ui!("Auto-refresh every {secs}s");
+                self.arm(secs);
+            }
+            TimerCommand::RefreshAndStart(secs) => {
+                debug_ui!("Window shown again, refreshing now then every {secs}s");
+                let _ = (self.refresh.borrow_mut())();
+                self.arm(secs);
+            }
+            TimerCommand::Pause => {
+                debug_ui!("Window hidden, auto-refresh paused");
+            }
+            TimerCommand::Keep => {}
+        }
+    }
+
+    /// Runs `refresh` every `secs` seconds
+    ///
+    /// Each tick asks the controller again; when the interval changed, a new
+    /// timeout is started and this one stops.
+    fn arm(self: &Rc<Self>, secs: u64) {
+        let timer = Rc::downgrade(self);
+        let source = timeout_add_local(Duration::from_secs(secs), move || {
+            let Some(timer) = timer.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let flow = (timer.refresh.borrow_mut())();
+            let command = match timer.wanted() {
+                Some((hidden, wanted)) if flow == glib::ControlFlow::Continue => {
+                    timer.controller.borrow_mut().update(hidden, wanted)
+                }
+                _ => TimerCommand::Pause,
+            };
+            if command == TimerCommand::Keep {
+                return glib::ControlFlow::Continue;
+            }
+
+            // This source ends by returning Break, its id must not be removed
+            timer.source.borrow_mut().take();
+            match command {
+                TimerCommand::Start(wanted) | TimerCommand::RefreshAndStart(wanted) => {
+                    debug_ui!("Auto-refresh interval changed: {secs}s -> {wanted}s");
+                    timer.arm(wanted);
+                }
+                TimerCommand::Pause if flow == glib::ControlFlow::Continue => {
+                    debug_ui!("Window hidden, auto-refresh paused");
+                }
+                TimerCommand::Pause | TimerCommand::Keep => {}
+            }
+            glib::ControlFlow::Break
+        });
+        *self.source.borrow_mut() = Some(source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::vendor_detection::{ThresholdFiles, VendorType};
+
+    #[test]
+    fn test_system_information() {
+        let vendor = VendorInfo {
+            manufacturer: "LENOVO".to_string(),
+            product_name: "20XY".to_string(),
+            supports_start_threshold: true,
+            supports_stop_threshold: true,
+            threshold_files: ThresholdFiles {
+                start_paths: Vec::new(),
+                stop_paths: vec![
+                    "/sys/class/power_supply/BAT0/charge_control_end_threshold".to_string()
+                ],
+            },
+            vendor_type: VendorType::Lenovo,
+        };
+
+        assert_eq!(
+            system_information(
+                &[("BAT0".to_string(), &vendor)],
+                Some(KernelVersion { major: 6, minor: 8 })
+            ),
+            "Vendor: LENOVO (Lenovo)\nProduct: 20XY\nKernel: 6.8\nBAT0 start: none\n\
+             BAT0 stop: /sys/class/power_supply/BAT0/charge_control_end_threshold"
+        );
+        assert_eq!(
+            system_information(&[], None),
+            "Kernel: unknown\nNo battery detected"
+        );
+    }
+
+    #[test]
+    fn test_format_window_title() {
+        let info = |status: &str, capacity: u8| BatteryInfo {
+            status: status.to_string(),
+            capacity_percent: capacity,
+            charge_now: 3_900_000,
+            current_now: 1_000_000,
+            cycle_count: 10,
+            ..BatteryInfo::sample()
+        };
+
+        let app = t("app_title");
+        for (status, capacity, icon) in [
+            ("Charging", 78, "⚡"),
+            ("Discharging", 78, "🔋"),
+            ("Full", 100, "✓"),
+            ("Not charging", 100, "✓"),
+        ] {
+            let title = format_window_title(&info(status, capacity));
+            assert!(
+                title.starts_with(&format!("{app} – {capacity}% {icon} ")),
+                "{title}"
+            );
+            assert!(!title.contains('{'), "{title}");
+        }
+
+        let title = format_window_title(&info("Not charging", 80));
+        assert!(title.starts_with(&format!("{app} – 80% ")), "{title}");
+        assert!(title.ends_with(&t("not_charging")), "{title}");
+    }
+
+    #[test]
+    fn test_shortcut_actions_are_registered() {
+        // Registered by register_shortcut_actions
+        let window_actions = [
+            ACTION_REFRESH,
+            ACTION_SWITCH_TAB,
+            ACTION_SHOW_PREFERENCES,
+            ACTION_APPLY_SETTINGS,
+        ];
+        for shortcut in &crate::ui::shortcuts::SHORTCUTS {
+            let (scope, detailed) = shortcut.action.split_once('.').unwrap();
+            let name = detailed.split('(').next().unwrap();
+            match scope {
+                "app" => assert!(
+                    HELP_MENU_ACTIONS.contains(&name),
+                    "{name} is not registered by ensure_help_menu"
+                ),
+                "win" => assert!(
+                    window_actions.contains(&name),
+                    "{name} is not a window action"
+                ),
+                _ => panic!("unknown action scope in {}", shortcut.action),
+            }
+        }
+    }
+
+    #[test]
+    fn test_refresh_action_rereads_battery_info() {
+        /// Counts the battery reads done by the refresh callback
+        struct CountingService {
+            reads: Rc<std::cell::Cell<u32>>,
+        }
+
+        impl BatteryService for CountingService {
+            fn get_info(
+                &self,
+                name: &str,
+            ) -> Result<BatteryInfo, crate::core::battery::BatteryError> {
+                self.reads.set(self.reads.get() + 1);
+                BatteryInfo::new(name)
+            }
+
+            fn list_batteries(&self) -> Vec<String> {
+                vec!["BAT0".to_string()]
+            }
+        }
+
+        let reads = Rc::new(std::cell::Cell::new(0));
+        let service = CountingService {
+            reads: reads.clone(),
+        };
+        let refresh: RefreshFn = Rc::new(RefCell::new(move || {
+            let _ = service.get_info("BAT0");
+            glib::ControlFlow::Continue
+        }));
+
+        let action = refresh_action(refresh);
+        assert_eq!(action.name(), ACTION_REFRESH);
+        assert_eq!(reads.get(), 0);
+        action.activate(None);
+        action.activate(None);
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn test_battery_dropdown_items() {
+        assert_eq!(battery_dropdown_items(&["BAT0".to_string()]), None);
+
+        let batteries = vec!["BAT0".to_string(), "BAT1".to_string()];
+        assert_eq!(
+            battery_dropdown_items(&batteries),
+            Some(vec!["🔋 BAT0".to_string(), "🔋 BAT1".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_background_slowdown() {
+        assert_eq!(effective_interval(30, false, false), 30);
+        assert_eq!(effective_interval(30, false, true), 30);
+        assert_eq!(effective_interval(30, true, true), 30);
+        assert_eq!(
+            effective_interval(30, true, false),
+            30 * BACKGROUND_SLOWDOWN
+        );
+        assert_eq!(effective_interval(5, true, false), 20);
+    }
+
+    #[test]
+    fn test_refresh_controller_hide_and_show() {
+        let mut controller = RefreshController::default();
+        assert_eq!(controller.update(false, 30), TimerCommand::Start(30));
+        assert_eq!(controller.update(false, 30), TimerCommand::Keep);
+
+        // Minimised: paused once, then nothing until shown again
+        assert_eq!(controller.update(true, 30), TimerCommand::Pause);
+        assert_eq!(controller.update(true, 30), TimerCommand::Keep);
+        assert_eq!(
+            controller.update(false, 30),
+            TimerCommand::RefreshAndStart(30)
+        );
+        assert_eq!(controller.update(false, 30), TimerCommand::Keep);
+    }
+
+    #[test]
+    fn test_refresh_controller_interval_changes() {
+        let mut controller = RefreshController::default();
+        assert_eq!(controller.update(false, 30), TimerCommand::Start(30));
+        assert_eq!(controller.update(false, 10), TimerCommand::Start(10));
+
+        // A change while hidden is picked up when the window comes back
+        assert_eq!(controller.update(true, 10), TimerCommand::Pause);
+        assert_eq!(controller.update(true, 60), TimerCommand::Keep);
+        assert_eq!(
+            controller.update(false, 60),
+            TimerCommand::RefreshAndStart(60)
+        );
+    }
 }
@@ -0,0 +1,276 @@
+//! Calibration wizard
+//!
+//! Starts and stops the calibration cycle of `core::calibration` and shows
+//! its progress. The refresh timer feeds every sample to
+//! [`on_refresh_sample`], which writes what each new phase needs.
+
+use gtk4::prelude::*;
+use gtk4::{gio, Box, Button, Label, Orientation};
+use std::cell::{Cell, RefCell};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::core::calibration::{self, Calibration, CalibrationPhase};
+use crate::core::i18n::{t, t_fmt};
+use crate::core::threshold_apply::{self, ApplyEnvironment, ApplyPlan};
+use crate::core::writer::{execute_with_pkexec, failure_text, ApplyResult};
+use crate::core::{BatteryInfo, VendorInfo};
+
+thread_local! {
+    /// Last failed write of the calibration, shown by the wizard
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// A calibration write is running in the background
+    static WRITE_IN_FLIGHT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with the threshold files of a battery
+fn with_environment<R>(battery_name: &str, f: impl FnOnce(&ApplyEnvironment<'_>) -> R) -> R {
+    let vendor_info = VendorInfo::detect_cached(battery_name);
+    f(&ApplyEnvironment {
+        power_supply_root: Path::new("/sys/class/power_supply"),
+        config_dir: Path::new(threshold_apply::CONFIG_DIR),
+        start_paths: &vendor_info.threshold_files.start_paths,
+        stop_paths: &vendor_info.threshold_files.stop_paths,
+        // Calibration plans never touch the restore service
+        service_unit_exists: false,
+        udev_rule_exists: false,
+    })
+}
+
+/// Runs a plan through pkexec in a worker thread
+///
+/// A plan without writes succeeds right away.
+fn apply_in_background(plan: ApplyPlan, on_done: impl FnOnce(ApplyResult) + 'static) {
+    if plan.writes.is_empty() {
+        on_done(ApplyResult::Success);
+        return;
+    }
+    glib::spawn_future_local(async move {
+        let result = gio::spawn_blocking(move || execute_with_pkexec(&plan))
+            .await
+            .unwrap_or_else(|_| ApplyResult::Failed(t("error_execution")));
+        on_done(result);
+    });
+}
+
+/// Records the outcome of a calibration write
+fn record_result(result: &ApplyResult) {
+    let error = failure_text(result);
+    if let Some(error) = &error {
+        crate::core::debug::terminal_error_args(std::format_args!(
+            "❌ [CALIBRATION] Write failed: {error}"
+        ));
+    }
+    LAST_ERROR.with(|last| *last.borrow_mut() = error);
+}
+
+/// Runs the writes of `phase`, then records their outcome
+///
+/// Only one write runs at a time; `on_done` is called once it is recorded.
+fn write_phase(
+    calibration: &Calibration,
+    phase: CalibrationPhase,
+    on_done: impl FnOnce() + 'static,
+) {
+    let plan = with_environment(&calibration.battery_name, |env| {
+        calibration.plan_for(phase, env)
+    });
+    let battery_name = calibration.battery_name.clone();
+    WRITE_IN_FLIGHT.with(|in_flight| in_flight.set(true));
+    apply_in_background(plan, move |result| {
+        WRITE_IN_FLIGHT.with(|in_flight| in_flight.set(false));
+        record_result(&result);
+        let succeeded = matches!(result, ApplyResult::Success);
+        if phase == CalibrationPhase::Done && succeeded {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "✅ [CALIBRATION] {battery_name} calibrated, thresholds restored"
+            ));
+        }
+        calibration::record_write(&battery_name, phase, succeeded);
+        on_done();
+    });
+}
+
+/// Advances the calibration in progress with a refresh sample
+///
+/// Entering a phase writes what it needs (`force-discharge`, stop at 100%,
+/// then the saved thresholds). A failed write is retried at the next sample
+/// and the calibration ends once the thresholds are restored.
+pub fn on_refresh_sample(info: &BatteryInfo) {
+    if WRITE_IN_FLIGHT.with(Cell::get) {
+        return;
+    }
+    let Some((calibration, phase)) = calibration::step_active(info) else {
+        return;
+    };
+    write_phase(&calibration, phase, || {});
+}
+
+/// Text describing the calibration of `battery_name`
+fn progress_text(battery_name: &str) -> String {
+    match calibration::active() {
+        Some(calibration) if calibration.battery_name == battery_name => {
+            t(calibration.phase.message_key())
+        }
+        Some(calibration) => t_fmt(
+            "calibration_other_battery",
+            &[("battery", &calibration.battery_name)],
+        ),
+        None => t("calibration_not_running"),
+    }
+}
+
+/// Starts calibrating a battery from its current state
+fn start_calibration(battery_name: &str, update: &Rc<dyn Fn()>) {
+    let info = match BatteryInfo::new(battery_name) {
+        Ok(info) => info,
+        Err(e) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(e.to_string()));
+            update();
+            return;
+        }
+    };
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🎯 [CALIBRATION] Started for {battery_name} at {}%",
+        info.capacity_percent
+    ));
+    LAST_ERROR.with(|last| last.borrow_mut().take());
+    calibration::set_active(Some(Calibration::new(&info)));
+    if let Some((calibration, phase)) = calibration::step_active(&info) {
+        let on_done = Rc::clone(update);
+        write_phase(&calibration, phase, move || on_done());
+    }
+    update();
+}
+
+/// Ends the calibration in progress, restoring the saved thresholds
+///
+/// A failed restore leaves the calibration in its last phase with the
+/// restore pending, so the refresh timer keeps retrying it.
+fn stop_calibration(update: &Rc<dyn Fn()>) {
+    if WRITE_IN_FLIGHT.with(Cell::get) {
+        return;
+    }
+    let Some(calibration) = calibration::active() else {
+        return;
+    };
+    crate::core::debug::debug_log_args(std::format_args!(
+        "⏹️ [CALIBRATION] Stopped for {}, restoring thresholds",
+        calibration.battery_name
+    ));
+    let on_done = Rc::clone(update);
+    write_phase(&calibration, CalibrationPhase::Done, move || on_done());
+    update();
+}
+
+/// Opens the calibration wizard for a battery
+///
+/// # Arguments
+///
+/// * `parent` - Window the wizard is attached to
+/// * `battery_name` - Battery to calibrate (e.g., "BAT0")
+pub fn show_calibration_window(parent: Option<&gtk4::Window>, battery_name: &str) {
+    let dialog = gtk4::Window::builder()
+        .title(t("calibration_title"))
+        .modal(true)
+        .default_width(460)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let content = Box::new(Orientation::Vertical, 10);
+    content.set_margin_top(14);
+    content.set_margin_bottom(14);
+    content.set_margin_start(14);
+    content.set_margin_end(14);
+
+    let intro = Label::new(Some(&t("calibration_intro")));
+    intro.set_wrap(true);
+    intro.set_xalign(0.0);
+    content.append(&intro);
+
+    let progress_label = Label::new(None);
+    progress_label.set_wrap(true);
+    progress_label.set_xalign(0.0);
+    progress_label.add_css_class("heading");
+    content.append(&progress_label);
+
+    let error_label = Label::new(None);
+    error_label.set_wrap(true);
+    error_label.set_xalign(0.0);
+    error_label.add_css_class("color-danger");
+    content.append(&error_label);
+
+    let buttons = Box::new(Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let start_button = Button::with_label(&t("calibration_start"));
+    start_button.add_css_class("suggested-action");
+    let stop_button = Button::with_label(&t("calibration_stop"));
+    buttons.append(&stop_button);
+    buttons.append(&start_button);
+    content.append(&buttons);
+
+    let battery = battery_name.to_string();
+    let was_running = Cell::new(false);
+    let update: Rc<dyn Fn()> = Rc::new(glib::clone!(
+        #[weak]
+        progress_label,
+        #[weak]
+        error_label,
+        #[weak]
+        start_button,
+        #[weak]
+        stop_button,
+        move || {
+            let running = calibration::active().is_some();
+            if was_running.replace(running) && !running {
+                // Ended since the last update: thresholds restored
+                progress_label.set_text(&t("calibration_done"));
+            } else {
+                progress_label.set_text(&progress_text(&battery));
+            }
+            let error = LAST_ERROR.with(|last| last.borrow().clone());
+            error_label.set_text(&error.map_or_else(String::new, |e| {
+                t_fmt("calibration_failed", &[("error", &e)])
+            }));
+            let in_flight = WRITE_IN_FLIGHT.with(Cell::get);
+            start_button.set_sensitive(!running && !in_flight);
+            stop_button.set_sensitive(running && !in_flight);
+        }
+    ));
+    update();
+
+    let start_battery = battery_name.to_string();
+    start_button.connect_clicked(glib::clone!(
+        #[strong]
+        update,
+        move |_| start_calibration(&start_battery, &update)
+    ));
+    stop_button.connect_clicked(glib::clone!(
+        #[strong]
+        update,
+        move |_| stop_calibration(&update)
+    ));
+
+    // Follow the progress made by the refresh timer while the wizard is open
+    glib::timeout_add_seconds_local(
+        2,
+        glib::clone!(
+            #[strong]
+            update,
+            #[weak]
+            dialog,
+            #[upgrade_or]
+            glib::ControlFlow::Break,
+            move || {
+                if !dialog.is_visible() {
+                    return glib::ControlFlow::Break;
+                }
+                update();
+                glib::ControlFlow::Continue
+            }
+        ),
+    );
+
+    dialog.set_child(Some(&content));
+    dialog.present();
+}
@@ -0,0 +1,125 @@
+//! Width-limited container
+//!
+//! GTK4 has no maximum width; `WidthClamp` gives its child the whole width
+//! up to a limit and centres it beyond, so forms don't stretch across a wide
+//! window.
+
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use gtk4::{Orientation, Widget};
+
+/// Maximum width of the settings and peripherals tab content, in pixels
+pub const TAB_MAX_WIDTH: i32 = 720;
+
+/// Position and width of the child within the available width
+///
+/// # Arguments
+///
+/// * `available` - Width allocated to the clamp
+/// * `max_width` - Widest the child may be
+/// * `child_min` - Minimum width of the child, always honoured
+///
+/// # Returns
+///
+/// `(x, width)` of the centred child
+fn child_span(available: i32, max_width: i32, child_min: i32) -> (i32, i32) {
+    let width = available.min(max_width).max(child_min);
+    (((available - width) / 2).max(0), width)
+}
+
+mod imp {
+    use super::{child_span, Orientation, Widget};
+    use gtk4::prelude::*;
+    use gtk4::subclass::prelude::*;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct WidthClamp {
+        pub max_width: Cell<i32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for WidthClamp {
+        const NAME: &'static str = "BatteryManagerWidthClamp";
+        type Type = super::WidthClamp;
+        type ParentType = Widget;
+    }
+
+    impl ObjectImpl for WidthClamp {
+        fn dispose(&self) {
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for WidthClamp {
+        fn request_mode(&self) -> gtk4::SizeRequestMode {
+            gtk4::SizeRequestMode::HeightForWidth
+        }
+
+        fn measure(&self, orientation: Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+            let Some(child) = self.obj().first_child() else {
+                return (0, 0, -1, -1);
+            };
+            let max_width = self.max_width.get();
+
+            if orientation == Orientation::Horizontal {
+                let (minimum, natural, _, _) = child.measure(orientation, for_size);
+                (minimum, natural.min(max_width).max(minimum), -1, -1)
+            } else {
+                // Height for the width the child will actually get
+                let width = if for_size < 0 {
+                    -1
+                } else {
+                    let (child_min, _, _, _) = child.measure(Orientation::Horizontal, -1);
+                    child_span(for_size, max_width, child_min).1
+                };
+                let (minimum, natural, _, _) = child.measure(orientation, width);
+                (minimum, natural, -1, -1)
+            }
+        }
+
+        fn size_allocate(&self, width: i32, height: i32, _baseline: i32) {
+            let Some(child) = self.obj().first_child() else {
+                return;
+            };
+            let (child_min, _, _, _) = child.measure(Orientation::Horizontal, -1);
+            let (x, child_width) = child_span(width, self.max_width.get(), child_min);
+            child.size_allocate(&gtk4::Allocation::new(x, 0, child_width, height), -1);
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Container limiting the width of its single child
+    pub struct WidthClamp(ObjectSubclass<imp::WidthClamp>)
+        @extends Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl WidthClamp {
+    /// Wraps `child`, which is never wider than `max_width`
+    pub fn new(child: &impl IsA<Widget>, max_width: i32) -> Self {
+        let clamp: Self = glib::Object::new();
+        clamp.imp().max_width.set(max_width);
+        clamp.set_hexpand(true);
+        child.set_parent(&clamp);
+        clamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_span() {
+        // Narrow window: the child takes the whole width
+        assert_eq!(child_span(500, 720, 300), (0, 500));
+        // Wide window: limited and centred
+        assert_eq!(child_span(1000, 720, 300), (140, 720));
+        // Narrower than the child can be: its minimum wins
+        assert_eq!(child_span(200, 720, 300), (0, 300));
+    }
+}
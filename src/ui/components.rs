@@ -1,10 +1,16 @@
 //! Reusable UI components and widget helpers
 //!
-//! Provides info cards, labels, grids, and updatable widget structure
+//! Provides info cards, the responsive card flow, labels, and updatable
+//! widget structure
 //! for consistent UI styling across tabs.
 
 use gtk4::prelude::*;
-use gtk4::{Box, Frame, Grid, Label, Orientation};
+use gtk4::{Box, DrawingArea, FlowBox, Frame, Label, Orientation};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::history::HistoryBuffer;
+use crate::ui::graphs::{CapacitySparklineWidget, ChargeBarWidget, SparklineWidget};
 
 /// Reusable UI component builder
 pub struct InfoCard;
@@ -39,8 +45,43 @@ impl InfoCard {
         main_box.append(&content_box);
 
         frame.set_child(Some(&main_box));
+        frame.set_size_request(CARD_MIN_WIDTH, -1);
         (frame, content_box)
     }
+
+    /// Adds a box pinned to the bottom of a card, for secondary details
+    ///
+    /// # Arguments
+    ///
+    /// * `content_box` - Content box returned by `create`
+    ///
+    /// # Returns
+    ///
+    /// The footer Box, aligned to the bottom when cards of a line differ in
+    /// height
+    pub fn footer(content_box: &Box) -> Box {
+        let footer = Box::new(Orientation::Vertical, 2);
+        footer.set_vexpand(true);
+        footer.set_valign(gtk4::Align::End);
+        content_box.append(&footer);
+        footer
+    }
+
+    /// Returns the title label of a card built by `create`
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Card frame
+    ///
+    /// # Returns
+    ///
+    /// The title Label, to change the title after creation
+    pub fn title_label(frame: &Frame) -> Option<Label> {
+        frame
+            .child()
+            .and_then(|main_box| main_box.first_child())
+            .and_downcast::<Label>()
+    }
 }
 
 /// Creates left-aligned information label
@@ -60,12 +101,18 @@ pub fn create_info_label(text: &str) -> Label {
 
 /// Container for widget references requiring periodic updates
 ///
-/// Stores Label references for battery metrics updated by timer
+/// Stores Label references for battery metrics updated by timer, and the
+/// charge history graph with its sample buffer
+#[derive(Clone)]
 pub struct UpdatableWidgets {
     pub power_source_value: Label,
     pub status_value: Label,
     pub capacity_label: Label,
+    pub charge_bar: ChargeBarWidget,
+    pub capacity_sparkline: CapacitySparklineWidget,
     pub health_label: Label,
+    pub health_grade_label: Label,
+    pub health_grade_description: Label,
     pub voltage_value: Label,
     pub current_value: Label,
     pub power_value: Label,
@@ -74,30 +121,36 @@ pub struct UpdatableWidgets {
     pub threshold_stop_label: Label,
     pub alarm_label: Option<Label>,
     pub service_label: Label,
+    pub ups_capacity_label: Option<Label>,
+    pub ups_source_label: Option<Label>,
+    pub wear_trend_labels: Vec<Label>,
+    pub capacity_trend_label: Label,
+    pub history_area: DrawingArea,
+    pub history: Rc<RefCell<HistoryBuffer>>,
+    pub health_sparkline: SparklineWidget,
+    pub updated_label: Label,
 }
 
-/// Creates a grid with homogeneous columns
-///
-/// # Returns
-///
-/// Configured Grid widget
-pub fn create_row_grid() -> Grid {
-    let grid = Grid::new();
-    grid.set_column_spacing(8);
-    grid.set_column_homogeneous(true);
-    grid.set_row_homogeneous(true);
-    grid
-}
+/// Narrowest width of an info card, below which cards reflow to fewer columns
+const CARD_MIN_WIDTH: i32 = 230;
 
-/// Creates vertical expanding spacer
+/// Creates the flow of info cards
+///
+/// Up to three cards per line, reflowing to two or one when the window is
+/// narrow. Cards share the size of the largest one.
 ///
 /// # Returns
 ///
-/// Box configured to expand vertically
-pub fn create_vertical_spacer() -> Box {
-    let spacer = Box::new(Orientation::Vertical, 0);
-    spacer.set_vexpand(true);
-    spacer
+/// Configured `FlowBox` widget
+pub fn create_card_flow() -> FlowBox {
+    let flow = FlowBox::new();
+    flow.set_selection_mode(gtk4::SelectionMode::None);
+    flow.set_homogeneous(true);
+    flow.set_min_children_per_line(1);
+    flow.set_max_children_per_line(3);
+    flow.set_column_spacing(8);
+    flow.set_row_spacing(8);
+    flow
 }
 
 /// Creates vertical box with standard margins
@@ -0,0 +1,312 @@
+//! Session D-Bus interface
+//!
+//! The GTK application already owns the `com.battery.manager` name on the
+//! session bus; this module exports the selected battery on it so status
+//! bars and scripts can query the app instead of parsing sysfs:
+//!
+//! ```text
+//! gdbus call --session --dest com.battery.manager \
+//!     --object-path /com/battery/manager/Battery \
+//!     --method org.freedesktop.DBus.Properties.GetAll com.battery.manager.Battery
+//! ```
+//!
+//! Properties are refreshed by the auto-update timer, which emits
+//! `org.freedesktop.DBus.Properties.PropertiesChanged` for the changed ones.
+
+use gtk4::prelude::*;
+use gtk4::{gio, Application};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use glib::Variant;
+
+use crate::core::BatteryInfo;
+
+/// Export the D-Bus interface (disabled by `--no-dbus`)
+static DBUS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Object path of the exported battery
+pub const OBJECT_PATH: &str = "/com/battery/manager/Battery";
+
+/// Name of the exported interface
+pub const INTERFACE: &str = "com.battery.manager.Battery";
+
+/// Introspection data of the exported object
+///
+/// Thresholds are `-1` when the firmware does not support them.
+pub const INTROSPECTION_XML: &str = r#"
+<node>
+  <interface name="com.battery.manager.Battery">
+    <property name="Name" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="CapacityPercent" type="y" access="read"/>
+    <property name="HealthPercent" type="d" access="read"/>
+    <property name="PowerWatts" type="d" access="read"/>
+    <property name="ChargeStartThreshold" type="i" access="read"/>
+    <property name="ChargeStopThreshold" type="i" access="read"/>
+    <method name="Refresh"/>
+  </interface>
+</node>
+"#;
+
+/// Disable the D-Bus interface (`--no-dbus`)
+pub fn disable_dbus() {
+    DBUS_ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Check if the D-Bus interface should be exported
+pub fn dbus_enabled() -> bool {
+    DBUS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Values of the exported properties
+#[derive(Debug, Clone, PartialEq)]
+struct BatteryProperties {
+    name: String,
+    status: String,
+    capacity_percent: u8,
+    health_percent: f64,
+    power_watts: f64,
+    charge_start_threshold: i32,
+    charge_stop_threshold: i32,
+}
+
+impl BatteryProperties {
+    fn from_info(info: &BatteryInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            status: info.status.clone(),
+            capacity_percent: info.capacity_percent,
+            health_percent: (f64::from(info.health_percent) * 10.0).round() / 10.0,
+            power_watts: (info.power_watts() * 100.0).round() / 100.0,
+            charge_start_threshold: info.charge_start_threshold.map_or(-1, i32::from),
+            charge_stop_threshold: info.charge_stop_threshold.map_or(-1, i32::from),
+        }
+    }
+
+    /// Returns the names of the properties that differ from `previous`
+    fn changed_since(&self, previous: &Self) -> Vec<&'static str> {
+        [
+            ("Name", self.name != previous.name),
+            ("Status", self.status != previous.status),
+            (
+                "CapacityPercent",
+                self.capacity_percent != previous.capacity_percent,
+            ),
+            (
+                "HealthPercent",
+                self.health_percent.to_bits() != previous.health_percent.to_bits(),
+            ),
+            (
+                "PowerWatts",
+                self.power_watts.to_bits() != previous.power_watts.to_bits(),
+            ),
+            (
+                "ChargeStartThreshold",
+                self.charge_start_threshold != previous.charge_start_threshold,
+            ),
+            (
+                "ChargeStopThreshold",
+                self.charge_stop_threshold != previous.charge_stop_threshold,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+
+    /// Returns a property as a variant (`None` for unknown names)
+    fn get(&self, property: &str) -> Option<Variant> {
+        match property {
+            "Name" => Some(self.name.to_variant()),
+            "Status" => Some(self.status.to_variant()),
+            "CapacityPercent" => Some(self.capacity_percent.to_variant()),
+            "HealthPercent" => Some(self.health_percent.to_variant()),
+            "PowerWatts" => Some(self.power_watts.to_variant()),
+            "ChargeStartThreshold" => Some(self.charge_start_threshold.to_variant()),
+            "ChargeStopThreshold" => Some(self.charge_stop_threshold.to_variant()),
+            _ => None,
+        }
+    }
+}
+
+/// Shared state read by the D-Bus handlers
+struct ServiceState {
+    properties: Option<BatteryProperties>,
+    refresh: Option<Rc<dyn Fn()>>,
+}
+
+/// Battery object exported on the session bus
+pub struct BatteryDbusService {
+    connection: gio::DBusConnection,
+    state: Rc<RefCell<ServiceState>>,
+}
+
+impl BatteryDbusService {
+    /// Exports the battery object on the application's bus connection
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - GTK Application (owns the `com.battery.manager` name)
+    ///
+    /// # Returns
+    ///
+    /// * `Some(BatteryDbusService)` - Object exported
+    /// * `None` - No session bus, or the export failed
+    pub fn new(app: &Application) -> Option<Self> {
+        let connection = app.dbus_connection()?;
+        let node = gio::DBusNodeInfo::for_xml(INTROSPECTION_XML).ok()?;
+        let interface = node.lookup_interface(INTERFACE)?;
+        let state = Rc::new(RefCell::new(ServiceState {
+            properties: None,
+            refresh: None,
+        }));
+
+        let method_state = state.clone();
+        let property_state = state.clone();
+        let registered = connection
+            .register_object(OBJECT_PATH, &interface)
+            .method_call(move |_, _, _, _, method, _, invocation| {
+                if method != "Refresh" {
+                    invocation.return_dbus_error(
+                        "org.freedesktop.DBus.Error.UnknownMethod",
+                        &format!("Unknown method {method}"),
+                    );
+                    return;
+                }
+
+                // Clone the handler out first: the refresh updates this state
+                let refresh = method_state.borrow().refresh.clone();
+                crate::core::debug::debug_log("🔄 [DBUS] Refresh requested over D-Bus");
+                if let Some(refresh) = refresh {
+                    refresh();
+                }
+                invocation.return_value(None);
+            })
+            .property(move |_, _, _, _, property| {
+                property_state
+                    .borrow()
+                    .properties
+                    .as_ref()
+                    .and_then(|properties| properties.get(property))
+                    .unwrap_or_else(|| "".to_variant())
+            })
+            .build();
+        if let Err(e) = registered {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [DBUS] Failed to export {OBJECT_PATH}: {e}"
+            ));
+            return None;
+        }
+
+        crate::core::debug::debug_log_args(std::format_args!(
+            "📡 [DBUS] Exported {INTERFACE} at {OBJECT_PATH}"
+        ));
+        Some(Self { connection, state })
+    }
+
+    /// Sets the callback run by the `Refresh()` method
+    pub fn set_refresh_handler(&self, refresh: Rc<dyn Fn()>) {
+        self.state.borrow_mut().refresh = Some(refresh);
+    }
+
+    /// Publishes fresh battery information
+    ///
+    /// Emits `PropertiesChanged` with the properties that changed since the
+    /// previous update.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Battery information
+    pub fn update(&self, info: &BatteryInfo) {
+        let properties = BatteryProperties::from_info(info);
+        let changed: HashMap<&str, Variant> = {
+            let mut state = self.state.borrow_mut();
+            let changed = match state.properties {
+                Some(ref previous) => properties.changed_since(previous),
+                None => Vec::new(),
+            };
+            let values = changed
+                .into_iter()
+                .filter_map(|name| properties.get(name).map(|value| (name, value)))
+                .collect();
+            state.properties = Some(properties);
+            values
+        };
+
+        if changed.is_empty() {
+            return;
+        }
+        let parameters = (INTERFACE, changed, Vec::<String>::new()).to_variant();
+        if let Err(e) = self.connection.emit_signal(
+            None,
+            OBJECT_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            Some(&parameters),
+        ) {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [DBUS] Failed to emit PropertiesChanged: {e}"
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(capacity: u8, power: f64) -> BatteryProperties {
+        BatteryProperties {
+            name: "BAT0".to_string(),
+            status: "Discharging".to_string(),
+            capacity_percent: capacity,
+            health_percent: 91.5,
+            power_watts: power,
+            charge_start_threshold: -1,
+            charge_stop_threshold: 80,
+        }
+    }
+
+    #[test]
+    fn test_changed_properties() {
+        let previous = properties(80, 7.25);
+        assert!(properties(80, 7.25).changed_since(&previous).is_empty());
+        assert_eq!(
+            properties(79, 7.5).changed_since(&previous),
+            vec!["CapacityPercent", "PowerWatts"]
+        );
+
+        let mut charging = properties(80, 7.25);
+        charging.status = "Charging".to_string();
+        charging.charge_stop_threshold = 90;
+        assert_eq!(
+            charging.changed_since(&previous),
+            vec!["Status", "ChargeStopThreshold"]
+        );
+    }
+
+    #[test]
+    fn test_introspection_lists_every_property() {
+        let all = properties(0, 0.0);
+        let mut other = properties(1, 1.0);
+        other.name = "BAT1".to_string();
+        other.status = "Full".to_string();
+        other.health_percent = 0.0;
+        other.charge_start_threshold = 40;
+        other.charge_stop_threshold = 60;
+
+        for name in other.changed_since(&all) {
+            assert!(
+                INTROSPECTION_XML.contains(&format!("<property name=\"{name}\"")),
+                "{name} missing from the introspection XML"
+            );
+        }
+        assert_eq!(
+            INTROSPECTION_XML.matches("<property ").count(),
+            other.changed_since(&all).len()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Diagnostic report window
+//!
+//! Shows the report built by `core::diagnostics` and lets the user copy it
+//! to the clipboard or save it to a file, serial numbers masked by default.
+
+use gtk4::prelude::*;
+use gtk4::{
+    ApplicationWindow, Box, Button, CheckButton, FileChooserAction, FileChooserNative, Label,
+    Orientation, ResponseType, ScrolledWindow, TextView,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::diagnostics::DiagnosticReport;
+use crate::core::i18n::{t, t_fmt};
+use crate::core::upower::{detect_peripherals, select_battery_service};
+
+/// Opens the diagnostic report window
+///
+/// # Arguments
+///
+/// * `window` - Main window (parent of the report window)
+pub fn show_diagnostics_window(window: &ApplicationWindow) {
+    let service = select_battery_service();
+    let report = Rc::new(DiagnosticReport::collect(
+        service.as_ref(),
+        detect_peripherals(),
+    ));
+    crate::core::debug::debug_log("🩺 [DIAGNOSTICS] Report collected");
+
+    let dialog = gtk4::Window::builder()
+        .title(t("diagnostic_report"))
+        .transient_for(window)
+        .modal(true)
+        .default_width(640)
+        .default_height(480)
+        .build();
+
+    let content = Box::new(Orientation::Vertical, 8);
+    content.set_margin_top(10);
+    content.set_margin_bottom(10);
+    content.set_margin_start(10);
+    content.set_margin_end(10);
+
+    let text_view = TextView::new();
+    text_view.set_editable(false);
+    text_view.set_monospace(true);
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&text_view));
+    content.append(&scrolled);
+
+    let redact_check = CheckButton::with_label(&t("diagnostic_redact"));
+    redact_check.set_active(true);
+    text_view.buffer().set_text(&report.to_text(true));
+    redact_check.connect_toggled(glib::clone!(
+        #[weak]
+        text_view,
+        #[strong]
+        report,
+        move |check| text_view
+            .buffer()
+            .set_text(&report.to_text(check.is_active()))
+    ));
+    content.append(&redact_check);
+
+    let actions = Box::new(Orientation::Horizontal, 8);
+    let status_label = Label::new(None);
+    status_label.set_hexpand(true);
+    status_label.set_halign(gtk4::Align::Start);
+    status_label.add_css_class("dim-label");
+    actions.append(&status_label);
+
+    let copy_button = Button::with_label(&t("diagnostic_copy"));
+    copy_button.connect_clicked(glib::clone!(
+        #[weak]
+        text_view,
+        #[weak]
+        status_label,
+        move |button| {
+            let buffer = text_view.buffer();
+            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            button.clipboard().set_text(&text);
+            status_label.set_text(&t("diagnostic_copied"));
+        }
+    ));
+    actions.append(&copy_button);
+
+    let save_button = Button::with_label(&t("diagnostic_save"));
+    save_button.connect_clicked(glib::clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        text_view,
+        #[weak]
+        status_label,
+        move |_| save_report(&dialog, &text_view, &status_label)
+    ));
+    actions.append(&save_button);
+    content.append(&actions);
+
+    dialog.set_child(Some(&content));
+    dialog.present();
+}
+
+/// Asks for a file and writes the report shown in `text_view` to it
+fn save_report(parent: &gtk4::Window, text_view: &TextView, status_label: &Label) {
+    let chooser = FileChooserNative::new(
+        Some(&t("diagnostic_save")),
+        Some(parent),
+        FileChooserAction::Save,
+        None,
+        Some(&t("cancel")),
+    );
+    chooser.set_current_name("battery-manager-report.txt");
+
+    // Native dialogs are not kept alive by GTK: hold it until it answers
+    let keep_alive = Rc::new(RefCell::new(Some(chooser.clone())));
+    chooser.connect_response(glib::clone!(
+        #[weak]
+        text_view,
+        #[weak]
+        status_label,
+        move |chooser, response| {
+            let path = chooser.file().and_then(|file| file.path());
+            chooser.destroy();
+            keep_alive.borrow_mut().take();
+            let (ResponseType::Accept, Some(path)) = (response, path) else {
+                return;
+            };
+
+            let buffer = text_view.buffer();
+            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            let shown = path.display().to_string();
+            match std::fs::write(&path, text.as_str()) {
+                Ok(()) => {
+                    crate::core::debug::debug_log_args(std::format_args!(
+                        "💾 [DIAGNOSTICS] Report saved to {shown}"
+                    ));
+                    status_label.set_text(&t_fmt("diagnostic_saved", &[("path", &shown)]));
+                }
+                Err(e) => {
+                    crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [DIAGNOSTICS] Cannot write {shown}: {e}"
+                    ));
+                    status_label.set_text(&t_fmt(
+                        "diagnostic_save_failed",
+                        &[("error", &e.to_string())],
+                    ));
+                }
+            }
+        }
+    ));
+    chooser.show();
+}
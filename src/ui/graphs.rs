@@ -0,0 +1,638 @@
+//! Small inline charts
+//!
+//! Provides `SparklineWidget`, a compact line chart drawn with Cairo on a
+//! `DrawingArea`, used for the health trend in the Information tab,
+//! `CapacitySparklineWidget` for the recent charge level, and
+//! `ChargeBarWidget`, the charge level bar with threshold markers.
+
+use gtk4::prelude::*;
+use gtk4::{cairo, DrawingArea, Overlay, ProgressBar};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::core::traits::HistorySample;
+
+/// Maximum number of points drawn by a sparkline
+pub const SPARKLINE_POINTS: usize = 60;
+
+/// Height of the sparkline in pixels
+const SPARKLINE_HEIGHT: i32 = 28;
+
+/// Vertical padding so the line and dot are not clipped
+const PADDING: f64 = 3.0;
+
+/// Smallest value range drawn, so a flat series is not stretched into noise
+const MIN_RANGE: f32 = 1.0;
+
+/// Health loss (percentage points) over the series drawn fully red
+const RED_DROP: f32 = 2.0;
+
+const GREEN: (f64, f64, f64) = (0.298, 0.686, 0.314);
+/// Capacity sparkline colors (light theme success and warning colors)
+const CHARGING_COLOR: (f64, f64, f64) = GREEN;
+const DISCHARGING_COLOR: (f64, f64, f64) = (1.0, 0.596, 0.0);
+const YELLOW: (f64, f64, f64) = (1.0, 0.757, 0.027);
+const RED: (f64, f64, f64) = (0.957, 0.263, 0.212);
+
+/// Height of the charge bar in pixels
+const CHARGE_BAR_HEIGHT: i32 = 14;
+
+/// Width of the threshold markers in pixels
+const MARKER_WIDTH: f64 = 2.0;
+
+/// What to draw for a series
+#[derive(Debug, PartialEq)]
+enum SparklineShape {
+    /// Empty or single-value series
+    Dot(f64, f64),
+    /// Points of the line, left to right
+    Line(Vec<(f64, f64)>),
+}
+
+/// Compact line chart of a value series
+#[derive(Clone)]
+pub struct SparklineWidget {
+    area: DrawingArea,
+    values: Rc<RefCell<Vec<f32>>>,
+}
+
+impl SparklineWidget {
+    /// Creates a sparkline showing `values` (oldest first)
+    pub fn new(values: Vec<f32>) -> Self {
+        let values = Rc::new(RefCell::new(values));
+        let area = DrawingArea::new();
+        area.set_content_height(SPARKLINE_HEIGHT);
+        area.set_hexpand(true);
+        area.set_draw_func(glib::clone!(
+            #[strong]
+            values,
+            move |_area, cr, width, height| {
+                draw_sparkline(cr, f64::from(width), f64::from(height), &values.borrow());
+            }
+        ));
+
+        Self { area, values }
+    }
+
+    /// Returns the drawing area to add to a container
+    pub fn widget(&self) -> &DrawingArea {
+        &self.area
+    }
+
+    /// Replaces the series and redraws
+    pub fn set_values(&self, values: Vec<f32>) {
+        *self.values.borrow_mut() = values;
+        self.area.queue_draw();
+    }
+}
+
+/// Extracts the health series from history samples
+///
+/// # Arguments
+///
+/// * `samples` - History samples, oldest first
+/// * `max_points` - Maximum number of values (evenly picked when there are more)
+///
+/// # Returns
+///
+/// Health percentages, oldest first
+pub fn health_series<'a>(
+    samples: impl Iterator<Item = &'a HistorySample>,
+    max_points: usize,
+) -> Vec<f32> {
+    let health: Vec<f32> = samples.map(|s| s.health_percent).collect();
+    if health.len() <= max_points || max_points < 2 {
+        return health;
+    }
+
+    let last = health.len() - 1;
+    (0..max_points)
+        .map(|i| health[i * last / (max_points - 1)])
+        .collect()
+}
+
+/// Color of the series: green when stable, through yellow, to red on a steep drop
+#[allow(clippy::cast_precision_loss)]
+fn trend_color(values: &[f32]) -> (f64, f64, f64) {
+    let (Some(first), Some(last)) = (values.first(), values.last()) else {
+        return GREEN;
+    };
+    let drop = f64::from(((first - last) / RED_DROP).clamp(0.0, 1.0));
+
+    let mix = |from: (f64, f64, f64), to: (f64, f64, f64), t: f64| {
+        (
+            from.0 + (to.0 - from.0) * t,
+            from.1 + (to.1 - from.1) * t,
+            from.2 + (to.2 - from.2) * t,
+        )
+    };
+    if drop < 0.5 {
+        mix(GREEN, YELLOW, drop * 2.0)
+    } else {
+        mix(YELLOW, RED, (drop - 0.5) * 2.0)
+    }
+}
+
+/// Vertical range of a chart, fitted to its values
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct YScale {
+    /// Value drawn at the bottom
+    floor: f64,
+    /// Value span from bottom to top
+    range: f64,
+}
+
+impl YScale {
+    /// Fits the scale to the values, at least `MIN_RANGE` tall and centered
+    /// on a flat or narrow series
+    fn fit(values: impl Iterator<Item = f32> + Clone) -> Self {
+        let min = values.clone().fold(f32::INFINITY, f32::min);
+        let max = values.fold(f32::NEG_INFINITY, f32::max);
+        if !min.is_finite() || !max.is_finite() {
+            return Self {
+                floor: 0.0,
+                range: f64::from(MIN_RANGE),
+            };
+        }
+        let range = (max - min).max(MIN_RANGE);
+        Self {
+            floor: f64::from(min - (range - (max - min)) / 2.0),
+            range: f64::from(range),
+        }
+    }
+}
+
+/// Maps the `index`-th of `count` values to drawing area coordinates
+///
+/// Points are spread evenly from the left to the right edge; the value is
+/// placed within the scale, keeping `PADDING` pixels free at the top and
+/// bottom.
+#[allow(clippy::cast_precision_loss)]
+fn point_to_pixel(
+    index: usize,
+    count: usize,
+    value: f32,
+    scale: YScale,
+    width: f64,
+    height: f64,
+) -> (f64, f64) {
+    let step = width / count.saturating_sub(1).max(1) as f64;
+    let usable = (height - 2.0 * PADDING).max(0.0);
+    let fraction = (f64::from(value) - scale.floor) / scale.range;
+    (index as f64 * step, height - PADDING - fraction * usable)
+}
+
+/// Lays out a series in a `width` × `height` area
+fn sparkline_shape(values: &[f32], width: f64, height: f64) -> SparklineShape {
+    if values.len() < 2 {
+        return SparklineShape::Dot(width / 2.0, height / 2.0);
+    }
+
+    let scale = YScale::fit(values.iter().copied());
+    SparklineShape::Line(
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| point_to_pixel(i, values.len(), *value, scale, width, height))
+            .collect(),
+    )
+}
+
+/// Draws a series as a line with a translucent fill below it
+///
+/// An empty series is drawn as a single dot.
+///
+/// # Arguments
+///
+/// * `cr` - Cairo context of the drawing area
+/// * `width` - Drawing area width in pixels
+/// * `height` - Drawing area height in pixels
+/// * `values` - Series to draw, oldest first
+fn draw_sparkline(cr: &cairo::Context, width: f64, height: f64, values: &[f32]) {
+    let (red, green, blue) = trend_color(values);
+
+    match sparkline_shape(values, width, height) {
+        SparklineShape::Dot(x, y) => {
+            cr.set_source_rgb(red, green, blue);
+            cr.arc(x, y, 2.5, 0.0, std::f64::consts::TAU);
+            let _ = cr.fill();
+        }
+        SparklineShape::Line(points) => {
+            for (i, (x, y)) in points.iter().enumerate() {
+                if i == 0 {
+                    cr.move_to(*x, *y);
+                } else {
+                    cr.line_to(*x, *y);
+                }
+            }
+            cr.set_source_rgb(red, green, blue);
+            cr.set_line_width(1.5);
+            let _ = cr.stroke_preserve();
+
+            // Fill under the line
+            cr.line_to(width, height);
+            cr.line_to(0.0, height);
+            cr.close_path();
+            cr.set_source_rgba(red, green, blue, 0.25);
+            let _ = cr.fill();
+        }
+    }
+}
+
+/// One point of the capacity sparkline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapacityPoint {
+    pub capacity_percent: f32,
+    /// Charging (or full) when recorded, drawn green instead of orange
+    pub charging: bool,
+}
+
+/// Compact chart of the recent charge level, colored by charge direction
+///
+/// Hidden until the history holds at least two samples.
+#[derive(Clone)]
+pub struct CapacitySparklineWidget {
+    area: DrawingArea,
+    points: Rc<RefCell<Vec<CapacityPoint>>>,
+}
+
+impl CapacitySparklineWidget {
+    /// Creates a sparkline showing `points` (oldest first)
+    pub fn new(points: Vec<CapacityPoint>) -> Self {
+        let area = DrawingArea::new();
+        area.set_content_height(SPARKLINE_HEIGHT);
+        area.set_hexpand(true);
+        area.set_visible(points.len() >= 2);
+        let points = Rc::new(RefCell::new(points));
+        area.set_draw_func(glib::clone!(
+            #[strong]
+            points,
+            move |_area, cr, width, height| {
+                draw_capacity_sparkline(cr, f64::from(width), f64::from(height), &points.borrow());
+            }
+        ));
+
+        Self { area, points }
+    }
+
+    /// Returns the drawing area to add to a container
+    pub fn widget(&self) -> &DrawingArea {
+        &self.area
+    }
+
+    /// Replaces the points and redraws
+    pub fn set_points(&self, points: Vec<CapacityPoint>) {
+        self.area.set_visible(points.len() >= 2);
+        *self.points.borrow_mut() = points;
+        self.area.queue_draw();
+    }
+}
+
+/// Extracts the capacity series from history samples
+///
+/// # Arguments
+///
+/// * `samples` - History samples, oldest first
+/// * `max_points` - Maximum number of points (the most recent ones are kept)
+///
+/// # Returns
+///
+/// Capacity points, oldest first
+pub fn capacity_series<'a>(
+    samples: impl Iterator<Item = &'a HistorySample>,
+    max_points: usize,
+) -> Vec<CapacityPoint> {
+    let points: Vec<CapacityPoint> = samples
+        .map(|s| CapacityPoint {
+            capacity_percent: f32::from(s.capacity_percent),
+            charging: s.status == "Charging" || s.status == "Full",
+        })
+        .collect();
+    let skip = points.len().saturating_sub(max_points);
+    points[skip..].to_vec()
+}
+
+/// Draws the capacity series, each segment in the color of its end point
+fn draw_capacity_sparkline(cr: &cairo::Context, width: f64, height: f64, points: &[CapacityPoint]) {
+    if points.len() < 2 {
+        return;
+    }
+
+    let scale = YScale::fit(points.iter().map(|p| p.capacity_percent));
+    let pixel = |index: usize| {
+        point_to_pixel(
+            index,
+            points.len(),
+            points[index].capacity_percent,
+            scale,
+            width,
+            height,
+        )
+    };
+
+    cr.set_line_width(1.5);
+    for (index, point) in points.iter().enumerate().skip(1) {
+        let color = if point.charging {
+            CHARGING_COLOR
+        } else {
+            DISCHARGING_COLOR
+        };
+        let (from_x, from_y) = pixel(index - 1);
+        let (to_x, to_y) = pixel(index);
+        cr.set_source_rgb(color.0, color.1, color.2);
+        cr.move_to(from_x, from_y);
+        cr.line_to(to_x, to_y);
+        let _ = cr.stroke();
+    }
+}
+
+/// Position of the charge level relative to the thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChargeLevel {
+    /// Under the start threshold
+    Below,
+    /// Between the start and stop thresholds
+    Within,
+    /// Over the stop threshold
+    Above,
+}
+
+impl ChargeLevel {
+    /// CSS classes of the bar, one per level (colors set by the theme)
+    const CSS_CLASSES: [&'static str; 3] = ["charge-below", "charge-within", "charge-above"];
+
+    /// Classifies a charge level; missing thresholds default to 0 and 100%
+    fn of(capacity: u8, start: Option<u8>, stop: Option<u8>) -> Self {
+        if stop.is_some_and(|stop| capacity > stop) {
+            Self::Above
+        } else if start.is_some_and(|start| capacity < start) {
+            Self::Below
+        } else {
+            Self::Within
+        }
+    }
+
+    const fn css_class(self) -> &'static str {
+        match self {
+            Self::Below => Self::CSS_CLASSES[0],
+            Self::Within => Self::CSS_CLASSES[1],
+            Self::Above => Self::CSS_CLASSES[2],
+        }
+    }
+}
+
+/// Charge level bar with tick marks at the start and stop thresholds
+#[derive(Clone)]
+pub struct ChargeBarWidget {
+    overlay: Overlay,
+    bar: ProgressBar,
+    markers: DrawingArea,
+    thresholds: Rc<Cell<(Option<u8>, Option<u8>)>>,
+}
+
+impl ChargeBarWidget {
+    /// Creates an empty bar, filled by `update`
+    pub fn new() -> Self {
+        let bar = ProgressBar::new();
+        bar.set_valign(gtk4::Align::Center);
+        bar.set_size_request(-1, CHARGE_BAR_HEIGHT);
+
+        let thresholds = Rc::new(Cell::new((None, None)));
+        let markers = DrawingArea::new();
+        // Let clicks and tooltips reach the bar
+        markers.set_can_target(false);
+        markers.set_draw_func(glib::clone!(
+            #[strong]
+            thresholds,
+            move |_area, cr, width, height| {
+                let (start, stop) = thresholds.get();
+                draw_threshold_markers(cr, f64::from(width), f64::from(height), [start, stop]);
+            }
+        ));
+
+        let overlay = Overlay::new();
+        overlay.set_child(Some(&bar));
+        overlay.add_overlay(&markers);
+        overlay.set_hexpand(true);
+
+        Self {
+            overlay,
+            bar,
+            markers,
+            thresholds,
+        }
+    }
+
+    /// Returns the overlay to add to a container
+    pub fn widget(&self) -> &Overlay {
+        &self.overlay
+    }
+
+    /// Shows a charge level and the current thresholds
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Charge level in percent
+    /// * `start` - Charge start threshold, if supported
+    /// * `stop` - Charge stop threshold, if supported
+    pub fn update(&self, capacity: u8, start: Option<u8>, stop: Option<u8>) {
+        self.bar.set_fraction(f64::from(capacity.min(100)) / 100.0);
+        for class in ChargeLevel::CSS_CLASSES {
+            self.bar.remove_css_class(class);
+        }
+        self.bar
+            .add_css_class(ChargeLevel::of(capacity, start, stop).css_class());
+
+        // Also picks up a theme switch since the last refresh
+        self.thresholds.set((start, stop));
+        self.markers.queue_draw();
+    }
+}
+
+/// Horizontal position of a threshold marker, kept inside the area
+fn marker_x(threshold: u8, width: f64) -> f64 {
+    let half = MARKER_WIDTH / 2.0;
+    (f64::from(threshold.min(100)) / 100.0 * width).clamp(half, (width - half).max(half))
+}
+
+/// Draws a vertical tick for each threshold
+///
+/// Ticks use the text color of the current theme so they show on both the
+/// filled and empty parts of the bar.
+fn draw_threshold_markers(
+    cr: &cairo::Context,
+    width: f64,
+    height: f64,
+    thresholds: [Option<u8>; 2],
+) {
+    if crate::ui::theme::get_theme() == "dark" {
+        cr.set_source_rgba(0.835, 0.835, 0.835, 0.9);
+    } else {
+        cr.set_source_rgba(0.180, 0.204, 0.212, 0.9);
+    }
+    cr.set_line_width(MARKER_WIDTH);
+    for threshold in thresholds.into_iter().flatten() {
+        let x = marker_x(threshold, width);
+        cr.move_to(x, 0.0);
+        cr.line_to(x, height);
+    }
+    let _ = cr.stroke();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(health: f32) -> HistorySample {
+        HistorySample {
+            timestamp: 0,
+            capacity_percent: 50,
+            health_percent: health,
+            status: "Discharging".to_string(),
+            power_watts: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_series_is_a_dot() {
+        // Fixed 120×28 area: the draw function only needs this layout
+        assert_eq!(
+            sparkline_shape(&[], 120.0, 28.0),
+            SparklineShape::Dot(60.0, 14.0)
+        );
+        assert_eq!(
+            sparkline_shape(&[91.0], 120.0, 28.0),
+            SparklineShape::Dot(60.0, 14.0)
+        );
+        assert_eq!(trend_color(&[]), GREEN);
+        // Zero-sized area (before allocation) must not panic either
+        assert_eq!(
+            sparkline_shape(&[], 0.0, 0.0),
+            SparklineShape::Dot(0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_line_fits_the_area() {
+        let SparklineShape::Line(points) = sparkline_shape(&[95.0, 94.0, 93.0], 100.0, 28.0) else {
+            panic!("expected a line");
+        };
+        assert_eq!(points.len(), 3);
+        assert!((points[0].0).abs() < f64::EPSILON);
+        assert!((points[2].0 - 100.0).abs() < f64::EPSILON);
+        // Highest value at the top, lowest at the bottom
+        assert!((points[0].1 - PADDING).abs() < 1e-9);
+        assert!((points[2].1 - (28.0 - PADDING)).abs() < 1e-9);
+
+        // A flat series stays inside the area
+        let SparklineShape::Line(flat) = sparkline_shape(&[90.0; 4], 100.0, 28.0) else {
+            panic!("expected a line");
+        };
+        assert!(flat.iter().all(|(_, y)| (14.0 - y).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_trend_color() {
+        assert_eq!(trend_color(&[90.0, 90.5]), GREEN);
+        assert_eq!(trend_color(&[90.0, 89.0]), YELLOW);
+        assert_eq!(trend_color(&[90.0, 80.0]), RED);
+    }
+
+    #[test]
+    fn test_health_series_downsampling() {
+        let samples: Vec<HistorySample> = (0..100u8).map(|i| sample(f32::from(i))).collect();
+
+        let series = health_series(samples.iter(), 10);
+        assert_eq!(series.len(), 10);
+        assert!((series[0] - 0.0).abs() < f32::EPSILON);
+        assert!((series[9] - 99.0).abs() < f32::EPSILON);
+
+        assert_eq!(health_series(samples.iter().take(5), 10).len(), 5);
+        assert!(health_series(std::iter::empty(), 10).is_empty());
+    }
+
+    #[test]
+    fn test_point_to_pixel() {
+        let scale = YScale {
+            floor: 40.0,
+            range: 40.0,
+        };
+        // Oldest point on the left edge at the bottom, newest on the right at the top
+        let (x, y) = point_to_pixel(0, 5, 40.0, scale, 100.0, 28.0);
+        assert!(x.abs() < f64::EPSILON);
+        assert!((y - (28.0 - PADDING)).abs() < 1e-9);
+        let (x, y) = point_to_pixel(4, 5, 80.0, scale, 100.0, 28.0);
+        assert!((x - 100.0).abs() < f64::EPSILON);
+        assert!((y - PADDING).abs() < 1e-9);
+        // Middle value, middle point
+        let (x, y) = point_to_pixel(2, 5, 60.0, scale, 100.0, 28.0);
+        assert!((x - 50.0).abs() < f64::EPSILON);
+        assert!((y - 14.0).abs() < 1e-9);
+        // A single point does not divide by zero
+        assert!(point_to_pixel(0, 1, 60.0, scale, 100.0, 28.0).0.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_y_scale_fits_values() {
+        assert_eq!(
+            YScale::fit([55.0_f32, 80.0, 62.0].into_iter()),
+            YScale {
+                floor: 55.0,
+                range: 25.0
+            }
+        );
+        // Flat series: minimum range, centered
+        assert_eq!(
+            YScale::fit([70.0_f32; 3].into_iter()),
+            YScale {
+                floor: 69.5,
+                range: 1.0
+            }
+        );
+        assert!((YScale::fit(std::iter::empty()).range - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_capacity_series() {
+        let mut charging = sample(90.0);
+        charging.status = "Charging".to_string();
+        charging.capacity_percent = 75;
+        let samples = [sample(90.0), sample(90.0), charging];
+
+        let series = capacity_series(samples.iter(), 2);
+        assert_eq!(
+            series,
+            vec![
+                CapacityPoint {
+                    capacity_percent: 50.0,
+                    charging: false
+                },
+                CapacityPoint {
+                    capacity_percent: 75.0,
+                    charging: true
+                },
+            ]
+        );
+        assert!(capacity_series(std::iter::empty(), 10).is_empty());
+    }
+
+    #[test]
+    fn test_charge_level() {
+        assert_eq!(ChargeLevel::of(50, Some(40), Some(80)), ChargeLevel::Within);
+        assert_eq!(ChargeLevel::of(80, Some(40), Some(80)), ChargeLevel::Within);
+        assert_eq!(ChargeLevel::of(39, Some(40), Some(80)), ChargeLevel::Below);
+        assert_eq!(ChargeLevel::of(81, Some(40), Some(80)), ChargeLevel::Above);
+        // Stop threshold only (no start file)
+        assert_eq!(ChargeLevel::of(5, None, Some(80)), ChargeLevel::Within);
+        assert_eq!(ChargeLevel::of(100, None, None), ChargeLevel::Within);
+    }
+
+    #[test]
+    fn test_marker_stays_inside_the_bar() {
+        assert!((marker_x(80, 200.0) - 160.0).abs() < f64::EPSILON);
+        assert!((marker_x(0, 200.0) - MARKER_WIDTH / 2.0).abs() < f64::EPSILON);
+        assert!((marker_x(100, 200.0) - (200.0 - MARKER_WIDTH / 2.0)).abs() < f64::EPSILON);
+        // Not allocated yet
+        assert!((marker_x(50, 0.0) - MARKER_WIDTH / 2.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,17 +1,39 @@
 //! Information tab displaying battery metrics and status
 //!
-//! Shows charge thresholds, current status, voltage, power consumption,
-//! capacity, health, and systemd service status with auto-refresh.
+//! Shows charge thresholds, charge level bar with threshold markers and
+//! recent charge sparkline, current status, voltage, power consumption, capacity, health with its wear grade and trend sparkline and long-term wear trend, systemd service status,
+//! the UPS of desktops (when one is connected) and a charge history graph with auto-refresh.
+//! A header shows the time of the last read, next to a manual refresh button
+//! and a CSV export of the battery data.
 
 use gtk4::prelude::*;
-use gtk4::{Box, Label, Orientation};
+use gtk4::{
+    cairo, Box, DrawingArea, Expander, FileChooserAction, FileChooserNative, Label, Orientation,
+    ResponseType,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
 
-use crate::core::i18n::t;
+use crate::core::battery::ManufactureDate;
+use crate::core::csv;
+use crate::core::health::{local_date, HealthHistory, TREND_WINDOWS};
+use crate::core::health_tracker::{CapacityTrend, TrendDirection};
+use crate::core::history::{unix_now, HistoryBuffer, MAX_GAP_SECS};
+use crate::core::i18n::{t, t_fmt, tn};
+use crate::core::power_supply::UpsInfo;
+use crate::core::service;
+use crate::core::systemd::UnitState;
+use crate::core::traits::{BatteryHistoryService, FlatFileBatteryHistory, HistorySample};
+use crate::core::upower::select_battery_service;
 use crate::core::{BatteryInfo, PowerSupplyInfo};
 use crate::ui::components::{
-    create_content_box, create_info_label, create_row_grid, create_vertical_spacer, InfoCard,
-    UpdatableWidgets,
+    create_card_flow, create_content_box, create_info_label, InfoCard, UpdatableWidgets,
+};
+use crate::ui::graphs::{
+    capacity_series, health_series, CapacitySparklineWidget, ChargeBarWidget, SparklineWidget,
+    SPARKLINE_POINTS,
 };
+use crate::ui::shortcuts::ACTION_REFRESH;
 
 /// Builds the Information tab content
 ///
@@ -19,6 +41,8 @@ use crate::ui::components::{
 ///
 /// * `info` - Battery information
 /// * `power_supply` - AC power supply information
+/// * `history` - Charge history of this battery, drawn in the history graph
+/// * `health` - Daily health snapshots, for the wear trend lines
 ///
 /// # Returns
 ///
@@ -27,12 +51,41 @@ use crate::ui::components::{
 pub fn build_info_tab(
     info: &BatteryInfo,
     power_supply: &PowerSupplyInfo,
+    history: HistoryBuffer,
+    health: &HealthHistory,
 ) -> (Box, UpdatableWidgets) {
     crate::core::debug::debug_log("📋 [INFO_TAB] Building info tab...");
     let content_box = create_content_box(10);
 
-    // === LIGNE 1: Seuils + Charge + Santé ===
-    let row1 = create_row_grid();
+    // En-tête : heure de la dernière lecture + actualisation manuelle
+    let header = Box::new(Orientation::Horizontal, 6);
+    header.set_halign(gtk4::Align::End);
+    // Résultat du dernier export CSV
+    let export_status = Label::new(None);
+    export_status.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+    header.append(&export_status);
+    let updated_label = Label::new(None);
+    updated_label.add_css_class("dim-label");
+    update_timestamp(&updated_label);
+    header.append(&updated_label);
+    let refresh_button = gtk4::Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.add_css_class("flat");
+    refresh_button.set_tooltip_text(Some(&format!("{} (F5)", t("shortcut_refresh"))));
+    // Same window action as F5: runs the auto-update callback
+    refresh_button.set_action_name(Some(&format!("win.{ACTION_REFRESH}")));
+    header.append(&refresh_button);
+    let export_button = gtk4::Button::from_icon_name("document-save-symbolic");
+    export_button.add_css_class("flat");
+    export_button.set_tooltip_text(Some(t("export_csv").as_str()));
+    let export_battery = info.name.clone();
+    export_button.connect_clicked(move |button| {
+        save_csv(button, &export_battery, &export_status);
+    });
+    header.append(&export_button);
+    content_box.append(&header);
+
+    // === Cartes : 3 colonnes, 2 ou 1 quand la fenêtre est étroite ===
+    let cards = create_card_flow();
 
     // Card Seuils
     let (thresholds_frame, thresholds_box) =
@@ -97,53 +150,40 @@ pub fn build_info_tab(
     thresholds_grid.attach(&stop_col_box, col, 0, 1, 1);
     col += 1;
 
-    // Alarme de décharge (seulement si supportée)
-    let alarm_label = info.alarm_percent().map_or_else(
-        || None,
-        |alarm_pct| {
-            let alarm_col_box = Box::new(Orientation::Vertical, 4);
+    // Alarme de décharge (seulement si supportée, masquée tant qu'elle vaut 0)
+    let alarm_label = info.alarm.map(|_| {
+        let alarm_col_box = Box::new(Orientation::Vertical, 4);
 
-            let alarm_title = Label::new(Some(&t("alarm")));
-            alarm_title.set_halign(gtk4::Align::Center);
-            alarm_col_box.append(&alarm_title);
+        let alarm_title = Label::new(Some(&t("alarm")));
+        alarm_title.set_halign(gtk4::Align::Center);
+        alarm_col_box.append(&alarm_title);
 
-            let value = Label::new(None);
-            value.set_halign(gtk4::Align::Center);
-            value.set_markup(&format!(
-                "<span size='x-large' weight='bold'>{alarm_pct:.1}%</span>"
-            ));
-            value.add_css_class("color-danger");
-            crate::core::debug::debug_log_args(std::format_args!(
-                "🎨 [INFO_TAB] Alarm label: added color-danger class ({alarm_pct:.1}%)"
-            ));
-            alarm_col_box.append(&value);
+        let value = Label::new(None);
+        value.set_halign(gtk4::Align::Center);
+        value.add_css_class("color-danger");
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🎨 [INFO_TAB] Alarm label: added color-danger class ({:?})",
+            info.alarm_percent()
+        ));
+        alarm_col_box.append(&value);
+        update_alarm_label(&value, info);
 
-            thresholds_grid.attach(&alarm_col_box, col, 0, 1, 1);
-            Some(value)
-        },
-    );
+        thresholds_grid.attach(&alarm_col_box, col, 0, 1, 1);
+        value
+    });
 
     thresholds_box.append(&thresholds_grid);
 
-    // Espaceur pour uniformiser la hauteur
-    thresholds_box.append(&create_vertical_spacer());
-
-    // Lignes vides pour uniformiser avec les autres cards
-    thresholds_box.append(&create_info_label(""));
-    thresholds_box.append(&create_info_label(""));
-    thresholds_box.append(&create_info_label(""));
-
-    row1.attach(&thresholds_frame, 0, 0, 1, 1);
+    cards.insert(&thresholds_frame, -1);
 
     // Card Charge
     let (charge_frame, charge_box) = InfoCard::create(&format!("🔋 {}", t("card_charge")));
-    charge_box.append(&create_info_label(""));
 
     let capacity_label = Label::new(None);
     capacity_label.set_halign(gtk4::Align::Center);
     capacity_label.set_markup(&format!(
         "<span size='xx-large' weight='bold'>{}</span><span size='large'>%</span>",
-        info.capacity_percent
+        info.capacity_text()
     ));
     capacity_label.add_css_class("color-primary");
     crate::core::debug::debug_log_args(std::format_args!(
@@ -152,21 +192,46 @@ pub fn build_info_tab(
     ));
     charge_box.append(&capacity_label);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    charge_box.append(&create_vertical_spacer());
+    // Barre de charge avec repères aux seuils
+    let charge_bar = ChargeBarWidget::new();
+    charge_bar.widget().set_margin_top(6);
+    charge_bar.widget().set_margin_start(12);
+    charge_bar.widget().set_margin_end(12);
+    charge_bar.update(
+        info.capacity_percent,
+        info.charge_start_threshold,
+        info.charge_stop_threshold,
+    );
+    charge_box.append(charge_bar.widget());
+
+    // Évolution récente de la charge (orange en décharge, vert en charge)
+    let capacity_sparkline =
+        CapacitySparklineWidget::new(capacity_series(history.samples(), SPARKLINE_POINTS));
+    capacity_sparkline.widget().set_margin_top(4);
+    capacity_sparkline.widget().set_margin_start(12);
+    capacity_sparkline.widget().set_margin_end(12);
+    capacity_sparkline
+        .widget()
+        .set_tooltip_text(Some(t("capacity_trend").as_str()));
+    charge_box.append(capacity_sparkline.widget());
+
+    // Infos secondaires, alignées en bas de la carte
+    let charge_footer = InfoCard::footer(&charge_box);
 
-    charge_box.append(&create_info_label(""));
-    charge_box.append(&create_info_label(""));
     if let Some(time_text) = info.time_remaining_formatted() {
-        charge_box.append(&create_info_label(&time_text));
-    } else {
-        charge_box.append(&create_info_label(""));
+        charge_footer.append(&create_info_label(&time_text));
     }
-    row1.attach(&charge_frame, 1, 0, 1, 1);
+    cards.insert(&charge_frame, -1);
 
     // Card Santé
     let (health_frame, health_box) = InfoCard::create(&format!("❤️ {}", t("card_health")));
-    health_box.append(&create_info_label(""));
+
+    // Note A-D en grand, à côté du pourcentage
+    let health_row = Box::new(Orientation::Horizontal, 12);
+    health_row.set_halign(gtk4::Align::Center);
+    let health_grade_label = Label::new(None);
+    health_grade_label.set_tooltip_text(Some(t("health_grade_tooltip").as_str()));
+    health_row.append(&health_grade_label);
 
     let health_label = Label::new(None);
     health_label.set_halign(gtk4::Align::Center);
@@ -181,32 +246,52 @@ pub fn build_info_tab(
         health_class,
         info.health_percent
     ));
-    health_box.append(&health_label);
+    health_row.append(&health_label);
+    health_box.append(&health_row);
+
+    let health_grade_description = Label::new(None);
+    health_grade_description.set_halign(gtk4::Align::Center);
+    health_grade_description.set_wrap(true);
+    health_grade_description.set_justify(gtk4::Justification::Center);
+    health_grade_description.add_css_class("dim-label");
+    health_box.append(&health_grade_description);
+    show_health_grade(&health_grade_label, &health_grade_description, info);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    health_box.append(&create_vertical_spacer());
+    // Tendance de santé (historique des échantillons)
+    let health_sparkline = SparklineWidget::new(health_series(history.samples(), SPARKLINE_POINTS));
+    health_sparkline
+        .widget()
+        .set_tooltip_text(Some(t("health_trend").as_str()));
+    health_box.append(health_sparkline.widget());
 
-    health_box.append(&create_info_label(""));
-    health_box.append(&create_info_label(&format!(
+    // Infos secondaires, alignées en bas de la carte
+    let health_footer = InfoCard::footer(&health_box);
+
+    health_footer.append(&create_info_label(&format!(
         "{}: {:.1}%",
         t("wear"),
         info.wear_percent
     )));
-    health_box.append(&create_info_label(&format!(
-        "{}: {}",
-        t("cycles"),
-        info.cycle_count
+    health_footer.append(&create_info_label(&tn(
+        "cycle_count_singular",
+        "cycle_count_plural",
+        u64::from(info.cycle_count),
     )));
-    row1.attach(&health_frame, 2, 0, 1, 1);
-
-    content_box.append(&row1);
-
-    // === LIGNE 2: Alimentation + État + Batterie ===
-    let row2 = create_row_grid();
+    let wear_trend_labels: Vec<Label> = TREND_WINDOWS
+        .iter()
+        .map(|&days| {
+            let label = create_info_label(&wear_trend_text(health, &info.name, days));
+            health_footer.append(&label);
+            label
+        })
+        .collect();
+    // Rempli par update_capacity_trend (relevés quotidiens)
+    let capacity_trend_label = create_info_label("");
+    health_footer.append(&capacity_trend_label);
+    cards.insert(&health_frame, -1);
 
     // Card Alimentation
     let (power_frame, power_box) = InfoCard::create(&format!("🔌 {}", t("card_power")));
-    power_box.append(&create_info_label(""));
 
     let power_source_value = Label::new(None);
     power_source_value.set_halign(gtk4::Align::Center);
@@ -214,21 +299,18 @@ pub fn build_info_tab(
     power_source_value.add_css_class(power_supply.get_power_source_css_class());
     power_box.append(&power_source_value);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    power_box.append(&create_vertical_spacer());
+    // Infos secondaires, alignées en bas de la carte
+    let power_footer = InfoCard::footer(&power_box);
 
-    power_box.append(&create_info_label(""));
-    power_box.append(&create_info_label(""));
-    power_box.append(&create_info_label(&format!(
+    power_footer.append(&create_info_label(&format!(
         "{}: {}",
         t("adapter"),
         power_supply.ac_name
     )));
-    row2.attach(&power_frame, 0, 0, 1, 1);
+    cards.insert(&power_frame, -1);
 
     // Card État
     let (status_frame, status_box) = InfoCard::create(&format!("📊 {}", t("card_status")));
-    status_box.append(&create_info_label(""));
 
     let status_value = Label::new(None);
     status_value.set_halign(gtk4::Align::Center);
@@ -242,21 +324,18 @@ pub fn build_info_tab(
     ));
     status_box.append(&status_value);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    status_box.append(&create_vertical_spacer());
+    // Infos secondaires, alignées en bas de la carte
+    let status_footer = InfoCard::footer(&status_box);
 
-    status_box.append(&create_info_label(""));
-    status_box.append(&create_info_label(""));
-    status_box.append(&create_info_label(&format!(
+    status_footer.append(&create_info_label(&format!(
         "{}: {}",
         t("capacity_level"),
         info.capacity_level
     )));
-    row2.attach(&status_frame, 1, 0, 1, 1);
+    cards.insert(&status_frame, -1);
 
     // Card Batterie
     let (battery_frame, battery_box) = InfoCard::create(&format!("🔋 {}", t("card_battery")));
-    battery_box.append(&create_info_label(""));
 
     let battery_main = Label::new(None);
     battery_main.set_halign(gtk4::Align::Center);
@@ -271,31 +350,28 @@ pub fn build_info_tab(
     ));
     battery_box.append(&battery_main);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    battery_box.append(&create_vertical_spacer());
+    // Infos secondaires, alignées en bas de la carte
+    let battery_footer = InfoCard::footer(&battery_box);
 
-    battery_box.append(&create_info_label(&format!("{}: {}", t("name"), info.name)));
-    battery_box.append(&create_info_label(&format!(
+    battery_footer.append(&create_info_label(&format!("{}: {}", t("name"), info.name)));
+    battery_footer.append(&create_info_label(&format!(
         "{}: {}",
         t("model"),
         info.model_name
     )));
-    battery_box.append(&create_info_label(&format!(
+    battery_footer.append(&create_info_label(&format!(
         "{}: {}",
         t("type"),
         info.technology
     )));
-    row2.attach(&battery_frame, 2, 0, 1, 1);
-
-    content_box.append(&row2);
-
-    // === LIGNE 3: Électrique + Capacité + Infos ===
-    let row3 = create_row_grid();
+    if let Some(date) = info.manufacture_date {
+        battery_footer.append(&create_info_label(&manufacture_text(date)));
+    }
+    cards.insert(&battery_frame, -1);
 
     // Card Électrique
     let (electrical_frame, electrical_box) =
         InfoCard::create(&format!("⚡ {}", t("card_electrical")));
-    electrical_box.append(&create_info_label(""));
 
     let power_main = Label::new(None);
     power_main.set_halign(gtk4::Align::Center);
@@ -310,20 +386,19 @@ pub fn build_info_tab(
     ));
     electrical_box.append(&power_main);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    electrical_box.append(&create_vertical_spacer());
+    // Infos secondaires, alignées en bas de la carte
+    let electrical_footer = InfoCard::footer(&electrical_box);
 
     let voltage_value = create_info_label(&format!("{}: {:.2} V", t("voltage"), info.voltage_v()));
-    electrical_box.append(&voltage_value);
+    electrical_footer.append(&voltage_value);
     let current_value = create_info_label(&format!("{}: {} mA", t("current"), info.current_ma()));
-    electrical_box.append(&current_value);
+    electrical_footer.append(&current_value);
     let power_value = create_info_label(&format!("{}: {:.2} W", t("power"), info.power_watts()));
-    electrical_box.append(&power_value);
-    row3.attach(&electrical_frame, 0, 0, 1, 1);
+    electrical_footer.append(&power_value);
+    cards.insert(&electrical_frame, -1);
 
     // Card Capacité
     let (capacity_frame, capacity_box) = InfoCard::create(&format!("⚡ {}", t("card_capacity")));
-    capacity_box.append(&create_info_label(""));
 
     let capacity_main = Label::new(None);
     capacity_main.set_halign(gtk4::Align::Center);
@@ -338,60 +413,101 @@ pub fn build_info_tab(
     ));
     capacity_box.append(&capacity_main);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    capacity_box.append(&create_vertical_spacer());
+    // Infos secondaires, alignées en bas de la carte
+    let capacity_footer = InfoCard::footer(&capacity_box);
 
     let charge_now_value = create_info_label(&format!(
         "{}: {} mAh",
         t("current_capacity"),
         info.charge_now_mah()
     ));
-    capacity_box.append(&charge_now_value);
-    capacity_box.append(&create_info_label(&format!(
+    capacity_footer.append(&charge_now_value);
+    capacity_footer.append(&create_info_label(&format!(
         "{}: {} mAh",
         t("full_capacity"),
         info.charge_full_mah()
     )));
-    capacity_box.append(&create_info_label(&format!(
+    capacity_footer.append(&create_info_label(&format!(
         "{}: {} mAh",
         t("design_capacity"),
         info.charge_full_design_mah()
     )));
-    row3.attach(&capacity_frame, 1, 0, 1, 1);
+    cards.insert(&capacity_frame, -1);
 
     // Card Service
     let (service_frame, service_box) = InfoCard::create(&format!("🔄 {}", t("card_service")));
-    service_box.append(&create_info_label(""));
 
     let service_label = Label::new(None);
     service_label.set_halign(gtk4::Align::Center);
-    service_label.set_markup(&info.service_status_markup());
-    let service_class = info.service_status_css_class();
-    service_label.add_css_class(service_class);
-    crate::core::debug::debug_log_args(std::format_args!(
-        "🎨 [INFO_TAB] Service label: added {} class (active={})",
-        service_class,
-        info.service_active
-    ));
+    update_service_label(&service_label, service::cached_unit_state());
+    refresh_service_label(&service_label);
     service_box.append(&service_label);
 
-    // Espaceur pour pousser les infos secondaires vers le bas
-    service_box.append(&create_vertical_spacer());
+    cards.insert(&service_frame, -1);
+
+    content_box.append(&cards);
+
+    // === Onduleur (seulement si présent) ===
+    let (ups_capacity_label, ups_source_label) =
+        power_supply.ups.as_ref().map_or((None, None), |ups| {
+            let (ups_frame, ups_box) = InfoCard::create(&format!("🔋 {}", t("card_ups")));
+
+            let ups_capacity = Label::new(None);
+            ups_capacity.set_halign(gtk4::Align::Center);
+            ups_capacity.add_css_class("color-primary");
+            ups_box.append(&ups_capacity);
 
-    service_box.append(&create_info_label(""));
-    service_box.append(&create_info_label(""));
-    service_box.append(&create_info_label(""));
+            let ups_source = Label::new(None);
+            ups_source.set_halign(gtk4::Align::Center);
+            ups_box.append(&ups_source);
 
-    row3.attach(&service_frame, 2, 0, 1, 1);
+            ups_box.append(&create_info_label(&format!("{}: {}", t("name"), ups.name)));
+            update_ups_labels(&ups_capacity, &ups_source, ups);
 
-    content_box.append(&row3);
+            content_box.append(&ups_frame);
+            (Some(ups_capacity), Some(ups_source))
+        });
+
+    // === Historique de charge ===
+    let history = Rc::new(RefCell::new(history));
+    let history_area = DrawingArea::new();
+    history_area.set_content_height(120);
+    history_area.set_hexpand(true);
+    history_area.set_draw_func(glib::clone!(
+        #[strong]
+        history,
+        move |_area, cr, width, height| {
+            draw_history(cr, f64::from(width), f64::from(height), &history.borrow());
+        }
+    ));
+
+    let history_legend = Label::new(None);
+    history_legend.set_markup(&format!(
+        "<span size='small'><span foreground='{CAPACITY_COLOR_HEX}'>━</span> {}   <span foreground='{POWER_COLOR_HEX}'>━</span> {} (W)</span>",
+        t("capacity"),
+        t("power")
+    ));
+    history_legend.set_halign(gtk4::Align::End);
+
+    let history_box = Box::new(Orientation::Vertical, 4);
+    history_box.set_margin_top(4);
+    history_box.append(&history_area);
+    history_box.append(&history_legend);
+
+    let history_expander = Expander::new(Some(&format!("📈 {}", t("history_graph"))));
+    history_expander.set_child(Some(&history_box));
+    content_box.append(&history_expander);
 
     // Create updatable widgets structure
     let updatable = UpdatableWidgets {
         power_source_value,
         status_value,
         capacity_label,
+        charge_bar,
+        capacity_sparkline,
         health_label,
+        health_grade_label,
+        health_grade_description,
         voltage_value,
         current_value,
         power_value,
@@ -400,7 +516,561 @@ pub fn build_info_tab(
         threshold_stop_label,
         alarm_label,
         service_label,
+        ups_capacity_label,
+        ups_source_label,
+        wear_trend_labels,
+        capacity_trend_label,
+        history_area,
+        history,
+        health_sparkline,
+        updated_label,
     };
 
     (content_box, updatable)
 }
+
+/// Formats the "last updated" header label
+///
+/// # Arguments
+///
+/// * `time` - Time of the last successful read
+///
+/// # Returns
+///
+/// e.g. "Mis à jour à 09:05:03"
+pub fn updated_text(time: &glib::DateTime) -> String {
+    let clock = time
+        .format("%H:%M:%S")
+        .map(|clock| clock.to_string())
+        .unwrap_or_default();
+    t_fmt("updated_at", &[("time", &clock)])
+}
+
+/// Stamps the "last updated" label with the current local time
+pub fn update_timestamp(updated_label: &Label) {
+    if let Ok(now) = glib::DateTime::now_local() {
+        updated_label.set_text(&updated_text(&now));
+    }
+}
+
+/// Formats the manufacture date with the approximate battery age
+///
+/// # Arguments
+///
+/// * `date` - Manufacture date reported by the firmware
+///
+/// # Returns
+///
+/// Line such as "Fabrication: 2021-03 (≈ 42 mois)"
+fn manufacture_text(date: ManufactureDate) -> String {
+    let today = local_date();
+    let mut parts = today.split('-').filter_map(|part| part.parse().ok());
+    let age = match (parts.next(), parts.next()) {
+        (Some(year), Some(month)) => date.age_months(year, u8::try_from(month).unwrap_or(1)),
+        _ => None,
+    };
+
+    match age {
+        Some(months) => format!(
+            "{}: {date} (≈ {months} {})",
+            t("manufacture_date"),
+            t("months")
+        ),
+        None => format!("{}: {date}", t("manufacture_date")),
+    }
+}
+
+/// Formats the wear of a battery over a trend window
+///
+/// # Arguments
+///
+/// * `health` - Daily health snapshots
+/// * `battery` - Battery name
+/// * `days` - Trend window in days
+///
+/// # Returns
+///
+/// Line such as "Usure sur 30 jours: 0.4%", or a placeholder until two days are recorded
+fn wear_trend_text(health: &HealthHistory, battery: &str, days: u32) -> String {
+    let value = health.wear_trend(battery, days).map_or_else(
+        || t("wear_trend_pending"),
+        |trend| {
+            if trend.days_covered < days {
+                format!(
+                    "{:.1}% ({} {})",
+                    trend.wear_percent,
+                    trend.days_covered,
+                    t("days")
+                )
+            } else {
+                format!("{:.1}%", trend.wear_percent)
+            }
+        },
+    );
+    format!("{} {days} {}: {value}", t("wear_over"), t("days"))
+}
+
+/// Shows the result of a CSV export in the header
+fn show_export_result(label: &Label, result: Result<String, String>) {
+    label.remove_css_class("dim-label");
+    label.remove_css_class("color-danger");
+    match result {
+        Ok(path) => {
+            label.set_text(&t_fmt("export_csv_saved", &[("path", &path)]));
+            label.add_css_class("dim-label");
+        }
+        Err(error) => {
+            label.set_text(&t_fmt("export_csv_failed", &[("error", &error)]));
+            label.add_css_class("color-danger");
+        }
+    }
+}
+
+/// Asks for a file and writes the battery state and its recorded history to it
+///
+/// # Arguments
+///
+/// * `button` - Export button (its window is the dialog parent)
+/// * `battery_name` - Battery shown by the tab
+/// * `status_label` - Label receiving the result
+fn save_csv(button: &gtk4::Button, battery_name: &str, status_label: &Label) {
+    let parent = button.root().and_downcast::<gtk4::Window>();
+    let chooser = FileChooserNative::new(
+        Some(&t("export_csv")),
+        parent.as_ref(),
+        FileChooserAction::Save,
+        None,
+        Some(&t("cancel")),
+    );
+    chooser.set_current_name(&format!("battery-{battery_name}.csv"));
+
+    // Native dialogs are not kept alive by GTK: hold it until it answers
+    let keep_alive = Rc::new(RefCell::new(Some(chooser.clone())));
+    let battery_name = battery_name.to_string();
+    chooser.connect_response(glib::clone!(
+        #[weak]
+        status_label,
+        move |chooser, response| {
+            let path = chooser.file().and_then(|file| file.path());
+            chooser.destroy();
+            keep_alive.borrow_mut().take();
+            let (ResponseType::Accept, Some(path)) = (response, path) else {
+                return;
+            };
+
+            let shown = path.display().to_string();
+            let result = select_battery_service()
+                .get_info(&battery_name)
+                .map_err(|e| e.to_string())
+                .and_then(|info| {
+                    let samples = FlatFileBatteryHistory::default_path()
+                        .map(|history| {
+                            FlatFileBatteryHistory::new(&history)
+                                .get_samples(&battery_name, usize::MAX)
+                        })
+                        .unwrap_or_default();
+                    std::fs::write(&path, csv::export(&info, &samples, unix_now()))
+                        .map_err(|e| e.to_string())
+                });
+            match &result {
+                Ok(()) => crate::core::debug::debug_log_args(std::format_args!(
+                    "💾 [INFO_TAB] CSV exported to {shown}"
+                )),
+                Err(e) => crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [INFO_TAB] CSV export to {shown} failed: {e}"
+                )),
+            }
+            show_export_result(&status_label, result.map(|()| shown));
+        }
+    ));
+    chooser.show();
+}
+
+/// Shows the wear grade letter, colored, and its description
+///
+/// # Arguments
+///
+/// * `grade_label` - Large grade letter
+/// * `description_label` - Subtitle under the health figures
+/// * `info` - Battery information
+fn show_health_grade(grade_label: &Label, description_label: &Label, info: &BatteryInfo) {
+    grade_label.set_markup(&format!(
+        "<span size='xx-large' weight='bold'>{}</span>",
+        info.health_grade()
+    ));
+    for class in [
+        "color-success",
+        "color-primary",
+        "color-warning",
+        "color-danger",
+    ] {
+        grade_label.remove_css_class(class);
+    }
+    grade_label.add_css_class(info.health_grade_css_class());
+    description_label.set_text(&t(&info.health_grade_description_key()));
+}
+
+/// Updates the wear trend lines after a new daily health snapshot
+///
+/// # Arguments
+///
+/// * `widgets` - Updatable widget references
+/// * `health` - Daily health snapshots
+/// * `battery` - Battery name
+pub fn update_wear_trends(widgets: &UpdatableWidgets, health: &HealthHistory, battery: &str) {
+    for (label, &days) in widgets.wear_trend_labels.iter().zip(TREND_WINDOWS.iter()) {
+        label.set_text(&wear_trend_text(health, battery, days));
+    }
+}
+
+/// Capacity line color (matches the light theme primary color)
+const CAPACITY_COLOR_HEX: &str = "#2196f3";
+const CAPACITY_COLOR: (f64, f64, f64) = (0.129, 0.588, 0.953);
+
+/// Power line color (matches the light theme warning color)
+const POWER_COLOR_HEX: &str = "#ff9800";
+const POWER_COLOR: (f64, f64, f64) = (1.0, 0.596, 0.0);
+
+/// Shortest time span shown by the history graph, in seconds
+const MIN_GRAPH_SPAN_SECS: u64 = 600;
+
+/// Draws the charge history: capacity (0–100%) and power (scaled to its maximum)
+///
+/// Lines are broken where samples are missing (suspend, app closed).
+///
+/// # Arguments
+///
+/// * `cr` - Cairo context of the drawing area
+/// * `width` - Drawing area width in pixels
+/// * `height` - Drawing area height in pixels
+/// * `history` - Samples to draw
+#[allow(clippy::cast_precision_loss)]
+fn draw_history(cr: &cairo::Context, width: f64, height: f64, history: &HistoryBuffer) {
+    // Grille légère à 25/50/75%
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.3);
+    cr.set_line_width(1.0);
+    for fraction in [0.25, 0.5, 0.75] {
+        let y = (height * fraction).round() + 0.5;
+        cr.move_to(0.0, y);
+        cr.line_to(width, y);
+    }
+    let _ = cr.stroke();
+
+    let (Some(first), Some(last)) = (history.samples().next(), history.samples().last()) else {
+        return;
+    };
+    let end = last.timestamp;
+    let span = (end - first.timestamp).max(MIN_GRAPH_SPAN_SECS);
+    let start = end - span;
+
+    let x_of = |sample: &HistorySample| (sample.timestamp - start) as f64 / span as f64 * width;
+    let max_power = history
+        .samples()
+        .filter_map(|s| s.power_watts)
+        .fold(1.0_f32, f32::max);
+
+    let segments = history.segments(MAX_GAP_SECS);
+    cr.set_line_width(2.0);
+
+    // Puissance
+    cr.set_source_rgb(POWER_COLOR.0, POWER_COLOR.1, POWER_COLOR.2);
+    for segment in &segments {
+        let mut pen_down = false;
+        for sample in segment {
+            let Some(power) = sample.power_watts else {
+                pen_down = false;
+                continue;
+            };
+            let y = height - f64::from(power / max_power) * height;
+            if pen_down {
+                cr.line_to(x_of(sample), y);
+            } else {
+                cr.move_to(x_of(sample), y);
+                pen_down = true;
+            }
+        }
+    }
+    let _ = cr.stroke();
+
+    // Capacité
+    cr.set_source_rgb(CAPACITY_COLOR.0, CAPACITY_COLOR.1, CAPACITY_COLOR.2);
+    for segment in &segments {
+        for (i, sample) in segment.iter().enumerate() {
+            let y = height - f64::from(sample.capacity_percent) / 100.0 * height;
+            if i == 0 {
+                cr.move_to(x_of(sample), y);
+            } else {
+                cr.line_to(x_of(sample), y);
+            }
+        }
+    }
+    let _ = cr.stroke();
+
+    // Échelle de puissance
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.9);
+    cr.set_font_size(10.0);
+    cr.move_to(4.0, 12.0);
+    let _ = cr.show_text(&format!("100% · {max_power:.1} W"));
+}
+
+/// Refreshes every dynamic label of the Information tab
+///
+/// # Arguments
+///
+/// * `widgets` - Updatable widget references
+/// * `info` - Freshly read battery information
+/// * `power_supply` - Freshly read AC power supply information
+pub fn update_info_widgets(
+    widgets: &UpdatableWidgets,
+    info: &BatteryInfo,
+    power_supply: &PowerSupplyInfo,
+) {
+    // Update power supply
+    let power_source_value = &widgets.power_source_value;
+    power_source_value.set_markup(&power_supply.get_power_source_markup());
+    power_source_value.remove_css_class("color-success");
+    power_source_value.remove_css_class("color-warning");
+    power_source_value.add_css_class(power_supply.get_power_source_css_class());
+
+    // Update status
+    let status_value = &widgets.status_value;
+    status_value.set_markup(&info.get_status_markup());
+    // Remove old classes and add new one
+    status_value.remove_css_class("color-success");
+    status_value.remove_css_class("color-warning");
+    status_value.remove_css_class("color-primary");
+    let status_class = info.get_status_css_class();
+    status_value.add_css_class(status_class);
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🔄 [UPDATE] Status class updated to: {status_class}"
+    ));
+
+    // Update labels
+    widgets.capacity_label.set_markup(&format!(
+        "<span size='xx-large' weight='bold'>{}</span><span size='large'>%</span>",
+        info.capacity_text()
+    ));
+    // Note: capacity_label keeps color-primary class, no update needed
+
+    let health_label = &widgets.health_label;
+    health_label.set_markup(&format!(
+        "<span size='xx-large' weight='bold'>{:.1}</span><span size='large'>%</span>",
+        info.health_percent
+    ));
+    // Remove old classes and add new one
+    health_label.remove_css_class("color-success");
+    health_label.remove_css_class("color-warning");
+    health_label.remove_css_class("color-danger");
+    let health_class = info.get_health_css_class();
+    health_label.add_css_class(health_class);
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🔄 [UPDATE] Health class updated to: {health_class}"
+    ));
+    show_health_grade(
+        &widgets.health_grade_label,
+        &widgets.health_grade_description,
+        info,
+    );
+
+    // Update electrical values
+    widgets
+        .voltage_value
+        .set_text(&format!("{}: {:.2} V", t("voltage"), info.voltage_v()));
+    widgets
+        .current_value
+        .set_text(&format!("{}: {} mA", t("current"), info.current_ma()));
+    widgets
+        .power_value
+        .set_text(&format!("{}: {:.2} W", t("power"), info.power_watts()));
+    widgets.charge_now_value.set_text(&format!(
+        "{}: {} mAh",
+        t("current_capacity"),
+        info.charge_now_mah()
+    ));
+
+    // Update UPS (its card only exists when one was connected at startup)
+    if let (Some(capacity_label), Some(source_label), Some(ups)) = (
+        &widgets.ups_capacity_label,
+        &widgets.ups_source_label,
+        &power_supply.ups,
+    ) {
+        update_ups_labels(capacity_label, source_label, ups);
+    }
+
+    // Update thresholds and alarm
+    update_threshold_labels(widgets, info);
+
+    // Update history graph
+    widgets
+        .history
+        .borrow_mut()
+        .push(HistorySample::from_info(info, unix_now()));
+    widgets.history_area.queue_draw();
+    widgets.capacity_sparkline.set_points(capacity_series(
+        widgets.history.borrow().samples(),
+        SPARKLINE_POINTS,
+    ));
+    widgets.health_sparkline.set_values(health_series(
+        widgets.history.borrow().samples(),
+        SPARKLINE_POINTS,
+    ));
+
+    // Service status (queried off the main thread, cached 30 s)
+    refresh_service_label(&widgets.service_label);
+}
+
+/// Shows the systemd service state
+///
+/// # Arguments
+///
+/// * `service_label` - Service card label
+/// * `state` - Service state, `None` while the first check is running
+fn update_service_label(service_label: &Label, state: Option<UnitState>) {
+    service_label.set_markup(&service::status_markup(state));
+    // Remove old classes and add new one
+    service_label.remove_css_class("color-success");
+    service_label.remove_css_class("color-danger");
+    service_label.remove_css_class("dim-label");
+    let service_class = service::status_css_class(state);
+    service_label.add_css_class(service_class);
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🔄 [UPDATE] Service class updated to: {service_class}"
+    ));
+}
+
+/// Updates the service label once the (possibly cached) state is known
+///
+/// Never blocks: a stale state is checked in a worker thread.
+///
+/// # Arguments
+///
+/// * `service_label` - Service card label
+pub fn refresh_service_label(service_label: &Label) {
+    let service_label = service_label.downgrade();
+    glib::spawn_future_local(async move {
+        let state = service::unit_state().await;
+        if let Some(service_label) = service_label.upgrade() {
+            update_service_label(&service_label, Some(state));
+        }
+    });
+}
+
+/// Shows the capacity trend computed from the health snapshots
+///
+/// # Arguments
+///
+/// * `widgets` - Updatable widget references
+/// * `trend` - Capacity trend, `None` until two weeks were recorded
+pub fn update_capacity_trend(widgets: &UpdatableWidgets, trend: Option<CapacityTrend>) {
+    let text = match trend {
+        None => format!("→ {}", t("capacity_trend_pending")),
+        Some(trend) if trend.direction == TrendDirection::Stable => {
+            format!("{} {}", trend.direction.arrow(), t("capacity_stable"))
+        }
+        Some(trend) => format!(
+            "{} {}",
+            trend.direction.arrow(),
+            t_fmt(
+                "capacity_loss_month",
+                &[("loss", &format!("{:.2}", trend.loss_percent_per_month))]
+            )
+        ),
+    };
+    widgets.capacity_trend_label.set_text(&text);
+    widgets
+        .capacity_trend_label
+        .set_tooltip_text(Some(t("capacity_trend_tooltip").as_str()));
+}
+
+/// Refreshes the UPS card labels
+///
+/// # Arguments
+///
+/// * `capacity_label` - UPS charge label
+/// * `source_label` - Mains / outage label
+/// * `ups` - Freshly read UPS information
+fn update_ups_labels(capacity_label: &Label, source_label: &Label, ups: &UpsInfo) {
+    capacity_label.set_markup(&format!(
+        "<span size='xx-large' weight='bold'>{}</span><span size='large'>%</span>",
+        ups.capacity_percent
+            .map_or_else(|| "?".to_string(), |c| c.to_string())
+    ));
+    source_label.set_markup(&ups.get_source_markup());
+    source_label.remove_css_class("color-success");
+    source_label.remove_css_class("color-danger");
+    source_label.add_css_class(ups.get_source_css_class());
+    source_label.set_tooltip_text(Some(&format!("{}: {}", t("status"), ups.status)));
+}
+
+/// Refreshes the threshold and alarm labels and the charge bar of the Information tab
+///
+/// Shared by the auto-refresh timer and the settings tab (right after
+/// applying new thresholds, without waiting for the next tick).
+///
+/// # Arguments
+///
+/// * `widgets` - Updatable widget references
+/// * `info` - Freshly read battery information
+pub fn update_threshold_labels(widgets: &UpdatableWidgets, info: &BatteryInfo) {
+    if let Some(ref start_label) = widgets.threshold_start_label {
+        if let Some(start_val) = info.charge_start_threshold {
+            start_label.set_markup(&format!(
+                "<span size='x-large' weight='bold'>{start_val}%</span>"
+            ));
+            // Note: start_label keeps color-primary class
+        }
+    }
+
+    widgets.threshold_stop_label.set_markup(&format!(
+        "<span size='x-large' weight='bold'>{}</span>",
+        info.charge_stop_threshold
+            .map_or_else(|| "N/A".to_string(), |v| format!("{v}%"))
+    ));
+    // Note: threshold_stop_label garde sa classe color-success
+
+    // Charge bar color and markers follow the thresholds
+    widgets.charge_bar.update(
+        info.capacity_percent,
+        info.charge_start_threshold,
+        info.charge_stop_threshold,
+    );
+
+    if let Some(ref alarm_label) = widgets.alarm_label {
+        update_alarm_label(alarm_label, info);
+    }
+}
+
+/// Shows the alarm percentage, or hides its column when the alarm is cleared
+///
+/// # Arguments
+///
+/// * `alarm_label` - Alarm value label (keeps its color-danger class)
+/// * `info` - Battery information
+fn update_alarm_label(alarm_label: &Label, info: &BatteryInfo) {
+    let alarm_pct = info.alarm_percent();
+    if let Some(alarm_pct) = alarm_pct {
+        alarm_label.set_markup(&format!(
+            "<span size='x-large' weight='bold'>{alarm_pct:.1}%</span>"
+        ));
+    }
+    if let Some(column) = alarm_label.parent() {
+        column.set_visible(alarm_pct.is_some());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::i18n::{set_language, TEST_LANG_LOCK};
+
+    #[test]
+    fn test_updated_text() {
+        let _lock = TEST_LANG_LOCK.lock().unwrap();
+        let time = glib::DateTime::new(&glib::TimeZone::utc(), 2026, 10, 16, 9, 5, 3.0).unwrap();
+
+        set_language("en");
+        assert_eq!(updated_text(&time), "Updated 09:05:03");
+        set_language("fr");
+        assert_eq!(updated_text(&time), "Mis à jour à 09:05:03");
+    }
+}
@@ -1,13 +1,24 @@
 //! User interface module for GTK4 application
 //!
-//! Contains main window, information tab, settings tab, UI preferences tab, theme management, and reusable components.
+//! - Main window and tabs: `app`, `info_tab`, `settings_tab`, `ui_tab`, `peripherals_tab`
+//! - Dialogs: `calibration`, `diagnostics`, `shortcuts`
+//! - Desktop integration: `notifications`, `tray`, `dbus`, `theme`
+//! - Widgets: `components`, `graphs`, `clamp`
 
 pub mod app;
+pub mod calibration;
+pub mod clamp;
 pub mod components;
+pub mod dbus;
+pub mod diagnostics;
+pub mod graphs;
 pub mod info_tab;
+pub mod notifications;
 pub mod peripherals_tab;
 pub mod settings_tab;
+pub mod shortcuts;
 pub mod theme;
+pub mod tray;
 pub mod ui_tab;
 
 pub use app::build_ui;
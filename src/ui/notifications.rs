@@ -0,0 +1,124 @@
+//! Desktop notification backends
+//!
+//! Sends notifications through the GTK application (GIO) by default, or
+//! through libnotify when built with the `notifications` feature.
+
+use gtk4::prelude::*;
+use gtk4::{gio, Application};
+use std::rc::Rc;
+
+use crate::core::alerts::discharge_body;
+use crate::core::i18n::{t, t_fmt};
+use crate::core::traits::NotificationService;
+
+/// Notification service backed by `gio::Application::send_notification`
+#[cfg_attr(feature = "notifications", allow(dead_code))]
+pub struct GioNotificationService {
+    app: glib::WeakRef<Application>,
+}
+
+#[cfg_attr(feature = "notifications", allow(dead_code))]
+impl GioNotificationService {
+    /// Creates a service sending through the given application
+    pub fn new(app: &Application) -> Self {
+        Self {
+            app: app.downgrade(),
+        }
+    }
+
+    /// Sends a notification, replacing any previous one with the same id
+    fn send(&self, id: &str, title: &str, body: &str, priority: gio::NotificationPriority) {
+        let Some(app) = self.app.upgrade() else {
+            return;
+        };
+
+        let notification = gio::Notification::new(title);
+        notification.set_body(Some(body));
+        notification.set_priority(priority);
+        crate::debug_ui!("Sending notification {id}");
+        app.send_notification(Some(id), &notification);
+    }
+}
+
+impl NotificationService for GioNotificationService {
+    fn notify_low_battery(&self, capacity: u8, time_remaining: Option<u32>) {
+        self.send(
+            "battery-alarm",
+            &t("notif_low_title"),
+            &discharge_body(capacity, time_remaining),
+            gio::NotificationPriority::High,
+        );
+    }
+
+    fn notify_critical_battery(&self, capacity: u8, time_remaining: Option<u32>) {
+        // Same id: replaces the low battery notification
+        self.send(
+            "battery-alarm",
+            &t("notif_critical_title"),
+            &discharge_body(capacity, time_remaining),
+            gio::NotificationPriority::Urgent,
+        );
+    }
+
+    fn notify_charge_complete(&self, stop: u8) {
+        self.send(
+            "battery-charged",
+            &t("notif_charged_title"),
+            &t_fmt("notif_charged_body", &[("stop", &stop.to_string())]),
+            gio::NotificationPriority::Normal,
+        );
+    }
+
+    fn notify_power_source(&self, ac_online: bool, capacity: u8) {
+        self.send(
+            "battery-power-source",
+            &t(if ac_online {
+                "notif_ac_plugged_title"
+            } else {
+                "notif_ac_unplugged_title"
+            }),
+            &format!("{capacity}%"),
+            gio::NotificationPriority::Low,
+        );
+    }
+
+    fn notify_threshold_applied(&self, stop: u8) {
+        self.send(
+            "battery-applied",
+            &t("notif_applied_title"),
+            &format!("{} {stop}%", t("threshold_stop")),
+            gio::NotificationPriority::Low,
+        );
+    }
+
+    fn notify_error(&self, msg: &str) {
+        self.send(
+            "battery-error",
+            &t("notif_error_title"),
+            msg,
+            gio::NotificationPriority::Normal,
+        );
+    }
+}
+
+/// Creates the notification service for this build
+///
+/// # Arguments
+///
+/// * `app` - GTK Application instance
+///
+/// # Returns
+///
+/// libnotify service with the `notifications` feature, GIO service otherwise
+#[cfg_attr(feature = "notifications", allow(unused_variables))]
+pub fn create_notification_service(app: &Application) -> Rc<dyn NotificationService> {
+    #[cfg(feature = "notifications")]
+    {
+        Rc::new(crate::core::traits::LibnotifyService)
+    }
+
+    #[cfg(not(feature = "notifications"))]
+    {
+        Rc::new(GioNotificationService::new(app))
+    }
+}
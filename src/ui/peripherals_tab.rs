@@ -1,30 +1,72 @@
 //! Peripherals tab displaying wireless device batteries
 //!
 //! Shows battery status for detected peripheral devices (mouse, keyboard, etc.)
-//! Each peripheral gets a single compact card with all information.
+//! Each peripheral gets a single compact card with all information, grouped
+//! by device category (lowest charge first). Cards are added and removed as
+//! devices connect and disconnect, and their title carries a ⚠️ below 15%
+//! charge. A rate line shows how fast each device charges or drains, and a
+//! time left row appears once enough discharge has been observed.
 
 use gtk4::prelude::*;
-use gtk4::{Box, Grid, Label};
+use gtk4::{Box, Frame, Grid, Label};
 use std::cell::Cell;
 
-use crate::core::i18n::t;
-use crate::core::PeripheralBattery;
+use crate::core::history::unix_now;
+use crate::core::i18n::{duration_text, t};
+use crate::core::{CapacityTracker, DeviceCategory, PeripheralBattery, TimeToEmpty};
+use crate::ui::clamp::{WidthClamp, TAB_MAX_WIDTH};
 use crate::ui::components::{create_content_box, InfoCard};
 
+/// Charge level under which the card title carries a warning
+const LOW_BATTERY_PERCENT: u8 = 15;
+
+/// Refreshes a vanished device stays listed (as disconnected) before its card is removed
+const REMOVE_AFTER_MISSED_REFRESHES: u32 = 3;
+
+/// Drain rate (percent per hour) shown as a danger
+const FAST_DRAIN_PERCENT_PER_HOUR: f32 = 10.0;
+
 #[derive(Clone)]
 pub struct UpdatablePeripheralsWidgets {
+    /// Notebook page, limiting the width of `content`
+    pub page: WidthClamp,
+    /// Tab content, holding one section per device category
+    pub content: Box,
+    /// Sections shown, in category order
+    pub sections: Vec<PeripheralSection>,
     pub devices: Vec<PeripheralDeviceWidgets>,
+    /// Recent levels of every device, for the rate line
+    pub capacity_tracker: CapacityTracker,
+}
+
+/// Header and cards of one device category
+#[derive(Clone)]
+pub struct PeripheralSection {
+    pub category: DeviceCategory,
+    /// Header + cards, child of the tab content
+    pub container: Box,
+    pub cards: Box,
 }
 
 #[derive(Clone)]
 pub struct PeripheralDeviceWidgets {
     pub stable_id: String,
+    pub category: DeviceCategory,
+    pub frame: Frame,
+    pub title_label: Option<Label>,
+    pub icon: &'static str,
     pub capacity_value: Label,
+    pub rate_value: Label,
+    /// Time left row (key + value), hidden while unknown
+    pub time_left_key: Label,
+    pub time_left_value: Label,
     pub status_value: Label,
     pub connection_value: Label,
     pub voltage_value: Label,
     pub name_value: Label,
     pub last_capacity: Cell<Option<u8>>,
+    /// Consecutive refreshes without the device
+    pub missed_refreshes: Cell<u32>,
 }
 
 fn remove_value_color_classes(label: &Label) {
@@ -47,13 +89,35 @@ fn value_label(text: &str) -> Label {
     label
 }
 
-fn attach_kv_row(grid: &Grid, row: i32, key: &str, value: &Label) {
+/// Attaches a "key : value" row, returning the key label
+fn attach_kv_row(grid: &Grid, row: i32, key: &str, value: &Label) -> Label {
     let key = key_label(&format!("{key} :"));
     grid.attach(&key, 0, row, 1, 1);
     grid.attach(value, 1, row, 1, 1);
+    key
+}
+
+/// Charge rate and time left of a device, from its recent levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CapacityTrend {
+    /// Percent per hour, if known
+    rate: Option<f32>,
+    time_to_empty: TimeToEmpty,
 }
 
-fn update_value_from_peripheral(widgets: &PeripheralDeviceWidgets, peripheral: &PeripheralBattery) {
+impl CapacityTrend {
+    /// Nothing known (e.g. device out of range)
+    const UNKNOWN: Self = Self {
+        rate: None,
+        time_to_empty: TimeToEmpty::Unknown,
+    };
+}
+
+fn update_value_from_peripheral(
+    widgets: &PeripheralDeviceWidgets,
+    peripheral: &PeripheralBattery,
+    trend: CapacityTrend,
+) {
     // Name (sysfs)
     widgets.name_value.set_text(&peripheral.name);
 
@@ -69,14 +133,26 @@ fn update_value_from_peripheral(widgets: &PeripheralDeviceWidgets, peripheral: &
     widgets
         .capacity_value
         .add_css_class(peripheral.get_capacity_css_class());
+    update_card_title(widgets, is_low_battery(peripheral.capacity_percent));
+
+    // Rate
+    remove_value_color_classes(&widgets.rate_value);
+    if let Some(rate) = trend.rate {
+        widgets.rate_value.set_text(&format_rate(rate));
+        widgets.rate_value.add_css_class(rate_css_class(rate));
+    } else {
+        widgets.rate_value.set_text("—");
+    }
+    update_time_left(widgets, trend.time_to_empty);
 
     // Status
     // Some HID++ devices report `Unknown` when plugged via USB even while charging.
     // Use a small heuristic + capacity delta to show something useful.
     let raw_status = peripheral.status.trim();
-    let (status_text, status_class) = if raw_status.eq_ignore_ascii_case("Full")
-        || peripheral.capacity_percent >= 100
-    {
+    let (status_text, status_class) = if peripheral.connected == Some(false) {
+        // Out of range (Bluetooth): the level shown is the last one known
+        ("—".to_string(), "color-warning")
+    } else if raw_status.eq_ignore_ascii_case("Full") || peripheral.capacity_percent >= 100 {
         (t("full"), "color-success")
     } else if raw_status.eq_ignore_ascii_case("Charging") {
         (t("charging"), "color-primary")
@@ -130,6 +206,207 @@ fn update_value_from_peripheral(widgets: &PeripheralDeviceWidgets, peripheral: &
     }
 }
 
+/// Formats a charge rate, e.g. "+12%/h" or "−3%/h"
+#[allow(clippy::cast_possible_truncation)]
+fn format_rate(rate: f32) -> String {
+    let rounded = rate.round() as i32;
+    match rounded {
+        0 => "0%/h".to_string(),
+        r if r > 0 => format!("+{r}%/h"),
+        r => format!("−{}%/h", r.unsigned_abs()),
+    }
+}
+
+/// Formats a time to empty, e.g. "3 hours 5 min" or "2 hours"; "—" for an idle device
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_time_to_empty(time_to_empty: TimeToEmpty) -> Option<String> {
+    match time_to_empty {
+        TimeToEmpty::Unknown => None,
+        TimeToEmpty::Idle => Some("—".to_string()),
+        TimeToEmpty::Hours(hours) => {
+            let minutes = (hours * 60.0).round() as u32;
+            Some(duration_text(minutes))
+        }
+    }
+}
+
+/// Shows the time left row once an estimate exists
+fn update_time_left(widgets: &PeripheralDeviceWidgets, time_to_empty: TimeToEmpty) {
+    let text = format_time_to_empty(time_to_empty);
+    widgets.time_left_key.set_visible(text.is_some());
+    widgets.time_left_value.set_visible(text.is_some());
+    if let Some(text) = text {
+        widgets.time_left_value.set_text(&text);
+    }
+}
+
+/// Color class of a charge rate: danger when draining fast
+fn rate_css_class(rate: f32) -> &'static str {
+    if rate <= -FAST_DRAIN_PERCENT_PER_HOUR {
+        "color-danger"
+    } else if rate <= -0.5 {
+        "color-warning"
+    } else if rate >= 0.5 {
+        "color-success"
+    } else {
+        "color-primary"
+    }
+}
+
+/// Whether a charge level deserves a warning on the card title
+const fn is_low_battery(capacity_percent: u8) -> bool {
+    capacity_percent < LOW_BATTERY_PERCENT
+}
+
+/// Card title, prefixed with ⚠️ when the battery is low
+fn card_title(icon: &str, low: bool) -> String {
+    let title = format!("{icon} {}", t("card_peripherals"));
+    if low {
+        format!("⚠️ {title}")
+    } else {
+        title
+    }
+}
+
+/// Shows or clears the low battery warning on the card title
+fn update_card_title(widgets: &PeripheralDeviceWidgets, low: bool) {
+    let Some(ref title_label) = widgets.title_label else {
+        return;
+    };
+    title_label.set_markup(&format!(
+        "<span size='large' weight='bold'>{}</span>",
+        card_title(widgets.icon, low)
+    ));
+    if low {
+        title_label.add_css_class("color-danger");
+    } else {
+        title_label.remove_css_class("color-danger");
+    }
+}
+
+/// Builds the card of one peripheral
+///
+/// # Arguments
+///
+/// * `peripheral` - Device shown on the card
+/// * `trend` - Charge rate and time left, if known
+///
+/// # Returns
+///
+/// Card frame + updatable widget handles
+fn build_peripheral_card(
+    peripheral: &PeripheralBattery,
+    trend: CapacityTrend,
+) -> (Frame, PeripheralDeviceWidgets) {
+    // Une seule carte par périphérique
+    let icon = peripheral.get_device_icon();
+    let (device_frame, device_box) = InfoCard::create(&card_title(icon, false));
+
+    // Marque et Modèle en grand en haut
+    let manufacturer_label = Label::new(None);
+    manufacturer_label.set_halign(gtk4::Align::Start);
+    manufacturer_label.set_markup(&format!(
+        "<span size='large' weight='bold'>{}</span>",
+        peripheral.manufacturer
+    ));
+    manufacturer_label.add_css_class("color-primary");
+    device_box.append(&manufacturer_label);
+
+    let model_label = Label::new(None);
+    model_label.set_halign(gtk4::Align::Start);
+    model_label.set_markup(&format!(
+        "<span size='medium'>{}</span>",
+        peripheral.model_name
+    ));
+    model_label.set_margin_bottom(8);
+    device_box.append(&model_label);
+
+    // Grille 2 colonnes pour organiser les infos (Label : Valeur)
+    let info_grid = Grid::new();
+    info_grid.set_column_spacing(20);
+    info_grid.set_row_spacing(4);
+    info_grid.set_halign(gtk4::Align::Fill);
+
+    // Make the value column expand a bit more nicely
+    info_grid.set_column_homogeneous(false);
+
+    let mut row = 0;
+
+    // Capacity (dynamic)
+    let capacity_value = Label::new(None);
+    capacity_value.set_halign(gtk4::Align::Start);
+    attach_kv_row(&info_grid, row, &t("capacity"), &capacity_value);
+    row += 1;
+
+    // Charge rate (dynamic)
+    let rate_value = value_label("—");
+    attach_kv_row(&info_grid, row, &t("charge_rate"), &rate_value);
+    row += 1;
+
+    // Time left (dynamic, hidden until estimated)
+    let time_left_value = value_label("");
+    let time_left_key = attach_kv_row(&info_grid, row, &t("time_to_empty"), &time_left_value);
+    row += 1;
+
+    // Status (dynamic)
+    let status_value = value_label(&peripheral.status);
+    attach_kv_row(&info_grid, row, &t("status"), &status_value);
+    row += 1;
+
+    // Connection (dynamic)
+    let connection_value = value_label("");
+    attach_kv_row(&info_grid, row, &t("connection"), &connection_value);
+    row += 1;
+
+    // Voltage (dynamic)
+    let voltage_value = value_label("");
+    attach_kv_row(&info_grid, row, &t("voltage"), &voltage_value);
+    row += 1;
+
+    // Type (static)
+    let type_value = value_label(&peripheral.device_type);
+    attach_kv_row(&info_grid, row, &t("device_type"), &type_value);
+    row += 1;
+
+    // Scope (static)
+    let scope_value = value_label(&peripheral.scope);
+    attach_kv_row(&info_grid, row, &t("device_scope"), &scope_value);
+    row += 1;
+
+    // Name (dynamic - can change across modes)
+    let name_value = value_label(&peripheral.name);
+    attach_kv_row(&info_grid, row, &t("name"), &name_value);
+    row += 1;
+
+    // Serial (static if present)
+    if let Some(serial) = peripheral.serial_number.as_deref() {
+        let serial_value = value_label(serial);
+        attach_kv_row(&info_grid, row, &t("serial_number"), &serial_value);
+    }
+
+    let device_widgets = PeripheralDeviceWidgets {
+        stable_id: peripheral.stable_id(),
+        category: peripheral.category(),
+        title_label: InfoCard::title_label(&device_frame),
+        frame: device_frame.clone(),
+        icon,
+        capacity_value,
+        rate_value,
+        time_left_key,
+        time_left_value,
+        status_value,
+        connection_value,
+        voltage_value,
+        name_value,
+        last_capacity: Cell::new(None),
+        missed_refreshes: Cell::new(0),
+    };
+    update_value_from_peripheral(&device_widgets, peripheral, trend);
+
+    device_box.append(&info_grid);
+    (device_frame, device_widgets)
+}
+
 /// Builds the Peripherals tab content
 ///
 /// # Arguments
@@ -138,121 +415,183 @@ fn update_value_from_peripheral(widgets: &PeripheralDeviceWidgets, peripheral: &
 ///
 /// # Returns
 ///
-/// Tab Box containing all peripheral information + updatable widget handles
-pub fn build_peripherals_tab(
-    peripherals: &[PeripheralBattery],
-) -> (Box, UpdatablePeripheralsWidgets) {
+/// Updatable widget handles, including the tab Box
+pub fn build_peripherals_tab(peripherals: &[PeripheralBattery]) -> UpdatablePeripheralsWidgets {
     crate::core::debug::debug_log_args(std::format_args!(
         "🕹️ [PERIPHERALS_TAB] Building peripherals tab with {} device(s)...",
         peripherals.len()
     ));
     let content_box = create_content_box(6);
     let mut updatable = UpdatablePeripheralsWidgets {
+        page: WidthClamp::new(&content_box, TAB_MAX_WIDTH),
+        content: content_box,
+        sections: Vec::new(),
         devices: Vec::new(),
+        capacity_tracker: CapacityTracker::new(),
     };
 
-    for peripheral in peripherals {
-        // Une seule carte par périphérique
-        let (device_frame, device_box) = InfoCard::create(&format!(
-            "{} {}",
-            peripheral.get_device_icon(),
-            t("card_peripherals")
-        ));
+    let mut sorted: Vec<&PeripheralBattery> = peripherals.iter().collect();
+    sorted.sort_by_key(|p| display_key(p));
+    for peripheral in sorted {
+        add_device_card(&mut updatable, peripheral);
+    }
 
-        // Marque et Modèle en grand en haut
-        let manufacturer_label = Label::new(None);
-        manufacturer_label.set_halign(gtk4::Align::Start);
-        manufacturer_label.set_markup(&format!(
-            "<span size='large' weight='bold'>{}</span>",
-            peripheral.manufacturer
-        ));
-        manufacturer_label.add_css_class("color-primary");
-        device_box.append(&manufacturer_label);
-
-        let model_label = Label::new(None);
-        model_label.set_halign(gtk4::Align::Start);
-        model_label.set_markup(&format!(
-            "<span size='medium'>{}</span>",
-            peripheral.model_name
-        ));
-        model_label.set_margin_bottom(8);
-        device_box.append(&model_label);
-
-        // Grille 2 colonnes pour organiser les infos (Label : Valeur)
-        let info_grid = Grid::new();
-        info_grid.set_column_spacing(20);
-        info_grid.set_row_spacing(4);
-        info_grid.set_halign(gtk4::Align::Fill);
-
-        // Make the value column expand a bit more nicely
-        info_grid.set_column_homogeneous(false);
-
-        let mut row = 0;
-
-        // Capacity (dynamic)
-        let capacity_value = Label::new(None);
-        capacity_value.set_halign(gtk4::Align::Start);
-        attach_kv_row(&info_grid, row, &t("capacity"), &capacity_value);
-        row += 1;
-
-        // Status (dynamic)
-        let status_value = value_label(&peripheral.status);
-        attach_kv_row(&info_grid, row, &t("status"), &status_value);
-        row += 1;
-
-        // Connection (dynamic)
-        let connection_value = value_label("");
-        attach_kv_row(&info_grid, row, &t("connection"), &connection_value);
-        row += 1;
-
-        // Voltage (dynamic)
-        let voltage_value = value_label("");
-        attach_kv_row(&info_grid, row, &t("voltage"), &voltage_value);
-        row += 1;
-
-        // Type (static)
-        let type_value = value_label(&peripheral.device_type);
-        attach_kv_row(&info_grid, row, &t("device_type"), &type_value);
-        row += 1;
-
-        // Scope (static)
-        let scope_value = value_label(&peripheral.scope);
-        attach_kv_row(&info_grid, row, &t("device_scope"), &scope_value);
-        row += 1;
-
-        // Name (dynamic - can change across modes)
-        let name_value = value_label(&peripheral.name);
-        attach_kv_row(&info_grid, row, &t("name"), &name_value);
-        row += 1;
-
-        // Serial (static if present)
-        if let Some(serial) = peripheral.serial_number.as_deref() {
-            let serial_value = value_label(serial);
-            attach_kv_row(&info_grid, row, &t("serial_number"), &serial_value);
-        }
+    updatable
+}
 
-        let device_widgets = PeripheralDeviceWidgets {
-            stable_id: peripheral.stable_id(),
-            capacity_value: capacity_value.clone(),
-            status_value: status_value.clone(),
-            connection_value: connection_value.clone(),
-            voltage_value: voltage_value.clone(),
-            name_value: name_value.clone(),
-            last_capacity: Cell::new(None),
-        };
-        update_value_from_peripheral(&device_widgets, peripheral);
-        updatable.devices.push(device_widgets);
+/// Display order: by category, then lowest charge first
+fn display_key(peripheral: &PeripheralBattery) -> (DeviceCategory, u8, String) {
+    (
+        peripheral.category(),
+        peripheral.capacity_percent,
+        peripheral.stable_id(),
+    )
+}
+
+/// Index at which a section goes to keep the categories in order
+fn section_position(shown: &[DeviceCategory], category: DeviceCategory) -> usize {
+    shown.partition_point(|shown| *shown < category)
+}
+
+/// Returns the cards box of a category, creating its section if needed
+fn section_cards(widgets: &mut UpdatablePeripheralsWidgets, category: DeviceCategory) -> Box {
+    if let Some(section) = widgets.sections.iter().find(|s| s.category == category) {
+        return section.cards.clone();
+    }
+
+    let container = Box::new(gtk4::Orientation::Vertical, 6);
+    let header = Label::new(None);
+    header.set_halign(gtk4::Align::Start);
+    header.set_markup(&format!(
+        "<span size='large' weight='bold'>{} {}</span>",
+        category.icon(),
+        t(category.label_key())
+    ));
+    header.set_margin_top(6);
+    container.append(&header);
+    let cards = Box::new(gtk4::Orientation::Vertical, 6);
+    container.append(&cards);
+
+    let shown: Vec<DeviceCategory> = widgets.sections.iter().map(|s| s.category).collect();
+    let position = section_position(&shown, category);
+    let previous = position
+        .checked_sub(1)
+        .map(|index| widgets.sections[index].container.clone());
+    widgets
+        .content
+        .insert_child_after(&container, previous.as_ref());
+    widgets.sections.insert(
+        position,
+        PeripheralSection {
+            category,
+            container,
+            cards: cards.clone(),
+        },
+    );
+    cards
+}
+
+/// Adds a card at the end of its category section
+fn add_device_card(widgets: &mut UpdatablePeripheralsWidgets, peripheral: &PeripheralBattery) {
+    let trend = track_capacity(&mut widgets.capacity_tracker, peripheral);
+    let (device_frame, device_widgets) = build_peripheral_card(peripheral, trend);
+    section_cards(widgets, device_widgets.category).append(&device_frame);
+    widgets.devices.push(device_widgets);
+}
 
-        device_box.append(&info_grid);
-        content_box.append(&device_frame);
+/// Removes a card, and its section once empty
+fn remove_device_card(widgets: &mut UpdatablePeripheralsWidgets, device: &PeripheralDeviceWidgets) {
+    widgets.capacity_tracker.forget(&device.stable_id);
+    let Some(index) = widgets
+        .sections
+        .iter()
+        .position(|s| s.category == device.category)
+    else {
+        return;
+    };
+    let section = &widgets.sections[index];
+    section.cards.remove(&device.frame);
+    if section.cards.first_child().is_none() {
+        widgets.content.remove(&section.container);
+        widgets.sections.remove(index);
     }
+}
 
-    (content_box, updatable)
+/// Records the level of a device and returns its charge rate and time left
+///
+/// Out of range devices only repeat their last known level, so they are
+/// not recorded.
+fn track_capacity(tracker: &mut CapacityTracker, peripheral: &PeripheralBattery) -> CapacityTrend {
+    if peripheral.connected == Some(false) {
+        return CapacityTrend::UNKNOWN;
+    }
+    let id = peripheral.stable_id();
+    tracker.record(&id, unix_now(), peripheral.capacity_percent);
+    CapacityTrend {
+        rate: tracker.rate_per_hour(&id),
+        time_to_empty: tracker.time_to_empty(&id),
+    }
 }
 
-pub fn update_peripherals_tab(widgets: &UpdatablePeripheralsWidgets) {
-    let peripherals = PeripheralBattery::detect_all();
+/// Marks a card whose device vanished as disconnected
+fn mark_disconnected(device_widgets: &PeripheralDeviceWidgets) {
+    device_widgets.rate_value.set_text("—");
+    remove_value_color_classes(&device_widgets.rate_value);
+    update_time_left(device_widgets, TimeToEmpty::Unknown);
+
+    device_widgets.status_value.set_text("—");
+    remove_value_color_classes(&device_widgets.status_value);
+    device_widgets.status_value.add_css_class("color-warning");
+
+    device_widgets.connection_value.set_text(&t("disconnected"));
+    remove_value_color_classes(&device_widgets.connection_value);
+    device_widgets
+        .connection_value
+        .add_css_class("color-danger");
+
+    device_widgets.voltage_value.set_text("N/A");
+    remove_value_color_classes(&device_widgets.voltage_value);
+    device_widgets.voltage_value.add_css_class("color-warning");
+}
+
+/// Compares the cards shown with the devices detected
+///
+/// # Arguments
+///
+/// * `known` - Stable ids of the existing cards
+/// * `detected` - Stable ids of the devices detected now
+///
+/// # Returns
+///
+/// Indexes in `detected` of the devices without a card
+fn new_device_indexes(known: &[&str], detected: &[String]) -> Vec<usize> {
+    detected
+        .iter()
+        .enumerate()
+        .filter(|(_, id)| !known.contains(&id.as_str()))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Whether a card absent for `missed_refreshes` refreshes in a row should go
+const fn is_expired(missed_refreshes: u32) -> bool {
+    missed_refreshes > REMOVE_AFTER_MISSED_REFRESHES
+}
 
+/// Refreshes the peripheral cards
+///
+/// Cards of detected devices are updated, new devices get a card, and
+/// devices absent for more than `REMOVE_AFTER_MISSED_REFRESHES` refreshes
+/// lose theirs (they are only marked disconnected until then).
+///
+/// # Arguments
+///
+/// * `widgets` - Peripherals tab widgets
+/// * `peripherals` - Devices detected now
+pub fn update_peripherals_tab(
+    widgets: &mut UpdatablePeripheralsWidgets,
+    peripherals: &[PeripheralBattery],
+) {
     crate::core::debug::debug_log_args(std::format_args!(
         "🔄 [UPDATE] Peripherals refresh: detected={} widgets={}",
         peripherals.len(),
@@ -264,22 +603,151 @@ pub fn update_peripherals_tab(widgets: &UpdatablePeripheralsWidgets) {
             .iter()
             .find(|p| p.stable_id() == device_widgets.stable_id)
         {
-            update_value_from_peripheral(device_widgets, peripheral);
+            device_widgets.missed_refreshes.set(0);
+            let trend = track_capacity(&mut widgets.capacity_tracker, peripheral);
+            update_value_from_peripheral(device_widgets, peripheral, trend);
         } else {
-            // Device disappeared; keep it visible but mark as disconnected.
-            device_widgets.status_value.set_text("—");
-            remove_value_color_classes(&device_widgets.status_value);
-            device_widgets.status_value.add_css_class("color-warning");
-
-            device_widgets.connection_value.set_text(&t("disconnected"));
-            remove_value_color_classes(&device_widgets.connection_value);
+            // Device disappeared; keep it visible for a while, marked as disconnected.
             device_widgets
-                .connection_value
-                .add_css_class("color-danger");
-
-            device_widgets.voltage_value.set_text("N/A");
-            remove_value_color_classes(&device_widgets.voltage_value);
-            device_widgets.voltage_value.add_css_class("color-warning");
+                .missed_refreshes
+                .set(device_widgets.missed_refreshes.get() + 1);
+            mark_disconnected(device_widgets);
         }
     }
+
+    let (expired, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut widgets.devices)
+        .into_iter()
+        .partition(|d| is_expired(d.missed_refreshes.get()));
+    widgets.devices = kept;
+    for device_widgets in &expired {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🕹️ [PERIPHERALS_TAB] Removing card of {}",
+            device_widgets.stable_id
+        ));
+        remove_device_card(widgets, device_widgets);
+    }
+
+    // Existing cards keep their place; new ones go to the end of their section
+    let detected_ids: Vec<String> = peripherals
+        .iter()
+        .map(PeripheralBattery::stable_id)
+        .collect();
+    let known_ids: Vec<&str> = widgets
+        .devices
+        .iter()
+        .map(|d| d.stable_id.as_str())
+        .collect();
+    let mut new_devices: Vec<&PeripheralBattery> = new_device_indexes(&known_ids, &detected_ids)
+        .into_iter()
+        .map(|index| &peripherals[index])
+        .collect();
+    new_devices.sort_by_key(|p| display_key(p));
+    for peripheral in new_devices {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🕹️ [PERIPHERALS_TAB] Adding card for {}",
+            peripheral.stable_id()
+        ));
+        add_device_card(widgets, peripheral);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_device_indexes() {
+        let detected = vec!["serial:a".to_string(), "serial:b".to_string()];
+        assert_eq!(new_device_indexes(&["serial:a"], &detected), vec![1]);
+        assert_eq!(new_device_indexes(&[], &detected), vec![0, 1]);
+        assert!(new_device_indexes(&["serial:a", "serial:b"], &detected).is_empty());
+        assert!(new_device_indexes(&["serial:a"], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_rate_display() {
+        assert_eq!(format_rate(12.4), "+12%/h");
+        assert_eq!(format_rate(-3.2), "−3%/h");
+        assert_eq!(format_rate(0.3), "0%/h");
+
+        assert_eq!(rate_css_class(-15.0), "color-danger");
+        assert_eq!(rate_css_class(-3.0), "color-warning");
+        assert_eq!(rate_css_class(0.0), "color-primary");
+        assert_eq!(rate_css_class(12.0), "color-success");
+    }
+
+    #[test]
+    fn test_time_to_empty_display() {
+        let _lock = crate::core::i18n::TEST_LANG_LOCK.lock().unwrap();
+        crate::core::i18n::set_language("en");
+        assert_eq!(format_time_to_empty(TimeToEmpty::Unknown), None);
+        assert_eq!(
+            format_time_to_empty(TimeToEmpty::Idle).as_deref(),
+            Some("—")
+        );
+        assert_eq!(
+            format_time_to_empty(TimeToEmpty::Hours(3.09)).as_deref(),
+            Some("3 hours 5 min")
+        );
+        crate::core::i18n::set_language("fr");
+    }
+
+    #[test]
+    fn test_low_battery_title() {
+        assert!(is_low_battery(5));
+        assert!(is_low_battery(LOW_BATTERY_PERCENT - 1));
+        assert!(!is_low_battery(LOW_BATTERY_PERCENT));
+        assert!(!is_low_battery(80));
+
+        let low = card_title("🖱️", is_low_battery(5));
+        assert!(low.starts_with("⚠️ 🖱️ "));
+        // Charged again: the glyph goes away
+        let charged = card_title("🖱️", is_low_battery(60));
+        assert!(charged.starts_with("🖱️ "));
+        assert!(!charged.contains("⚠️"));
+    }
+
+    #[test]
+    fn test_section_position() {
+        use DeviceCategory::{Gamepad, Headset, Keyboard, Mouse, Other};
+
+        assert_eq!(section_position(&[], Headset), 0);
+        assert_eq!(section_position(&[Mouse, Headset], Keyboard), 1);
+        assert_eq!(section_position(&[Mouse, Headset], Other), 2);
+        assert_eq!(section_position(&[Keyboard, Gamepad], Mouse), 0);
+    }
+
+    #[test]
+    fn test_display_order() {
+        let device = |model: &str, serial: &str, capacity: u8| PeripheralBattery {
+            name: format!("hidpp_battery_{serial}"),
+            manufacturer: "Logitech".to_string(),
+            model_name: model.to_string(),
+            status: "Discharging".to_string(),
+            capacity_percent: capacity,
+            voltage_now: None,
+            serial_number: Some(serial.to_string()),
+            online: true,
+            device_type: "Battery".to_string(),
+            scope: "Device".to_string(),
+            connected: None,
+        };
+        let mut devices = [
+            device("G733 Headset", "h1", 70),
+            device("MX Keys Keyboard", "k1", 90),
+            device("G Pro Mouse", "m1", 40),
+            device("Zone Headset", "h2", 20),
+        ];
+
+        devices.sort_by_key(display_key);
+        let order: Vec<String> = devices.iter().map(PeripheralBattery::stable_id).collect();
+        assert_eq!(order, ["serial:m1", "serial:k1", "serial:h2", "serial:h1"]);
+    }
+
+    #[test]
+    fn test_cards_expire_after_missed_refreshes() {
+        assert!(!is_expired(0));
+        assert!(!is_expired(REMOVE_AFTER_MISSED_REFRESHES));
+        assert!(is_expired(REMOVE_AFTER_MISSED_REFRESHES + 1));
+    }
 }
@@ -4,223 +4,238 @@
 //! systemd service, and view hardware support information.
 
 use gtk4::prelude::*;
-use gtk4::{Adjustment, Box, Button, Label, Orientation, ScrolledWindow, SpinButton, Switch};
-use std::fmt::Write;
+use gtk4::{
+    gio, Adjustment, Box, Button, DropDown, Entry, Label, LinkButton, MessageDialog, MessageType,
+    Orientation, ResponseType, ScrolledWindow, SpinButton, Spinner, StringList, Switch,
+    ToggleButton,
+};
+use std::cell::{Cell, RefCell};
 use std::path::Path;
-use std::process::Command;
-
-use crate::core::i18n::t;
+use std::rc::Rc;
+
+use crate::core::battery::{
+    compute_alarm_raw, CapacityUnit, ChargeBehaviour, RequestedSettings, SettingChange,
+    SettingMismatch, ThresholdChangeSet,
+};
+use crate::core::config;
+use crate::core::i18n::{t, t_fmt};
+use crate::core::kernel;
+use crate::core::presets::PRESETS;
+use crate::core::profiles::{self, Profile};
+use crate::core::resume::{self, ReapplyOutcome};
+use crate::core::service;
+use crate::core::systemd::UnitState;
+use crate::core::threshold_apply::{
+    self, validate_threshold_values, ApplyEnvironment, ApplyPlan, ApplyRequest, ThresholdError,
+};
+use crate::core::vendor_detection::{hp_bioscfg_present, ThresholdControl, VendorType};
+use crate::core::writer::{execute_with_pkexec, failure_text, ApplyResult};
 use crate::core::{BatteryInfo, VendorInfo};
+use crate::ui::clamp::{WidthClamp, TAB_MAX_WIDTH};
 use crate::ui::components::InfoCard;
+use crate::ui::shortcuts::ACTION_APPLY_SETTINGS;
 
+/// Whether the restore unit is installed (last systemd state, else the unit file locations)
 fn service_unit_exists() -> bool {
-    [
-        "/etc/systemd/system/battery-manager.service",
-        "/usr/lib/systemd/system/battery-manager.service",
-        "/lib/systemd/system/battery-manager.service",
-    ]
-    .into_iter()
-    .any(|p| Path::new(p).is_file())
+    service::cached_unit_state().map_or_else(service::unit_file_exists, |state| {
+        state != UnitState::NotInstalled
+    })
 }
 
-/// Truncates a string for logging purposes
-fn truncate_for_log(s: &str, max_chars: usize) -> String {
-    if s.chars().count() <= max_chars {
-        return s.to_string();
-    }
-    let mut out = s.chars().take(max_chars).collect::<String>();
-    out.push('…');
-    out
+/// Reflects the restore unit state on the service switch
+///
+/// A missing unit greys the switch out, shows why and offers to install it.
+///
+/// # Arguments
+///
+/// * `switch` - Service switch
+/// * `missing_note` - Note shown when the unit is not installed
+/// * `install_button` - Button shown when the unit is not installed
+/// * `state` - Unit state
+fn show_service_state(
+    switch: &Switch,
+    missing_note: &Label,
+    install_button: &Button,
+    state: UnitState,
+) {
+    switch.set_active(state == UnitState::Active);
+    switch.set_sensitive(state != UnitState::NotInstalled);
+    missing_note.set_visible(state == UnitState::NotInstalled);
+    install_button.set_visible(state == UnitState::NotInstalled);
 }
 
-/// Computed alarm value from battery `charge_full`/`energy_full` and percentage
-fn compute_alarm_value(battery_name: &str, alarm_pct: f32) -> String {
-    let charge_full_str = std::fs::read_to_string(format!(
-        "/sys/class/power_supply/{battery_name}/charge_full"
-    ))
-    .or_else(|charge_err| {
-        crate::core::debug::debug_log_args(std::format_args!(
-            "⚠️ [SETTINGS_TAB] charge_full read failed, trying energy_full: {charge_err}"
-        ));
-        std::fs::read_to_string(format!(
-            "/sys/class/power_supply/{battery_name}/energy_full"
-        ))
-    });
-
-    match charge_full_str {
-        Ok(charge_full) => match charge_full.trim().parse::<u64>() {
-            Ok(full_value) => {
-                #[allow(
-                    clippy::cast_precision_loss,
-                    clippy::cast_possible_truncation,
-                    clippy::cast_sign_loss
-                )]
-                let alarm_value = (full_value as f32 * (alarm_pct / 100.0)) as u64;
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "🧮 [SETTINGS_TAB] Alarm computed from full_value={full_value}: alarm_value={alarm_value}"
-                ));
-                alarm_value.to_string()
-            }
-            Err(parse_err) => {
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "⚠️ [SETTINGS_TAB] Failed to parse charge_full/energy_full value: {parse_err} (raw='{}')",
-                    truncate_for_log(charge_full.trim(), 80)
-                ));
-                "0".to_string()
-            }
-        },
-        Err(read_err) => {
-            crate::core::debug::debug_log_args(std::format_args!(
-                "⚠️ [SETTINGS_TAB] Failed to read charge_full/energy_full: {read_err}; falling back to alarm_value=0"
-            ));
-            "0".to_string()
+/// Checks the unit state off the main thread, then updates the service widgets
+fn follow_service_state(switch: &Switch, missing_note: &Label, install_button: &Button) {
+    let switch = switch.downgrade();
+    let note = missing_note.downgrade();
+    let button = install_button.downgrade();
+    glib::spawn_future_local(async move {
+        let state = service::unit_state().await;
+        if let (Some(switch), Some(note), Some(button)) =
+            (switch.upgrade(), note.upgrade(), button.upgrade())
+        {
+            show_service_state(&switch, &note, &button, state);
         }
-    }
+    });
 }
 
-/// Builds the shell script to apply thresholds, alarm, and service configuration
-fn build_apply_script(
-    battery_name: &str,
-    start: u8,
-    stop: u8,
-    has_start: bool,
-    alarm_value_str: &str,
-    enable_service: bool,
-) -> String {
-    let base_path = format!("/sys/class/power_supply/{battery_name}");
-    let alarm_path = format!("{base_path}/alarm");
-    let start_paths = [
-        format!("{base_path}/charge_control_start_threshold"),
-        format!("{base_path}/charge_start_threshold"),
-    ];
-    let stop_paths = [
-        format!("{base_path}/charge_control_end_threshold"),
-        format!("{base_path}/charge_stop_threshold"),
-        format!("{base_path}/charge_end_threshold"),
-    ];
+/// Computed alarm value from battery `charge_full`/`energy_full` and percentage
+///
+/// The alarm is written in the unit of the battery's capacity interface
+/// (µAh for `charge_*`, µWh for `energy_*`).
+fn compute_alarm_value(battery_name: &str, alarm_pct: f32) -> u64 {
+    let Some((unit, full_value)) =
+        CapacityUnit::detect(&format!("/sys/class/power_supply/{battery_name}"))
+    else {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [SETTINGS_TAB] Failed to read charge_full/energy_full of {battery_name}; falling back to alarm_value=0"
+        ));
+        return 0;
+    };
 
+    let alarm = compute_alarm_raw(full_value, unit, alarm_pct);
     crate::core::debug::debug_log_args(std::format_args!(
-        "🗂️ [SETTINGS_TAB] Sysfs paths: alarm_path='{alarm_path}' exists={}, start_paths_exist=[{}, {}], stop_paths_exist=[{}, {}, {}]",
-        Path::new(&alarm_path).is_file(),
-        Path::new(&start_paths[0]).is_file(),
-        Path::new(&start_paths[1]).is_file(),
-        Path::new(&stop_paths[0]).is_file(),
-        Path::new(&stop_paths[1]).is_file(),
-        Path::new(&stop_paths[2]).is_file(),
+        "🧮 [SETTINGS_TAB] Alarm computed from full_value={full_value} ({unit:?}): alarm_value={} ({alarm})",
+        alarm.raw
     ));
+    alarm.raw
+}
 
-    let mut script = String::new();
-
-    // Create config directory
-    script.push_str("mkdir -p /etc/battery-manager; ");
+/// Reads the stop threshold back after a successful apply
+///
+/// pkexec only reports that the helper ran; some firmwares silently clamp
+/// or ignore the value, so the kernel's view is checked against the request.
+///
+/// # Arguments
+///
+/// * `battery` - Battery name (e.g., "BAT0")
+/// * `expected_stop` - Stop threshold that was written
+///
+/// # Returns
+///
+/// * `Ok(())` - Value kept, or no readable threshold file
+/// * `Err(String)` - Translated warning with the value the kernel reports
+fn verify_threshold_applied(battery: &str, expected_stop: u8) -> Result<(), String> {
+    let stop_paths = &VendorInfo::detect_cached(battery)
+        .threshold_files
+        .stop_paths;
+    let Some((path, raw)) = threshold_apply::stop_read_back_paths(
+        Path::new("/sys/class/power_supply"),
+        battery,
+        stop_paths,
+    )
+    .into_iter()
+    .find_map(|path| std::fs::read_to_string(&path).ok().map(|raw| (path, raw))) else {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [SETTINGS_TAB] No stop threshold file readable for {battery}, skipping read-back"
+        ));
+        return Ok(());
+    };
 
-    // Write thresholds (values are pre-validated numeric strings)
-    for path in &start_paths {
-        let _ = write!(&mut script, "[ -f {path} ] && echo {start} > {path}; ");
-    }
-    for path in &stop_paths {
-        let _ = write!(&mut script, "[ -f {path} ] && echo {stop} > {path}; ");
+    match threshold_apply::stop_read_back_mismatch(expected_stop, &raw) {
+        Some(actual) => {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [SETTINGS_TAB] {} reports {actual}% instead of {expected_stop}%",
+                path.display()
+            ));
+            Err(t_fmt(
+                "threshold_not_applied",
+                &[("actual", &actual.to_string())],
+            ))
+        }
+        None => Ok(()),
     }
+}
 
-    // Write alarm
-    let _ = write!(
-        &mut script,
-        "[ -f {alarm_path} ] && echo {alarm_value_str} > {alarm_path}; "
-    );
+/// Runs the apply plan in a worker thread, keeping the window responsive
+///
+/// The spinner turns while the polkit dialog is up and the helper runs;
+/// `on_done` gets the result back on the main loop.
+///
+/// # Arguments
+///
+/// * `plan` - Writes and service action for the helper
+/// * `spinner` - Spinner shown next to the status message
+/// * `on_done` - Called with the result
+fn execute_in_background(
+    plan: ApplyPlan,
+    spinner: &Spinner,
+    on_done: impl FnOnce(ApplyResult) + 'static,
+) {
+    spinner.set_visible(true);
+    spinner.start();
+    let spinner = spinner.clone();
+    glib::spawn_future_local(async move {
+        let result = gio::spawn_blocking(move || execute_with_pkexec(&plan))
+            .await
+            .unwrap_or_else(|_| ApplyResult::Failed(t("error_execution")));
+        spinner.stop();
+        spinner.set_visible(false);
+        on_done(result);
+    });
+}
 
-    // Save config (START_THRESHOLD only if supported)
-    let config_content = if has_start {
-        format!("START_THRESHOLD={start}\\nSTOP_THRESHOLD={stop}\\n")
-    } else {
-        format!("STOP_THRESHOLD={stop}\\n")
+/// Shows a failed apply result in the status message
+fn show_apply_failure(status_message: &Label, result: &ApplyResult) {
+    let Some(detail) = failure_text(result) else {
+        return;
     };
-    let _ = write!(
-        &mut script,
-        "echo '{config_content}' > /etc/battery-manager/{battery_name}.conf; "
-    );
-
-    // Manage service
-    if enable_service {
-        script.push_str("systemctl enable battery-manager.service; ");
-        script.push_str("systemctl start battery-manager.service; ");
+    let detail = glib::markup_escape_text(&detail);
+    if matches!(result, ApplyResult::Cancelled) {
+        // Not an error: the user closed the password prompt
+        status_message.set_markup(&format!("<span>🔒 {detail}</span>"));
+        set_status_class(status_message, "color-warning");
     } else {
-        script.push_str("systemctl disable battery-manager.service 2>/dev/null || true; ");
-        script.push_str("systemctl stop battery-manager.service 2>/dev/null || true; ");
+        status_message.set_markup(&format!("<span>{}: {detail}</span>", t("error")));
+        set_status_class(status_message, "color-danger");
     }
-
-    crate::core::debug::debug_log_args(std::format_args!(
-        "🔧 [SETTINGS_TAB] Prepared script: bytes={}, service_enable={enable_service}",
-        script.len()
-    ));
-
-    script
 }
 
-/// Result of executing settings via pkexec
-enum ApplyResult {
-    /// Settings applied successfully
-    Success,
-    /// pkexec execution failed
-    Failed(String),
-    /// pkexec not installed
-    NoPkexec,
+/// Shows the last re-apply after resume of a battery
+///
+/// # Arguments
+///
+/// * `label` - Label under the re-apply switch
+/// * `battery_name` - Battery name (e.g., "BAT0")
+pub fn update_resume_label(label: &Label, battery_name: &str) {
+    for class in ["color-success", "color-danger", "dim-label"] {
+        label.remove_css_class(class);
+    }
+    let Some(last) = resume::last_reapply(battery_name) else {
+        label.set_text(&t("resume_never"));
+        label.add_css_class("dim-label");
+        return;
+    };
+    let (result, class) = match &last.outcome {
+        ReapplyOutcome::AlreadySet => (t("resume_kept"), "dim-label"),
+        ReapplyOutcome::Applied => (t("resume_reapplied"), "color-success"),
+        ReapplyOutcome::Failed(error) => {
+            (t_fmt("resume_failed", &[("error", error)]), "color-danger")
+        }
+    };
+    label.set_text(&t_fmt(
+        "resume_last",
+        &[("time", &last.time), ("result", &result)],
+    ));
+    label.add_css_class(class);
 }
 
-/// Executes the apply script via pkexec and returns the result
-fn execute_with_pkexec(script: &str) -> ApplyResult {
-    let pkexec_check = Command::new("which").arg("pkexec").output();
-
-    match pkexec_check {
-        Ok(result) if result.status.success() => {
-            crate::core::debug::debug_log(
-                "🔐 [SETTINGS_TAB] pkexec found, executing script via pkexec",
-            );
-            let output = Command::new("pkexec")
-                .arg("sh")
-                .arg("-c")
-                .arg(script)
-                .output();
-
-            match output {
-                Ok(result) if result.status.success() => {
-                    crate::core::debug::debug_log("✅ [SETTINGS_TAB] pkexec execution succeeded");
-                    ApplyResult::Success
-                }
-                Ok(result) => {
-                    let stderr = String::from_utf8_lossy(&result.stderr);
-                    let stdout = String::from_utf8_lossy(&result.stdout);
-                    let code = result.status.code();
-                    let stderr_preview = truncate_for_log(stderr.trim(), 400);
-                    let stdout_preview = truncate_for_log(stdout.trim(), 400);
-
-                    let ui_error = if !stderr.trim().is_empty() {
-                        stderr_preview.clone()
-                    } else if !stdout.trim().is_empty() {
-                        stdout_preview.clone()
-                    } else {
-                        format!("pkexec returned non-zero status: {code:?}")
-                    };
-
-                    crate::core::debug::debug_log_args(std::format_args!(
-                        "❌ [SETTINGS_TAB] Script execution failed: code={code:?} stdout='{stdout_preview}' stderr='{stderr_preview}'"
-                    ));
-                    ApplyResult::Failed(ui_error)
-                }
-                Err(err) => {
-                    crate::core::debug::debug_log_args(std::format_args!(
-                        "❌ [SETTINGS_TAB] Execution error: {err}"
-                    ));
-                    ApplyResult::Failed(format!("{}: {err}", t("error_execution")))
-                }
-            }
-        }
-        _ => {
-            crate::core::debug::debug_log(
-                "❌ [SETTINGS_TAB] pkexec not found (which pkexec failed or returned non-zero)",
-            );
-            ApplyResult::NoPkexec
-        }
-    }
+/// Formats read-back mismatches for the status message
+fn format_mismatches(mismatches: &[SettingMismatch]) -> String {
+    mismatches
+        .iter()
+        .map(|m| {
+            format!(
+                "{}: {} {}, {} {}",
+                t(m.setting),
+                t("requested"),
+                m.requested,
+                t("actual"),
+                m.actual
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
 }
 
 /// Updates the status message label with appropriate color class
@@ -228,6 +243,7 @@ fn set_status_class(label: &Label, class: &str) {
     label.remove_css_class("color-success");
     label.remove_css_class("color-warning");
     label.remove_css_class("color-danger");
+    label.remove_css_class("dim-label");
     label.add_css_class(class);
 }
 
@@ -266,8 +282,66 @@ fn create_vendor_card(vendor_info: &VendorInfo) -> gtk4::Frame {
         t("threshold_stop"),
         stop_support
     ));
+    let detected_files: Vec<&str> = vendor_info
+        .threshold_files
+        .start_paths
+        .iter()
+        .chain(&vendor_info.threshold_files.stop_paths)
+        .map(String::as_str)
+        .collect();
+    if !detected_files.is_empty() {
+        support_label.set_tooltip_text(Some(&detected_files.join("\n")));
+    }
     vendor_box.append(&support_label);
 
+    let docs_link = LinkButton::with_label(
+        vendor_info.get_documentation_url().unwrap_or_default(),
+        &t("learn_more"),
+    );
+    docs_link.set_halign(gtk4::Align::Start);
+    docs_link.set_visible(vendor_info.get_documentation_url().is_some());
+    vendor_box.append(&docs_link);
+
+    // HP: thresholds depend on the BIOS settings driver being loaded
+    if vendor_info.vendor_type == VendorType::Hp {
+        let hp_label = Label::new(None);
+        hp_label.set_halign(gtk4::Align::Start);
+        hp_label.set_wrap(true);
+        let (hp_status, hp_hint) = if hp_bioscfg_present() {
+            ("✅", String::new())
+        } else {
+            ("❌", format!(" — {}", t("hp_bioscfg_missing")))
+        };
+        hp_label.set_markup(&format!(
+            "<span size='small'>{}: {hp_status}{hp_hint}</span>",
+            t("hp_bioscfg")
+        ));
+        vendor_box.append(&hp_label);
+    }
+
+    // Threshold driver newer than the running kernel: explains missing files
+    if let Some((major, minor)) = vendor_info.vendor_type.min_kernel() {
+        if !kernel::kernel_at_least(major, minor) {
+            let running = kernel::running_version()
+                .map_or_else(|| t("unknown"), |version| version.to_string());
+            let kernel_label = Label::new(None);
+            kernel_label.set_halign(gtk4::Align::Start);
+            kernel_label.set_wrap(true);
+            kernel_label.add_css_class("color-warning");
+            kernel_label.set_markup(&format!(
+                "<span size='small'>ℹ️ {}</span>",
+                glib::markup_escape_text(&t_fmt(
+                    "kernel_too_old",
+                    &[
+                        ("required", &format!("{major}.{minor}")),
+                        ("running", &running)
+                    ],
+                ))
+            ));
+            vendor_box.append(&kernel_label);
+        }
+    }
+
     vendor_frame
 }
 
@@ -295,26 +369,518 @@ fn create_threshold_row(
     (row, spin)
 }
 
+/// Creates the `charge_behaviour` row: one linked toggle button per kernel mode
+///
+/// # Returns
+///
+/// The row and each button with the mode it writes
+fn create_charge_behaviour_row(behaviour: &ChargeBehaviour) -> (Box, Vec<(ToggleButton, String)>) {
+    let row = Box::new(Orientation::Horizontal, 10);
+    row.set_tooltip_text(Some(t("charge_behaviour_tooltip").as_str()));
+
+    let label = Label::new(None);
+    label.set_halign(gtk4::Align::Start);
+    label.set_hexpand(true);
+    label.set_markup(&format!(
+        "<span weight='bold'>{}</span>",
+        t("charge_behaviour")
+    ));
+    row.append(&label);
+
+    let modes_box = Box::new(Orientation::Horizontal, 0);
+    modes_box.add_css_class("linked");
+    modes_box.set_halign(gtk4::Align::End);
+    let mut buttons: Vec<(ToggleButton, String)> = Vec::new();
+    for mode in &behaviour.available {
+        // Modes added by newer kernels are shown under their sysfs name
+        let key = format!("behaviour_{}", mode.replace('-', "_"));
+        let text = t(&key);
+        let button = ToggleButton::with_label(if text == key { mode } else { &text });
+        if let Some((first, _)) = buttons.first() {
+            button.set_group(Some(first));
+        }
+        button.set_active(*mode == behaviour.active);
+        modes_box.append(&button);
+        buttons.push((button, mode.clone()));
+    }
+    row.append(&modes_box);
+
+    (row, buttons)
+}
+
+/// Writes `charge_behaviour` when a mode button is toggled on
+///
+/// A failed write puts the previously active mode back.
+///
+/// # Arguments
+///
+/// * `buttons` - Mode buttons from [`create_charge_behaviour_row`]
+/// * `active` - Mode active when the tab was built
+/// * `battery_name` - Battery name (e.g., "BAT0")
+/// * `status_message` - Label receiving the result
+/// * `on_applied` - Called with the battery state read back after a switch
+fn connect_charge_behaviour(
+    buttons: &[(ToggleButton, String)],
+    active: &str,
+    battery_name: &str,
+    status_message: &Label,
+    on_applied: &Rc<dyn Fn(&BatteryInfo)>,
+) {
+    let current = Rc::new(RefCell::new(active.to_string()));
+    let group: Rc<Vec<(glib::WeakRef<ToggleButton>, String)>> = Rc::new(
+        buttons
+            .iter()
+            .map(|(button, mode)| (button.downgrade(), mode.clone()))
+            .collect(),
+    );
+
+    for (button, mode) in buttons {
+        let current = current.clone();
+        let group = group.clone();
+        let mode = mode.clone();
+        let battery = battery_name.to_string();
+        let status_message = status_message.downgrade();
+        let on_applied = on_applied.clone();
+        button.connect_toggled(move |button| {
+            // Also ignores the toggle caused by putting the previous mode back
+            if !button.is_active() || *current.borrow() == mode {
+                return;
+            }
+            let Some(status_message) = status_message.upgrade() else {
+                return;
+            };
+            let previous = current.replace(mode.clone());
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔀 [SETTINGS_TAB] charge_behaviour {previous} -> {mode} requested for {battery}"
+            ));
+            let Some(plan) = threshold_apply::build_charge_behaviour_plan(
+                &battery,
+                &mode,
+                Path::new("/sys/class/power_supply"),
+            ) else {
+                return;
+            };
+
+            match execute_with_pkexec(&plan) {
+                ApplyResult::Success => {
+                    status_message.set_markup(&format!(
+                        "<span>✓ {}</span>",
+                        glib::markup_escape_text(&t_fmt(
+                            "charge_behaviour_applied",
+                            &[("mode", &button.label().unwrap_or_default())]
+                        ))
+                    ));
+                    set_status_class(&status_message, "color-success");
+                    if let Ok(actual) = BatteryInfo::new(&battery) {
+                        on_applied(&actual);
+                    }
+                }
+                failure => {
+                    show_apply_failure(&status_message, &failure);
+                    current.borrow_mut().clone_from(&previous);
+                    if let Some(previous_button) = group
+                        .iter()
+                        .find(|(_, m)| *m == previous)
+                        .and_then(|(b, _)| b.upgrade())
+                    {
+                        previous_button.set_active(true);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Formats one line of the confirmation dialog ("• Charge stop: 80% → 90%")
+///
+/// # Arguments
+///
+/// * `change` - Setting about to change
+/// * `care_mode` - Whether the stop value is a care code (on/off) instead of a percentage
+fn format_change(change: SettingChange, care_mode: bool) -> String {
+    let percent = |value: Option<u8>| value.map_or_else(|| t("unknown"), |v| format!("{v}%"));
+    let toggle = |value: Option<bool>| {
+        value.map_or_else(
+            || t("unknown"),
+            |on| if on { t("enabled") } else { t("disabled") },
+        )
+    };
+    let (setting, old, new) = match change {
+        SettingChange::Stop { old, new } if care_mode => (
+            "battery_care",
+            toggle(old.map(|code| code != 0)),
+            toggle(Some(new != 0)),
+        ),
+        SettingChange::Start { old, new } | SettingChange::Stop { old, new } => {
+            (change.setting(), percent(old), percent(Some(new)))
+        }
+        SettingChange::Alarm { old, new } => (
+            change.setting(),
+            old.map_or_else(|| t("unknown"), |v| format!("{v:.1}%")),
+            format!("{new:.1}%"),
+        ),
+        SettingChange::Service { old, new } => (change.setting(), toggle(old), toggle(Some(new))),
+        SettingChange::UdevRule { old, new } => {
+            (change.setting(), toggle(Some(old)), toggle(Some(new)))
+        }
+    };
+    t_fmt(
+        "change_line",
+        &[("setting", &t(setting)), ("old", &old), ("new", &new)],
+    )
+}
+
+/// Formats the change list shown in the confirmation dialog, one setting per line
+fn format_changes(change_set: &ThresholdChangeSet, care_mode: bool) -> String {
+    change_set
+        .changes
+        .iter()
+        .map(|&change| format_change(change, care_mode))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asks for confirmation before the privileged apply
+///
+/// # Arguments
+///
+/// * `button` - Apply button (its window is the dialog parent)
+/// * `summary` - Changes about to be written
+/// * `on_confirm` - Runs the apply when the user clicks Apply
+fn confirm_apply(button: &Button, summary: &str, on_confirm: impl Fn() + 'static) {
+    let parent = button.root().and_downcast::<gtk4::Window>();
+    let dialog = MessageDialog::builder()
+        .modal(true)
+        .message_type(MessageType::Question)
+        .text(t("confirm_apply_title"))
+        .secondary_text(summary)
+        .build();
+    dialog.set_transient_for(parent.as_ref());
+    dialog.add_button(&t("cancel"), ResponseType::Cancel);
+    dialog.add_button(&t("confirm_apply_button"), ResponseType::Accept);
+    dialog.set_default_response(ResponseType::Cancel);
+
+    dialog.connect_response(move |dialog, response| {
+        dialog.close();
+        if response == ResponseType::Accept {
+            on_confirm();
+        } else {
+            crate::core::debug::debug_log(
+                "🚫 [SETTINGS_TAB] Apply cancelled in confirmation dialog",
+            );
+        }
+    });
+    dialog.present();
+}
+
+/// Creates the hidden red label showing a threshold error below its spinner
+fn create_error_label() -> Label {
+    let label = Label::new(None);
+    label.set_halign(gtk4::Align::Start);
+    label.set_wrap(true);
+    label.add_css_class("color-danger");
+    label.set_visible(false);
+    label
+}
+
+/// Shows a validation error under the field it concerns, or clears both labels
+///
+/// # Arguments
+///
+/// * `start_error` - Label below the start spinner (range and order errors)
+/// * `stop_error` - Label below the stop spinner (range errors)
+/// * `result` - Outcome of `validate_threshold_values`
+fn show_threshold_error(
+    start_error: &Label,
+    stop_error: &Label,
+    result: Result<(), ThresholdError>,
+) {
+    start_error.set_visible(false);
+    stop_error.set_visible(false);
+    let Err(e) = result else {
+        return;
+    };
+
+    crate::core::debug::debug_log_args(std::format_args!(
+        "⚠️ [SETTINGS_TAB] Invalid thresholds: {e:?}"
+    ));
+    let label = if e == ThresholdError::StopOutOfRange {
+        stop_error
+    } else {
+        start_error
+    };
+    label.set_text(&e.to_string());
+    label.set_visible(true);
+}
+
+/// Creates the conservation-mode row used instead of the stop spinner
+///
+/// The switch starts in the state read from the vendor's care file.
+fn create_care_row(vendor_info: &VendorInfo) -> (Box, Switch) {
+    let row = Box::new(Orientation::Horizontal, 10);
+
+    let label = Label::new(None);
+    label.set_halign(gtk4::Align::Start);
+    label.set_hexpand(true);
+    label.set_markup(&format!("<span weight='bold'>{}</span>", t("battery_care")));
+
+    let enabled = vendor_info
+        .threshold_files
+        .stop_paths
+        .first()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|value| value.trim().parse::<u8>().ok())
+        .is_some_and(|value| value != 0);
+    crate::core::debug::debug_log_args(std::format_args!(
+        "🛡️ [SETTINGS_TAB] Care toggle for {:?}: enabled={enabled}",
+        vendor_info.vendor_type
+    ));
+
+    let switch = Switch::new();
+    switch.set_active(enabled);
+    switch.set_valign(gtk4::Align::Center);
+    switch.set_halign(gtk4::Align::End);
+    row.set_tooltip_text(Some(t("battery_care_tooltip").as_str()));
+
+    row.append(&label);
+    row.append(&switch);
+
+    (row, switch)
+}
+
+/// Replaces the profile dropdown entries (index 0 is the "no profile" placeholder)
+fn refresh_profile_model(model: &StringList, profiles: &[Profile]) {
+    let placeholder = t("profile_none");
+    let names: Vec<&str> = std::iter::once(placeholder.as_str())
+        .chain(profiles.iter().map(|p| p.name.as_str()))
+        .collect();
+    model.splice(0, model.n_items(), &names);
+}
+
+/// Creates the user profiles card (load, save and delete named settings)
+///
+/// Selecting a profile only fills the controls; the Apply button still
+/// performs the privileged write.
+#[allow(clippy::too_many_lines)]
+fn create_profiles_card(
+    start_spin: Option<&SpinButton>,
+    stop_spin: &SpinButton,
+    alarm_spin: &SpinButton,
+    service_switch: &Switch,
+) -> gtk4::Frame {
+    let (profiles_frame, profiles_box) = InfoCard::create(&format!("👤 {}", t("card_profiles")));
+    profiles_box.set_spacing(6);
+
+    let Some(path) = profiles::profiles_path() else {
+        crate::core::debug::debug_log("⚠️ [SETTINGS_TAB] No config dir, profiles disabled");
+        profiles_frame.set_visible(false);
+        return profiles_frame;
+    };
+    let profiles = Rc::new(RefCell::new(profiles::load_profiles(&path)));
+    let path = Rc::new(path);
+
+    let model = StringList::new(&[]);
+    refresh_profile_model(&model, &profiles.borrow());
+    let dropdown = DropDown::new(Some(model.clone()), None::<gtk4::Expression>);
+    dropdown.set_hexpand(true);
+
+    let delete_button = Button::with_label(&t("profile_delete"));
+    let select_row = Box::new(Orientation::Horizontal, 6);
+    select_row.append(&dropdown);
+    select_row.append(&delete_button);
+    profiles_box.append(&select_row);
+
+    let name_entry = Entry::new();
+    name_entry.set_placeholder_text(Some(t("profile_name").as_str()));
+    name_entry.set_hexpand(true);
+    let save_button = Button::with_label(&t("profile_save"));
+    let save_row = Box::new(Orientation::Horizontal, 6);
+    save_row.append(&name_entry);
+    save_row.append(&save_button);
+    profiles_box.append(&save_row);
+
+    let profile_status = Label::new(None);
+    profile_status.set_halign(gtk4::Align::Start);
+    profiles_box.append(&profile_status);
+
+    let start_spin = start_spin.cloned();
+    // Rebuilding the model moves the selection: don't load a profile then
+    let repopulating = Rc::new(Cell::new(false));
+
+    dropdown.connect_selected_notify(glib::clone!(
+        #[weak]
+        stop_spin,
+        #[weak]
+        alarm_spin,
+        #[weak]
+        service_switch,
+        #[weak]
+        name_entry,
+        #[strong]
+        profiles,
+        #[strong]
+        start_spin,
+        #[strong]
+        repopulating,
+        move |dropdown| {
+            if repopulating.get() {
+                return;
+            }
+            let Some(profile) = usize::try_from(dropdown.selected())
+                .ok()
+                .and_then(|i| i.checked_sub(1))
+                .and_then(|i| profiles.borrow().get(i).cloned())
+            else {
+                return;
+            };
+
+            crate::core::debug::debug_log_args(std::format_args!(
+                "👤 [SETTINGS_TAB] Profile loaded: {profile:?}"
+            ));
+            if let (Some(spin), Some(start)) = (start_spin.as_ref(), profile.start) {
+                spin.set_value(f64::from(start));
+            }
+            stop_spin.set_value(f64::from(profile.stop));
+            alarm_spin.set_value(f64::from(profile.alarm_percent));
+            service_switch.set_active(profile.enable_service);
+            name_entry.set_text(&profile.name);
+        }
+    ));
+
+    save_button.connect_clicked(glib::clone!(
+        #[weak]
+        stop_spin,
+        #[weak]
+        alarm_spin,
+        #[weak]
+        service_switch,
+        #[weak]
+        name_entry,
+        #[weak]
+        dropdown,
+        #[weak]
+        model,
+        #[weak]
+        profile_status,
+        #[strong]
+        profiles,
+        #[strong]
+        path,
+        #[strong]
+        repopulating,
+        move |_| {
+            let name = name_entry.text().trim().to_string();
+            if name.is_empty() {
+                profile_status.set_text(&t("profile_name_required"));
+                return;
+            }
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let profile = Profile {
+                name: name.clone(),
+                start: start_spin.as_ref().map(|s| s.value() as u8),
+                stop: stop_spin.value() as u8,
+                alarm_percent: alarm_spin.value() as f32,
+                enable_service: service_switch.is_active(),
+            };
+            profiles::upsert_profile(&mut profiles.borrow_mut(), profile);
+
+            match profiles::save_profiles(&path, &profiles.borrow()) {
+                Ok(()) => profile_status.set_text(&format!("✓ {}", t("profile_saved"))),
+                Err(e) => {
+                    crate::core::debug::terminal_error_args(std::format_args!(
+                        "❌ [SETTINGS_TAB] Saving profiles failed: {e}"
+                    ));
+                    profile_status.set_text(&format!("{}: {e}", t("error")));
+                }
+            }
+
+            repopulating.set(true);
+            refresh_profile_model(&model, &profiles.borrow());
+            let index = profiles.borrow().iter().position(|p| p.name == name);
+            if let Some(index) = index.and_then(|i| u32::try_from(i + 1).ok()) {
+                dropdown.set_selected(index);
+            }
+            repopulating.set(false);
+        }
+    ));
+
+    delete_button.connect_clicked(glib::clone!(
+        #[weak]
+        dropdown,
+        #[weak]
+        model,
+        #[weak]
+        name_entry,
+        #[weak]
+        profile_status,
+        #[strong]
+        profiles,
+        #[strong]
+        path,
+        #[strong]
+        repopulating,
+        move |_| {
+            let Some(index) = usize::try_from(dropdown.selected())
+                .ok()
+                .and_then(|i| i.checked_sub(1))
+                .filter(|i| *i < profiles.borrow().len())
+            else {
+                return;
+            };
+
+            let removed = profiles.borrow_mut().remove(index);
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🗑️ [SETTINGS_TAB] Profile deleted: {}",
+                removed.name
+            ));
+            if let Err(e) = profiles::save_profiles(&path, &profiles.borrow()) {
+                crate::core::debug::terminal_error_args(std::format_args!(
+                    "❌ [SETTINGS_TAB] Saving profiles failed: {e}"
+                ));
+                profile_status.set_text(&format!("{}: {e}", t("error")));
+            } else {
+                profile_status.set_text("");
+            }
+
+            repopulating.set(true);
+            refresh_profile_model(&model, &profiles.borrow());
+            dropdown.set_selected(0);
+            repopulating.set(false);
+            name_entry.set_text("");
+        }
+    ));
+
+    profiles_frame
+}
+
 /// Builds the Settings tab content
 ///
 /// # Arguments
 ///
 /// * `battery_info` - Current battery information
 /// * `current_battery` - Name of active battery (e.g., "BAT0")
+/// * `on_applied` - Called with freshly read battery information after a successful apply
 ///
 /// # Returns
 ///
-/// `ScrolledWindow` containing settings controls
+/// Tuple of (`ScrolledWindow` containing settings controls, Apply button,
+/// apply action run by the button and the apply shortcut, last re-apply
+/// after resume label)
 #[allow(clippy::too_many_lines)]
-pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) -> ScrolledWindow {
+pub fn build_settings_tab(
+    battery_info: &BatteryInfo,
+    current_battery: &str,
+    on_applied: Rc<dyn Fn(&BatteryInfo)>,
+) -> (ScrolledWindow, Button, gio::SimpleAction, Label) {
     crate::core::debug::debug_log_args(std::format_args!(
         "⚙️ [SETTINGS_TAB] Building settings tab for {current_battery}..."
     ));
 
     let unit_exists = service_unit_exists();
     crate::core::debug::debug_log_args(std::format_args!(
-        "🧩 [SETTINGS_TAB] Service unit present: {unit_exists} (service_active={})",
-        battery_info.service_active
+        "🧩 [SETTINGS_TAB] Service unit present: {unit_exists} (state={:?})",
+        service::cached_unit_state()
     ));
     let scrolled = ScrolledWindow::new();
     scrolled.set_vexpand(true);
@@ -326,8 +892,8 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
     content_box.set_margin_end(10);
 
     // === Card Informations Fabricant ===
-    let vendor_info = VendorInfo::detect();
-    let vendor_frame = create_vendor_card(&vendor_info);
+    let vendor_info = VendorInfo::detect_cached(current_battery);
+    let vendor_frame = create_vendor_card(vendor_info);
     content_box.append(&vendor_frame);
 
     // === Card Seuils de charge ===
@@ -335,22 +901,121 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
         InfoCard::create(&format!("⚙️ {}", t("card_threshold_settings")));
     settings_box.set_spacing(8);
 
-    // Seuil début (seulement si supporté)
-    let start_spin = battery_info.charge_start_threshold.map(|threshold| {
-        let (start_row, spin) =
-            create_threshold_row(&t("threshold_start_pct"), threshold, 0.0, 99.0);
+    // Premier lancement : valeurs recommandées pour ce matériel
+    let first_launch = battery_info.charge_start_threshold.is_none()
+        && battery_info.charge_stop_threshold.is_none();
+    let (default_start, default_stop) = if first_launch {
+        let (start, stop) = vendor_info.get_recommended_thresholds();
+        crate::core::debug::debug_log_args(std::format_args!(
+            "💡 [SETTINGS_TAB] No thresholds set, using recommended defaults: start={start:?} stop={stop}"
+        ));
+        (start.unwrap_or(0), stop)
+    } else {
+        (0, 80)
+    };
+
+    // Seuil début (seulement si supporté par cette batterie)
+    let start_spin = (vendor_info.supports_start_threshold
+        || battery_info.charge_start_threshold.is_some())
+    .then(|| {
+        let (start_row, spin) = create_threshold_row(
+            &t("threshold_start_pct"),
+            battery_info.charge_start_threshold.unwrap_or(default_start),
+            0.0,
+            99.0,
+        );
         settings_box.append(&start_row);
         spin
     });
+    let start_error = create_error_label();
+    settings_box.append(&start_error);
 
     // Seuil fin
     let (stop_row, stop_spin) = create_threshold_row(
         &t("threshold_stop_pct"),
-        battery_info.charge_stop_threshold.unwrap_or(80),
+        battery_info.charge_stop_threshold.unwrap_or(default_stop),
         1.0,
         100.0,
     );
+    stop_row.set_sensitive(
+        vendor_info.supports_stop_threshold || battery_info.charge_stop_threshold.is_some(),
+    );
     settings_box.append(&stop_row);
+    let stop_error = create_error_label();
+    settings_box.append(&stop_error);
+
+    let recommended_label = Label::new(None);
+    recommended_label.set_halign(gtk4::Align::Start);
+    recommended_label.set_markup(&format!(
+        "<span size='small'>💡 {}</span>",
+        t("recommended_defaults")
+    ));
+    recommended_label.add_css_class("dim-label");
+    recommended_label.set_visible(
+        first_launch
+            && stop_row.is_sensitive()
+            && vendor_info.threshold_control() == ThresholdControl::Percentage,
+    );
+    settings_box.append(&recommended_label);
+
+    // Samsung/Sony : fichier « battery care » on/off au lieu d'un pourcentage
+    let care_switch =
+        (vendor_info.threshold_control() == ThresholdControl::CareToggle).then(|| {
+            let (care_row, switch) = create_care_row(vendor_info);
+            stop_row.set_visible(false);
+            settings_box.append(&care_row);
+            switch
+        });
+
+    // Préréglages (remplissent les champs, sans appliquer)
+    let presets_row = Box::new(Orientation::Horizontal, 6);
+    presets_row.set_homogeneous(true);
+    let start_supported = start_spin.is_some();
+    for preset in PRESETS {
+        let button = Button::with_label(&preset.label(start_supported));
+        button.connect_clicked(glib::clone!(
+            #[weak]
+            stop_spin,
+            #[strong]
+            start_spin,
+            move |_| {
+                let (start, stop) = preset.values(start_supported);
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "🎛️ [SETTINGS_TAB] Preset {} selected: start={start:?} stop={stop}",
+                    preset.name_key
+                ));
+                if let (Some(spin), Some(start)) = (start_spin.as_ref(), start) {
+                    spin.set_value(f64::from(start));
+                }
+                stop_spin.set_value(f64::from(stop));
+            }
+        ));
+        presets_row.append(&button);
+    }
+    presets_row.set_sensitive(stop_row.is_sensitive());
+    presets_row.set_visible(care_switch.is_none());
+    settings_box.append(&presets_row);
+
+    // Charge unique à 100% (non persistée)
+    let charge_full_button = Button::with_label(&format!("🔌 {}", t("charge_100")));
+    charge_full_button.set_tooltip_text(Some(t("charge_100_tooltip").as_str()));
+
+    // Cycle complet décharge/charge guidé
+    let calibration_button = Button::with_label(&format!("🎯 {}", t("calibration_button")));
+    calibration_button.set_tooltip_text(Some(t("calibration_tooltip").as_str()));
+    let calibration_battery = current_battery.to_string();
+    calibration_button.connect_clicked(move |button| {
+        let parent = button.root().and_downcast::<gtk4::Window>();
+        crate::ui::calibration::show_calibration_window(parent.as_ref(), &calibration_battery);
+    });
+
+    let full_charge_row = Box::new(Orientation::Horizontal, 6);
+    full_charge_row.set_halign(gtk4::Align::Center);
+    full_charge_row.set_sensitive(vendor_info.supports_stop_threshold);
+    full_charge_row.set_visible(care_switch.is_none());
+    full_charge_row.append(&charge_full_button);
+    full_charge_row.append(&calibration_button);
+    settings_box.append(&full_charge_row);
 
     // Alarme de décharge
     let alarm_value = battery_info.alarm_percent().unwrap_or(10.0);
@@ -359,8 +1024,49 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
         create_threshold_row(&t("alarm_threshold"), alarm_value as u8, 1.0, 100.0);
     // Override decimal places for alarm
     alarm_spin.set_digits(1);
+    // Absolute value actually written, to sanity-check the unit
+    let alarm_preview = Label::new(None);
+    alarm_preview.set_halign(gtk4::Align::End);
+    alarm_preview.add_css_class("dim-label");
+    alarm_row.insert_child_after(&alarm_preview, alarm_row.first_child().as_ref());
+    if let Some((unit, full_value)) =
+        CapacityUnit::detect(&format!("/sys/class/power_supply/{}", battery_info.name))
+    {
+        #[allow(clippy::cast_possible_truncation)]
+        let update_preview = move |spin: &SpinButton| {
+            let alarm = compute_alarm_raw(full_value, unit, spin.value() as f32);
+            alarm_preview.set_text(&format!("≈ {alarm}"));
+        };
+        update_preview(&alarm_spin);
+        alarm_spin.connect_value_changed(update_preview);
+    }
+    // The spin button can't go below 1%: clearing (0) has its own button
+    let alarm_reset_plan = threshold_apply::build_alarm_reset_plan(
+        current_battery,
+        Path::new("/sys/class/power_supply"),
+    );
+    let alarm_reset_button = Button::with_label(&format!("↺ {}", t("alarm_reset")));
+    alarm_reset_button.set_tooltip_text(Some(t("alarm_reset_tooltip").as_str()));
+    alarm_reset_button.set_sensitive(alarm_reset_plan.is_some());
+    let alarm_controls = Box::new(Orientation::Horizontal, 6);
+    alarm_controls.set_halign(gtk4::Align::End);
+    alarm_row.remove(&alarm_spin);
+    alarm_controls.append(&alarm_spin);
+    alarm_controls.append(&alarm_reset_button);
+    alarm_row.append(&alarm_controls);
     settings_box.append(&alarm_row);
 
+    // Mode de charge noyau (indépendant des seuils, utile pour calibrer)
+    let charge_behaviour_buttons = battery_info
+        .charge_behaviour
+        .as_ref()
+        .filter(|behaviour| behaviour.available.len() > 1)
+        .map(|behaviour| {
+            let (row, buttons) = create_charge_behaviour_row(behaviour);
+            settings_box.append(&row);
+            (buttons, behaviour.active.clone())
+        });
+
     content_box.append(&settings_frame);
 
     // === Card Service ===
@@ -379,7 +1085,34 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
     ));
 
     let service_switch = Switch::new();
-    service_switch.set_active(battery_info.service_active);
+    let service_missing_note = Label::new(None);
+    service_missing_note.set_halign(gtk4::Align::Start);
+    service_missing_note.set_wrap(true);
+    service_missing_note.set_markup(&format!(
+        "<span size='small'>⚠️ {}</span>",
+        glib::markup_escape_text(&t("service_missing_note"))
+    ));
+    service_missing_note.add_css_class("color-warning");
+    service_missing_note.set_visible(false);
+    let install_service_button = Button::with_label(&format!("📥 {}", t("install_service")));
+    install_service_button.set_halign(gtk4::Align::Start);
+    install_service_button.set_tooltip_text(Some(&t("install_service_tooltip")));
+    install_service_button.set_visible(false);
+    if let Some(state) = service::cached_unit_state() {
+        show_service_state(
+            &service_switch,
+            &service_missing_note,
+            &install_service_button,
+            state,
+        );
+    } else {
+        // First check still running: follow its result
+        follow_service_state(
+            &service_switch,
+            &service_missing_note,
+            &install_service_button,
+        );
+    }
     service_switch.set_valign(gtk4::Align::Center);
     service_switch.set_halign(gtk4::Align::End);
 
@@ -393,6 +1126,80 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
     service_row.append(&service_label);
     service_row.append(&service_switch);
     service_box.append(&service_row);
+    service_box.append(&service_missing_note);
+    service_box.append(&install_service_button);
+
+    // Règle udev : persistance sans le service systemd (l'un ou l'autre)
+    let udev_row = Box::new(Orientation::Horizontal, 10);
+    let udev_label = Label::new(None);
+    udev_label.set_halign(gtk4::Align::Start);
+    udev_label.set_hexpand(true);
+    udev_label.set_markup(&format!(
+        "<span weight='bold'>{}</span>",
+        t("persist_udev_rule")
+    ));
+    let udev_switch = Switch::new();
+    udev_switch.set_valign(gtk4::Align::Center);
+    udev_switch.set_halign(gtk4::Align::End);
+    udev_switch.set_tooltip_text(Some(t("persist_udev_rule_tooltip").as_str()));
+    udev_switch.set_active(threshold_apply::udev_rule_installed());
+    udev_switch.connect_active_notify(glib::clone!(
+        #[weak]
+        service_switch,
+        move |switch| {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔁 [SETTINGS_TAB] udev rule switch toggled: active={}",
+                switch.is_active()
+            ));
+            if switch.is_active() {
+                service_switch.set_active(false);
+            }
+        }
+    ));
+    service_switch.connect_active_notify(glib::clone!(
+        #[weak]
+        udev_switch,
+        move |switch| {
+            if switch.is_active() {
+                udev_switch.set_active(false);
+            }
+        }
+    ));
+    udev_row.append(&udev_label);
+    udev_row.append(&udev_switch);
+    service_box.append(&udev_row);
+
+    // Ré-application après la mise en veille (opt-in)
+    let resume_row = Box::new(Orientation::Horizontal, 10);
+    let resume_label = Label::new(None);
+    resume_label.set_halign(gtk4::Align::Start);
+    resume_label.set_hexpand(true);
+    resume_label.set_markup(&format!(
+        "<span weight='bold'>{}</span>",
+        t("reapply_on_resume")
+    ));
+    let resume_switch = Switch::new();
+    resume_switch.set_valign(gtk4::Align::Center);
+    resume_switch.set_halign(gtk4::Align::End);
+    resume_switch.set_tooltip_text(Some(t("reapply_on_resume_tooltip").as_str()));
+    resume_switch.set_active(config::reapply_on_resume());
+    resume_switch.connect_active_notify(|switch| {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🌅 [SETTINGS_TAB] Re-apply on resume: {}",
+            switch.is_active()
+        ));
+        config::set_reapply_on_resume(switch.is_active());
+        config::update(|config| config.reapply_on_resume = switch.is_active());
+    });
+    resume_row.append(&resume_label);
+    resume_row.append(&resume_switch);
+    service_box.append(&resume_row);
+
+    let resume_status = Label::new(None);
+    resume_status.set_halign(gtk4::Align::Start);
+    resume_status.set_wrap(true);
+    update_resume_label(&resume_status, current_battery);
+    service_box.append(&resume_status);
 
     // Note d'information avec fond coloré
     let note_frame = gtk4::Frame::new(None);
@@ -428,11 +1235,102 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
 
     content_box.append(&service_frame);
 
-    // Message de statut (en dehors du frame)
+    // === Card Profils (au-dessus des seuils) ===
+    let profiles_frame = create_profiles_card(
+        start_spin.as_ref(),
+        &stop_spin,
+        &alarm_spin,
+        &service_switch,
+    );
+    content_box.insert_child_after(&profiles_frame, Some(&vendor_frame));
+
+    // Message de statut (en dehors du frame), avec l'indicateur d'application
+    let status_row = Box::new(Orientation::Horizontal, 8);
+    status_row.set_halign(gtk4::Align::Center);
+    status_row.set_margin_top(10);
+    let apply_spinner = Spinner::new();
+    apply_spinner.set_visible(false);
     let status_message = Label::new(None);
-    status_message.set_halign(gtk4::Align::Center);
-    status_message.set_margin_top(10);
-    content_box.append(&status_message);
+    status_row.append(&apply_spinner);
+    status_row.append(&status_message);
+    content_box.append(&status_row);
+
+    if let Some((buttons, active)) = &charge_behaviour_buttons {
+        connect_charge_behaviour(
+            buttons,
+            active,
+            current_battery,
+            &status_message,
+            &on_applied,
+        );
+    }
+
+    let current_start = battery_info.charge_start_threshold;
+    let full_charge_battery = current_battery.to_string();
+    let full_charge_applied = on_applied.clone();
+    charge_full_button.connect_clicked(glib::clone!(
+        #[weak]
+        status_message,
+        move |_| {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔌 [SETTINGS_TAB] Charge to 100% once requested for {full_charge_battery}"
+            ));
+            let plan = threshold_apply::build_full_charge_plan(
+                current_start,
+                &ApplyEnvironment {
+                    power_supply_root: Path::new("/sys/class/power_supply"),
+                    config_dir: Path::new(threshold_apply::CONFIG_DIR),
+                    start_paths: &vendor_info.threshold_files.start_paths,
+                    stop_paths: &vendor_info.threshold_files.stop_paths,
+                    service_unit_exists: service_unit_exists(),
+                    udev_rule_exists: threshold_apply::udev_rule_installed(),
+                },
+            );
+
+            match execute_with_pkexec(&plan) {
+                ApplyResult::Success => {
+                    status_message.set_markup(&format!(
+                        "<span>✓ {}\n<span size='small'>⚠️ {}</span></span>",
+                        t("charge_100_applied"),
+                        t("charge_100_temporary")
+                    ));
+                    set_status_class(&status_message, "color-success");
+                    if let Ok(actual) = BatteryInfo::new(&full_charge_battery) {
+                        full_charge_applied(&actual);
+                    }
+                }
+                failure => show_apply_failure(&status_message, &failure),
+            }
+        }
+    ));
+
+    let alarm_reset_battery = current_battery.to_string();
+    let alarm_reset_applied = on_applied.clone();
+    alarm_reset_button.connect_clicked(glib::clone!(
+        #[weak]
+        status_message,
+        move |_| {
+            let Some(plan) = &alarm_reset_plan else {
+                return;
+            };
+            crate::core::debug::debug_log_args(std::format_args!(
+                "↺ [SETTINGS_TAB] Alarm reset to driver default requested for {alarm_reset_battery}"
+            ));
+            match execute_with_pkexec(plan) {
+                ApplyResult::Success => {
+                    crate::core::debug::debug_log(
+                        "✅ [SETTINGS_TAB] Alarm reset to driver default (0 written)",
+                    );
+                    status_message.set_markup(&format!("<span>✓ {}</span>", t("alarm_reset_done")));
+                    set_status_class(&status_message, "color-success");
+                    if let Ok(actual) = BatteryInfo::new(&alarm_reset_battery) {
+                        alarm_reset_applied(&actual);
+                    }
+                }
+                failure => show_apply_failure(&status_message, &failure),
+            }
+        }
+    ));
 
     // Single button to apply all modifications (centered outside frame)
     let current_battery_clone = current_battery.to_string();
@@ -464,8 +1362,106 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
         .style_context()
         .add_provider(&css_provider, gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION);
 
-    apply_button.connect_clicked(
+    // Apply exposed as an action, so the window shortcut can trigger it
+    let apply_action = gio::SimpleAction::new(ACTION_APPLY_SETTINGS, None);
+
+    // Set while pkexec runs: only one apply at a time
+    let applying = Rc::new(Cell::new(false));
+    let battery_snapshot = battery_info.clone();
+
+    // Validation immédiate des champs
+    let validate = {
+        let start_spin = start_spin.clone();
+        // Conservation mode has no percentages to check
+        let care = care_switch.is_some();
+        let min_gap = vendor_info.vendor_type.min_threshold_gap();
+        let stop_spin = stop_spin.clone();
+        let apply_button = apply_button.downgrade();
+        let apply_action = apply_action.clone();
+        let applying = applying.clone();
+        Rc::new(move || {
+            let Some(apply_button) = apply_button.upgrade() else {
+                return;
+            };
+            let result = if care {
+                Ok(())
+            } else {
+                validate_threshold_values(
+                    start_spin.as_ref().map(SpinButton::value),
+                    stop_spin.value(),
+                    min_gap,
+                )
+            };
+            show_threshold_error(&start_error, &stop_error, result);
+            apply_button.set_sensitive(result.is_ok() && !applying.get());
+            apply_action.set_enabled(result.is_ok());
+        })
+    };
+    for spin in start_spin.iter().chain(std::iter::once(&stop_spin)) {
+        let validate = validate.clone();
+        spin.connect_value_changed(move |_| validate());
+    }
+    validate();
+
+    let install_battery = current_battery.to_string();
+    let install_care_switch = care_switch.clone();
+    let install_start_spin = start_spin.clone();
+    install_service_button.connect_clicked(glib::clone!(
+        #[weak]
+        stop_spin,
+        #[weak]
+        service_switch,
+        #[weak]
+        service_missing_note,
+        #[weak]
+        status_message,
+        move |button| {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let start = install_start_spin.as_ref().map(|s| s.value() as u8);
+            let care = install_care_switch.as_ref().map(Switch::is_active);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let stop = care.map_or(stop_spin.value() as u8, |enabled| {
+                vendor_info.vendor_type.care_value(enabled)
+            });
+            let start = start.filter(|_| care.is_none());
+            let plan = threshold_apply::build_install_plan(
+                &install_battery,
+                start,
+                stop,
+                &ApplyEnvironment {
+                    power_supply_root: Path::new("/sys/class/power_supply"),
+                    config_dir: Path::new(threshold_apply::CONFIG_DIR),
+                    start_paths: &vendor_info.threshold_files.start_paths,
+                    stop_paths: &vendor_info.threshold_files.stop_paths,
+                    service_unit_exists: service_unit_exists(),
+                    udev_rule_exists: threshold_apply::udev_rule_installed(),
+                },
+            );
+            if let Some(unit) = &plan.install_unit {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "📥 [SETTINGS_TAB] Installing restore unit for {install_battery}:\n{}",
+                    service::unit_file(unit)
+                ));
+            }
+            match execute_with_pkexec(&plan) {
+                ApplyResult::Success => {
+                    service::invalidate();
+                    status_message.set_markup(&format!(
+                        "<span>✓ {}</span>",
+                        glib::markup_escape_text(&t("service_installed"))
+                    ));
+                    set_status_class(&status_message, "color-success");
+                    follow_service_state(&service_switch, &service_missing_note, button);
+                }
+                failure => show_apply_failure(&status_message, &failure),
+            }
+        }
+    ));
+
+    apply_action.connect_activate(
         glib::clone!(
+            #[weak]
+            apply_button,
             #[weak]
             stop_spin,
             #[weak]
@@ -473,15 +1469,29 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
             #[weak]
             service_switch,
             #[weak]
+            udev_switch,
+            #[weak]
             status_message,
-            move |_| {
+            #[weak]
+            apply_spinner,
+            move |_, _| {
+            if applying.get() {
+                crate::core::debug::debug_log(
+                    "⏳ [SETTINGS_TAB] Apply already running, ignoring the new request",
+                );
+                return;
+            }
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
             let start = start_spin.as_ref().map_or(0, |s| s.value() as u8);
+            let care = care_switch.as_ref().map(Switch::is_active);
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let stop = stop_spin.value() as u8;
+            let stop = care.map_or(stop_spin.value() as u8, |enabled| {
+                vendor_info.vendor_type.care_value(enabled)
+            });
             #[allow(clippy::cast_possible_truncation)]
             let alarm_pct = alarm_spin.value() as f32;
             let enable_service = service_switch.is_active();
+            let use_udev_rule = udev_switch.is_active();
             let has_start = start_spin.is_some();
 
             crate::core::debug::debug_log_args(std::format_args!(
@@ -494,82 +1504,197 @@ pub fn build_settings_tab(battery_info: &BatteryInfo, current_battery: &str) ->
                 );
             }
 
-            // Validation
-            if has_start && start >= stop {
-                status_message.set_markup(&format!(
-                    "<span>{}</span>",
-                    t("error_start_greater_stop")
-                ));
-                set_status_class(&status_message, "color-danger");
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "❌ [SETTINGS_TAB] Validation error: start ({start}) >= stop ({stop})"
-                ));
-                return;
+            // Validation (Apply is already disabled while the fields are invalid)
+            if care.is_none() {
+                if let Err(e) = validate_threshold_values(
+                    start_spin.as_ref().map(SpinButton::value),
+                    stop_spin.value(),
+                    vendor_info.vendor_type.min_threshold_gap(),
+                ) {
+                    status_message.set_markup(&format!(
+                        "<span>{}</span>",
+                        glib::markup_escape_text(&e.to_string())
+                    ));
+                    set_status_class(&status_message, "color-danger");
+                    crate::core::debug::debug_log_args(std::format_args!(
+                        "❌ [SETTINGS_TAB] Validation error: start={start} stop={stop}: {e:?}"
+                    ));
+                    return;
+                }
             }
 
-            // Compute alarm value
-            let alarm_value_str = compute_alarm_value(&current_battery_clone, alarm_pct);
+            let plan = threshold_apply::build_plan(
+                &ApplyRequest {
+                    battery_name: &current_battery_clone,
+                    start: has_start.then_some(start),
+                    stop,
+                    alarm_value: compute_alarm_value(&current_battery_clone, alarm_pct),
+                    enable_service,
+                    udev_rule: use_udev_rule,
+                },
+                &ApplyEnvironment {
+                    power_supply_root: Path::new("/sys/class/power_supply"),
+                    config_dir: Path::new(threshold_apply::CONFIG_DIR),
+                    start_paths: &vendor_info.threshold_files.start_paths,
+                    stop_paths: &vendor_info.threshold_files.stop_paths,
+                    service_unit_exists: service_unit_exists(),
+                    udev_rule_exists: threshold_apply::udev_rule_installed(),
+                },
+            );
 
-            // Validate numeric inputs
-            if !alarm_value_str.chars().all(|c| c.is_ascii_digit()) {
-                status_message.set_markup(&format!("<span>{}: Invalid numeric values</span>", t("error")));
-                set_status_class(&status_message, "color-danger");
+            // Compare with what the kernel reports now, not when the tab was built
+            let current = BatteryInfo::new(&current_battery_clone).unwrap_or_else(|e| {
                 crate::core::debug::debug_log_args(std::format_args!(
-                    "❌ [SETTINGS_TAB] Numeric validation failed: alarm_value_str='{}'",
-                    truncate_for_log(&alarm_value_str, 80)
+                    "⚠️ [SETTINGS_TAB] Cannot re-read {current_battery_clone}, comparing with the last known values: {e}"
                 ));
+                battery_snapshot.clone()
+            });
+            let change_set = ThresholdChangeSet::diff(
+                &current,
+                service::cached_unit_state().map(|state| state == UnitState::Active),
+                threshold_apply::udev_rule_installed(),
+                RequestedSettings {
+                    start: has_start.then_some(start),
+                    stop,
+                    alarm_pct,
+                    service_enabled: enable_service,
+                    udev_rule: use_udev_rule,
+                },
+            );
+            if change_set.is_empty() {
+                status_message.set_text(&t("no_changes"));
+                set_status_class(&status_message, "dim-label");
+                crate::core::debug::debug_log(
+                    "🟰 [SETTINGS_TAB] Requested settings already applied, nothing to do",
+                );
                 return;
             }
-
-            // Build and execute script
-            let script = build_apply_script(
-                &current_battery_clone,
-                start, stop, has_start,
-                &alarm_value_str,
-                enable_service,
+            if use_udev_rule {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "📥 [SETTINGS_TAB] udev rule for {current_battery_clone}:\n{}",
+                    threshold_apply::udev_rule(&current_battery_clone, stop)
+                ));
+            }
+            let summary = t_fmt(
+                "confirm_apply_body",
+                &[("changes", &format_changes(&change_set, care.is_some()))],
             );
-
-            match execute_with_pkexec(&script) {
-                ApplyResult::Success => {
-                    let service_status = if enable_service { t("enabled") } else { t("disabled") };
-                    let threshold_msg = if has_start {
-                        format!("{start}%-{stop}%")
-                    } else {
-                        format!("{stop}%")
-                    };
-                    let persistence_note = if enable_service {
-                        String::new()
-                    } else {
-                        format!("\n<span size='small'>{}</span>", t("warning_not_persistent"))
-                    };
-                    status_message.set_markup(&format!(
-                        "<span>✓ {}: {}, {}: {:.1}%, {}: {}{}</span>",
-                        t("settings_applied"), threshold_msg, t("alarm"), alarm_pct,
-                        t("service"), service_status, persistence_note
-                    ));
-                    set_status_class(&status_message, "color-success");
-                    crate::core::debug::debug_log_args(std::format_args!(
-                        "✅ [SETTINGS_TAB] Settings applied successfully: {threshold_msg}, alarm={alarm_pct:.1}%, service={service_status}"
-                    ));
-                }
-                ApplyResult::Failed(error_msg) => {
-                    status_message.set_markup(&format!("<span>{}: {}</span>", t("error"), error_msg));
-                    set_status_class(&status_message, "color-danger");
-                }
-                ApplyResult::NoPkexec => {
-                    status_message.set_markup(&format!(
-                        "<span>{}: pkexec not installed. Install policykit-1 or polkit.</span>",
-                        t("error")
-                    ));
-                    set_status_class(&status_message, "color-danger");
+            let battery = current_battery_clone.clone();
+            let on_applied = on_applied.clone();
+            let applying = applying.clone();
+            let button = apply_button.clone();
+            confirm_apply(&apply_button, &summary, move || {
+                if applying.replace(true) {
+                    return;
                 }
-            }
+                button.set_sensitive(false);
+                status_message.set_text(&t("applying"));
+                set_status_class(&status_message, "dim-label");
+
+                let battery = battery.clone();
+                let on_applied = on_applied.clone();
+                let applying = applying.clone();
+                let button = button.clone();
+                let status_message = status_message.clone();
+                execute_in_background(plan.clone(), &apply_spinner, move |result| {
+                    applying.set(false);
+                    button.set_sensitive(true);
+                    match result {
+                        ApplyResult::Success => {
+                            // The service may have been enabled or disabled
+                            service::invalidate();
+                            let service_status = if enable_service { t("enabled") } else { t("disabled") };
+                            let threshold_msg = if let Some(enabled) = care {
+                                format!(
+                                    "{} {}",
+                                    t("battery_care"),
+                                    if enabled { t("enabled") } else { t("disabled") }
+                                )
+                            } else if has_start {
+                                format!("{start}%-{stop}%")
+                            } else {
+                                format!("{stop}%")
+                            };
+                            let persistence_note = if enable_service {
+                                String::new()
+                            } else {
+                                format!("\n<span size='small'>{}</span>", t("warning_not_persistent"))
+                            };
+                            let applied = t_fmt(
+                                "settings_applied",
+                                &[
+                                    ("thresholds", &threshold_msg),
+                                    ("alarm", &format!("{alarm_pct:.1}%")),
+                                    ("service", &service_status),
+                                ],
+                            );
+                            status_message.set_markup(&format!(
+                                "<span>{}{persistence_note}</span>",
+                                glib::markup_escape_text(&applied)
+                            ));
+                            set_status_class(&status_message, "color-success");
+                            crate::core::debug::debug_log_args(std::format_args!(
+                                "✅ [SETTINGS_TAB] Settings applied successfully: {threshold_msg}, alarm={alarm_pct:.1}%, service={service_status}"
+                            ));
+
+                            // Read back: some firmwares clamp the written values
+                            // (care files hold an on/off code, not a percentage)
+                            let stop_warning = if care.is_none() {
+                                verify_threshold_applied(&battery, stop).err()
+                            } else {
+                                None
+                            };
+                            if let Some(warning) = &stop_warning {
+                                status_message.set_markup(&format!(
+                                    "<span>⚠️ {}</span>",
+                                    glib::markup_escape_text(warning)
+                                ));
+                                set_status_class(&status_message, "color-warning");
+                            }
+                            match BatteryInfo::new(&battery) {
+                                Ok(actual) => {
+                                    let mismatches = actual.compare_with_requested(
+                                        has_start.then_some(start),
+                                        stop,
+                                        Some(alarm_pct),
+                                    );
+                                    if !mismatches.is_empty() {
+                                        crate::core::debug::debug_log_args(std::format_args!(
+                                            "⚠️ [SETTINGS_TAB] Read-back mismatch after apply: {mismatches:?}"
+                                        ));
+                                        let headline =
+                                            stop_warning.unwrap_or_else(|| t("settings_mismatch"));
+                                        status_message.set_markup(&format!(
+                                            "<span>⚠️ {}\n<span size='small'>{}</span></span>",
+                                            glib::markup_escape_text(&headline),
+                                            glib::markup_escape_text(&format_mismatches(&mismatches))
+                                        ));
+                                        set_status_class(&status_message, "color-warning");
+                                    }
+                                    on_applied(&actual);
+                                }
+                                Err(e) => {
+                                    crate::core::debug::debug_log_args(std::format_args!(
+                                        "⚠️ [SETTINGS_TAB] Read-back after apply failed: {e}"
+                                    ));
+                                }
+                            }
+                        }
+                        failure => show_apply_failure(&status_message, &failure),
+                    }
+                });
+            });
             }
         ),
     );
 
+    apply_button.connect_clicked(glib::clone!(
+        #[weak]
+        apply_action,
+        move |_| apply_action.activate(None)
+    ));
     content_box.append(&apply_button);
 
-    scrolled.set_child(Some(&content_box));
-    scrolled
+    scrolled.set_child(Some(&WidthClamp::new(&content_box, TAB_MAX_WIDTH)));
+    (scrolled, apply_button, apply_action, resume_status)
 }
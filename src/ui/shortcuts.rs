@@ -0,0 +1,217 @@
+//! Keyboard shortcuts
+//!
+//! Lists the accelerators registered by `app.rs` and builds the
+//! "Keyboard Shortcuts" window shown from the Help menu.
+
+use gtk4::prelude::*;
+use gtk4::{Application, ApplicationWindow, Builder, ShortcutsWindow};
+use std::fmt::Write;
+
+use crate::core::i18n::t;
+
+/// Window actions triggered by shortcuts (registered on the main window)
+pub const ACTION_REFRESH: &str = "refresh";
+pub const ACTION_SWITCH_TAB: &str = "switch-tab";
+pub const ACTION_SHOW_PREFERENCES: &str = "show-preferences";
+/// Also the name of the settings tab's own apply action
+pub const ACTION_APPLY_SETTINGS: &str = "apply-settings";
+
+/// Application actions registered by `ensure_help_menu`
+pub const ACTION_QUIT: &str = "quit";
+pub const ACTION_SHOW_SHORTCUTS: &str = "shortcuts";
+
+/// Keyboard shortcut of a detailed action
+pub struct Shortcut {
+    /// Detailed action name (`app.` or `win.` prefix, optional target)
+    pub action: &'static str,
+    /// GTK accelerator strings (the first one is the main one)
+    pub accels: &'static [&'static str],
+    /// i18n key of the description
+    pub title_key: &'static str,
+    /// Appended to the translated description (tab number)
+    pub title_suffix: &'static str,
+    /// i18n key of the group shown in the shortcuts window
+    pub group_key: &'static str,
+}
+
+/// All keyboard shortcuts, in display order
+pub const SHORTCUTS: [Shortcut; 9] = [
+    Shortcut {
+        action: "win.refresh",
+        accels: &["F5", "<Control>r"],
+        title_key: "shortcut_refresh",
+        title_suffix: "",
+        group_key: "shortcuts_general",
+    },
+    Shortcut {
+        action: "win.apply-settings",
+        accels: &["<Control>s"],
+        title_key: "shortcut_apply",
+        title_suffix: "",
+        group_key: "shortcuts_general",
+    },
+    Shortcut {
+        action: "app.shortcuts",
+        accels: &["<Control>question"],
+        title_key: "keyboard_shortcuts",
+        title_suffix: "",
+        group_key: "shortcuts_general",
+    },
+    Shortcut {
+        action: "app.quit",
+        accels: &["<Control>q"],
+        title_key: "shortcut_quit",
+        title_suffix: "",
+        group_key: "shortcuts_general",
+    },
+    Shortcut {
+        action: "win.show-preferences",
+        accels: &["<Control>comma"],
+        title_key: "shortcut_preferences",
+        title_suffix: "",
+        group_key: "shortcuts_navigation",
+    },
+    Shortcut {
+        action: "win.switch-tab(0)",
+        accels: &["<Control>1"],
+        title_key: "shortcut_tab",
+        title_suffix: " 1",
+        group_key: "shortcuts_navigation",
+    },
+    Shortcut {
+        action: "win.switch-tab(1)",
+        accels: &["<Control>2"],
+        title_key: "shortcut_tab",
+        title_suffix: " 2",
+        group_key: "shortcuts_navigation",
+    },
+    Shortcut {
+        action: "win.switch-tab(2)",
+        accels: &["<Control>3"],
+        title_key: "shortcut_tab",
+        title_suffix: " 3",
+        group_key: "shortcuts_navigation",
+    },
+    Shortcut {
+        action: "win.switch-tab(3)",
+        accels: &["<Control>4"],
+        title_key: "shortcut_tab",
+        title_suffix: " 4",
+        group_key: "shortcuts_navigation",
+    },
+];
+
+/// Binds every shortcut accelerator to its action
+///
+/// # Arguments
+///
+/// * `app` - GTK Application instance
+pub fn set_accels(app: &Application) {
+    for shortcut in &SHORTCUTS {
+        app.set_accels_for_action(shortcut.action, shortcut.accels);
+    }
+}
+
+/// Escapes text for use inside an XML element
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Builds the `GtkBuilder` definition of the shortcuts window
+///
+/// # Returns
+///
+/// UI definition with translated group and shortcut titles
+fn shortcuts_window_xml() -> String {
+    let mut xml = String::from(
+        "<interface>\n  <object class=\"GtkShortcutsWindow\" id=\"shortcuts\">\n    <property name=\"modal\">1</property>\n    <child>\n      <object class=\"GtkShortcutsSection\">\n        <property name=\"section-name\">main</property>\n",
+    );
+
+    let mut current_group = "";
+    for shortcut in &SHORTCUTS {
+        if shortcut.group_key != current_group {
+            if !current_group.is_empty() {
+                xml.push_str("          </object>\n        </child>\n");
+            }
+            current_group = shortcut.group_key;
+            let _ = write!(
+                xml,
+                "        <child>\n          <object class=\"GtkShortcutsGroup\">\n            <property name=\"title\">{}</property>\n",
+                escape(&t(current_group))
+            );
+        }
+        let _ = write!(
+            xml,
+            "            <child>\n              <object class=\"GtkShortcutsShortcut\">\n                <property name=\"title\">{}{}</property>\n                <property name=\"accelerator\">{}</property>\n              </object>\n            </child>\n",
+            escape(&t(shortcut.title_key)),
+            shortcut.title_suffix,
+            escape(&shortcut.accels.join(" "))
+        );
+    }
+    if !current_group.is_empty() {
+        xml.push_str("          </object>\n        </child>\n");
+    }
+
+    xml.push_str("      </object>\n    </child>\n  </object>\n</interface>\n");
+    xml
+}
+
+/// Shows the keyboard shortcuts window
+///
+/// # Arguments
+///
+/// * `window` - Parent window
+pub fn show_shortcuts_window(window: &ApplicationWindow) {
+    let builder = Builder::from_string(&shortcuts_window_xml());
+    let Some(shortcuts) = builder.object::<ShortcutsWindow>("shortcuts") else {
+        crate::core::debug::terminal_error_args(std::format_args!(
+            "❌ [SHORTCUTS] Failed to build the shortcuts window"
+        ));
+        return;
+    };
+
+    shortcuts.set_transient_for(Some(window));
+    shortcuts.present();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_shortcuts_are_unique_and_prefixed() {
+        let accels: Vec<&str> = SHORTCUTS.iter().flat_map(|s| s.accels).copied().collect();
+        let unique: HashSet<&str> = accels.iter().copied().collect();
+        assert_eq!(unique.len(), accels.len());
+
+        for shortcut in &SHORTCUTS {
+            assert!(
+                shortcut.action.starts_with("app.") || shortcut.action.starts_with("win."),
+                "{} has no action scope",
+                shortcut.action
+            );
+        }
+    }
+
+    #[test]
+    fn test_shortcuts_window_xml_groups() {
+        let xml = shortcuts_window_xml();
+        assert_eq!(
+            xml.matches("<object class=\"GtkShortcutsGroup\">").count(),
+            2
+        );
+        assert_eq!(
+            xml.matches("<object class=\"GtkShortcutsShortcut\">")
+                .count(),
+            SHORTCUTS.len()
+        );
+        assert!(xml.contains("F5 &lt;Control&gt;r"));
+        assert_eq!(
+            xml.matches("<object").count(),
+            xml.matches("</object>").count()
+        );
+    }
+}
@@ -1,11 +1,23 @@
 //! Theme management for dark/light mode
 //!
 //! Applies CSS styling for dark theme while preserving default light theme.
+//! An optional accent color (`accent_color` in `config.toml`, `#RRGGBB`)
+//! replaces the primary, success and checked-switch colors; the
+//! charge bar keeps its green/yellow/red status colors.
 
+use std::cell::RefCell;
 use std::sync::RwLock;
 
 static CURRENT_THEME: RwLock<String> = RwLock::new(String::new());
 
+/// User accent color (`None` = theme defaults)
+static ACCENT: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    /// Provider currently installed on the display, replaced on each apply
+    static PROVIDER: RefCell<Option<gtk4::CssProvider>> = const { RefCell::new(None) };
+}
+
 pub fn set_theme(theme: &str) {
     *CURRENT_THEME.write().expect("Theme RwLock poisoned") = theme.to_string();
 }
@@ -14,8 +26,62 @@ pub fn get_theme() -> String {
     CURRENT_THEME.read().expect("Theme RwLock poisoned").clone()
 }
 
-/// Applies CSS theme with given colors
-fn apply_theme_css(is_dark: bool) {
+/// Checks that a color is written `#RRGGBB`
+pub fn is_valid_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Sets the accent color
+///
+/// # Arguments
+///
+/// * `color` - `#RRGGBB` color, or `None` for the theme defaults
+///
+/// # Returns
+///
+/// `false` if the color is invalid (the default colors are used instead)
+pub fn set_accent(color: Option<&str>) -> bool {
+    let color = color.map(str::trim);
+    let valid = color.is_none_or(is_valid_hex_color);
+    *ACCENT.write().expect("Accent RwLock poisoned") =
+        color.filter(|_| valid).map(str::to_ascii_lowercase);
+    valid
+}
+
+/// Returns the accent color, if one is set
+pub fn accent() -> Option<String> {
+    ACCENT.read().expect("Accent RwLock poisoned").clone()
+}
+
+/// Loads the saved accent color, ignoring invalid values
+///
+/// # Arguments
+///
+/// * `saved` - `accent_color` from `config.toml`, empty for the defaults
+pub fn load_accent(saved: &str) {
+    if saved.is_empty() {
+        return;
+    }
+    if !set_accent(Some(saved)) {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⚠️ [THEME] Invalid accent_color in config.toml: '{saved}'"
+        ));
+    }
+}
+
+/// Persists the accent color in `config.toml` (empty for the defaults)
+pub fn save_accent() {
+    let color = accent().unwrap_or_default();
+    crate::core::config::update(|config| config.accent_color = color);
+}
+
+/// Builds the theme CSS
+///
+/// # Arguments
+///
+/// * `is_dark` - Dark or light palette
+/// * `accent` - Validated `#RRGGBB` accent color, if any
+fn theme_css(is_dark: bool, accent: Option<&str>) -> String {
     let (bg, fg, frame_bg, border, note_bg, note_border, note_text) = if is_dark {
         (
             "#252525", "#d5d5d5", "#323232", "#3a3a3a", "#1e3a52", "#2d5373", "#a8c8e8",
@@ -31,8 +97,11 @@ fn apply_theme_css(is_dark: bool) {
     } else {
         ("#2196f3", "#4caf50", "#ff9800", "#f44336")
     };
+    // The charge bar keeps the status colors whatever the accent
+    let charge_within = success;
+    let (primary, success) = accent.map_or((primary, success), |color| (color, color));
 
-    let css = format!("
+    format!("
         window {{ background-color: {bg}; color: {fg}; }}
         * {{ border-color: {border}; }}
         .color-primary {{ color: {primary}; }}
@@ -59,15 +128,33 @@ fn apply_theme_css(is_dark: bool) {
         button:hover {{ background-color: {border}; }}
         switch {{ background-color: {frame_bg}; }}
         switch:checked {{ background-color: {success}; }}
-    ");
+        progressbar.charge-within > trough > progress {{ background-color: {charge_within}; border-color: {charge_within}; }}
+        progressbar.charge-below > trough > progress {{ background-color: {warning}; border-color: {warning}; }}
+        progressbar.charge-above > trough > progress {{ background-color: {danger}; border-color: {danger}; }}
+    ")
+}
+
+/// Applies CSS theme with given colors
+///
+/// Replaces the provider installed by the previous call, so switching
+/// theme or accent does not stack providers.
+fn apply_theme_css(is_dark: bool) {
+    let css = theme_css(is_dark, accent().as_deref());
+    let display = gtk4::gdk::Display::default().expect("Display required");
 
     let provider = gtk4::CssProvider::new();
     provider.load_from_data(&css);
-    gtk4::style_context_add_provider_for_display(
-        &gtk4::gdk::Display::default().expect("Display required"),
-        &provider,
-        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
+    PROVIDER.with(|current| {
+        if let Some(previous) = current.borrow_mut().take() {
+            gtk4::style_context_remove_provider_for_display(&display, &previous);
+        }
+        gtk4::style_context_add_provider_for_display(
+            &display,
+            &provider,
+            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+        *current.borrow_mut() = Some(provider);
+    });
 }
 
 pub fn apply_dark_theme() {
@@ -88,3 +175,37 @@ pub fn apply_current_theme() {
         apply_light_theme();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_color_validation() {
+        assert!(is_valid_hex_color("#0d7377"));
+        assert!(is_valid_hex_color("#3584E4"));
+        assert!(!is_valid_hex_color("0d7377"));
+        assert!(!is_valid_hex_color("#0d737"));
+        assert!(!is_valid_hex_color("#0d73777"));
+        assert!(!is_valid_hex_color("#0g7377"));
+        assert!(!is_valid_hex_color("red"));
+        assert!(!is_valid_hex_color(""));
+        assert!(!is_valid_hex_color("#éé7377"));
+    }
+
+    #[test]
+    fn test_css_uses_accent_color() {
+        for is_dark in [true, false] {
+            let css = theme_css(is_dark, Some("#0d7377"));
+            assert!(css.contains(".color-primary { color: #0d7377; }"));
+            assert!(css.contains(".color-success { color: #0d7377; }"));
+            assert!(css.contains("switch:checked { background-color: #0d7377; }"));
+            // Charge bar colors carry meaning and ignore the accent
+            assert!(!css.contains("charge-within > trough > progress { background-color: #0d7377"));
+        }
+
+        let default = theme_css(false, None);
+        assert!(!default.contains("#0d7377"));
+        assert!(default.contains("switch:checked { background-color: #4caf50; }"));
+    }
+}
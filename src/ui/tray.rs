@@ -0,0 +1,452 @@
+//! System tray icon
+//!
+//! GTK4 has no `StatusIcon`, so the icon is exported over D-Bus as a
+//! `StatusNotifierItem` (KDE, GNOME with the `AppIndicator` extension, most
+//! panels) with a `com.canonical.dbusmenu` context menu. Menu entries
+//! activate application actions registered by `app.rs`.
+
+use gtk4::prelude::*;
+use gtk4::{gio, Application};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use glib::variant::ObjectPath;
+use glib::{Variant, VariantTy};
+
+use crate::core::i18n::t;
+use crate::core::BatteryInfo;
+
+/// Hide the window to the tray instead of quitting (disabled by default)
+static MINIMIZE_TO_TRAY: AtomicBool = AtomicBool::new(false);
+
+/// Start hidden in the tray (`--tray`), for session autostart
+static TRAY_MODE: AtomicBool = AtomicBool::new(false);
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+
+/// Application actions triggered from the tray
+pub const ACTION_OPEN: &str = "tray-open";
+pub const ACTION_APPLY_THRESHOLDS: &str = "tray-apply-thresholds";
+pub const ACTION_QUIT: &str = "tray-quit";
+
+/// Context menu entries: (dbusmenu id, i18n label key, action); no label is a separator
+const MENU_ITEMS: [(i32, Option<&str>, &str); 4] = [
+    (1, Some("tray_open"), ACTION_OPEN),
+    (2, Some("tray_apply_thresholds"), ACTION_APPLY_THRESHOLDS),
+    (3, None, ""),
+    (4, Some("tray_quit"), ACTION_QUIT),
+];
+
+const DBUS_XML: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconPixmap" type="a(iiay)" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="ContextMenu"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Activate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="SecondaryActivate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Scroll"><arg name="delta" type="i" direction="in"/><arg name="orientation" type="s" direction="in"/></method>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+  </interface>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <signal name="LayoutUpdated"><arg name="revision" type="u"/><arg name="parent" type="i"/></signal>
+  </interface>
+</node>
+"#;
+
+/// Enable or disable hiding the window to the tray on close
+pub fn set_minimize_to_tray(enabled: bool) {
+    MINIMIZE_TO_TRAY.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if closing the window hides it to the tray
+pub fn minimize_to_tray() -> bool {
+    MINIMIZE_TO_TRAY.load(Ordering::Relaxed)
+}
+
+/// Start in the tray without presenting the window (`--tray`)
+pub fn enable_tray_mode() {
+    TRAY_MODE.store(true, Ordering::Relaxed);
+}
+
+/// Check if the app runs in tray mode (window hidden until opened from the tray)
+pub fn tray_mode() -> bool {
+    TRAY_MODE.load(Ordering::Relaxed)
+}
+
+/// Returns the freedesktop icon name matching the battery state
+///
+/// # Arguments
+///
+/// * `capacity` - Capacity in percent
+/// * `status` - Raw sysfs status
+fn battery_icon_name(capacity: u8, status: &str) -> String {
+    let level = (capacity.min(100) / 10) * 10;
+    match status {
+        "Charging" => format!("battery-level-{level}-charging-symbolic"),
+        _ if level == 100 && status != "Discharging" => {
+            "battery-level-100-charged-symbolic".to_string()
+        }
+        _ => format!("battery-level-{level}-symbolic"),
+    }
+}
+
+/// Builds one dbusmenu layout item `(ia{sv}av)`
+fn menu_item(id: i32, properties: &HashMap<&str, Variant>, children: &[Variant]) -> Variant {
+    Variant::tuple_from_iter([
+        id.to_variant(),
+        properties.to_variant(),
+        Variant::array_from_iter_with_type(
+            VariantTy::VARIANT,
+            children.iter().map(Variant::from_variant),
+        ),
+    ])
+}
+
+/// Returns the dbusmenu properties of a menu entry
+fn menu_item_properties(label_key: Option<&str>) -> HashMap<&'static str, Variant> {
+    let mut properties = HashMap::new();
+    match label_key {
+        Some(key) => {
+            properties.insert("label", t(key).to_variant());
+        }
+        None => {
+            properties.insert("type", "separator".to_variant());
+        }
+    }
+    properties
+}
+
+/// Builds the full context menu layout (root id 0 and its entries)
+fn menu_layout() -> Variant {
+    let children: Vec<Variant> = MENU_ITEMS
+        .iter()
+        .map(|(id, label_key, _)| menu_item(*id, &menu_item_properties(*label_key), &[]))
+        .collect();
+
+    let mut root = HashMap::new();
+    root.insert("children-display", "submenu".to_variant());
+    menu_item(0, &root, &children)
+}
+
+/// Shared tray state read by the D-Bus property handlers
+struct TrayState {
+    icon_name: String,
+    tooltip: String,
+}
+
+/// Exports the `StatusNotifierItem` object
+fn register_item(
+    connection: &gio::DBusConnection,
+    interface: &gio::DBusInterfaceInfo,
+    app: &Application,
+    state: &Rc<RefCell<TrayState>>,
+) -> Result<gio::RegistrationId, glib::Error> {
+    let item_state = state.clone();
+    let activate_app = app.downgrade();
+    connection
+        .register_object(ITEM_PATH, interface)
+        .method_call(move |_, _, _, _, method, _, invocation| {
+            if method == "Activate" {
+                if let Some(app) = activate_app.upgrade() {
+                    app.activate_action(ACTION_OPEN, None);
+                }
+            }
+            invocation.return_value(None);
+        })
+        .property(move |_, _, _, _, property| {
+            let state = item_state.borrow();
+            match property {
+                "Category" => "Hardware".to_variant(),
+                "Id" => "battery-manager".to_variant(),
+                "Title" => t("app_title").to_variant(),
+                "Status" => "Active".to_variant(),
+                "IconName" => state.icon_name.to_variant(),
+                "IconPixmap" => Variant::array_from_iter_with_type(
+                    VariantTy::new("(iiay)").expect("valid variant type"),
+                    std::iter::empty::<Variant>(),
+                ),
+                "ToolTip" => Variant::tuple_from_iter([
+                    state.icon_name.to_variant(),
+                    Variant::array_from_iter_with_type(
+                        VariantTy::new("(iiay)").expect("valid variant type"),
+                        std::iter::empty::<Variant>(),
+                    ),
+                    t("app_title").to_variant(),
+                    state.tooltip.to_variant(),
+                ]),
+                "ItemIsMenu" => false.to_variant(),
+                _ => ObjectPath::try_from(MENU_PATH)
+                    .expect("valid object path")
+                    .to_variant(),
+            }
+        })
+        .build()
+}
+
+/// Exports the dbusmenu object backing the context menu
+fn register_menu(
+    connection: &gio::DBusConnection,
+    interface: &gio::DBusInterfaceInfo,
+    app: &Application,
+) -> Result<gio::RegistrationId, glib::Error> {
+    let menu_app = app.downgrade();
+    connection
+        .register_object(MENU_PATH, interface)
+        .method_call(move |_, _, _, _, method, params, invocation| match method {
+            "GetLayout" => {
+                invocation.return_value(Some(&Variant::tuple_from_iter([
+                    0_u32.to_variant(),
+                    menu_layout(),
+                ])));
+            }
+            "GetGroupProperties" => {
+                let properties: Vec<Variant> = MENU_ITEMS
+                    .iter()
+                    .map(|(id, label_key, _)| (*id, menu_item_properties(*label_key)).to_variant())
+                    .collect();
+                invocation.return_value(Some(&Variant::tuple_from_iter([
+                    Variant::array_from_iter_with_type(
+                        VariantTy::new("(ia{sv})").expect("valid variant type"),
+                        properties,
+                    ),
+                ])));
+            }
+            "Event" => {
+                let id = params.child_value(0).get::<i32>();
+                let event = params.child_value(1).get::<String>();
+                if event.as_deref() == Some("clicked") {
+                    let action = MENU_ITEMS
+                        .iter()
+                        .find(|(item_id, label_key, _)| Some(*item_id) == id && label_key.is_some())
+                        .map(|(_, _, action)| *action);
+                    if let (Some(action), Some(app)) = (action, menu_app.upgrade()) {
+                        crate::core::debug::debug_log_args(std::format_args!(
+                            "🖱️ [TRAY] Menu action: {action}"
+                        ));
+                        app.activate_action(action, None);
+                    }
+                }
+                invocation.return_value(None);
+            }
+            "AboutToShow" => invocation.return_value(Some(&(false,).to_variant())),
+            _ => invocation.return_dbus_error(
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                &format!("Unknown method {method}"),
+            ),
+        })
+        .property(|_, _, _, _, property| match property {
+            "Version" => 3_u32.to_variant(),
+            "TextDirection" => "ltr".to_variant(),
+            _ => "normal".to_variant(),
+        })
+        .build()
+}
+
+/// Tray icon exported as a `StatusNotifierItem`
+pub struct TrayIcon {
+    connection: gio::DBusConnection,
+    state: Rc<RefCell<TrayState>>,
+    /// A `StatusNotifierWatcher` accepted the icon
+    registered: Rc<Cell<bool>>,
+}
+
+impl TrayIcon {
+    /// Exports the tray icon and registers it with the panel
+    ///
+    /// Registration doesn't block the main loop: `on_registered` receives
+    /// whether a tray host accepted the icon once the panel answers.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - GTK Application (its session bus connection and actions are used)
+    /// * `on_registered` - Called with the registration outcome
+    ///
+    /// # Returns
+    ///
+    /// * `Some(TrayIcon)` - Icon exported, registration pending
+    /// * `None` - No session bus, or the icon cannot be exported
+    pub fn new(app: &Application, on_registered: impl FnOnce(bool) + 'static) -> Option<Self> {
+        let connection = app.dbus_connection()?;
+        let node = gio::DBusNodeInfo::for_xml(DBUS_XML).ok()?;
+        let item_interface = node.lookup_interface(ITEM_INTERFACE)?;
+        let menu_interface = node.lookup_interface(MENU_INTERFACE)?;
+
+        let state = Rc::new(RefCell::new(TrayState {
+            icon_name: "battery-symbolic".to_string(),
+            tooltip: String::new(),
+        }));
+
+        let registered = register_item(&connection, &item_interface, app, &state)
+            .and_then(|_| register_menu(&connection, &menu_interface, app));
+        if let Err(e) = registered {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "⚠️ [TRAY] Failed to export tray objects: {e}"
+            ));
+            return None;
+        }
+
+        let unique_name = connection.unique_name()?;
+        let registered = Rc::new(Cell::new(false));
+        let reply_registered = registered.clone();
+        connection.call(
+            Some("org.kde.StatusNotifierWatcher"),
+            "/StatusNotifierWatcher",
+            "org.kde.StatusNotifierWatcher",
+            "RegisterStatusNotifierItem",
+            Some(&(unique_name.as_str(),).to_variant()),
+            None,
+            gio::DBusCallFlags::NONE,
+            1000,
+            None::<&gio::Cancellable>,
+            move |reply| {
+                match reply {
+                    Ok(_) => {
+                        crate::core::debug::debug_log("🖥️ [TRAY] Tray icon registered");
+                        reply_registered.set(true);
+                    }
+                    Err(e) => crate::core::debug::debug_log_args(std::format_args!(
+                        "⚠️ [TRAY] No system tray available: {e}"
+                    )),
+                }
+                on_registered(reply_registered.get());
+            },
+        );
+
+        Some(Self {
+            connection,
+            state,
+            registered,
+        })
+    }
+
+    /// Whether a tray host shows the icon
+    pub fn is_registered(&self) -> bool {
+        self.registered.get()
+    }
+
+    /// Updates the icon and tooltip from fresh battery information
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Battery information
+    pub fn update(&self, info: &BatteryInfo) {
+        let icon_name = battery_icon_name(info.capacity_percent, &info.status);
+        let tooltip = format!(
+            "{}: {}% — {}",
+            info.name,
+            info.capacity_text(),
+            info.status_text()
+        );
+
+        let (icon_changed, tooltip_changed) = {
+            let mut state = self.state.borrow_mut();
+            let changes = (state.icon_name != icon_name, state.tooltip != tooltip);
+            state.icon_name = icon_name;
+            state.tooltip = tooltip;
+            changes
+        };
+
+        for (changed, signal) in [(icon_changed, "NewIcon"), (tooltip_changed, "NewToolTip")] {
+            if changed {
+                let _ = self
+                    .connection
+                    .emit_signal(None, ITEM_PATH, ITEM_INTERFACE, signal, None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_battery_icon_name() {
+        assert_eq!(
+            battery_icon_name(47, "Discharging"),
+            "battery-level-40-symbolic"
+        );
+        assert_eq!(
+            battery_icon_name(83, "Charging"),
+            "battery-level-80-charging-symbolic"
+        );
+        assert_eq!(
+            battery_icon_name(100, "Full"),
+            "battery-level-100-charged-symbolic"
+        );
+        assert_eq!(
+            battery_icon_name(100, "Discharging"),
+            "battery-level-100-symbolic"
+        );
+    }
+
+    #[test]
+    fn test_battery_icon_name_bounds() {
+        assert_eq!(
+            battery_icon_name(0, "Discharging"),
+            "battery-level-0-symbolic"
+        );
+        assert_eq!(
+            battery_icon_name(9, "Charging"),
+            "battery-level-0-charging-symbolic"
+        );
+        // Levels are rounded down, never up
+        assert_eq!(
+            battery_icon_name(99, "Discharging"),
+            "battery-level-90-symbolic"
+        );
+        // Held at the stop threshold by the firmware
+        assert_eq!(
+            battery_icon_name(100, "Not charging"),
+            "battery-level-100-charged-symbolic"
+        );
+        assert_eq!(
+            battery_icon_name(80, "Not charging"),
+            "battery-level-80-symbolic"
+        );
+        // Some drivers report slightly above 100 %
+        assert_eq!(
+            battery_icon_name(103, "Full"),
+            "battery-level-100-charged-symbolic"
+        );
+    }
+}
@@ -1,18 +1,30 @@
-//! UI preferences tab for language and theme settings
+//! UI preferences tab for language, theme, notification and behavior settings
 //!
-//! Allows users to switch between languages and themes with live preview.
+//! Allows users to switch between languages and themes with live preview,
+//! to toggle desktop notifications (each kind of event separately), to hide the window to the tray and to
+//! set the auto-refresh interval.
 
 use gtk4::prelude::*;
-use gtk4::{Box, Label, Orientation, ScrolledWindow, Switch};
+use gtk4::{
+    Box, Button, CheckButton, DropDown, Entry, Label, Orientation, ScrolledWindow, SpinButton,
+    Switch,
+};
 
-use crate::core::i18n::t;
+use crate::core::alerts::AlertKind;
+use crate::core::config::{
+    self, auto_refresh_secs, set_auto_refresh_secs, MAX_REFRESH_SECS, MIN_REFRESH_SECS,
+};
+use crate::core::i18n::{t, LANGUAGES};
 use crate::ui::components::InfoCard;
 
+/// Window action re-arming the auto-refresh timer after an interval change
+pub const ACTION_REFRESH_INTERVAL: &str = "refresh-interval";
+
 /// Builds the UI preferences tab content
 ///
 /// # Returns
 ///
-/// `ScrolledWindow` containing language and theme controls
+/// `ScrolledWindow` containing language, theme, notification and behavior controls
 #[allow(clippy::too_many_lines)]
 pub fn build_ui_tab() -> ScrolledWindow {
     crate::core::debug::debug_log("🎛️ [UI_TAB] Building UI preferences tab");
@@ -29,62 +41,52 @@ pub fn build_ui_tab() -> ScrolledWindow {
     let (lang_frame, lang_box) = InfoCard::create(&format!("🌐 {}", t("language_setting")));
     lang_box.set_spacing(10);
 
-    let lang_row = Box::new(Orientation::Horizontal, 10);
-    lang_row.set_halign(gtk4::Align::Center);
-
-    let lang_fr_label = Label::new(Some(&t("language_fr")));
-    lang_fr_label.set_markup(&format!("<span size='large'>{}</span>", t("language_fr")));
-
-    let lang_switch = Switch::new();
-    lang_switch.set_active(crate::core::i18n::get_language() == "en");
-    lang_switch.set_valign(gtk4::Align::Center);
-    lang_switch.set_margin_start(15);
-    lang_switch.set_margin_end(15);
-
-    let lang_en_label = Label::new(Some(&t("language_en")));
-    lang_en_label.set_markup(&format!("<span size='large'>{}</span>", t("language_en")));
-
-    lang_row.append(&lang_fr_label);
-    lang_row.append(&lang_switch);
-    lang_row.append(&lang_en_label);
-    lang_box.append(&lang_row);
+    // Each language is listed under its own name
+    let lang_names: Vec<String> = LANGUAGES
+        .iter()
+        .map(|code| t(&format!("language_{code}")))
+        .collect();
+    let lang_refs: Vec<&str> = lang_names.iter().map(String::as_str).collect();
+    let lang_dropdown = DropDown::from_strings(&lang_refs);
+    lang_dropdown.set_halign(gtk4::Align::Center);
+    let current_lang = crate::core::i18n::get_language();
+    if let Some(index) = LANGUAGES
+        .iter()
+        .position(|code| *code == current_lang)
+        .and_then(|i| u32::try_from(i).ok())
+    {
+        lang_dropdown.set_selected(index);
+    }
+    lang_box.append(&lang_dropdown);
 
     let lang_status = Label::new(None);
     lang_status.set_halign(gtk4::Align::Center);
     lang_status.set_margin_top(10);
     lang_box.append(&lang_status);
 
-    lang_switch.connect_state_set(glib::clone!(
+    lang_dropdown.connect_selected_notify(glib::clone!(
         #[weak]
         lang_status,
-        #[upgrade_or]
-        glib::Propagation::Proceed,
-        move |_switch, state| {
-            let new_lang = if state { "en" } else { "fr" };
+        move |dropdown| {
+            let Some(new_lang) = usize::try_from(dropdown.selected())
+                .ok()
+                .and_then(|i| LANGUAGES.get(i))
+            else {
+                return;
+            };
 
             crate::core::debug::debug_log_args(std::format_args!(
-                "🌐 [UI_TAB] Language switch toggled -> {new_lang}"
+                "🌐 [UI_TAB] Language selected -> {new_lang}"
             ));
             crate::core::i18n::set_language(new_lang);
 
-            // Save to config file
-            if let Some(config_dir) = dirs::config_dir() {
-                let app_config_dir = config_dir.join("battery-manager");
-                let _ = std::fs::create_dir_all(&app_config_dir);
-                let config_file = app_config_dir.join("language.conf");
-                let _ = std::fs::write(config_file, new_lang);
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "💾 [UI_TAB] Saved language.conf -> {new_lang}"
-                ));
-            }
+            config::update(|config| (*new_lang).clone_into(&mut config.language));
 
             lang_status.set_markup(&format!(
                 "<span size='small'>{}</span>",
                 t("restart_required")
             ));
             lang_status.add_css_class("color-warning");
-
-            glib::Propagation::Proceed
         }
     ));
 
@@ -141,16 +143,7 @@ pub fn build_ui_tab() -> ScrolledWindow {
                 crate::ui::theme::apply_light_theme();
             }
 
-            // Save to config file
-            if let Some(config_dir) = dirs::config_dir() {
-                let app_config_dir = config_dir.join("battery-manager");
-                let _ = std::fs::create_dir_all(&app_config_dir);
-                let config_file = app_config_dir.join("theme.conf");
-                let _ = std::fs::write(config_file, new_theme);
-                crate::core::debug::debug_log_args(std::format_args!(
-                    "💾 [UI_TAB] Saved theme.conf -> {new_theme}"
-                ));
-            }
+            config::update(|config| new_theme.clone_into(&mut config.theme));
 
             theme_status.set_markup(&format!(
                 "<span size='small'>✓ {}</span>",
@@ -167,8 +160,229 @@ pub fn build_ui_tab() -> ScrolledWindow {
         }
     ));
 
+    // Couleur d'accent (#RRGGBB)
+    let accent_row = Box::new(Orientation::Horizontal, 10);
+    accent_row.set_halign(gtk4::Align::Center);
+
+    let accent_label = Label::new(Some(&t("accent_color")));
+    let accent_entry = Entry::new();
+    accent_entry.set_max_length(7);
+    accent_entry.set_width_chars(8);
+    accent_entry.set_placeholder_text(Some("#RRGGBB"));
+    accent_entry.set_text(&crate::ui::theme::accent().unwrap_or_default());
+    accent_entry.set_tooltip_text(Some(&t("accent_color_tooltip")));
+    let accent_reset = Button::with_label(&t("accent_reset"));
+
+    accent_row.append(&accent_label);
+    accent_row.append(&accent_entry);
+    accent_row.append(&accent_reset);
+    theme_box.insert_child_after(&accent_row, Some(&theme_row));
+
+    accent_entry.connect_activate(glib::clone!(
+        #[weak]
+        theme_status,
+        move |entry| {
+            let color = entry.text().trim().to_string();
+            theme_status.remove_css_class("color-success");
+            theme_status.remove_css_class("color-danger");
+            if !crate::ui::theme::is_valid_hex_color(&color) {
+                crate::core::debug::debug_log_args(std::format_args!(
+                    "⚠️ [UI_TAB] Rejected accent color '{color}'"
+                ));
+                theme_status.set_markup(&format!(
+                    "<span size='small'>{}</span>",
+                    t("accent_invalid")
+                ));
+                theme_status.add_css_class("color-danger");
+                return;
+            }
+
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🎨 [UI_TAB] Accent color -> {color}"
+            ));
+            crate::ui::theme::set_accent(Some(&color));
+            crate::ui::theme::save_accent();
+            crate::ui::theme::apply_current_theme();
+            theme_status.set_markup(&format!(
+                "<span size='small'>✓ {}</span>",
+                t("theme_applied")
+            ));
+            theme_status.add_css_class("color-success");
+        }
+    ));
+
+    accent_reset.connect_clicked(glib::clone!(
+        #[weak]
+        accent_entry,
+        move |_| {
+            crate::core::debug::debug_log("🎨 [UI_TAB] Accent color reset to defaults");
+            crate::ui::theme::set_accent(None);
+            crate::ui::theme::save_accent();
+            crate::ui::theme::apply_current_theme();
+            accent_entry.set_text("");
+        }
+    ));
+
     content_box.append(&theme_frame);
 
+    // === Card Notifications ===
+    let (notif_frame, notif_box) = InfoCard::create(&format!("🔔 {}", t("notifications_setting")));
+    notif_box.set_spacing(10);
+
+    let notif_row = Box::new(Orientation::Horizontal, 10);
+    notif_row.set_halign(gtk4::Align::Center);
+
+    let notif_label = Label::new(None);
+    notif_label.set_markup(&format!(
+        "<span size='large'>{}</span>",
+        t("notifications_enable")
+    ));
+
+    let notif_switch = Switch::new();
+    notif_switch.set_active(crate::core::alerts::notifications_enabled());
+    notif_switch.set_valign(gtk4::Align::Center);
+    notif_switch.set_margin_start(15);
+    notif_switch.set_tooltip_text(Some(&t("notifications_tooltip")));
+
+    notif_row.append(&notif_label);
+    notif_row.append(&notif_switch);
+    notif_box.append(&notif_row);
+
+    let threshold_row = Box::new(Orientation::Horizontal, 10);
+    threshold_row.set_halign(gtk4::Align::Center);
+
+    let threshold_label = Label::new(Some(&t("notifications_low_threshold")));
+    let threshold_spin = SpinButton::with_range(5.0, 50.0, 1.0);
+    threshold_spin.set_value(f64::from(crate::core::alerts::low_threshold()));
+    threshold_spin.set_sensitive(crate::core::alerts::notifications_enabled());
+
+    threshold_row.append(&threshold_label);
+    threshold_row.append(&threshold_spin);
+    notif_box.append(&threshold_row);
+
+    // Un interrupteur par type d'événement
+    let events_row = Box::new(Orientation::Horizontal, 10);
+    events_row.set_halign(gtk4::Align::Center);
+    events_row.set_sensitive(crate::core::alerts::notifications_enabled());
+    events_row.append(&Label::new(Some(&t("notifications_events"))));
+    for (kind, label_key) in [
+        (AlertKind::LowBattery, "notif_low_title"),
+        (AlertKind::CriticalBattery, "notifications_critical"),
+        (AlertKind::ChargeComplete, "notif_charged_title"),
+        (AlertKind::PowerSource, "notifications_power_source"),
+    ] {
+        let check = CheckButton::with_label(&t(label_key));
+        check.set_active(crate::core::alerts::event_enabled(kind));
+        check.connect_toggled(move |check| {
+            let enabled = check.is_active();
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔔 [UI_TAB] {kind:?} notifications -> {enabled}"
+            ));
+            crate::core::alerts::set_event_enabled(kind, enabled);
+            config::update(|config| match kind {
+                AlertKind::LowBattery => config.notify_low_battery = enabled,
+                AlertKind::CriticalBattery => config.notify_critical_battery = enabled,
+                AlertKind::ChargeComplete => config.notify_charge_complete = enabled,
+                AlertKind::PowerSource => config.notify_power_source = enabled,
+            });
+        });
+        events_row.append(&check);
+    }
+    notif_box.append(&events_row);
+
+    notif_switch.connect_state_set(glib::clone!(
+        #[weak]
+        threshold_spin,
+        #[weak]
+        events_row,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_switch, state| {
+            crate::core::debug::debug_log_args(std::format_args!(
+                "🔔 [UI_TAB] Notifications switch toggled -> {state}"
+            ));
+            crate::core::alerts::set_notifications_enabled(state);
+            config::update(|config| config.notifications_enabled = state);
+            threshold_spin.set_sensitive(state);
+            events_row.set_sensitive(state);
+            glib::Propagation::Proceed
+        }
+    ));
+
+    threshold_spin.connect_value_changed(|spin| {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let percent = spin.value() as u8;
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🔔 [UI_TAB] Low battery threshold -> {percent}%"
+        ));
+        crate::core::alerts::set_low_threshold(percent);
+        let threshold = crate::core::alerts::low_threshold();
+        config::update(|config| config.notification_threshold = threshold);
+    });
+
+    content_box.append(&notif_frame);
+
+    // === Card Comportement ===
+    let (behavior_frame, behavior_box) = InfoCard::create(&format!("🖥️ {}", t("card_behavior")));
+    behavior_box.set_spacing(10);
+
+    let tray_row = Box::new(Orientation::Horizontal, 10);
+    tray_row.set_halign(gtk4::Align::Center);
+
+    let tray_label = Label::new(None);
+    tray_label.set_markup(&format!(
+        "<span size='large'>{}</span>",
+        t("minimize_to_tray")
+    ));
+
+    let tray_switch = Switch::new();
+    tray_switch.set_active(crate::ui::tray::minimize_to_tray());
+    tray_switch.set_valign(gtk4::Align::Center);
+    tray_switch.set_margin_start(15);
+    tray_switch.set_tooltip_text(Some(&t("minimize_to_tray_tooltip")));
+
+    tray_row.append(&tray_label);
+    tray_row.append(&tray_switch);
+    behavior_box.append(&tray_row);
+
+    tray_switch.connect_state_set(|_switch, state| {
+        crate::core::debug::debug_log_args(std::format_args!(
+            "🖥️ [UI_TAB] Minimize to tray toggled -> {state}"
+        ));
+        crate::ui::tray::set_minimize_to_tray(state);
+        config::update(|config| config.minimize_to_tray = state);
+        glib::Propagation::Proceed
+    });
+
+    let refresh_row = Box::new(Orientation::Horizontal, 10);
+    refresh_row.set_halign(gtk4::Align::Center);
+
+    let refresh_label = Label::new(Some(&t("auto_refresh_interval")));
+    #[allow(clippy::cast_precision_loss)]
+    let refresh_spin =
+        SpinButton::with_range(MIN_REFRESH_SECS as f64, MAX_REFRESH_SECS as f64, 1.0);
+    #[allow(clippy::cast_precision_loss)]
+    refresh_spin.set_value(auto_refresh_secs() as f64);
+    refresh_spin.set_tooltip_text(Some(&t("auto_refresh_tooltip")));
+
+    refresh_row.append(&refresh_label);
+    refresh_row.append(&refresh_spin);
+    behavior_box.append(&refresh_row);
+
+    refresh_spin.connect_value_changed(|spin| {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let secs = spin.value() as u64;
+        crate::core::debug::debug_log_args(std::format_args!(
+            "⏱️ [UI_TAB] Auto-refresh interval -> {secs}s"
+        ));
+        set_auto_refresh_secs(secs);
+        config::update(|config| config.refresh_interval_secs = auto_refresh_secs());
+        // Restart the timer now rather than at the end of the old interval
+        let _ = spin.activate_action(&format!("win.{ACTION_REFRESH_INTERVAL}"), None);
+    });
+
+    content_box.append(&behavior_frame);
+
     scrolled.set_child(Some(&content_box));
     scrolled
 }